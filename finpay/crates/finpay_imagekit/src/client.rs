@@ -34,6 +34,7 @@ pub struct VersionInfo {
 pub struct ImagekitClient {
     client: Client,
     upload_url: String,
+    files_url: String,
     public_key: String,
     private_key: String,
 }
@@ -43,6 +44,7 @@ impl ImagekitClient {
         Ok(Self {
             client: Client::builder().build()?,
             upload_url: "https://upload.imagekit.io/api/v1/files/upload".to_string(),
+            files_url: "https://api.imagekit.io/v1/files".to_string(),
             public_key: public_key.to_string(),
             private_key: private_key.to_string(),
         })
@@ -87,4 +89,29 @@ impl ImagekitClient {
         let parsed = response.json::<ImagekitUploadResponse>().await?;
         Ok(parsed)
     }
+
+    pub async fn delete_file(&self, file_id: &str) -> Result<(), ImagekitError> {
+        let mut headers = HeaderMap::new();
+
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str(&format!("Basic {}", self.private_key))?,
+        );
+
+        let response = self
+            .client
+            .request(Method::DELETE, format!("{}/{}", self.files_url, file_id))
+            .headers(headers)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ImagekitError::DeleteFailed(format!(
+                "Delete failed: {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
 }
@@ -6,6 +6,7 @@ pub enum ImagekitError {
     Io(std::io::Error),
     InvalidHeader(reqwest::header::InvalidHeaderValue),
     UploadFailed(String),
+    DeleteFailed(String),
 }
 
 impl fmt::Display for ImagekitError {
@@ -15,6 +16,7 @@ impl fmt::Display for ImagekitError {
             ImagekitError::Io(err) => write!(f, "I/O error: {err}"),
             ImagekitError::InvalidHeader(err) => write!(f, "Invalid header: {err}"),
             ImagekitError::UploadFailed(msg) => write!(f, "Upload failed: {msg}"),
+            ImagekitError::DeleteFailed(msg) => write!(f, "Delete failed: {msg}"),
         }
     }
 }
@@ -25,7 +27,7 @@ impl std::error::Error for ImagekitError {
             ImagekitError::Reqwest(err) => Some(err),
             ImagekitError::Io(err) => Some(err),
             ImagekitError::InvalidHeader(err) => Some(err),
-            ImagekitError::UploadFailed(_) => None,
+            ImagekitError::UploadFailed(_) | ImagekitError::DeleteFailed(_) => None,
         }
     }
 }
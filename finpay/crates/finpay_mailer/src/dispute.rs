@@ -0,0 +1,35 @@
+use askama::Template;
+use serde::{Deserialize, Serialize};
+
+#[derive(Template)]
+#[template(path = "dispute.html")]
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct DisputeTemplate {
+    first_name: String,
+    reference: String,
+    transaction_reference: String,
+    status: String,
+    due_date: String,
+    note: String,
+}
+
+impl DisputeTemplate {
+    /// `status` is one of open, under_review, won or lost
+    pub fn new(
+        first_name: &str,
+        reference: &str,
+        transaction_reference: &str,
+        status: &str,
+        due_date: &str,
+        note: &str,
+    ) -> Self {
+        Self {
+            first_name: first_name.to_string(),
+            reference: reference.to_string(),
+            transaction_reference: transaction_reference.to_string(),
+            status: status.to_string(),
+            due_date: due_date.to_string(),
+            note: note.to_string(),
+        }
+    }
+}
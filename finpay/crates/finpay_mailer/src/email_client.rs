@@ -9,8 +9,8 @@ use serde::Serialize;
 use finpay_utils::extract_env;
 
 use crate::{
-    ConfirmEmailTemplate, ForgottenPasswordTemplate, PasswordUpdatedTemplate, WelcomeTemplate,
-    email::Email, errors::EmailError,
+    ConfirmEmailTemplate, DisputeTemplate, ForgottenPasswordTemplate, PasswordUpdatedTemplate,
+    RefundTemplate, WelcomeTemplate, email::Email, errors::EmailError,
 };
 
 #[derive(Debug, Clone)]
@@ -125,6 +125,18 @@ pub trait EmailClientExt {
         user_email: &str,
         user_name: &str,
     ) -> impl std::future::Future<Output = Result<(), EmailError>> + Send;
+
+    fn send_refund_email(
+        &self,
+        user_email: &str,
+        template: RefundTemplate,
+    ) -> impl std::future::Future<Output = Result<(), EmailError>> + Send;
+
+    fn send_dispute_email(
+        &self,
+        user_email: &str,
+        template: DisputeTemplate,
+    ) -> impl std::future::Future<Output = Result<(), EmailError>> + Send;
 }
 
 impl EmailClientExt for EmailClient {
@@ -215,4 +227,40 @@ impl EmailClientExt for EmailClient {
 
         Ok(())
     }
+
+    async fn send_refund_email(
+        &self,
+        user_email: &str,
+        template: RefundTemplate,
+    ) -> Result<(), EmailError> {
+        let email = Email::builder()
+            .subject("Refund Processed")
+            .to(user_email)
+            .template(template)
+            .build();
+        self.send_email(&email).map_err(|err| {
+            log::error!("Failed to send refund email due to: {err}");
+            EmailError::SendError(err.to_string())
+        })?;
+
+        Ok(())
+    }
+
+    async fn send_dispute_email(
+        &self,
+        user_email: &str,
+        template: DisputeTemplate,
+    ) -> Result<(), EmailError> {
+        let email = Email::builder()
+            .subject("Dispute Update")
+            .to(user_email)
+            .template(template)
+            .build();
+        self.send_email(&email).map_err(|err| {
+            log::error!("Failed to send dispute email due to: {err}");
+            EmailError::SendError(err.to_string())
+        })?;
+
+        Ok(())
+    }
 }
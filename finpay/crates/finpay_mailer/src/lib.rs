@@ -1,15 +1,19 @@
 mod confirm_email;
+mod dispute;
 mod email;
 mod email_client;
 mod errors;
 mod forgotten_password;
 mod password_updated;
+mod refund;
 mod welcome;
 pub use confirm_email::ConfirmEmailTemplate;
+pub use dispute::DisputeTemplate;
 pub use email::Email;
 pub use email_client::EmailClient;
 pub use email_client::EmailClientExt;
 pub use errors::EmailError;
 pub use forgotten_password::ForgottenPasswordTemplate;
 pub use password_updated::PasswordUpdatedTemplate;
+pub use refund::RefundTemplate;
 pub use welcome::WelcomeTemplate;
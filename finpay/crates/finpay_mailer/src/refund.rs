@@ -0,0 +1,34 @@
+use askama::Template;
+use serde::{Deserialize, Serialize};
+
+#[derive(Template)]
+#[template(path = "refund.html")]
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RefundTemplate {
+    first_name: String,
+    amount: String,
+    currency_code: String,
+    reference: String,
+    reason: String,
+    credited: bool,
+}
+
+impl RefundTemplate {
+    pub fn new(
+        first_name: &str,
+        amount: &str,
+        currency_code: &str,
+        reference: &str,
+        reason: &str,
+        credited: bool,
+    ) -> Self {
+        Self {
+            first_name: first_name.to_string(),
+            amount: amount.to_string(),
+            currency_code: currency_code.to_string(),
+            reference: reference.to_string(),
+            reason: reason.to_string(),
+            credited,
+        }
+    }
+}
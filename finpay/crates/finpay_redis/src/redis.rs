@@ -1,11 +1,13 @@
 use finpay_utils::extract_env;
 use redis::{
-    AsyncCommands,
+    AsyncCommands, ExistenceCheck, SetExpiry, SetOptions,
     aio::{ConnectionManager, ConnectionManagerConfig},
 };
 
 use crate::RedisClientError;
 
+/// cheap to clone, the clones share one connection manager
+#[derive(Clone)]
 pub struct RedisClient {
     connection_manager: ConnectionManager,
 }
@@ -56,6 +58,39 @@ pub trait RedisClientExt {
         &mut self,
         token: &str,
     ) -> impl Future<Output = Result<Option<String>, RedisClientError>> + Send;
+
+    /// Claims an idempotency key, returns false when the key is already held
+    fn reserve_idempotency_key(
+        &mut self,
+        key: &str,
+        record: &str,
+        ttl_secs: u64,
+    ) -> impl Future<Output = Result<bool, RedisClientError>> + Send;
+
+    fn fetch_idempotency_record(
+        &mut self,
+        key: &str,
+    ) -> impl Future<Output = Result<Option<String>, RedisClientError>> + Send;
+
+    fn save_idempotency_record(
+        &mut self,
+        key: &str,
+        record: &str,
+        ttl_secs: u64,
+    ) -> impl Future<Output = Result<(), RedisClientError>> + Send;
+
+    fn release_idempotency_key(
+        &mut self,
+        key: &str,
+    ) -> impl Future<Output = Result<(), RedisClientError>> + Send;
+
+    /// Counts one more event against a fixed window that starts with the first event, returns the
+    /// count so far
+    fn increment_velocity_counter(
+        &mut self,
+        key: &str,
+        window_secs: u64,
+    ) -> impl Future<Output = Result<u64, RedisClientError>> + Send;
 }
 
 impl RedisClientExt for RedisClient {
@@ -134,4 +169,87 @@ impl RedisClientExt for RedisClient {
 
         Ok(result)
     }
+
+    async fn reserve_idempotency_key(
+        &mut self,
+        key: &str,
+        record: &str,
+        ttl_secs: u64,
+    ) -> Result<bool, RedisClientError> {
+        let key = &format!("idempotency_key:{key}");
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(ttl_secs));
+        let result: Option<String> = self
+            .connection_manager
+            .set_options(key, record, options)
+            .await
+            .map_err(RedisClientError::from)?;
+
+        Ok(result.is_some())
+    }
+
+    async fn fetch_idempotency_record(
+        &mut self,
+        key: &str,
+    ) -> Result<Option<String>, RedisClientError> {
+        let key = &format!("idempotency_key:{key}");
+        let result: Option<String> = self
+            .connection_manager
+            .get(key)
+            .await
+            .map_err(RedisClientError::from)?;
+
+        Ok(result)
+    }
+
+    async fn save_idempotency_record(
+        &mut self,
+        key: &str,
+        record: &str,
+        ttl_secs: u64,
+    ) -> Result<(), RedisClientError> {
+        let key = &format!("idempotency_key:{key}");
+        let _: () = self
+            .connection_manager
+            .set_ex(key, record, ttl_secs)
+            .await
+            .map_err(RedisClientError::from)?;
+
+        Ok(())
+    }
+
+    async fn release_idempotency_key(&mut self, key: &str) -> Result<(), RedisClientError> {
+        let key = &format!("idempotency_key:{key}");
+        let _: () = self
+            .connection_manager
+            .del(key)
+            .await
+            .map_err(RedisClientError::from)?;
+
+        Ok(())
+    }
+
+    async fn increment_velocity_counter(
+        &mut self,
+        key: &str,
+        window_secs: u64,
+    ) -> Result<u64, RedisClientError> {
+        let key = &format!("velocity_counter:{key}");
+        // the window starts with the first increment, both go in one MULTI so a counter can
+        // never be left behind without an expiry
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(window_secs));
+        let (count,): (u64,) = redis::pipe()
+            .atomic()
+            .set_options(key, 0, options)
+            .ignore()
+            .incr(key, 1)
+            .query_async(&mut self.connection_manager)
+            .await
+            .map_err(RedisClientError::from)?;
+
+        Ok(count)
+    }
 }
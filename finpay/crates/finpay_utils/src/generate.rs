@@ -10,3 +10,19 @@ pub fn generate_otp() -> String {
     let otp: u32 = rng.random_range(0..=999_999);
     format!("{:06}", otp) // Always 6 digits, leading zeros preserved
 }
+
+pub fn generate_reference(prefix: &str) -> String {
+    let suffix: String = rand::rng()
+        .sample_iter(&rand::distr::Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect();
+
+    format!("{prefix}-{}", suffix.to_uppercase())
+}
+
+pub fn generate_account_number() -> String {
+    let mut rng = rand::rng();
+    let account_number: u64 = rng.random_range(0..=9_999_999_999);
+    format!("{:010}", account_number)
+}
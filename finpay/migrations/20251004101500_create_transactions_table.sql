@@ -0,0 +1,44 @@
+-- Add migration script here
+
+DO $$ BEGIN
+CREATE TYPE transaction_status_enum AS ENUM ('pending', 'completed', 'failed');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+CREATE TYPE transaction_type_enum AS ENUM ('transfer');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- a wallet can never be overdrawn, regardless of the code path that moves money
+ALTER TABLE wallets
+    ADD CONSTRAINT wallets_balance_not_negative CHECK (balance >= 0);
+
+CREATE TABLE IF NOT EXISTS transactions
+(
+    identifier                    UUID PRIMARY KEY        NOT NULL,
+    reference                     VARCHAR                 NOT NULL UNIQUE,
+    transaction_type              transaction_type_enum   NOT NULL,
+    status                        transaction_status_enum NOT NULL DEFAULT 'pending',
+    source_wallet_identifier      UUID REFERENCES wallets (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    destination_wallet_identifier UUID REFERENCES wallets (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    amount                        NUMERIC(20, 6)          NOT NULL CHECK (amount > 0),
+    currency_identifier           UUID                    NOT NULL REFERENCES countries (identifier) ON UPDATE CASCADE,
+    description                   VARCHAR,
+    failure_reason                VARCHAR,
+    initiated_by                  UUID REFERENCES users (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    created_date                  TIMESTAMPTZ             NOT NULL DEFAULT NOW(),
+    updated_at                    TIMESTAMPTZ             NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS transactions_source_wallet_identifier_idx ON transactions (source_wallet_identifier);
+CREATE INDEX IF NOT EXISTS transactions_destination_wallet_identifier_idx ON transactions (destination_wallet_identifier);
+
+-- Attach trigger
+CREATE TRIGGER update_transactions_updated_at
+    BEFORE UPDATE
+    ON transactions
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
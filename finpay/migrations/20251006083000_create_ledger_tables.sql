@@ -0,0 +1,230 @@
+-- Add migration script here
+
+DO $$ BEGIN
+CREATE TYPE ledger_account_type_enum AS ENUM ('asset', 'liability', 'equity', 'revenue', 'expense');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+CREATE TYPE posting_direction_enum AS ENUM ('debit', 'credit');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+CREATE TABLE IF NOT EXISTS ledger_accounts
+(
+    identifier          UUID PRIMARY KEY         NOT NULL,
+    name                VARCHAR                  NOT NULL,
+    account_type        ledger_account_type_enum NOT NULL,
+    currency_identifier UUID                     NOT NULL REFERENCES countries (identifier) ON UPDATE CASCADE,
+    wallet_identifier   UUID UNIQUE REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    system_code         VARCHAR,
+    created_date        TIMESTAMPTZ              NOT NULL DEFAULT NOW(),
+    UNIQUE (system_code, currency_identifier),
+    CHECK (wallet_identifier IS NOT NULL OR system_code IS NOT NULL)
+);
+
+CREATE TABLE IF NOT EXISTS journal_entries
+(
+    identifier             UUID PRIMARY KEY NOT NULL,
+    transaction_identifier UUID REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    description            VARCHAR          NOT NULL,
+    created_date           TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS postings
+(
+    identifier                UUID PRIMARY KEY       NOT NULL,
+    journal_entry_identifier  UUID                   NOT NULL REFERENCES journal_entries (identifier) ON DELETE RESTRICT,
+    ledger_account_identifier UUID                   NOT NULL REFERENCES ledger_accounts (identifier) ON DELETE RESTRICT,
+    direction                 posting_direction_enum NOT NULL,
+    amount                    NUMERIC(20, 6)         NOT NULL CHECK (amount > 0),
+    created_date              TIMESTAMPTZ            NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS journal_entries_transaction_identifier_idx ON journal_entries (transaction_identifier);
+CREATE INDEX IF NOT EXISTS postings_journal_entry_identifier_idx ON postings (journal_entry_identifier);
+CREATE INDEX IF NOT EXISTS postings_ledger_account_identifier_idx ON postings (ledger_account_identifier);
+
+-- journal entries and postings are append-only
+CREATE
+OR REPLACE
+FUNCTION reject_ledger_mutation()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'ledger records are append-only, % on % is not allowed', TG_OP, TG_TABLE_NAME;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER journal_entries_append_only
+    BEFORE UPDATE OR DELETE
+    ON journal_entries
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_ledger_mutation();
+
+CREATE TRIGGER journal_entries_no_truncate
+    BEFORE TRUNCATE
+    ON journal_entries
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION reject_ledger_mutation();
+
+CREATE TRIGGER postings_append_only
+    BEFORE UPDATE OR DELETE
+    ON postings
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_ledger_mutation();
+
+CREATE TRIGGER postings_no_truncate
+    BEFORE TRUNCATE
+    ON postings
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION reject_ledger_mutation();
+
+-- debits and credits of an entry must cancel out per currency once the transaction commits
+CREATE
+OR REPLACE
+FUNCTION assert_journal_entry_balanced()
+RETURNS TRIGGER AS $$
+DECLARE
+    unbalanced_currency UUID;
+BEGIN
+    SELECT ledger_accounts.currency_identifier
+    INTO unbalanced_currency
+    FROM postings
+             JOIN ledger_accounts ON ledger_accounts.identifier = postings.ledger_account_identifier
+    WHERE postings.journal_entry_identifier = NEW.journal_entry_identifier
+    GROUP BY ledger_accounts.currency_identifier
+    HAVING SUM(CASE WHEN postings.direction = 'debit' THEN postings.amount ELSE -postings.amount END) <> 0
+    LIMIT 1;
+
+    IF unbalanced_currency IS NOT NULL THEN
+        RAISE EXCEPTION 'journal entry % does not balance in currency %', NEW.journal_entry_identifier, unbalanced_currency;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE CONSTRAINT TRIGGER postings_balanced
+    AFTER INSERT
+    ON postings
+    DEFERRABLE INITIALLY DEFERRED
+    FOR EACH ROW
+    EXECUTE FUNCTION assert_journal_entry_balanced();
+
+-- wallets.balance is a projection of the postings on the wallet's ledger account
+CREATE
+OR REPLACE
+FUNCTION project_wallet_balance()
+RETURNS TRIGGER AS $$
+DECLARE
+    target_wallet UUID;
+BEGIN
+    SELECT wallet_identifier INTO target_wallet FROM ledger_accounts WHERE identifier = NEW.ledger_account_identifier;
+
+    IF target_wallet IS NULL THEN
+        RETURN NEW;
+    END IF;
+
+    PERFORM set_config('finpay.ledger_projection', 'on', true);
+    UPDATE wallets
+    SET balance = balance + CASE WHEN NEW.direction = 'credit' THEN NEW.amount ELSE -NEW.amount END
+    WHERE identifier = target_wallet;
+    PERFORM set_config('finpay.ledger_projection', 'off', true);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER postings_project_wallet_balance
+    AFTER INSERT
+    ON postings
+    FOR EACH ROW
+    EXECUTE FUNCTION project_wallet_balance();
+
+CREATE
+OR REPLACE
+FUNCTION guard_wallet_balance()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF COALESCE(current_setting('finpay.ledger_projection', true), 'off') = 'on' THEN
+        RETURN NEW;
+    END IF;
+
+    IF TG_OP = 'INSERT' AND NEW.balance <> 0 THEN
+        RAISE EXCEPTION 'wallets are created with a zero balance, fund them through the ledger';
+    END IF;
+
+    IF TG_OP = 'UPDATE' AND NEW.balance IS DISTINCT FROM OLD.balance THEN
+        RAISE EXCEPTION 'wallets.balance is derived from the ledger and cannot be written directly';
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER wallets_balance_guard
+    BEFORE INSERT OR UPDATE
+    ON wallets
+    FOR EACH ROW
+    EXECUTE FUNCTION guard_wallet_balance();
+
+-- every wallet owns a liability account, the ledger owes the balance to the wallet holder
+CREATE
+OR REPLACE
+FUNCTION create_wallet_ledger_account()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO ledger_accounts (identifier, name, account_type, currency_identifier, wallet_identifier)
+    VALUES (gen_random_uuid(), 'wallet:' || NEW.identifier, 'liability', NEW.currency_identifier, NEW.identifier);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER wallets_create_ledger_account
+    AFTER INSERT
+    ON wallets
+    FOR EACH ROW
+    EXECUTE FUNCTION create_wallet_ledger_account();
+
+-- backfill accounts for existing wallets and book their current balances as opening balances
+DO $$
+DECLARE
+    wallet_row      RECORD;
+    equity_account  UUID;
+    wallet_account  UUID;
+    entry           UUID;
+BEGIN
+    FOR wallet_row IN SELECT identifier, currency_identifier, balance FROM wallets LOOP
+        wallet_account := gen_random_uuid();
+        INSERT INTO ledger_accounts (identifier, name, account_type, currency_identifier, wallet_identifier)
+        VALUES (wallet_account, 'wallet:' || wallet_row.identifier, 'liability', wallet_row.currency_identifier,
+                wallet_row.identifier);
+
+        IF wallet_row.balance <> 0 THEN
+            INSERT INTO ledger_accounts (identifier, name, account_type, currency_identifier, system_code)
+            VALUES (gen_random_uuid(), 'opening balances', 'equity', wallet_row.currency_identifier,
+                    'opening_balances')
+            ON CONFLICT (system_code, currency_identifier) DO NOTHING;
+
+            SELECT identifier
+            INTO equity_account
+            FROM ledger_accounts
+            WHERE system_code = 'opening_balances'
+              AND currency_identifier = wallet_row.currency_identifier;
+
+            -- the postings below project the balance back onto the wallet
+            PERFORM set_config('finpay.ledger_projection', 'on', true);
+            UPDATE wallets SET balance = 0 WHERE identifier = wallet_row.identifier;
+            PERFORM set_config('finpay.ledger_projection', 'off', true);
+
+            entry := gen_random_uuid();
+            INSERT INTO journal_entries (identifier, description) VALUES (entry, 'opening balance');
+            INSERT INTO postings (identifier, journal_entry_identifier, ledger_account_identifier, direction, amount)
+            VALUES (gen_random_uuid(), entry, equity_account, 'debit', wallet_row.balance),
+                   (gen_random_uuid(), entry, wallet_account, 'credit', wallet_row.balance);
+        END IF;
+    END LOOP;
+END $$;
@@ -0,0 +1,36 @@
+-- Add migration script here
+
+DO $$ BEGIN
+CREATE TYPE wallet_status_enum AS ENUM ('active', 'frozen');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+ALTER TABLE wallets
+    ADD COLUMN IF NOT EXISTS status wallet_status_enum NOT NULL DEFAULT 'active';
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS is_admin BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS reconciliation_runs
+(
+    identifier           UUID PRIMARY KEY NOT NULL,
+    wallets_checked      BIGINT           NOT NULL DEFAULT 0,
+    discrepancies_found  BIGINT           NOT NULL DEFAULT 0,
+    started_date         TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    completed_date       TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS balance_discrepancies
+(
+    identifier                     UUID PRIMARY KEY NOT NULL,
+    reconciliation_run_identifier  UUID             NOT NULL REFERENCES reconciliation_runs (identifier) ON DELETE CASCADE,
+    wallet_identifier              UUID             NOT NULL REFERENCES wallets (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    recorded_balance               NUMERIC(20, 6)   NOT NULL,
+    computed_balance               NUMERIC(20, 6)   NOT NULL,
+    difference                     NUMERIC(20, 6)   NOT NULL,
+    wallet_frozen                  BOOLEAN          NOT NULL DEFAULT FALSE,
+    created_date                   TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS balance_discrepancies_wallet_identifier_idx ON balance_discrepancies (wallet_identifier);
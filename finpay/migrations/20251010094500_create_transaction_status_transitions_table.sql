@@ -0,0 +1,27 @@
+-- Add migration script here
+ALTER TYPE transaction_status_enum ADD VALUE IF NOT EXISTS 'reversed';
+
+CREATE TABLE IF NOT EXISTS transaction_status_transitions
+(
+    identifier             UUID PRIMARY KEY        NOT NULL,
+    transaction_identifier UUID                    NOT NULL REFERENCES transactions (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    from_status            transaction_status_enum,
+    to_status              transaction_status_enum NOT NULL,
+    reason                 VARCHAR,
+    created_date           TIMESTAMPTZ             NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS transaction_status_transitions_transaction_identifier_idx
+    ON transaction_status_transitions (transaction_identifier);
+
+-- the transition history is an audit trail, it is never rewritten
+CREATE TRIGGER transaction_status_transitions_append_only
+    BEFORE UPDATE
+    ON transaction_status_transitions
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_ledger_mutation();
+
+-- record the existing transactions as having been created in their current state
+INSERT INTO transaction_status_transitions (identifier, transaction_identifier, from_status, to_status, reason, created_date)
+SELECT gen_random_uuid(), identifier, NULL, status, failure_reason, created_date
+FROM transactions;
@@ -0,0 +1,16 @@
+-- Add migration script here
+-- fallback store for idempotency keys, used whenever redis cannot be reached
+CREATE TABLE IF NOT EXISTS idempotency_keys
+(
+    identifier      UUID PRIMARY KEY NOT NULL,
+    idempotency_key VARCHAR(255)     NOT NULL,
+    user_identifier UUID             NOT NULL REFERENCES users (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    fingerprint     VARCHAR(64)      NOT NULL,
+    status_code     INTEGER,
+    response_body   TEXT,
+    created_date    TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    expires_at      TIMESTAMPTZ      NOT NULL,
+    UNIQUE (user_identifier, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idempotency_keys_expires_at_idx ON idempotency_keys (expires_at);
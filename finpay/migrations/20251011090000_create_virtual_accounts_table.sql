@@ -0,0 +1,30 @@
+-- Add migration script here
+ALTER TYPE transaction_type_enum ADD VALUE IF NOT EXISTS 'deposit';
+
+-- the reference the bank partner assigned to a movement, used to discard webhook retries
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS external_reference VARCHAR;
+
+CREATE UNIQUE INDEX IF NOT EXISTS transactions_external_reference_idx
+    ON transactions (transaction_type, external_reference)
+    WHERE external_reference IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS virtual_accounts
+(
+    identifier        UUID PRIMARY KEY NOT NULL,
+    account_number    VARCHAR(10)      NOT NULL UNIQUE,
+    account_name      VARCHAR          NOT NULL,
+    bank_identifier   UUID             NOT NULL REFERENCES banks (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    wallet_identifier UUID             NOT NULL UNIQUE REFERENCES wallets (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    user_identifier   UUID             NOT NULL REFERENCES users (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    created_date      TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    updated_at        TIMESTAMPTZ               DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS virtual_accounts_user_identifier_idx ON virtual_accounts (user_identifier);
+
+CREATE TRIGGER update_virtual_accounts_updated_at
+    BEFORE UPDATE
+    ON virtual_accounts
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,56 @@
+-- Add migration script here
+ALTER TYPE transaction_type_enum ADD VALUE IF NOT EXISTS 'withdrawal';
+
+DO $$
+BEGIN
+    CREATE TYPE payout_status_enum AS ENUM ('queued', 'processing', 'paid', 'failed');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+CREATE TABLE IF NOT EXISTS beneficiaries
+(
+    identifier      UUID PRIMARY KEY NOT NULL,
+    user_identifier UUID             NOT NULL REFERENCES users (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    bank_identifier UUID             NOT NULL REFERENCES banks (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    account_number  VARCHAR(10)      NOT NULL,
+    account_name    VARCHAR          NOT NULL,
+    created_date    TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ               DEFAULT NOW(),
+    UNIQUE (user_identifier, bank_identifier, account_number)
+);
+
+CREATE TRIGGER update_beneficiaries_updated_at
+    BEFORE UPDATE
+    ON beneficiaries
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- the destination is copied onto the payout so that removing a beneficiary keeps the record intact
+CREATE TABLE IF NOT EXISTS payouts
+(
+    identifier             UUID PRIMARY KEY   NOT NULL,
+    transaction_identifier UUID               NOT NULL UNIQUE REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    user_identifier        UUID               NOT NULL REFERENCES users (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    wallet_identifier      UUID               NOT NULL REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    beneficiary_identifier UUID REFERENCES beneficiaries (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    bank_identifier        UUID               NOT NULL REFERENCES banks (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    account_number         VARCHAR(10)        NOT NULL,
+    account_name           VARCHAR            NOT NULL,
+    amount                 NUMERIC(20, 6)     NOT NULL CHECK (amount > 0),
+    currency_identifier    UUID               NOT NULL REFERENCES countries (identifier),
+    status                 payout_status_enum NOT NULL DEFAULT 'queued',
+    provider_reference     VARCHAR,
+    failure_reason         VARCHAR,
+    created_date           TIMESTAMPTZ        NOT NULL DEFAULT NOW(),
+    updated_at             TIMESTAMPTZ        NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS payouts_user_identifier_idx ON payouts (user_identifier);
+CREATE INDEX IF NOT EXISTS payouts_queued_idx ON payouts (created_date) WHERE status = 'queued';
+
+CREATE TRIGGER update_payouts_updated_at
+    BEFORE UPDATE
+    ON payouts
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
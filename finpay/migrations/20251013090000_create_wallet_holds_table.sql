@@ -0,0 +1,38 @@
+-- Add migration script here
+ALTER TYPE transaction_type_enum ADD VALUE IF NOT EXISTS 'hold_capture';
+
+DO $$
+BEGIN
+    CREATE TYPE hold_status_enum AS ENUM ('active', 'captured', 'voided', 'expired');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- a hold reserves part of a wallet's balance without moving it, the funds stay on the wallet's
+-- ledger account until the hold is captured
+CREATE TABLE IF NOT EXISTS wallet_holds
+(
+    identifier                    UUID PRIMARY KEY NOT NULL,
+    reference                     VARCHAR          NOT NULL UNIQUE,
+    wallet_identifier             UUID             NOT NULL REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    destination_wallet_identifier UUID             NOT NULL REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    user_identifier               UUID             NOT NULL REFERENCES users (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    amount                        NUMERIC(20, 6)   NOT NULL CHECK (amount > 0),
+    captured_amount               NUMERIC(20, 6)   NOT NULL DEFAULT 0 CHECK (captured_amount >= 0 AND captured_amount <= amount),
+    currency_identifier           UUID             NOT NULL REFERENCES countries (identifier),
+    status                        hold_status_enum NOT NULL DEFAULT 'active',
+    description                   VARCHAR,
+    transaction_identifier        UUID REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    expires_at                    TIMESTAMPTZ      NOT NULL,
+    created_date                  TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    updated_at                    TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS wallet_holds_active_idx ON wallet_holds (wallet_identifier) WHERE status = 'active';
+CREATE INDEX IF NOT EXISTS wallet_holds_user_identifier_idx ON wallet_holds (user_identifier);
+
+CREATE TRIGGER update_wallet_holds_updated_at
+    BEFORE UPDATE
+    ON wallet_holds
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,19 @@
+-- Add migration script here
+
+ALTER TYPE wallet_status_enum ADD VALUE IF NOT EXISTS 'closed';
+
+DO $$ BEGIN
+CREATE TYPE wallet_freeze_origin_enum AS ENUM ('owner', 'admin');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+ALTER TABLE wallets
+    ADD COLUMN IF NOT EXISTS frozen_by   wallet_freeze_origin_enum,
+    ADD COLUMN IF NOT EXISTS closed_date TIMESTAMPTZ;
+
+-- wallets frozen before this column existed were frozen by reconciliation, only an admin may lift those
+UPDATE wallets
+SET frozen_by = 'admin'
+WHERE status = 'frozen'
+  AND frozen_by IS NULL;
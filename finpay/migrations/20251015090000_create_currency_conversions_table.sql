@@ -0,0 +1,52 @@
+-- Add migration script here
+
+ALTER TYPE transaction_type_enum ADD VALUE IF NOT EXISTS 'conversion';
+
+-- rates are quoted against a base currency, 1 unit of the base buys `rate` units of the quote
+CREATE TABLE IF NOT EXISTS exchange_rates
+(
+    identifier          UUID PRIMARY KEY NOT NULL,
+    base_currency_code  VARCHAR(10)      NOT NULL,
+    quote_currency_code VARCHAR(10)      NOT NULL,
+    rate                NUMERIC(24, 10)  NOT NULL CHECK (rate > 0),
+    source              VARCHAR          NOT NULL,
+    created_date        TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS exchange_rates_pair_created_date_idx
+    ON exchange_rates (base_currency_code, quote_currency_code, created_date DESC);
+
+-- offline rates so conversions work without a market data feed
+INSERT INTO exchange_rates (identifier, base_currency_code, quote_currency_code, rate, source)
+VALUES (gen_random_uuid(), 'USD', 'AED', 3.6725, 'seed'),
+       (gen_random_uuid(), 'USD', 'AFN', 68.5000, 'seed'),
+       (gen_random_uuid(), 'USD', 'CAD', 1.3700, 'seed'),
+       (gen_random_uuid(), 'USD', 'CNY', 7.1200, 'seed'),
+       (gen_random_uuid(), 'USD', 'EUR', 0.8600, 'seed'),
+       (gen_random_uuid(), 'USD', 'GBP', 0.7500, 'seed'),
+       (gen_random_uuid(), 'USD', 'GHS', 12.2000, 'seed'),
+       (gen_random_uuid(), 'USD', 'INR', 88.7000, 'seed'),
+       (gen_random_uuid(), 'USD', 'JPY', 151.0000, 'seed'),
+       (gen_random_uuid(), 'USD', 'KES', 129.2000, 'seed'),
+       (gen_random_uuid(), 'USD', 'NGN', 1465.0000, 'seed'),
+       (gen_random_uuid(), 'USD', 'ZAR', 17.3000, 'seed');
+
+CREATE TABLE IF NOT EXISTS currency_conversions
+(
+    identifier                      UUID PRIMARY KEY NOT NULL,
+    transaction_identifier          UUID             NOT NULL UNIQUE REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    user_identifier                 UUID             NOT NULL REFERENCES users (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    source_wallet_identifier        UUID             NOT NULL REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    destination_wallet_identifier   UUID             NOT NULL REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    source_amount                   NUMERIC(20, 6)   NOT NULL CHECK (source_amount > 0),
+    source_currency_identifier      UUID             NOT NULL REFERENCES countries (identifier),
+    destination_amount              NUMERIC(20, 6)   NOT NULL CHECK (destination_amount > 0),
+    destination_currency_identifier UUID             NOT NULL REFERENCES countries (identifier),
+    mid_rate                        NUMERIC(24, 10)  NOT NULL CHECK (mid_rate > 0),
+    rate                            NUMERIC(24, 10)  NOT NULL CHECK (rate > 0),
+    spread_basis_points             INTEGER          NOT NULL CHECK (spread_basis_points >= 0),
+    rate_source                     VARCHAR          NOT NULL,
+    created_date                    TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS currency_conversions_user_identifier_idx ON currency_conversions (user_identifier);
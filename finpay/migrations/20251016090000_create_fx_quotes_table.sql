@@ -0,0 +1,28 @@
+-- Add migration script here
+
+CREATE TABLE IF NOT EXISTS fx_quotes
+(
+    identifier                      UUID PRIMARY KEY NOT NULL,
+    user_identifier                 UUID             NOT NULL REFERENCES users (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    source_wallet_identifier        UUID             NOT NULL REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    destination_wallet_identifier   UUID             NOT NULL REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    source_currency_identifier      UUID             NOT NULL REFERENCES countries (identifier),
+    destination_currency_identifier UUID             NOT NULL REFERENCES countries (identifier),
+    source_amount                   NUMERIC(20, 6)   NOT NULL CHECK (source_amount > 0),
+    fee_amount                      NUMERIC(20, 6)   NOT NULL DEFAULT 0 CHECK (fee_amount >= 0),
+    destination_amount              NUMERIC(20, 6)   NOT NULL CHECK (destination_amount > 0),
+    mid_rate                        NUMERIC(24, 10)  NOT NULL CHECK (mid_rate > 0),
+    rate                            NUMERIC(24, 10)  NOT NULL CHECK (rate > 0),
+    spread_basis_points             INTEGER          NOT NULL CHECK (spread_basis_points >= 0),
+    rate_source                     VARCHAR          NOT NULL,
+    transaction_identifier          UUID UNIQUE REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    expires_at                      TIMESTAMPTZ      NOT NULL,
+    used_date                       TIMESTAMPTZ,
+    created_date                    TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS fx_quotes_user_identifier_idx ON fx_quotes (user_identifier);
+
+ALTER TABLE currency_conversions
+    ADD COLUMN IF NOT EXISTS quote_identifier UUID UNIQUE REFERENCES fx_quotes (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    ADD COLUMN IF NOT EXISTS fee_amount       NUMERIC(20, 6) NOT NULL DEFAULT 0;
@@ -0,0 +1,60 @@
+-- Add migration script here
+
+DO $$ BEGIN
+CREATE TYPE fee_method_enum AS ENUM ('flat', 'percentage', 'tiered');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- a schedule applies to transactions of its type whose amount falls in
+-- [minimum_amount, maximum_amount), a null currency or account type matches any
+CREATE TABLE IF NOT EXISTS fee_schedules
+(
+    identifier              UUID PRIMARY KEY      NOT NULL,
+    name                    VARCHAR(255)          NOT NULL,
+    transaction_type        transaction_type_enum NOT NULL,
+    currency_identifier     UUID REFERENCES countries (identifier) ON UPDATE CASCADE,
+    account_type            account_type_enum,
+    minimum_amount          NUMERIC(20, 6)        NOT NULL DEFAULT 0 CHECK (minimum_amount >= 0),
+    maximum_amount          NUMERIC(20, 6) CHECK (maximum_amount > minimum_amount),
+    fee_method              fee_method_enum       NOT NULL,
+    flat_amount             NUMERIC(20, 6)        NOT NULL DEFAULT 0 CHECK (flat_amount >= 0),
+    percentage_basis_points INTEGER               NOT NULL DEFAULT 0 CHECK (percentage_basis_points >= 0),
+    minimum_fee             NUMERIC(20, 6) CHECK (minimum_fee >= 0),
+    maximum_fee             NUMERIC(20, 6) CHECK (maximum_fee >= 0),
+    effective_from          TIMESTAMPTZ           NOT NULL DEFAULT NOW(),
+    effective_to            TIMESTAMPTZ CHECK (effective_to >= effective_from),
+    created_date            TIMESTAMPTZ           NOT NULL DEFAULT NOW(),
+    CHECK (maximum_fee IS NULL OR minimum_fee IS NULL OR maximum_fee >= minimum_fee)
+);
+
+CREATE INDEX IF NOT EXISTS fee_schedules_transaction_type_idx ON fee_schedules (transaction_type, effective_from);
+
+-- the tier with the highest lower bound not above the amount prices the whole amount
+CREATE TABLE IF NOT EXISTS fee_schedule_tiers
+(
+    identifier              UUID PRIMARY KEY NOT NULL,
+    fee_schedule_identifier UUID             NOT NULL REFERENCES fee_schedules (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    lower_bound             NUMERIC(20, 6)   NOT NULL CHECK (lower_bound >= 0),
+    flat_amount             NUMERIC(20, 6)   NOT NULL DEFAULT 0 CHECK (flat_amount >= 0),
+    percentage_basis_points INTEGER          NOT NULL DEFAULT 0 CHECK (percentage_basis_points >= 0),
+    UNIQUE (fee_schedule_identifier, lower_bound)
+);
+
+CREATE TABLE IF NOT EXISTS fee_charges
+(
+    identifier              UUID PRIMARY KEY NOT NULL,
+    transaction_identifier  UUID             NOT NULL UNIQUE REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    fee_schedule_identifier UUID REFERENCES fee_schedules (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    wallet_identifier       UUID             NOT NULL REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    amount                  NUMERIC(20, 6)   NOT NULL CHECK (amount > 0),
+    currency_identifier     UUID             NOT NULL REFERENCES countries (identifier) ON UPDATE CASCADE,
+    refunded_date           TIMESTAMPTZ,
+    created_date            TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS fee_charges_wallet_identifier_idx ON fee_charges (wallet_identifier);
+
+-- quotes keep the schedule their fee was priced from, later schedule changes do not reprice them
+ALTER TABLE fx_quotes
+    ADD COLUMN IF NOT EXISTS fee_schedule_identifier UUID REFERENCES fee_schedules (identifier) ON DELETE RESTRICT ON UPDATE CASCADE;
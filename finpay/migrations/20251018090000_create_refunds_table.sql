@@ -0,0 +1,22 @@
+-- Add migration script here
+
+ALTER TYPE transaction_type_enum ADD VALUE IF NOT EXISTS 'refund';
+
+-- how much of a transaction has been handed back, it is fully reversed once this reaches amount
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS refunded_amount NUMERIC(20, 6) NOT NULL DEFAULT 0,
+    ADD CONSTRAINT transactions_refunded_amount_within_amount CHECK (refunded_amount >= 0 AND refunded_amount <= amount);
+
+-- links a refund transaction to the transaction it gives money back for
+CREATE TABLE IF NOT EXISTS refunds
+(
+    identifier                      UUID PRIMARY KEY NOT NULL,
+    original_transaction_identifier UUID             NOT NULL REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    refund_transaction_identifier   UUID             NOT NULL UNIQUE REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    amount                          NUMERIC(20, 6)   NOT NULL CHECK (amount > 0),
+    reason                          VARCHAR          NOT NULL,
+    requested_by                    UUID REFERENCES users (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    created_date                    TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS refunds_original_transaction_identifier_idx ON refunds (original_transaction_identifier);
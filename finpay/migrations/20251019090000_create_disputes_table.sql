@@ -0,0 +1,49 @@
+-- Add migration script here
+
+DO $$
+BEGIN
+    CREATE TYPE dispute_status_enum AS ENUM ('open', 'under_review', 'won', 'lost');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- a dispute is raised by the payer of a transfer, winning it reverses what is left of the transfer.
+-- due_date is the deadline for the current stage, it is moved when the case is taken up for review
+CREATE TABLE IF NOT EXISTS disputes
+(
+    identifier             UUID PRIMARY KEY    NOT NULL,
+    reference              VARCHAR             NOT NULL UNIQUE,
+    transaction_identifier UUID                NOT NULL REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    user_identifier        UUID                NOT NULL REFERENCES users (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    reason                 VARCHAR             NOT NULL,
+    status                 dispute_status_enum NOT NULL DEFAULT 'open',
+    due_date               TIMESTAMPTZ         NOT NULL,
+    decision_note          VARCHAR,
+    decided_by             UUID REFERENCES users (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    decided_date           TIMESTAMPTZ,
+    refund_identifier      UUID REFERENCES refunds (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    created_date           TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    updated_at             TIMESTAMPTZ         NOT NULL DEFAULT NOW()
+);
+
+-- a transaction can only have one dispute in progress at a time
+CREATE UNIQUE INDEX IF NOT EXISTS disputes_active_transaction_idx ON disputes (transaction_identifier) WHERE status IN ('open', 'under_review');
+CREATE INDEX IF NOT EXISTS disputes_user_identifier_idx ON disputes (user_identifier);
+
+CREATE TRIGGER update_disputes_updated_at
+    BEFORE UPDATE
+    ON disputes
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE IF NOT EXISTS dispute_evidence
+(
+    identifier         UUID PRIMARY KEY NOT NULL,
+    dispute_identifier UUID             NOT NULL REFERENCES disputes (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    file_name          VARCHAR          NOT NULL,
+    url                VARCHAR          NOT NULL,
+    uploaded_by        UUID REFERENCES users (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    created_date       TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS dispute_evidence_dispute_identifier_idx ON dispute_evidence (dispute_identifier);
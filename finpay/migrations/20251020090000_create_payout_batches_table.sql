@@ -0,0 +1,66 @@
+-- Add migration script here
+
+DO $$
+BEGIN
+    CREATE TYPE payout_batch_status_enum AS ENUM ('validated', 'rejected', 'executed');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$
+BEGIN
+    CREATE TYPE payout_batch_item_status_enum AS ENUM ('valid', 'invalid', 'queued', 'failed');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- a csv of payouts uploaded by a company account. A batch with any invalid row is rejected as a
+-- whole, a validated batch waits for the user to commit it
+CREATE TABLE IF NOT EXISTS payout_batches
+(
+    identifier        UUID PRIMARY KEY         NOT NULL,
+    reference         VARCHAR                  NOT NULL UNIQUE,
+    user_identifier   UUID                     NOT NULL REFERENCES users (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    wallet_identifier UUID                     NOT NULL REFERENCES wallets (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    file_name         VARCHAR                  NOT NULL,
+    status            payout_batch_status_enum NOT NULL,
+    item_count        INTEGER                  NOT NULL,
+    total_amount      NUMERIC(20, 6)           NOT NULL DEFAULT 0,
+    result_file       VARCHAR,
+    executed_date     TIMESTAMPTZ,
+    created_date      TIMESTAMPTZ              NOT NULL DEFAULT NOW(),
+    updated_at        TIMESTAMPTZ              NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS payout_batches_user_identifier_idx ON payout_batches (user_identifier);
+
+CREATE TRIGGER update_payout_batches_updated_at
+    BEFORE UPDATE
+    ON payout_batches
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- one row of the csv, kept as uploaded so that invalid rows can be reported back
+CREATE TABLE IF NOT EXISTS payout_batch_items
+(
+    identifier        UUID PRIMARY KEY              NOT NULL,
+    batch_identifier  UUID                          NOT NULL REFERENCES payout_batches (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    row_number        INTEGER                       NOT NULL,
+    bank_identifier   UUID,
+    account_number    VARCHAR                       NOT NULL,
+    account_name      VARCHAR                       NOT NULL,
+    amount            NUMERIC(20, 6),
+    narration         VARCHAR,
+    status            payout_batch_item_status_enum NOT NULL,
+    errors            VARCHAR[]                     NOT NULL DEFAULT '{}',
+    payout_identifier UUID REFERENCES payouts (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    created_date      TIMESTAMPTZ                   NOT NULL DEFAULT NOW(),
+    updated_at        TIMESTAMPTZ                   NOT NULL DEFAULT NOW(),
+    UNIQUE (batch_identifier, row_number)
+);
+
+CREATE TRIGGER update_payout_batch_items_updated_at
+    BEFORE UPDATE
+    ON payout_batch_items
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
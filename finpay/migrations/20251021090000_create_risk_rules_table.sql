@@ -0,0 +1,87 @@
+-- Add migration script here
+
+DO $$ BEGIN
+CREATE TYPE risk_rule_type_enum AS ENUM ('amount_threshold', 'velocity', 'new_beneficiary', 'currency_pair');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+CREATE TYPE risk_action_enum AS ENUM ('allow', 'review', 'block');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+CREATE TYPE risk_review_status_enum AS ENUM ('pending', 'approved', 'rejected', 'blocked');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- rules are read on every transfer and withdrawal, so a change takes effect straight away.
+-- a null transaction type or currency matches any
+CREATE TABLE IF NOT EXISTS risk_rules
+(
+    identifier                      UUID PRIMARY KEY      NOT NULL,
+    name                            VARCHAR(255)          NOT NULL,
+    rule_type                       risk_rule_type_enum   NOT NULL,
+    transaction_type                transaction_type_enum,
+    currency_identifier             UUID REFERENCES countries (identifier) ON UPDATE CASCADE,
+    -- amount_threshold: fires at or above this amount
+    threshold_amount                NUMERIC(20, 6) CHECK (threshold_amount > 0),
+    -- velocity: fires once more than max_count movements are made within window_minutes
+    max_count                       INTEGER CHECK (max_count > 0),
+    window_minutes                  INTEGER CHECK (window_minutes > 0),
+    -- new_beneficiary: fires when the payee was first paid or saved less than this long ago
+    cooling_off_hours               INTEGER CHECK (cooling_off_hours > 0),
+    -- currency_pair: fires when currency_identifier is sent into this currency
+    destination_currency_identifier UUID REFERENCES countries (identifier) ON UPDATE CASCADE,
+    action                          risk_action_enum      NOT NULL,
+    score                           INTEGER               NOT NULL DEFAULT 0 CHECK (score >= 0),
+    enabled                         BOOLEAN               NOT NULL DEFAULT TRUE,
+    created_date                    TIMESTAMPTZ           NOT NULL DEFAULT NOW(),
+    updated_at                      TIMESTAMPTZ           NOT NULL DEFAULT NOW(),
+    CHECK (rule_type <> 'amount_threshold' OR threshold_amount IS NOT NULL),
+    CHECK (rule_type <> 'velocity' OR (max_count IS NOT NULL AND window_minutes IS NOT NULL)),
+    CHECK (rule_type <> 'new_beneficiary' OR cooling_off_hours IS NOT NULL),
+    CHECK (rule_type <> 'currency_pair' OR
+           (currency_identifier IS NOT NULL AND destination_currency_identifier IS NOT NULL))
+);
+
+CREATE TRIGGER update_risk_rules_updated_at
+    BEFORE UPDATE
+    ON risk_rules
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- every movement the rules did not allow, held ones wait as pending until an admin decides them.
+-- the request is kept as it was made so that an approved one can be carried out unchanged
+CREATE TABLE IF NOT EXISTS risk_reviews
+(
+    identifier             UUID PRIMARY KEY        NOT NULL,
+    reference              VARCHAR                 NOT NULL UNIQUE,
+    user_identifier        UUID                    NOT NULL REFERENCES users (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    transaction_type       transaction_type_enum   NOT NULL,
+    request                JSONB                   NOT NULL,
+    amount                 NUMERIC(20, 6)          NOT NULL CHECK (amount > 0),
+    currency_identifier    UUID                    NOT NULL REFERENCES countries (identifier) ON UPDATE CASCADE,
+    action                 risk_action_enum        NOT NULL,
+    score                  INTEGER                 NOT NULL,
+    triggered_rules        JSONB                   NOT NULL DEFAULT '[]',
+    status                 risk_review_status_enum NOT NULL,
+    transaction_identifier UUID REFERENCES transactions (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    decided_by             UUID REFERENCES users (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    decision_note          VARCHAR,
+    decided_date           TIMESTAMPTZ,
+    created_date           TIMESTAMPTZ             NOT NULL DEFAULT NOW(),
+    updated_at             TIMESTAMPTZ             NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS risk_reviews_status_idx ON risk_reviews (status, created_date);
+CREATE INDEX IF NOT EXISTS risk_reviews_user_identifier_idx ON risk_reviews (user_identifier);
+
+CREATE TRIGGER update_risk_reviews_updated_at
+    BEFORE UPDATE
+    ON risk_reviews
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,86 @@
+-- Add migration script here
+
+DO $$ BEGIN
+CREATE TYPE screening_subject_type_enum AS ENUM ('user', 'beneficiary');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+CREATE TYPE compliance_case_status_enum AS ENUM ('open', 'cleared', 'confirmed');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- each upload replaces the whole list, the imports themselves are kept as a history
+CREATE TABLE IF NOT EXISTS watch_list_imports
+(
+    identifier   UUID PRIMARY KEY NOT NULL,
+    file_name    VARCHAR          NOT NULL,
+    source       VARCHAR(255),
+    entry_count  INTEGER          NOT NULL DEFAULT 0,
+    uploaded_by  UUID REFERENCES users (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    created_date TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+-- aliases are loaded as entries of their own sharing the listed party's reference
+CREATE TABLE IF NOT EXISTS watch_list_entries
+(
+    identifier                   UUID PRIMARY KEY NOT NULL,
+    watch_list_import_identifier UUID             NOT NULL REFERENCES watch_list_imports (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    name                         VARCHAR          NOT NULL,
+    normalized_name              VARCHAR          NOT NULL,
+    reference                    VARCHAR
+);
+
+-- every screening is kept, clear or not. a subject is null when the action it guarded was blocked
+-- before anything was created
+CREATE TABLE IF NOT EXISTS screening_results
+(
+    identifier                   UUID PRIMARY KEY            NOT NULL,
+    subject_type                 screening_subject_type_enum NOT NULL,
+    subject_identifier           UUID,
+    user_identifier              UUID REFERENCES users (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    subject_name                 VARCHAR                     NOT NULL,
+    subject_reference            VARCHAR                     NOT NULL,
+    watch_list_import_identifier UUID REFERENCES watch_list_imports (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    matched_name                 VARCHAR,
+    matched_reference            VARCHAR,
+    score                        DOUBLE PRECISION            NOT NULL,
+    is_hit                       BOOLEAN                     NOT NULL,
+    created_date                 TIMESTAMPTZ                 NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS screening_results_subject_idx ON screening_results (subject_type, subject_reference);
+
+CREATE TABLE IF NOT EXISTS compliance_cases
+(
+    identifier                  UUID PRIMARY KEY            NOT NULL,
+    reference                   VARCHAR                     NOT NULL UNIQUE,
+    screening_result_identifier UUID                        NOT NULL REFERENCES screening_results (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    subject_type                screening_subject_type_enum NOT NULL,
+    subject_identifier          UUID,
+    user_identifier             UUID REFERENCES users (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    subject_name                VARCHAR                     NOT NULL,
+    subject_reference           VARCHAR                     NOT NULL,
+    matched_name                VARCHAR                     NOT NULL,
+    score                       DOUBLE PRECISION            NOT NULL,
+    status                      compliance_case_status_enum NOT NULL DEFAULT 'open',
+    decision_note               VARCHAR,
+    decided_by                  UUID REFERENCES users (identifier) ON DELETE SET NULL ON UPDATE CASCADE,
+    decided_date                TIMESTAMPTZ,
+    created_date                TIMESTAMPTZ                 NOT NULL DEFAULT NOW(),
+    updated_at                  TIMESTAMPTZ                 NOT NULL DEFAULT NOW()
+);
+
+-- a subject has at most one open case against the same listed name, re-screening does not pile
+-- up duplicates
+CREATE UNIQUE INDEX IF NOT EXISTS compliance_cases_one_open_per_match_idx
+    ON compliance_cases (subject_type, subject_reference, matched_name)
+    WHERE status = 'open';
+
+CREATE TRIGGER update_compliance_cases_updated_at
+    BEFORE UPDATE
+    ON compliance_cases
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,14 @@
+-- Add migration script here
+
+-- free text a user keeps against a transaction they started
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS notes VARCHAR;
+
+-- full-text search over the description and notes, queries must use the same expression for the
+-- index to be picked up
+CREATE INDEX IF NOT EXISTS transactions_search_idx ON transactions
+    USING GIN (to_tsvector('english', COALESCE(description, '') || ' ' || COALESCE(notes, '')));
+
+-- keyset pagination walks these in either direction, the identifier breaks ties between equal keys
+CREATE INDEX IF NOT EXISTS transactions_created_date_identifier_idx ON transactions (created_date, identifier);
+CREATE INDEX IF NOT EXISTS transactions_amount_identifier_idx ON transactions (amount, identifier);
@@ -0,0 +1,44 @@
+-- Add migration script here
+
+DO $$ BEGIN
+CREATE TYPE statement_format_enum AS ENUM ('csv', 'pdf');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+CREATE TYPE statement_status_enum AS ENUM ('pending', 'generating', 'ready', 'failed');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- statements are generated in the background, the balances are filled in once the file is written
+CREATE TABLE IF NOT EXISTS statements
+(
+    identifier        UUID PRIMARY KEY      NOT NULL,
+    reference         VARCHAR               NOT NULL UNIQUE,
+    user_identifier   UUID                  NOT NULL REFERENCES users (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    wallet_identifier UUID                  NOT NULL REFERENCES wallets (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    format            statement_format_enum NOT NULL,
+    period_start      TIMESTAMPTZ           NOT NULL,
+    period_end        TIMESTAMPTZ           NOT NULL,
+    status            statement_status_enum NOT NULL DEFAULT 'pending',
+    file_name         VARCHAR,
+    opening_balance   NUMERIC(20, 6),
+    closing_balance   NUMERIC(20, 6),
+    line_count        INTEGER,
+    failure_reason    VARCHAR,
+    generated_date    TIMESTAMPTZ,
+    created_date      TIMESTAMPTZ           NOT NULL DEFAULT NOW(),
+    updated_at        TIMESTAMPTZ           NOT NULL DEFAULT NOW(),
+    CHECK (period_start < period_end)
+);
+
+CREATE INDEX IF NOT EXISTS statements_user_identifier_idx ON statements (user_identifier);
+CREATE INDEX IF NOT EXISTS statements_status_idx ON statements (status);
+
+CREATE TRIGGER update_statements_updated_at
+    BEFORE UPDATE
+    ON statements
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,6 @@
+-- Add migration script here
+
+-- the standard formats accounting tools import, exported through the same statements as csv and pdf
+ALTER TYPE statement_format_enum ADD VALUE IF NOT EXISTS 'ofx';
+ALTER TYPE statement_format_enum ADD VALUE IF NOT EXISTS 'mt940';
+ALTER TYPE statement_format_enum ADD VALUE IF NOT EXISTS 'camt053';
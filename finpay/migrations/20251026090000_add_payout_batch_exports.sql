@@ -0,0 +1,10 @@
+-- Add migration script here
+
+-- a validated batch can be sent to a partner bank as an ISO 20022 pain.001 file instead of being
+-- paid out through the provider one row at a time
+ALTER TYPE payout_status_enum ADD VALUE IF NOT EXISTS 'exported';
+ALTER TYPE payout_batch_status_enum ADD VALUE IF NOT EXISTS 'exported';
+ALTER TYPE payout_batch_item_status_enum ADD VALUE IF NOT EXISTS 'exported';
+
+ALTER TABLE payout_batches
+    ADD COLUMN IF NOT EXISTS export_file VARCHAR;
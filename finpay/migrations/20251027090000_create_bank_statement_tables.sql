@@ -0,0 +1,68 @@
+-- Add migration script here
+
+DO $$ BEGIN
+CREATE TYPE bank_statement_format_enum AS ENUM ('csv', 'ofx');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+CREATE TYPE bank_statement_line_status_enum AS ENUM ('unmatched', 'suggested', 'matched', 'confirmed');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- a statement of a bank account outside finpay, uploaded to reconcile it against a wallet
+CREATE TABLE IF NOT EXISTS bank_statement_imports
+(
+    identifier        UUID PRIMARY KEY           NOT NULL,
+    reference         VARCHAR                    NOT NULL UNIQUE,
+    user_identifier   UUID                       NOT NULL REFERENCES users (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    wallet_identifier UUID                       NOT NULL REFERENCES wallets (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    file_name         VARCHAR                    NOT NULL,
+    format            bank_statement_format_enum NOT NULL,
+    line_count        INTEGER                    NOT NULL,
+    created_date      TIMESTAMPTZ                NOT NULL DEFAULT NOW(),
+    updated_at        TIMESTAMPTZ                NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS bank_statement_imports_user_identifier_idx ON bank_statement_imports (user_identifier);
+
+CREATE TRIGGER update_bank_statement_imports_updated_at
+    BEFORE UPDATE
+    ON bank_statement_imports
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- one line of an uploaded statement and the transaction it was matched to, unmatched and
+-- suggested lines make up the review queue
+CREATE TABLE IF NOT EXISTS bank_statement_lines
+(
+    identifier             UUID PRIMARY KEY                NOT NULL,
+    import_identifier      UUID                            NOT NULL REFERENCES bank_statement_imports (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    line_number            INTEGER                         NOT NULL,
+    posted_date            DATE                            NOT NULL,
+    amount                 NUMERIC(20, 6)                  NOT NULL,
+    reference              VARCHAR,
+    description            VARCHAR                         NOT NULL DEFAULT '',
+    status                 bank_statement_line_status_enum NOT NULL,
+    transaction_identifier UUID REFERENCES transactions (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    confidence             SMALLINT CHECK (confidence BETWEEN 0 AND 100),
+    reviewed_date          TIMESTAMPTZ,
+    created_date           TIMESTAMPTZ                     NOT NULL DEFAULT NOW(),
+    updated_at             TIMESTAMPTZ                     NOT NULL DEFAULT NOW(),
+    UNIQUE (import_identifier, line_number)
+);
+
+CREATE INDEX IF NOT EXISTS bank_statement_lines_status_idx ON bank_statement_lines (status);
+
+-- a transaction is reconciled against one statement line at most, suggestions do not count
+CREATE UNIQUE INDEX IF NOT EXISTS bank_statement_lines_reconciled_transaction_idx
+    ON bank_statement_lines (transaction_identifier)
+    WHERE status IN ('matched', 'confirmed');
+
+CREATE TRIGGER update_bank_statement_lines_updated_at
+    BEFORE UPDATE
+    ON bank_statement_lines
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
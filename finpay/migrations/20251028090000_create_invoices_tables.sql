@@ -0,0 +1,62 @@
+-- Add migration script here
+
+DO $$ BEGIN
+CREATE TYPE invoice_status_enum AS ENUM ('draft', 'sent', 'partially_paid', 'paid', 'overdue', 'void');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- an invoice a user bills a customer with, the totals are priced from its line items
+CREATE TABLE IF NOT EXISTS invoices
+(
+    identifier          UUID PRIMARY KEY    NOT NULL,
+    reference           VARCHAR             NOT NULL UNIQUE,
+    user_identifier     UUID                NOT NULL REFERENCES users (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    customer_name       VARCHAR             NOT NULL,
+    customer_email      VARCHAR,
+    customer_address    VARCHAR,
+    currency_identifier UUID                NOT NULL REFERENCES countries (identifier) ON UPDATE CASCADE,
+    status              invoice_status_enum NOT NULL DEFAULT 'draft',
+    issue_date          DATE                NOT NULL,
+    due_date            DATE                NOT NULL,
+    notes               VARCHAR,
+    subtotal            NUMERIC(20, 6)      NOT NULL CHECK (subtotal >= 0),
+    discount_total      NUMERIC(20, 6)      NOT NULL CHECK (discount_total >= 0),
+    tax_total           NUMERIC(20, 6)      NOT NULL CHECK (tax_total >= 0),
+    total               NUMERIC(20, 6)      NOT NULL CHECK (total >= 0),
+    amount_paid         NUMERIC(20, 6)      NOT NULL DEFAULT 0 CHECK (amount_paid >= 0 AND amount_paid <= total),
+    sent_date           TIMESTAMPTZ,
+    paid_date           TIMESTAMPTZ,
+    voided_date         TIMESTAMPTZ,
+    created_date        TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    updated_at          TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    CHECK (due_date >= issue_date)
+);
+
+CREATE INDEX IF NOT EXISTS invoices_user_identifier_idx ON invoices (user_identifier);
+CREATE INDEX IF NOT EXISTS invoices_status_due_date_idx ON invoices (status, due_date);
+
+CREATE TRIGGER update_invoices_updated_at
+    BEFORE UPDATE
+    ON invoices
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- rates are in basis points, the amounts are what the rates came to once rounded
+CREATE TABLE IF NOT EXISTS invoice_line_items
+(
+    identifier              UUID PRIMARY KEY NOT NULL,
+    invoice_identifier      UUID             NOT NULL REFERENCES invoices (identifier) ON DELETE CASCADE ON UPDATE CASCADE,
+    position                INTEGER          NOT NULL,
+    description             VARCHAR          NOT NULL,
+    quantity                NUMERIC(20, 6)   NOT NULL CHECK (quantity > 0),
+    unit_price              NUMERIC(20, 6)   NOT NULL CHECK (unit_price >= 0),
+    tax_rate_basis_points   INTEGER          NOT NULL DEFAULT 0 CHECK (tax_rate_basis_points BETWEEN 0 AND 10000),
+    discount_basis_points   INTEGER          NOT NULL DEFAULT 0 CHECK (discount_basis_points BETWEEN 0 AND 10000),
+    subtotal                NUMERIC(20, 6)   NOT NULL,
+    discount_amount         NUMERIC(20, 6)   NOT NULL,
+    tax_amount              NUMERIC(20, 6)   NOT NULL,
+    total                   NUMERIC(20, 6)   NOT NULL,
+    created_date            TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    UNIQUE (invoice_identifier, position)
+);
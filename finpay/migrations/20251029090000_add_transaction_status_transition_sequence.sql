@@ -0,0 +1,6 @@
+-- Add migration script here
+
+-- transitions written in one database transaction share the same NOW(), the sequence keeps them
+-- in the order they were recorded
+ALTER TABLE transaction_status_transitions
+    ADD COLUMN IF NOT EXISTS sequence BIGINT GENERATED ALWAYS AS IDENTITY;
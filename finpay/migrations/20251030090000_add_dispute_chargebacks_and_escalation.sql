@@ -0,0 +1,10 @@
+-- Add migration script here
+
+-- the part of a chargeback finpay paid out ahead of the payee because the payee's wallet could not
+-- cover it, in the payee wallet's currency. it stays owed by the payee
+ALTER TABLE refunds
+    ADD COLUMN IF NOT EXISTS receivable_amount NUMERIC(20, 6) NOT NULL DEFAULT 0 CHECK (receivable_amount >= 0);
+
+-- set by the sweep once an undecided case has gone past its decision deadline
+ALTER TABLE disputes
+    ADD COLUMN IF NOT EXISTS escalated_date TIMESTAMPTZ;
@@ -0,0 +1,5 @@
+-- Add migration script here
+
+-- set when compliance confirms the account holder is a listed party, nothing is paid to it after
+ALTER TABLE beneficiaries
+    ADD COLUMN IF NOT EXISTS blocked_date TIMESTAMPTZ;
@@ -0,0 +1,6 @@
+-- Add migration script here
+
+-- a pain.001 file routes each payment by the BIC of the bank it is sent to, a bank without one
+-- cannot be paid through an exported batch
+ALTER TABLE banks
+    ADD COLUMN IF NOT EXISTS bic VARCHAR(11) CHECK (bic ~ '^[A-Z]{6}[A-Z0-9]{2}([A-Z0-9]{3})?$');
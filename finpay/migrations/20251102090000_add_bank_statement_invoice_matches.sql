@@ -0,0 +1,8 @@
+-- Add migration script here
+
+-- a statement line can be the payment of one of the user's open invoices instead of a transaction
+ALTER TABLE bank_statement_lines
+    ADD COLUMN IF NOT EXISTS invoice_identifier UUID REFERENCES invoices (identifier) ON DELETE RESTRICT ON UPDATE CASCADE,
+    ADD CONSTRAINT bank_statement_lines_single_match CHECK (transaction_identifier IS NULL OR invoice_identifier IS NULL);
+
+CREATE INDEX IF NOT EXISTS bank_statement_lines_invoice_identifier_idx ON bank_statement_lines (invoice_identifier);
@@ -0,0 +1,14 @@
+-- Add migration script here
+
+-- how many decimal places the currency's minor unit has, ISO 4217 puts most currencies at two
+ALTER TABLE countries
+    ADD COLUMN IF NOT EXISTS currency_exponent SMALLINT NOT NULL DEFAULT 2 CHECK (currency_exponent BETWEEN 0 AND 4);
+
+UPDATE countries
+SET currency_exponent = 0
+WHERE currency_code IN ('BIF', 'CFA', 'CLP', 'DJF', 'GNF', 'ISK', 'JPY', 'KMF', 'KRW', 'PYG', 'RWF', 'UGX', 'VND',
+                        'VUV', 'XAF', 'XOF', 'XPF');
+
+UPDATE countries
+SET currency_exponent = 3
+WHERE currency_code IN ('BHD', 'IQD', 'JOD', 'KWD', 'LYD', 'OMR', 'TND');
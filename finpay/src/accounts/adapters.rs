@@ -1,6 +1,78 @@
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use validator::Validate;
 
 pub struct AccountCreationParams {
     pub user_identifier: Uuid,
-    pub country_identifier: Uuid,
+    pub wallet_identifier: Uuid,
+    pub bank_identifier: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVirtualAccountRequest {
+    pub wallet_identifier: Uuid,
+    /// defaults to the first partner bank operating in the wallet's country
+    pub bank_identifier: Option<Uuid>,
+}
+
+/// The notification a partner bank sends when money lands in a virtual account
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundCreditRequest {
+    #[validate(length(
+        equal = 10,
+        message = "account number must be 10 digits",
+        code = "accountNumber"
+    ))]
+    pub account_number: String,
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: BigDecimal,
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "bank reference must be between 1 and 64 characters",
+        code = "bankReference"
+    ))]
+    pub bank_reference: String,
+    #[validate(length(
+        max = 255,
+        message = "sender name cannot be more than 255 characters",
+        code = "senderName"
+    ))]
+    pub sender_name: Option<String>,
+    #[validate(length(
+        max = 255,
+        message = "narration cannot be more than 255 characters",
+        code = "narration"
+    ))]
+    pub narration: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateInboundCreditRequest {
+    #[validate(length(
+        equal = 10,
+        message = "account number must be 10 digits",
+        code = "accountNumber"
+    ))]
+    pub account_number: String,
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: BigDecimal,
+    #[validate(length(
+        max = 255,
+        message = "sender name cannot be more than 255 characters",
+        code = "senderName"
+    ))]
+    pub sender_name: Option<String>,
 }
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A bank account number issued against a wallet, money paid into it is credited to the wallet
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualAccount {
+    pub identifier: Uuid,
+    pub account_number: String,
+    pub account_name: String,
+    pub bank_identifier: Uuid,
+    pub wallet_identifier: Uuid,
+    pub user_identifier: Uuid,
+    pub created_date: DateTime<Local>,
+    pub updated_at: Option<DateTime<Local>>,
+}
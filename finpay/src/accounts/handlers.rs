@@ -0,0 +1,64 @@
+use crate::accounts::adapters::{
+    CreateVirtualAccountRequest, InboundCreditRequest, SimulateInboundCreditRequest,
+};
+use crate::accounts::entities::VirtualAccount;
+use crate::accounts::middleware::BankPartner;
+use crate::accounts::service::{AccountService, AccountServiceExt};
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::shared::middlewares::validator::ValidatedRequest;
+use crate::transactions::entities::Transaction;
+use crate::utils::{ApiResponse, AuthenticatedRequest};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+pub async fn create_account(
+    State(account_service): State<AccountService>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateVirtualAccountRequest>,
+) -> Result<ApiResponse<VirtualAccount>, ServiceError> {
+    let account = account_service.create_account(&claims, &request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(account)
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_account(
+    State(account_service): State<AccountService>,
+    claims: Claims,
+    Path(account_identifier): Path<Uuid>,
+) -> Result<ApiResponse<VirtualAccount>, ServiceError> {
+    let account = account_service
+        .fetch_account(&claims, &account_identifier)
+        .await?;
+
+    Ok(ApiResponse::builder().data(account).build())
+}
+
+pub async fn receive_inbound_credit(
+    State(account_service): State<AccountService>,
+    _: BankPartner,
+    ValidatedRequest(request): ValidatedRequest<InboundCreditRequest>,
+) -> Result<ApiResponse<Transaction>, ServiceError> {
+    let transaction = account_service.receive_inbound_credit(&request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(transaction)
+        .message("inbound credit received")
+        .build())
+}
+
+pub async fn simulate_inbound_credit(
+    State(account_service): State<AccountService>,
+    AuthenticatedRequest { request, .. }: AuthenticatedRequest<SimulateInboundCreditRequest>,
+) -> Result<ApiResponse<Transaction>, ServiceError> {
+    let transaction = account_service.simulate_inbound_credit(&request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(transaction)
+        .message("simulated deposit credited")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
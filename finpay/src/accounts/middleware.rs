@@ -0,0 +1,44 @@
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use finpay_utils::extract_env;
+
+use crate::errors::AuthenticationError;
+
+pub const BANK_WEBHOOK_SECRET_HEADER: &str = "X-Webhook-Secret";
+
+/// Guards the endpoints a partner bank calls, the shared secret must match `BANK_WEBHOOK_SECRET`
+pub struct BankPartner;
+
+impl<S> FromRequestParts<S> for BankPartner
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthenticationError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let expected_secret: String = extract_env("BANK_WEBHOOK_SECRET");
+        let provided_secret = parts
+            .headers
+            .get(BANK_WEBHOOK_SECRET_HEADER)
+            .ok_or(AuthenticationError::MissingCredentials)?
+            .as_bytes();
+
+        if expected_secret.is_empty()
+            || !constant_time_eq(expected_secret.as_bytes(), provided_secret)
+        {
+            log::warn!("rejected bank webhook with an invalid secret");
+            return Err(AuthenticationError::InvalidToken);
+        }
+
+        Ok(BankPartner)
+    }
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
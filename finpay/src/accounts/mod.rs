@@ -1,6 +1,7 @@
+pub mod adapters;
+pub mod entities;
 pub mod handlers;
 pub mod middleware;
 pub mod repository;
-pub mod service;
 pub mod router;
-mod adapters;
\ No newline at end of file
+pub mod service;
@@ -1,8 +1,23 @@
-use crate::accounts::adapters::AccountCreationParams;
-use crate::errors::ServiceError;
+use crate::accounts::adapters::{AccountCreationParams, InboundCreditRequest};
+use crate::accounts::entities::VirtualAccount;
+use crate::banks::entities::Bank;
+use crate::errors::RepositoryError;
+use crate::ledger::adapters::NewJournalEntry;
+use crate::ledger::entities::SystemAccount;
+use crate::ledger::repository::LedgerRepository;
+use crate::transactions::adapters::NewTransaction;
+use crate::transactions::entities::{Transaction, TransactionStatus, TransactionType};
+use crate::transactions::repository::TransactionRepository;
+use crate::users::entities::User;
+use crate::wallet::entities::{Wallet, WalletStatus};
+use finpay_utils::generate_account_number;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+/// how many times to draw a fresh account number when the drawn one is already taken
+const ACCOUNT_NUMBER_ATTEMPTS: usize = 5;
+
+#[derive(Clone)]
 pub struct AccountRepository {
     pub pool: PgPool,
 }
@@ -11,27 +26,366 @@ impl AccountRepository {
     pub fn new(pool: &PgPool) -> AccountRepository {
         Self { pool: pool.clone() }
     }
+
+    async fn fetch_delivered(
+        &self,
+        bank_reference: &str,
+    ) -> Result<Option<Transaction>, RepositoryError> {
+        sqlx::query_as::<_, Transaction>(
+            r#"SELECT * FROM transactions WHERE transaction_type = $1 AND external_reference = $2"#,
+        )
+        .bind(TransactionType::Deposit)
+        .bind(bank_reference)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
 }
 
 pub trait AccountRepositoryExt {
+    fn create_account(
+        &self,
+        params: &AccountCreationParams,
+    ) -> impl std::future::Future<Output = Result<VirtualAccount, RepositoryError>> + Send;
+
+    fn fetch_account(
+        &self,
+        account_identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<VirtualAccount>, RepositoryError>> + Send;
+
+    fn credit_inbound(
+        &self,
+        payload: &InboundCreditRequest,
+    ) -> impl std::future::Future<Output = Result<Transaction, RepositoryError>> + Send;
+}
+
+impl AccountRepositoryExt for AccountRepository {
     async fn create_account(
         &self,
         params: &AccountCreationParams,
-    ) -> Result<Uuid, ServiceError>
+    ) -> Result<VirtualAccount, RepositoryError> {
+        let wallet = sqlx::query_as::<_, Wallet>(
+            r#"SELECT * FROM wallets WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(params.wallet_identifier)
+        .bind(params.user_identifier)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+        if wallet.status == WalletStatus::Closed {
+            return Err(RepositoryError::InactiveWallet);
+        }
+
+        // a wallet only ever has one account number, asking again hands back the same one
+        let existing = sqlx::query_as::<_, VirtualAccount>(
+            r#"SELECT * FROM virtual_accounts WHERE wallet_identifier = $1"#,
+        )
+        .bind(wallet.identifier)
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some(existing) = existing {
+            return Ok(existing);
+        }
+
+        // wallets are denominated by country, so the bank has to operate in that country
+        let bank = sqlx::query_as::<_, Bank>(
+            r#"
+            SELECT *
+            FROM banks
+            WHERE country_identifier = $1
+              AND ($2::UUID IS NULL OR identifier = $2)
+            ORDER BY bank_name
+            LIMIT 1
+            "#,
+        )
+        .bind(wallet.currency_identifier)
+        .bind(params.bank_identifier)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(RepositoryError::OperationFailed(
+            "no partner bank operating in the wallet's country".into(),
+        ))?;
+
+        let user = sqlx::query_as::<_, User>(r#"SELECT * FROM users WHERE identifier = $1"#)
+            .bind(params.user_identifier)
+            .fetch_one(&self.pool)
+            .await?;
+        let account_name = format!("Finpay/{} {}", user.first_name, user.last_name);
+
+        let query = r#"
+        INSERT INTO virtual_accounts (identifier, account_number, account_name, bank_identifier, wallet_identifier, user_identifier)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (wallet_identifier) DO NOTHING
+        RETURNING *
+        "#;
+        for _ in 0..ACCOUNT_NUMBER_ATTEMPTS {
+            let result = sqlx::query_as::<_, VirtualAccount>(query)
+                .bind(Uuid::new_v4())
+                .bind(generate_account_number())
+                .bind(&account_name)
+                .bind(bank.identifier)
+                .bind(wallet.identifier)
+                .bind(params.user_identifier)
+                .fetch_optional(&self.pool)
+                .await;
+
+            match result {
+                Ok(Some(account)) => return Ok(account),
+                // a concurrent request issued the wallet its number first, hand back that one
+                Ok(None) => {
+                    return sqlx::query_as::<_, VirtualAccount>(
+                        r#"SELECT * FROM virtual_accounts WHERE wallet_identifier = $1"#,
+                    )
+                    .bind(wallet.identifier)
+                    .fetch_one(&self.pool)
+                    .await
+                    .map_err(RepositoryError::from);
+                }
+                Err(sqlx::Error::Database(err))
+                    if err.constraint() == Some("virtual_accounts_account_number_key") =>
+                {
+                    continue;
+                }
+                Err(err) => return Err(RepositoryError::from(err)),
+            }
+        }
+
+        Err(RepositoryError::OperationFailed(
+            "could not allocate a unique account number".into(),
+        ))
+    }
 
     async fn fetch_account(
         &self,
         account_identifier: &Uuid,
-    ) -> Result<Uuid, ServiceError>;
+        user_identifier: &Uuid,
+    ) -> Result<Option<VirtualAccount>, RepositoryError> {
+        sqlx::query_as::<_, VirtualAccount>(
+            r#"SELECT * FROM virtual_accounts WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(account_identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn credit_inbound(
+        &self,
+        payload: &InboundCreditRequest,
+    ) -> Result<Transaction, RepositoryError> {
+        // banks redeliver webhooks, a reference we have already booked is acknowledged as is
+        if let Some(delivered) = self.fetch_delivered(&payload.bank_reference).await? {
+            return Ok(delivered);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let wallet = sqlx::query_as::<_, Wallet>(
+            r#"
+            SELECT wallets.*
+            FROM wallets
+                     JOIN virtual_accounts ON virtual_accounts.wallet_identifier = wallets.identifier
+            WHERE virtual_accounts.account_number = $1
+            FOR UPDATE OF wallets
+            "#,
+        )
+        .bind(&payload.account_number)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+
+        // turned away without booking the reference, so the bank returns the funds or
+        // redelivers once the wallet can take them
+        if wallet.status != WalletStatus::Active {
+            return Err(RepositoryError::InactiveWallet);
+        }
+
+        let description = match &payload.sender_name {
+            Some(sender_name) => Some(format!("deposit from {sender_name}")),
+            None => payload.narration.clone(),
+        };
+        let transaction = TransactionRepository::open_once(
+            &mut tx,
+            &NewTransaction {
+                transaction_type: TransactionType::Deposit,
+                source_wallet_identifier: None,
+                destination_wallet_identifier: Some(wallet.identifier),
+                amount: payload.amount.clone(),
+                currency_identifier: wallet.currency_identifier,
+                description,
+                initiated_by: None,
+                external_reference: Some(payload.bank_reference.clone()),
+            },
+        )
+        .await?;
+        let Some(mut transaction) = transaction else {
+            // a concurrent delivery of the same reference got there first
+            tx.rollback().await?;
+            return self
+                .fetch_delivered(&payload.bank_reference)
+                .await?
+                .ok_or(RepositoryError::DuplicateRecord);
+        };
+
+        let settlement_account = LedgerRepository::system_account(
+            &mut tx,
+            SystemAccount::BankSettlement,
+            &wallet.currency_identifier,
+        )
+        .await?;
+        let wallet_account = LedgerRepository::wallet_account(&mut tx, &wallet.identifier).await?;
+
+        let entry = NewJournalEntry::new(&format!("deposit {}", transaction.reference))
+            .transaction(&transaction.identifier)
+            .debit(&settlement_account, &payload.amount)
+            .credit(&wallet_account, &payload.amount);
+        LedgerRepository::record_entry(&mut tx, &entry).await?;
+
+        TransactionRepository::transition(
+            &mut tx,
+            &mut transaction,
+            TransactionStatus::Completed,
+            None,
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(transaction)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::fixtures::{balance, create_user, create_wallet};
+    use crate::wallet::entities::WalletFreezeOrigin;
+    use crate::wallet::repository::{WalletRepository, WalletRepositoryExt};
+    use bigdecimal::BigDecimal;
 
-impl AccountRepositoryExt for AccountRepository {
-    async fn create_account(&self, params: &AccountCreationParams) -> Result<Uuid, ServiceError> {
-        todo!()
+    async fn create_account(pool: &PgPool) -> (Uuid, VirtualAccount) {
+        let user_identifier = create_user(pool).await;
+        let wallet_identifier = create_wallet(pool, &user_identifier).await;
+        let account = AccountRepository::new(pool)
+            .create_account(&AccountCreationParams {
+                user_identifier,
+                wallet_identifier,
+                bank_identifier: None,
+            })
+            .await
+            .unwrap();
+
+        (wallet_identifier, account)
+    }
+
+    fn inbound_credit(account: &VirtualAccount, bank_reference: &str) -> InboundCreditRequest {
+        InboundCreditRequest {
+            account_number: account.account_number.clone(),
+            amount: BigDecimal::from(250),
+            bank_reference: bank_reference.to_string(),
+            sender_name: None,
+            narration: None,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_create_account_is_issued_once_per_wallet(pool: PgPool) {
+        let repository = AccountRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let wallet_identifier = create_wallet(&pool, &user_identifier).await;
+        let params = AccountCreationParams {
+            user_identifier,
+            wallet_identifier,
+            bank_identifier: None,
+        };
+
+        let account = repository.create_account(&params).await.unwrap();
+        let again = repository.create_account(&params).await.unwrap();
+
+        assert_eq!(account.account_number.len(), 10);
+        assert_eq!(account.identifier, again.identifier);
     }
 
-    async fn fetch_account(&self, account_identifier: &Uuid) -> Result<Uuid, ServiceError> {
-        todo!()
+    #[sqlx::test]
+    async fn test_inbound_credit_funds_wallet_once(pool: PgPool) {
+        let repository = AccountRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let wallet_identifier = create_wallet(&pool, &user_identifier).await;
+        let account = repository
+            .create_account(&AccountCreationParams {
+                user_identifier,
+                wallet_identifier,
+                bank_identifier: None,
+            })
+            .await
+            .unwrap();
+
+        let payload = InboundCreditRequest {
+            account_number: account.account_number,
+            amount: BigDecimal::from(250),
+            bank_reference: "BANK-REF-1".to_string(),
+            sender_name: None,
+            narration: None,
+        };
+        let first = repository.credit_inbound(&payload).await.unwrap();
+        let redelivered = repository.credit_inbound(&payload).await.unwrap();
+
+        assert_eq!(first.status, TransactionStatus::Completed);
+        assert_eq!(first.identifier, redelivered.identifier);
+
+        let wallet = WalletRepository::new(pool.clone())
+            .fetch_wallet(&wallet_identifier, &user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(wallet.wallet.balance, BigDecimal::from(250));
     }
-}
\ No newline at end of file
+
+    #[sqlx::test]
+    async fn test_inbound_credit_to_a_frozen_wallet_can_be_redelivered(pool: PgPool) {
+        let repository = AccountRepository::new(&pool);
+        let (wallet_identifier, account) = create_account(&pool).await;
+        let wallet_repository = WalletRepository::new(pool.clone());
+        wallet_repository
+            .freeze(&wallet_identifier, None, WalletFreezeOrigin::Admin)
+            .await
+            .unwrap();
+
+        let payload = inbound_credit(&account, "BANK-REF-2");
+        assert!(matches!(
+            repository.credit_inbound(&payload).await,
+            Err(RepositoryError::InactiveWallet)
+        ));
+        // the reference was not used up, so the redelivery after the wallet is unfrozen lands
+        wallet_repository
+            .unfreeze(&wallet_identifier, None, WalletFreezeOrigin::Admin)
+            .await
+            .unwrap();
+        let redelivered = repository.credit_inbound(&payload).await.unwrap();
+
+        assert_eq!(redelivered.status, TransactionStatus::Completed);
+        assert_eq!(
+            balance(&pool, &wallet_identifier).await,
+            BigDecimal::from(250)
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_concurrent_deliveries_credit_once(pool: PgPool) {
+        let repository = AccountRepository::new(&pool);
+        let (wallet_identifier, account) = create_account(&pool).await;
+        let payload = inbound_credit(&account, "BANK-REF-3");
+
+        let (first, second) = tokio::join!(
+            repository.credit_inbound(&payload),
+            repository.credit_inbound(&payload)
+        );
+
+        assert_eq!(first.unwrap().identifier, second.unwrap().identifier);
+        assert_eq!(
+            balance(&pool, &wallet_identifier).await,
+            BigDecimal::from(250)
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{
+    accounts::handlers::{
+        create_account, fetch_account, receive_inbound_credit, simulate_inbound_credit,
+    },
+    state::AppState,
+};
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use finpay_utils::extract_env;
+
+pub fn account_routes(state: &AppState) -> Router {
+    let router = Router::new()
+        .route("/", post(create_account))
+        .route("/{account_identifier}", get(fetch_account))
+        .route("/inbound-credits", post(receive_inbound_credit));
+
+    // lets deposits be fired locally without a bank partner, never exposed outside dev
+    let environment: String = extract_env("ENVIRONMENT");
+    let router = if environment == "dev" {
+        router.route("/simulator/inbound-credits", post(simulate_inbound_credit))
+    } else {
+        router
+    };
+
+    router.with_state(state.clone())
+}
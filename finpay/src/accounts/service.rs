@@ -1,5 +1,106 @@
-use crate::accounts::repository::AccountRepository;
+use crate::accounts::adapters::{
+    AccountCreationParams, CreateVirtualAccountRequest, InboundCreditRequest,
+    SimulateInboundCreditRequest,
+};
+use crate::accounts::entities::VirtualAccount;
+use crate::accounts::repository::{AccountRepository, AccountRepositoryExt};
+use crate::authentication::claims::Claims;
+use crate::errors::{RepositoryError, ServiceError};
+use crate::transactions::entities::Transaction;
+use finpay_utils::generate_reference;
+use sqlx::PgPool;
+use uuid::Uuid;
 
+#[derive(Clone)]
 pub struct AccountService {
-    pub repository : AccountRepository
-}
\ No newline at end of file
+    pub repository: AccountRepository,
+}
+
+impl AccountService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: AccountRepository::new(pool),
+        }
+    }
+}
+
+pub trait AccountServiceExt {
+    fn create_account(
+        &self,
+        claims: &Claims,
+        request: &CreateVirtualAccountRequest,
+    ) -> impl std::future::Future<Output = Result<VirtualAccount, ServiceError>> + Send;
+
+    fn fetch_account(
+        &self,
+        claims: &Claims,
+        account_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<VirtualAccount, ServiceError>> + Send;
+
+    fn receive_inbound_credit(
+        &self,
+        request: &InboundCreditRequest,
+    ) -> impl std::future::Future<Output = Result<Transaction, ServiceError>> + Send;
+
+    fn simulate_inbound_credit(
+        &self,
+        request: &SimulateInboundCreditRequest,
+    ) -> impl std::future::Future<Output = Result<Transaction, ServiceError>> + Send;
+}
+
+impl AccountServiceExt for AccountService {
+    async fn create_account(
+        &self,
+        claims: &Claims,
+        request: &CreateVirtualAccountRequest,
+    ) -> Result<VirtualAccount, ServiceError> {
+        let params = AccountCreationParams {
+            user_identifier: claims.user_identifier,
+            wallet_identifier: request.wallet_identifier,
+            bank_identifier: request.bank_identifier,
+        };
+        let account = self.repository.create_account(&params).await?;
+        Ok(account)
+    }
+
+    async fn fetch_account(
+        &self,
+        claims: &Claims,
+        account_identifier: &Uuid,
+    ) -> Result<VirtualAccount, ServiceError> {
+        let account = self
+            .repository
+            .fetch_account(account_identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(account)
+    }
+
+    async fn receive_inbound_credit(
+        &self,
+        request: &InboundCreditRequest,
+    ) -> Result<Transaction, ServiceError> {
+        let transaction = self.repository.credit_inbound(request).await?;
+        log::info!(
+            "credited {} to wallet {:?} for bank reference {}",
+            transaction.amount,
+            transaction.destination_wallet_identifier,
+            request.bank_reference
+        );
+        Ok(transaction)
+    }
+
+    async fn simulate_inbound_credit(
+        &self,
+        request: &SimulateInboundCreditRequest,
+    ) -> Result<Transaction, ServiceError> {
+        let inbound_credit = InboundCreditRequest {
+            account_number: request.account_number.clone(),
+            amount: request.amount.clone(),
+            bank_reference: generate_reference("SIM"),
+            sender_name: request.sender_name.clone(),
+            narration: Some("simulated deposit".to_string()),
+        };
+        self.receive_inbound_credit(&inbound_credit).await
+    }
+}
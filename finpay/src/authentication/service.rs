@@ -18,6 +18,9 @@ use crate::authentication::adapter::{
 };
 use crate::authentication::adapter::{LoginRequest, UploadProfilePictureRequest};
 use crate::authentication::claims::{Claims, TWENTY_FIVE_MINUTES};
+use crate::compliance::adapters::ScreeningSubject;
+use crate::compliance::entities::ScreeningSubjectType;
+use crate::compliance::service::{ComplianceService, ComplianceServiceExt};
 use crate::config::AppConfig;
 use crate::errors::AuthenticationError::{InvalidOtp, Unauthenticated};
 use crate::errors::RepositoryError::DuplicateRecord;
@@ -36,13 +39,19 @@ use crate::errors::AuthenticationError::WrongCredentials;
 pub struct AuthenticationService {
     user_service: UsersService,
     otp_service: OtpService,
+    compliance_service: ComplianceService,
 }
 
 impl AuthenticationService {
-    pub fn new(user_service: UsersService, otp_service: OtpService) -> Self {
+    pub fn new(
+        user_service: UsersService,
+        otp_service: OtpService,
+        compliance_service: ComplianceService,
+    ) -> Self {
         Self {
             user_service,
             otp_service,
+            compliance_service,
         }
     }
 
@@ -137,7 +146,22 @@ impl AuthenticationServiceExt for AuthenticationService {
         {
             return Err(ServiceError::RepositoryError(DuplicateRecord));
         }
+
+        let mut subject = ScreeningSubject {
+            subject_type: ScreeningSubjectType::User,
+            subject_identifier: None,
+            user_identifier: None,
+            name: format!("{} {}", payload.first_name, payload.last_name),
+            reference: payload.email.clone(),
+        };
+        let screening = self.compliance_service.guard(&subject).await?;
+
         let user_identifier = self.user_service.create_account(payload).await?;
+        subject.subject_identifier = Some(user_identifier);
+        subject.user_identifier = Some(user_identifier);
+        self.compliance_service
+            .record_screening(&subject, &screening)
+            .await?;
 
         let user = self.user_service.find_user_by_pk(&user_identifier).await?;
 
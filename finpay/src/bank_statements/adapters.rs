@@ -0,0 +1,36 @@
+use axum_typed_multipart::{FieldData, TryFromMultipart};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+use uuid::Uuid;
+use validator::Validate;
+
+/// A bank statement as a csv with date, description and either an amount or debit and credit
+/// columns, plus an optional reference column. OFX statements are read from their transactions
+#[derive(TryFromMultipart)]
+#[try_from_multipart(rename_all = "camelCase")]
+pub struct ImportBankStatementRequest {
+    pub wallet_identifier: String,
+    #[form_data(limit = "5MiB")]
+    pub file: FieldData<NamedTempFile>,
+}
+
+/// One line of an uploaded statement as it was read
+#[derive(Debug, Clone)]
+pub struct NewBankStatementLine {
+    pub line_number: i32,
+    pub posted_date: NaiveDate,
+    pub amount: BigDecimal,
+    pub reference: Option<String>,
+    pub description: String,
+}
+
+/// Confirms the match on a line, or matches it by hand to another transaction on the wallet or
+/// to an open invoice the line pays. At most one of the two can be given
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmBankStatementLineRequest {
+    pub transaction_identifier: Option<Uuid>,
+    pub invoice_identifier: Option<Uuid>,
+}
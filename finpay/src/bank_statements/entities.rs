@@ -0,0 +1,93 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "bank_statement_format_enum")]
+pub enum BankStatementFormat {
+    Csv,
+    Ofx,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(
+    rename_all = "snake_case",
+    type_name = "bank_statement_line_status_enum"
+)]
+pub enum BankStatementLineStatus {
+    /// no transaction or invoice looks like the line, it waits in the review queue
+    Unmatched,
+    /// a transaction or invoice might be the line but not surely enough, or the line would pay
+    /// an invoice, it waits in the review queue
+    Suggested,
+    /// matched to a transaction on import with enough confidence to need no review
+    Matched,
+    /// a match the user confirmed or made by hand, an invoice is paid when its line gets here
+    Confirmed,
+}
+
+/// A statement of a bank account outside finpay, reconciled against one of the user's wallets
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BankStatementImport {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub user_identifier: Uuid,
+    pub wallet_identifier: Uuid,
+    pub file_name: String,
+    pub format: BankStatementFormat,
+    pub line_count: i32,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+    #[sqlx(skip)]
+    pub lines: Vec<BankStatementLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BankStatementLine {
+    pub identifier: Uuid,
+    pub import_identifier: Uuid,
+    /// the line in the file, a csv header is line 1
+    pub line_number: i32,
+    pub posted_date: NaiveDate,
+    /// money into the bank account is positive, money out of it negative
+    pub amount: BigDecimal,
+    pub reference: Option<String>,
+    pub description: String,
+    pub status: BankStatementLineStatus,
+    pub transaction_identifier: Option<Uuid>,
+    /// the invoice the line pays, a line is matched to a transaction or an invoice but not both
+    pub invoice_identifier: Option<Uuid>,
+    /// how sure the match is out of 100, left empty for matches made by hand
+    pub confidence: Option<i16>,
+    pub reviewed_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+/// A completed transaction on the wallet that no statement line has been reconciled against yet
+#[derive(Debug, FromRow)]
+pub struct MatchCandidate {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub external_reference: Option<String>,
+    /// signed like a statement line, money into the wallet is positive and money out negative
+    pub amount: BigDecimal,
+    pub created_date: DateTime<Local>,
+}
+
+/// A sent invoice in the wallet's currency with money still owed on it
+#[derive(Debug, FromRow)]
+pub struct InvoiceCandidate {
+    pub identifier: Uuid,
+    pub reference: String,
+    /// what is left to pay, the total less what has been paid
+    pub outstanding: BigDecimal,
+    pub issue_date: NaiveDate,
+    pub due_date: NaiveDate,
+}
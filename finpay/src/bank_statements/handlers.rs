@@ -0,0 +1,94 @@
+use crate::authentication::claims::Claims;
+use crate::bank_statements::adapters::{
+    ConfirmBankStatementLineRequest, ImportBankStatementRequest,
+};
+use crate::bank_statements::entities::{BankStatementImport, BankStatementLine};
+use crate::bank_statements::service::{BankStatementService, BankStatementServiceExt};
+use crate::errors::ServiceError;
+use crate::utils::{ApiResponse, AuthenticatedRequest, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum_typed_multipart::TypedMultipart;
+use uuid::Uuid;
+
+pub async fn import_bank_statement(
+    State(bank_statement_service): State<BankStatementService>,
+    claims: Claims,
+    request: TypedMultipart<ImportBankStatementRequest>,
+) -> Result<ApiResponse<BankStatementImport>, ServiceError> {
+    let import = bank_statement_service
+        .import_statement(&claims, request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(import)
+        .message("bank statement imported successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_bank_statements(
+    State(bank_statement_service): State<BankStatementService>,
+    claims: Claims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<BankStatementImport>>, ServiceError> {
+    let imports = bank_statement_service
+        .fetch_imports(&claims, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(imports).build())
+}
+
+pub async fn fetch_bank_statement(
+    State(bank_statement_service): State<BankStatementService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<BankStatementImport>, ServiceError> {
+    let import = bank_statement_service
+        .fetch_import(&claims, &identifier)
+        .await?;
+
+    Ok(ApiResponse::builder().data(import).build())
+}
+
+pub async fn fetch_review_queue(
+    State(bank_statement_service): State<BankStatementService>,
+    claims: Claims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<BankStatementLine>>, ServiceError> {
+    let lines = bank_statement_service
+        .fetch_review_queue(&claims, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(lines).build())
+}
+
+pub async fn confirm_bank_statement_line(
+    State(bank_statement_service): State<BankStatementService>,
+    Path(identifier): Path<Uuid>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<ConfirmBankStatementLineRequest>,
+) -> Result<ApiResponse<BankStatementLine>, ServiceError> {
+    let line = bank_statement_service
+        .confirm_line(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(line)
+        .message("statement line reconciled successfully")
+        .build())
+}
+
+pub async fn reject_bank_statement_line(
+    State(bank_statement_service): State<BankStatementService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<BankStatementLine>, ServiceError> {
+    let line = bank_statement_service
+        .reject_line(&claims, &identifier)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(line)
+        .message("statement line match rejected, it is back in the review queue")
+        .build())
+}
@@ -0,0 +1,352 @@
+//! Scores how likely each transaction on a wallet is to be a line of an uploaded bank statement,
+//! from the amount, how close the dates are and whether the line mentions the transaction's
+//! reference. Money coming in can also be the payment of one of the user's open invoices
+
+use crate::bank_statements::adapters::NewBankStatementLine;
+use crate::bank_statements::entities::{BankStatementLineStatus, InvoiceCandidate, MatchCandidate};
+use bigdecimal::{BigDecimal, Zero};
+use uuid::Uuid;
+
+/// how many days either side of a line's date a transaction can fall and still match it
+pub const MATCH_WINDOW_IN_DAYS: i64 = 3;
+
+/// what the amount, the reference and a same day date each add to a match's confidence
+const AMOUNT_SCORE: i64 = 50;
+const REFERENCE_SCORE: i64 = 40;
+const DATE_SCORE: i64 = 10;
+
+/// a line is matched outright from this confidence and suggested for review from the lower one
+const MATCH_THRESHOLD: i16 = 90;
+const SUGGESTION_THRESHOLD: i16 = 40;
+
+/// What a line is matched to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchTarget {
+    Transaction(Uuid),
+    /// the line pays the invoice once the match is confirmed
+    Invoice(Uuid),
+}
+
+impl MatchTarget {
+    /// The target a stored line is matched to, if any
+    pub fn of(
+        transaction_identifier: Option<Uuid>,
+        invoice_identifier: Option<Uuid>,
+    ) -> Option<Self> {
+        transaction_identifier
+            .map(Self::Transaction)
+            .or(invoice_identifier.map(Self::Invoice))
+    }
+
+    pub fn transaction_identifier(&self) -> Option<Uuid> {
+        match self {
+            Self::Transaction(identifier) => Some(*identifier),
+            Self::Invoice(_) => None,
+        }
+    }
+
+    pub fn invoice_identifier(&self) -> Option<Uuid> {
+        match self {
+            Self::Transaction(_) => None,
+            Self::Invoice(identifier) => Some(*identifier),
+        }
+    }
+}
+
+/// The transaction or invoice picked for a line
+#[derive(Debug, PartialEq, Eq)]
+pub struct LineMatch {
+    pub target: MatchTarget,
+    pub confidence: i16,
+    pub status: BankStatementLineStatus,
+}
+
+/// Letters and digits only, in capitals, so `TRF-ABC` on a transaction is found as `trf abc` or
+/// `TRFABC` on a bank narration
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn mentions_reference(line: &NewBankStatementLine, references: &[Option<&str>]) -> bool {
+    let text = normalize(&format!(
+        "{} {}",
+        line.reference.as_deref().unwrap_or_default(),
+        line.description
+    ));
+    references
+        .iter()
+        .flatten()
+        .map(|reference| normalize(reference))
+        // too short a reference would be found in unrelated narrations
+        .any(|reference| reference.len() >= 6 && text.contains(&reference))
+}
+
+/// How sure it is that a transaction is the line, out of 100. A transaction outside the date
+/// window, or with neither the amount nor the reference of the line, is no match at all. The
+/// amounts are compared signed, money out of the bank account only matches money out of the
+/// wallet
+pub fn score(line: &NewBankStatementLine, candidate: &MatchCandidate) -> Option<i16> {
+    let days_apart = (candidate.created_date.date_naive() - line.posted_date)
+        .num_days()
+        .abs();
+    if days_apart > MATCH_WINDOW_IN_DAYS {
+        return None;
+    }
+
+    let amount_matches = line.amount == candidate.amount;
+    let reference_matches = mentions_reference(
+        line,
+        &[
+            Some(candidate.reference.as_str()),
+            candidate.external_reference.as_deref(),
+        ],
+    );
+    if !amount_matches && !reference_matches {
+        return None;
+    }
+
+    let mut confidence =
+        DATE_SCORE * (MATCH_WINDOW_IN_DAYS + 1 - days_apart) / (MATCH_WINDOW_IN_DAYS + 1);
+    if amount_matches {
+        confidence += AMOUNT_SCORE;
+    }
+    if reference_matches {
+        confidence += REFERENCE_SCORE;
+    }
+    Some(confidence as i16)
+}
+
+/// How sure it is that the line pays an invoice, out of 100. Only money in, on or after the
+/// issue date and no more than what is owed can pay an invoice. The amount counts when it
+/// settles the invoice and the date when it came in by the due date
+pub fn score_invoice(line: &NewBankStatementLine, invoice: &InvoiceCandidate) -> Option<i16> {
+    if line.amount <= BigDecimal::zero()
+        || line.amount > invoice.outstanding
+        || line.posted_date < invoice.issue_date
+    {
+        return None;
+    }
+
+    let amount_matches = line.amount == invoice.outstanding;
+    let reference_matches = mentions_reference(line, &[Some(invoice.reference.as_str())]);
+    if !amount_matches && !reference_matches {
+        return None;
+    }
+
+    let mut confidence = 0;
+    if line.posted_date <= invoice.due_date {
+        confidence += DATE_SCORE;
+    }
+    if amount_matches {
+        confidence += AMOUNT_SCORE;
+    }
+    if reference_matches {
+        confidence += REFERENCE_SCORE;
+    }
+    Some(confidence as i16)
+}
+
+/// Picks a transaction or invoice for every line it can, each goes to one line at most. The
+/// surest pairs are settled first so a weak match never takes one a stronger match needs, and a
+/// line with two equally good candidates is only suggested. So is a line that pays an invoice,
+/// the payment is only recorded once the user confirms it
+pub fn match_lines(
+    lines: &[NewBankStatementLine],
+    candidates: &[MatchCandidate],
+    invoices: &[InvoiceCandidate],
+) -> Vec<Option<LineMatch>> {
+    let targets: Vec<MatchTarget> = candidates
+        .iter()
+        .map(|candidate| MatchTarget::Transaction(candidate.identifier))
+        .chain(
+            invoices
+                .iter()
+                .map(|invoice| MatchTarget::Invoice(invoice.identifier)),
+        )
+        .collect();
+
+    let mut pairs: Vec<(usize, usize, i16)> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(line_index, line)| {
+            candidates
+                .iter()
+                .map(move |candidate| score(line, candidate))
+                .chain(
+                    invoices
+                        .iter()
+                        .map(move |invoice| score_invoice(line, invoice)),
+                )
+                .enumerate()
+                .filter_map(move |(target_index, confidence)| {
+                    confidence.map(|confidence| (line_index, target_index, confidence))
+                })
+        })
+        .filter(|(_, _, confidence)| *confidence >= SUGGESTION_THRESHOLD)
+        .collect();
+    pairs.sort_by_key(|&(_, _, confidence)| std::cmp::Reverse(confidence));
+
+    let mut matches: Vec<Option<LineMatch>> = lines.iter().map(|_| None).collect();
+    let mut taken = vec![false; targets.len()];
+    for &(line_index, target_index, confidence) in &pairs {
+        if matches[line_index].is_some() || taken[target_index] {
+            continue;
+        }
+        taken[target_index] = true;
+
+        let ambiguous = pairs.iter().any(|&(other_line, other_target, other)| {
+            other_line == line_index && other_target != target_index && other == confidence
+        });
+        let target = targets[target_index];
+        let status = match target {
+            MatchTarget::Transaction(_) if confidence >= MATCH_THRESHOLD && !ambiguous => {
+                BankStatementLineStatus::Matched
+            }
+            _ => BankStatementLineStatus::Suggested,
+        };
+        matches[line_index] = Some(LineMatch {
+            target,
+            confidence,
+            status,
+        });
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::BigDecimal;
+    use chrono::{Duration, Local};
+    use std::str::FromStr;
+
+    fn line(days_ago: i64, amount: &str, description: &str) -> NewBankStatementLine {
+        NewBankStatementLine {
+            line_number: 2,
+            posted_date: (Local::now() - Duration::days(days_ago)).date_naive(),
+            amount: BigDecimal::from_str(amount).unwrap(),
+            reference: None,
+            description: description.to_string(),
+        }
+    }
+
+    fn candidate(reference: &str, amount: &str) -> MatchCandidate {
+        MatchCandidate {
+            identifier: Uuid::new_v4(),
+            reference: reference.to_string(),
+            external_reference: None,
+            amount: BigDecimal::from_str(amount).unwrap(),
+            created_date: Local::now(),
+        }
+    }
+
+    #[test]
+    fn test_confidence_grows_with_each_signal() {
+        let transaction = candidate("TRF-AB12CD34EF56GH78", "-250.00");
+
+        let same_day_amount = score(&line(0, "-250", "rent"), &transaction);
+        let later_amount = score(&line(2, "-250", "rent"), &transaction);
+        let referenced = score(&line(0, "-250", "TRF AB12CD34EF56GH78 rent"), &transaction);
+
+        assert_eq!(same_day_amount, Some(60));
+        assert_eq!(later_amount, Some(55));
+        assert_eq!(referenced, Some(100));
+        assert_eq!(score(&line(5, "-250", "rent"), &transaction), None);
+        assert_eq!(score(&line(0, "-10", "rent"), &transaction), None);
+        assert_eq!(score(&line(0, "250", "rent"), &transaction), None);
+    }
+
+    #[test]
+    fn test_each_transaction_goes_to_the_surest_line() {
+        let transaction = candidate("DEP-AB12CD34EF56GH78", "100");
+        let lines = vec![
+            line(1, "100", "transfer"),
+            line(0, "100", "DEP-AB12CD34EF56GH78"),
+        ];
+
+        let matches = match_lines(&lines, &[transaction], &[]);
+
+        assert_eq!(matches[0], None);
+        let matched = matches[1].as_ref().unwrap();
+        assert_eq!(matched.confidence, 100);
+        assert_eq!(matched.status, BankStatementLineStatus::Matched);
+    }
+
+    #[test]
+    fn test_lines_with_two_equal_candidates_are_only_suggested() {
+        let mut first = candidate("WDL-AB12CD34EF56GH78", "-40");
+        first.external_reference = Some("INV-2024-001".to_string());
+        let mut second = candidate("WDL-ZZ12CD34EF56GH78", "-40");
+        second.external_reference = Some("INV-2024-001".to_string());
+
+        let matches = match_lines(
+            &[line(0, "-40", "payment INV-2024-001")],
+            &[first, second],
+            &[],
+        );
+
+        let suggested = matches[0].as_ref().unwrap();
+        assert_eq!(suggested.confidence, 100);
+        assert_eq!(suggested.status, BankStatementLineStatus::Suggested);
+    }
+
+    #[test]
+    fn test_money_in_pays_an_open_invoice_only_once_confirmed() {
+        let invoice = InvoiceCandidate {
+            identifier: Uuid::new_v4(),
+            reference: "INV-AB12CD34EF56GH78".to_string(),
+            outstanding: BigDecimal::from_str("120.50").unwrap(),
+            issue_date: (Local::now() - Duration::days(20)).date_naive(),
+            due_date: (Local::now() - Duration::days(1)).date_naive(),
+        };
+
+        assert_eq!(
+            score_invoice(&line(0, "120.5", "INV AB12CD34EF56GH78"), &invoice),
+            Some(90)
+        );
+        assert_eq!(
+            score_invoice(&line(2, "120.50", "transfer"), &invoice),
+            Some(60)
+        );
+        assert_eq!(
+            score_invoice(&line(0, "-120.50", "transfer"), &invoice),
+            None
+        );
+        assert_eq!(
+            score_invoice(&line(0, "200", "INV-AB12CD34EF56GH78"), &invoice),
+            None
+        );
+        assert_eq!(
+            score_invoice(&line(30, "120.50", "transfer"), &invoice),
+            None
+        );
+
+        let invoice_identifier = invoice.identifier;
+        let lines = vec![
+            line(0, "50", "part payment INV-AB12CD34EF56GH78"),
+            line(0, "-50", "card"),
+        ];
+        let matches = match_lines(
+            &lines,
+            &[candidate("WDL-AB12CD34EF56GH78", "-50")],
+            &[invoice],
+        );
+
+        let paying = matches[0].as_ref().unwrap();
+        assert_eq!(paying.target.invoice_identifier(), Some(invoice_identifier));
+        assert_eq!(paying.confidence, 40);
+        assert_eq!(paying.status, BankStatementLineStatus::Suggested);
+        assert!(
+            matches[1]
+                .as_ref()
+                .unwrap()
+                .target
+                .transaction_identifier()
+                .is_some()
+        );
+    }
+}
@@ -0,0 +1,8 @@
+pub mod adapters;
+pub mod entities;
+pub mod handlers;
+pub mod matching;
+pub mod parser;
+pub mod repository;
+pub mod router;
+pub mod service;
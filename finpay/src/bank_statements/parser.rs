@@ -0,0 +1,261 @@
+//! Reads the lines of a bank statement exported by another bank, as csv or OFX
+
+use crate::bank_statements::adapters::NewBankStatementLine;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use std::str::FromStr;
+
+/// the date layouts banks commonly write in a csv statement
+const CSV_DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y"];
+
+/// Reads an amount the way banks write it. With both `.` and `,` in it the last one is the
+/// decimal separator, so `1,250.00` and `1.250,00` are both `1250.00`. A lone comma separates
+/// thousands when three digits follow it and decimals otherwise, `-42,10` is `-42.10`
+fn read_amount(value: &str) -> Option<BigDecimal> {
+    let value = value.trim();
+    let decimal_separator = match (value.rfind('.'), value.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => ',',
+        (None, Some(comma)) if value.matches(',').count() == 1 && value.len() - comma != 4 => ',',
+        _ => '.',
+    };
+    let group_separator = if decimal_separator == ',' { '.' } else { ',' };
+    BigDecimal::from_str(
+        &value
+            .replace(group_separator, "")
+            .replace(decimal_separator, "."),
+    )
+    .ok()
+}
+
+/// OFX amounts carry no thousands separator, the decimal one is either `.` or `,`
+fn read_ofx_amount(value: &str) -> Option<BigDecimal> {
+    BigDecimal::from_str(&value.trim().replace(',', ".")).ok()
+}
+
+fn read_csv_date(value: &str) -> Option<NaiveDate> {
+    CSV_DATE_FORMATS
+        .into_iter()
+        .find_map(|format| NaiveDate::parse_from_str(value.trim(), format).ok())
+}
+
+/// The line a record starts on. The reader skips blank lines without counting them and gives
+/// the offset of the first one skipped, so they are stepped over here
+fn line_at(contents: &[u8], offset: usize) -> i32 {
+    let start = offset
+        + contents[offset..]
+            .iter()
+            .take_while(|byte| matches!(byte, b'\r' | b'\n'))
+            .count();
+    contents[..start]
+        .iter()
+        .filter(|&&byte| byte == b'\n')
+        .count() as i32
+        + 1
+}
+
+fn column(headers: &csv::StringRecord, names: &[&str]) -> Option<usize> {
+    headers
+        .iter()
+        .position(|header| names.contains(&header.to_lowercase().as_str()))
+}
+
+/// Reads a csv statement with a date, a description and either an amount column or debit and
+/// credit columns, a reference column is optional. Debits are read as negative amounts
+pub fn read_csv(contents: &[u8]) -> Result<Vec<NewBankStatementLine>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(contents);
+    let headers = reader
+        .headers()
+        .map_err(|err| format!("the file is not a valid csv: {err}"))?
+        .clone();
+
+    let date = column(
+        &headers,
+        &["date", "posted_date", "transaction_date", "value_date"],
+    )
+    .ok_or("the file is missing the date column")?;
+    let description = column(&headers, &["description", "narration", "details", "memo"])
+        .ok_or("the file is missing the description column")?;
+    let reference = column(&headers, &["reference", "ref"]);
+    let amount = column(&headers, &["amount"]);
+    let debit = column(&headers, &["debit", "withdrawal"]);
+    let credit = column(&headers, &["credit", "deposit"]);
+    if amount.is_none() && (debit.is_none() || credit.is_none()) {
+        return Err("the file needs an amount column or both debit and credit columns".to_string());
+    }
+
+    let mut lines = vec![];
+    for record in reader.records() {
+        let record = record.map_err(|err| format!("the file is not a valid csv: {err}"))?;
+        let line_number = line_at(
+            contents,
+            record
+                .position()
+                .map_or(0, |position| position.byte() as usize),
+        );
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let field = |index: usize| record.get(index).unwrap_or_default();
+
+        let posted_date =
+            read_csv_date(field(date)).ok_or(format!("line {line_number} has no valid date"))?;
+        let line_amount = match (amount, debit, credit) {
+            (Some(amount), _, _) => read_amount(field(amount)),
+            (None, Some(debit), Some(credit)) => match (field(debit), field(credit)) {
+                (debit, "") => read_amount(debit).map(|debit| -debit.abs()),
+                ("", credit) => read_amount(credit).map(|credit| credit.abs()),
+                _ => None,
+            },
+            _ => None,
+        }
+        .ok_or(format!("line {line_number} has no valid amount"))?;
+
+        lines.push(NewBankStatementLine {
+            line_number,
+            posted_date,
+            amount: line_amount,
+            reference: reference
+                .map(field)
+                .filter(|reference| !reference.is_empty())
+                .map(str::to_string),
+            description: field(description).to_string(),
+        });
+    }
+
+    Ok(lines)
+}
+
+/// The value of an OFX tag in a transaction block. OFX 1.x is SGML and may leave the tag
+/// unclosed, so the value runs up to the next tag either way
+fn ofx_value(block: &str, tag: &str) -> Option<String> {
+    let start = block.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let rest = &block[start..];
+    let value = rest[..rest.find('<').unwrap_or(rest.len())].trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Reads the `STMTTRN` transactions of an OFX statement, the line number is the transaction's
+/// position in the file
+pub fn read_ofx(contents: &str) -> Result<Vec<NewBankStatementLine>, String> {
+    let mut lines = vec![];
+    for (index, block) in contents.split("<STMTTRN>").skip(1).enumerate() {
+        let line_number = index as i32 + 1;
+        let block = block.split("</STMTTRN>").next().unwrap_or(block);
+
+        // OFX dates start with YYYYMMDD and may carry a time and zone after it
+        let posted_date = ofx_value(block, "DTPOSTED")
+            .and_then(|value| value.get(..8).map(str::to_string))
+            .and_then(|value| NaiveDate::parse_from_str(&value, "%Y%m%d").ok())
+            .ok_or(format!("transaction {line_number} has no valid DTPOSTED"))?;
+        let amount = ofx_value(block, "TRNAMT")
+            .and_then(|value| read_ofx_amount(&value))
+            .ok_or(format!("transaction {line_number} has no valid TRNAMT"))?;
+        let description = [ofx_value(block, "NAME"), ofx_value(block, "MEMO")]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        lines.push(NewBankStatementLine {
+            line_number,
+            posted_date,
+            amount,
+            reference: ofx_value(block, "REFNUM").or_else(|| ofx_value(block, "FITID")),
+            description,
+        });
+    }
+
+    if lines.is_empty() && !contents.contains("<OFX>") {
+        return Err("the file is not a valid OFX statement".to_string());
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_statements_read_amounts_or_debit_and_credit_columns() {
+        let amounts = read_csv(
+            b"Date,Description,Amount,Reference\n2025-03-01,Salary,\"1,500.00\",PAY-1\n\n02/03/2025,Rent,-700,\n",
+        )
+        .unwrap();
+        let columns = read_csv(
+            b"date,narration,debit,credit\n2025-03-01,Card,25.50,\n2025-03-02,Refund,,10\n",
+        )
+        .unwrap();
+
+        assert_eq!(amounts.len(), 2);
+        assert_eq!(amounts[0].amount, BigDecimal::from_str("1500.00").unwrap());
+        assert_eq!(amounts[0].reference.as_deref(), Some("PAY-1"));
+        assert_eq!(amounts[1].line_number, 4);
+        assert_eq!(
+            amounts[1].posted_date,
+            NaiveDate::from_ymd_opt(2025, 3, 2).unwrap()
+        );
+        assert_eq!(amounts[1].reference, None);
+        assert_eq!(columns[0].amount, BigDecimal::from_str("-25.50").unwrap());
+        assert_eq!(columns[1].amount, BigDecimal::from(10));
+    }
+
+    #[test]
+    fn test_csv_errors_name_the_line() {
+        let missing = read_csv(b"date,description\n2025-03-01,Salary\n").unwrap_err();
+        let invalid =
+            read_csv(b"date,description,amount\n2025-03-01,Salary,10\nyesterday,Rent,5\n")
+                .unwrap_err();
+
+        assert!(missing.contains("amount column"));
+        assert_eq!(invalid, "line 3 has no valid date");
+    }
+
+    #[test]
+    fn test_ofx_statements_are_read_with_or_without_closing_tags() {
+        let contents = "OFXHEADER:100\n<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><BANKTRANLIST>\n\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250301120000[0:GMT]<TRNAMT>-42.10<FITID>9001\
+            <NAME>GROCER<MEMO>card 1234\n\
+            <STMTTRN><TRNTYPE>CREDIT</TRNTYPE><DTPOSTED>20250302</DTPOSTED><TRNAMT>100.00</TRNAMT>\
+            <FITID>9002</FITID><REFNUM>DEP-AB12</REFNUM><NAME>Top up</NAME></STMTTRN>\n\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+
+        let lines = read_ofx(contents).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].amount, BigDecimal::from_str("-42.10").unwrap());
+        assert_eq!(lines[0].reference.as_deref(), Some("9001"));
+        assert_eq!(lines[0].description, "GROCER card 1234");
+        assert_eq!(
+            lines[1].posted_date,
+            NaiveDate::from_ymd_opt(2025, 3, 2).unwrap()
+        );
+        assert_eq!(lines[1].reference.as_deref(), Some("DEP-AB12"));
+        assert!(read_ofx("not a statement").is_err());
+    }
+
+    #[test]
+    fn test_amounts_keep_their_decimal_comma() {
+        for (value, expected) in [
+            ("-42,10", "-42.10"),
+            ("1,500", "1500"),
+            ("1,250.00", "1250.00"),
+            ("1.250,00", "1250.00"),
+            ("1,250,000", "1250000"),
+            ("0,5", "0.5"),
+            ("-700", "-700"),
+        ] {
+            assert_eq!(
+                read_amount(value),
+                Some(BigDecimal::from_str(expected).unwrap()),
+                "{value}"
+            );
+        }
+        assert_eq!(
+            read_ofx_amount("-42,10"),
+            Some(BigDecimal::from_str("-42.10").unwrap())
+        );
+    }
+}
@@ -0,0 +1,778 @@
+use crate::bank_statements::adapters::NewBankStatementLine;
+use crate::bank_statements::entities::{
+    BankStatementFormat, BankStatementImport, BankStatementLine, BankStatementLineStatus,
+    InvoiceCandidate, MatchCandidate,
+};
+use crate::bank_statements::matching::{LineMatch, MatchTarget};
+use crate::errors::RepositoryError;
+use crate::invoices::entities::InvoiceStatus;
+use crate::invoices::repository::InvoiceRepository;
+use crate::transactions::entities::TransactionStatus;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDate;
+use finpay_utils::generate_reference;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// A transaction can be on one matched or confirmed line at most, the database turns a second
+/// one away
+fn reconciled_elsewhere(err: sqlx::Error) -> RepositoryError {
+    match err {
+        sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+            RepositoryError::OperationFailed(
+                "the transaction is already reconciled against another statement line".to_string(),
+            )
+        }
+        err => RepositoryError::from(err),
+    }
+}
+
+#[derive(Clone)]
+pub struct BankStatementRepository {
+    pool: PgPool,
+}
+
+impl BankStatementRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    async fn fetch_lines(
+        &self,
+        import_identifier: &Uuid,
+    ) -> Result<Vec<BankStatementLine>, RepositoryError> {
+        sqlx::query_as::<_, BankStatementLine>(
+            r#"SELECT * FROM bank_statement_lines WHERE import_identifier = $1 ORDER BY line_number"#,
+        )
+        .bind(import_identifier)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    /// A line on one of the user's imports, locked until the transaction ends
+    async fn lock_line(
+        connection: &mut PgConnection,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<BankStatementLine, RepositoryError> {
+        let query = r#"
+        SELECT bank_statement_lines.*
+        FROM bank_statement_lines
+                 JOIN bank_statement_imports
+                      ON bank_statement_imports.identifier = bank_statement_lines.import_identifier
+        WHERE bank_statement_lines.identifier = $1
+          AND bank_statement_imports.user_identifier = $2
+        FOR UPDATE OF bank_statement_lines
+        "#;
+        sqlx::query_as::<_, BankStatementLine>(query)
+            .bind(identifier)
+            .bind(user_identifier)
+            .fetch_optional(&mut *connection)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)
+    }
+}
+
+pub trait BankStatementRepositoryExt {
+    /// The completed transactions on a wallet of the user within the dates that no statement
+    /// line has been reconciled against yet
+    fn fetch_candidates(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> impl std::future::Future<Output = Result<Vec<MatchCandidate>, RepositoryError>> + Send;
+
+    /// The user's sent invoices in the wallet's currency that still have money owed on them
+    fn fetch_invoice_candidates(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Vec<InvoiceCandidate>, RepositoryError>> + Send;
+
+    /// Records an import with its lines, `matches` holds the match found for each line if any
+    fn create_import(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+        file_name: &str,
+        format: BankStatementFormat,
+        lines: &[NewBankStatementLine],
+        matches: &[Option<LineMatch>],
+    ) -> impl std::future::Future<Output = Result<BankStatementImport, RepositoryError>> + Send;
+
+    fn fetch_import(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<BankStatementImport>, RepositoryError>> + Send;
+
+    fn fetch_imports(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<BankStatementImport>, RepositoryError>,
+    > + Send;
+
+    /// The unmatched and suggested lines of the user's imports, oldest first
+    fn fetch_review_queue(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<BankStatementLine>, RepositoryError>,
+    > + Send;
+
+    /// Confirms the match on a line, or matches it to another completed transaction on the
+    /// import's wallet or to an open invoice when one is given. A line confirmed against an
+    /// invoice is recorded as a payment of it
+    fn confirm_line(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        target: Option<MatchTarget>,
+    ) -> impl std::future::Future<Output = Result<BankStatementLine, RepositoryError>> + Send;
+
+    /// Drops the match on a line and sends it back to the review queue as unmatched, the payment
+    /// a confirmed line made on an invoice is taken back
+    fn reject_line(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<BankStatementLine, RepositoryError>> + Send;
+}
+
+impl BankStatementRepositoryExt for BankStatementRepository {
+    async fn fetch_candidates(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<MatchCandidate>, RepositoryError> {
+        let query = r#"
+        SELECT transactions.identifier,
+               transactions.reference,
+               transactions.external_reference,
+               CASE
+                   WHEN transactions.destination_wallet_identifier = $1 THEN transactions.amount
+                   ELSE -transactions.amount
+                   END AS amount,
+               transactions.created_date
+        FROM transactions
+                 JOIN wallets ON wallets.identifier = $1 AND wallets.user_identifier = $2
+        WHERE $1 IN (transactions.source_wallet_identifier, transactions.destination_wallet_identifier)
+          AND transactions.status = $3
+          AND transactions.created_date::date BETWEEN $4 AND $5
+          AND NOT EXISTS (SELECT 1
+                          FROM bank_statement_lines
+                          WHERE bank_statement_lines.transaction_identifier = transactions.identifier
+                            AND bank_statement_lines.status IN ($6, $7))
+        ORDER BY transactions.created_date
+        "#;
+        sqlx::query_as::<_, MatchCandidate>(query)
+            .bind(wallet_identifier)
+            .bind(user_identifier)
+            .bind(TransactionStatus::Completed)
+            .bind(from)
+            .bind(to)
+            .bind(BankStatementLineStatus::Matched)
+            .bind(BankStatementLineStatus::Confirmed)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_invoice_candidates(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Vec<InvoiceCandidate>, RepositoryError> {
+        let query = r#"
+        SELECT invoices.identifier,
+               invoices.reference,
+               invoices.total - invoices.amount_paid AS outstanding,
+               invoices.issue_date,
+               invoices.due_date
+        FROM invoices
+                 JOIN wallets ON wallets.identifier = $1 AND wallets.user_identifier = $2
+        WHERE invoices.user_identifier = $2
+          AND invoices.currency_identifier = wallets.currency_identifier
+          AND invoices.status IN ($3, $4, $5)
+        ORDER BY invoices.due_date
+        "#;
+        sqlx::query_as::<_, InvoiceCandidate>(query)
+            .bind(wallet_identifier)
+            .bind(user_identifier)
+            .bind(InvoiceStatus::Sent)
+            .bind(InvoiceStatus::PartiallyPaid)
+            .bind(InvoiceStatus::Overdue)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn create_import(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+        file_name: &str,
+        format: BankStatementFormat,
+        lines: &[NewBankStatementLine],
+        matches: &[Option<LineMatch>],
+    ) -> Result<BankStatementImport, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = r#"
+        INSERT INTO bank_statement_imports (identifier, reference, user_identifier, wallet_identifier, file_name, format, line_count)
+        SELECT $1, $2, wallets.user_identifier, wallets.identifier, $3, $4, $5
+        FROM wallets
+        WHERE wallets.identifier = $6 AND wallets.user_identifier = $7
+        RETURNING *
+        "#;
+        let mut import = sqlx::query_as::<_, BankStatementImport>(query)
+            .bind(Uuid::new_v4())
+            .bind(generate_reference("BSI"))
+            .bind(file_name)
+            .bind(format)
+            .bind(lines.len() as i32)
+            .bind(wallet_identifier)
+            .bind(user_identifier)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|err| match err {
+                sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                    RepositoryError::DuplicateRecord
+                }
+                err => RepositoryError::from(err),
+            })?
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        let query = r#"
+        INSERT INTO bank_statement_lines (identifier, import_identifier, line_number, posted_date, amount, reference, description, status, transaction_identifier, invoice_identifier, confidence)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#;
+        for (line, line_match) in lines.iter().zip(matches) {
+            let line = sqlx::query_as::<_, BankStatementLine>(query)
+                .bind(Uuid::new_v4())
+                .bind(import.identifier)
+                .bind(line.line_number)
+                .bind(line.posted_date)
+                .bind(&line.amount)
+                .bind(&line.reference)
+                .bind(&line.description)
+                .bind(
+                    line_match
+                        .as_ref()
+                        .map_or(BankStatementLineStatus::Unmatched, |line_match| {
+                            line_match.status
+                        }),
+                )
+                .bind(
+                    line_match
+                        .as_ref()
+                        .and_then(|line_match| line_match.target.transaction_identifier()),
+                )
+                .bind(
+                    line_match
+                        .as_ref()
+                        .and_then(|line_match| line_match.target.invoice_identifier()),
+                )
+                .bind(line_match.as_ref().map(|line_match| line_match.confidence))
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(reconciled_elsewhere)?;
+            import.lines.push(line);
+        }
+
+        tx.commit().await?;
+        Ok(import)
+    }
+
+    async fn fetch_import(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<BankStatementImport>, RepositoryError> {
+        let import = sqlx::query_as::<_, BankStatementImport>(
+            r#"SELECT * FROM bank_statement_imports WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(mut import) = import else {
+            return Ok(None);
+        };
+        import.lines = self.fetch_lines(&import.identifier).await?;
+        Ok(Some(import))
+    }
+
+    async fn fetch_imports(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<BankStatementImport>, RepositoryError> {
+        let total_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(identifier) FROM bank_statement_imports WHERE user_identifier = $1",
+        )
+        .bind(user_identifier)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let imports = sqlx::query_as::<_, BankStatementImport>(
+            r#"SELECT * FROM bank_statement_imports WHERE user_identifier = $1 ORDER BY created_date DESC LIMIT $2 OFFSET $3"#,
+        )
+        .bind(user_identifier)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            imports,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn fetch_review_queue(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<BankStatementLine>, RepositoryError> {
+        let total_count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(bank_statement_lines.identifier)
+            FROM bank_statement_lines
+                     JOIN bank_statement_imports
+                          ON bank_statement_imports.identifier = bank_statement_lines.import_identifier
+            WHERE bank_statement_imports.user_identifier = $1
+              AND bank_statement_lines.status IN ($2, $3)
+            "#,
+        )
+        .bind(user_identifier)
+        .bind(BankStatementLineStatus::Unmatched)
+        .bind(BankStatementLineStatus::Suggested)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let query = r#"
+        SELECT bank_statement_lines.*
+        FROM bank_statement_lines
+                 JOIN bank_statement_imports
+                      ON bank_statement_imports.identifier = bank_statement_lines.import_identifier
+        WHERE bank_statement_imports.user_identifier = $1
+          AND bank_statement_lines.status IN ($2, $3)
+        ORDER BY bank_statement_lines.posted_date, bank_statement_lines.line_number
+        LIMIT $4 OFFSET $5
+        "#;
+        let lines = sqlx::query_as::<_, BankStatementLine>(query)
+            .bind(user_identifier)
+            .bind(BankStatementLineStatus::Unmatched)
+            .bind(BankStatementLineStatus::Suggested)
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(PaginatedResponse::new(
+            lines,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn confirm_line(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        target: Option<MatchTarget>,
+    ) -> Result<BankStatementLine, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let line = Self::lock_line(&mut tx, identifier, user_identifier).await?;
+        if line.status == BankStatementLineStatus::Confirmed {
+            return Err(RepositoryError::InvalidStatementLineState);
+        }
+
+        let matched = MatchTarget::of(line.transaction_identifier, line.invoice_identifier);
+        let target = target
+            .or(matched)
+            .ok_or(RepositoryError::InvalidStatementLineState)?;
+        match target {
+            MatchTarget::Transaction(transaction_identifier) if matched != Some(target) => {
+                // a match made by hand must still be a completed transaction on the same wallet
+                let query = r#"
+                SELECT transactions.identifier
+                FROM transactions
+                         JOIN bank_statement_imports ON bank_statement_imports.identifier = $2
+                WHERE transactions.identifier = $1
+                  AND transactions.status = $3
+                  AND bank_statement_imports.wallet_identifier IN (transactions.source_wallet_identifier,
+                                                                   transactions.destination_wallet_identifier)
+                "#;
+                sqlx::query_scalar::<_, Uuid>(query)
+                    .bind(transaction_identifier)
+                    .bind(line.import_identifier)
+                    .bind(TransactionStatus::Completed)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                    .ok_or(RepositoryError::RecordNotFound)?;
+            }
+            MatchTarget::Transaction(_) => {}
+            MatchTarget::Invoice(invoice_identifier) => {
+                // only money that came into the bank account can pay an invoice
+                if line.amount <= BigDecimal::zero() {
+                    return Err(RepositoryError::InvalidStatementLineState);
+                }
+                let invoice = InvoiceRepository::apply_payment(
+                    &mut tx,
+                    &invoice_identifier,
+                    user_identifier,
+                    &line.amount,
+                )
+                .await?;
+
+                let query = r#"
+                SELECT wallets.currency_identifier
+                FROM wallets
+                         JOIN bank_statement_imports
+                              ON bank_statement_imports.wallet_identifier = wallets.identifier
+                WHERE bank_statement_imports.identifier = $1
+                "#;
+                let currency_identifier = sqlx::query_scalar::<_, Uuid>(query)
+                    .bind(line.import_identifier)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                if invoice.currency_identifier != currency_identifier {
+                    return Err(RepositoryError::CurrencyMismatch);
+                }
+            }
+        }
+
+        let query = r#"
+        UPDATE bank_statement_lines
+        SET status                 = $1,
+            confidence             = CASE
+                                         WHEN transaction_identifier IS NOT DISTINCT FROM $2
+                                             AND invoice_identifier IS NOT DISTINCT FROM $3
+                                             THEN confidence
+                END,
+            transaction_identifier = $2,
+            invoice_identifier     = $3,
+            reviewed_date          = NOW()
+        WHERE identifier = $4
+        RETURNING *
+        "#;
+        let line = sqlx::query_as::<_, BankStatementLine>(query)
+            .bind(BankStatementLineStatus::Confirmed)
+            .bind(target.transaction_identifier())
+            .bind(target.invoice_identifier())
+            .bind(identifier)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(reconciled_elsewhere)?;
+
+        tx.commit().await?;
+        Ok(line)
+    }
+
+    async fn reject_line(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<BankStatementLine, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let line = Self::lock_line(&mut tx, identifier, user_identifier).await?;
+        if line.status == BankStatementLineStatus::Unmatched {
+            return Err(RepositoryError::InvalidStatementLineState);
+        }
+        if let (BankStatementLineStatus::Confirmed, Some(invoice_identifier)) =
+            (line.status, line.invoice_identifier)
+        {
+            InvoiceRepository::reverse_payment(&mut tx, &invoice_identifier, &line.amount).await?;
+        }
+
+        let query = r#"
+        UPDATE bank_statement_lines
+        SET status                 = $1,
+            transaction_identifier = NULL,
+            invoice_identifier     = NULL,
+            confidence             = NULL,
+            reviewed_date          = NOW()
+        WHERE identifier = $2
+        RETURNING *
+        "#;
+        let line = sqlx::query_as::<_, BankStatementLine>(query)
+            .bind(BankStatementLineStatus::Unmatched)
+            .bind(identifier)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bank_statements::matching::{MATCH_WINDOW_IN_DAYS, match_lines};
+    use crate::invoices::adapters::{CreateInvoiceRequest, InvoiceLineItemRequest};
+    use crate::invoices::entities::{InvoiceTotals, PricedLineItem};
+    use crate::invoices::repository::InvoiceRepositoryExt;
+    use crate::shared::fixtures::{UAE_DIRHAM, create_user, create_wallet, fund};
+    use crate::transactions::adapters::CreateTransferRequest;
+    use crate::wallet::repository::{WalletRepository, WalletRepositoryExt};
+    use bigdecimal::BigDecimal;
+    use chrono::{Duration, Local};
+    use std::str::FromStr;
+
+    #[sqlx::test]
+    async fn test_reconciled_transaction_is_not_offered_again(pool: PgPool) {
+        let repository = BankStatementRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let source = create_wallet(&pool, &user_identifier).await;
+        let destination = create_wallet(&pool, &create_user(&pool).await).await;
+
+        fund(&pool, &source, "100").await;
+
+        let transfer = WalletRepository::new(pool.clone())
+            .transfer(
+                &CreateTransferRequest {
+                    source_wallet_identifier: source,
+                    destination_wallet_identifier: destination,
+                    amount: BigDecimal::from(40),
+                    description: None,
+                    quote_identifier: None,
+                },
+                &user_identifier,
+            )
+            .await
+            .expect("failed to transfer");
+
+        let today = Local::now().date_naive();
+        let from = today - Duration::days(MATCH_WINDOW_IN_DAYS);
+        let to = today + Duration::days(MATCH_WINDOW_IN_DAYS);
+        let candidates = repository
+            .fetch_candidates(&source, &user_identifier, from, to)
+            .await
+            .unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].amount, BigDecimal::from(-40));
+
+        let lines = vec![NewBankStatementLine {
+            line_number: 2,
+            posted_date: today,
+            amount: BigDecimal::from(-40),
+            reference: None,
+            description: format!("transfer {}", transfer.reference),
+        }];
+        let matches = match_lines(&lines, &candidates, &[]);
+        assert!(matches!(
+            repository
+                .create_import(
+                    &source,
+                    &Uuid::new_v4(),
+                    "statement.csv",
+                    BankStatementFormat::Csv,
+                    &lines,
+                    &matches,
+                )
+                .await,
+            Err(RepositoryError::RecordNotFound)
+        ));
+        let import = repository
+            .create_import(
+                &source,
+                &user_identifier,
+                "statement.csv",
+                BankStatementFormat::Csv,
+                &lines,
+                &matches,
+            )
+            .await
+            .unwrap();
+        let line = &import.lines[0];
+        assert_eq!(line.status, BankStatementLineStatus::Matched);
+        assert_eq!(line.transaction_identifier, Some(transfer.identifier));
+        assert!(
+            repository
+                .fetch_candidates(&source, &user_identifier, from, to)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let rejected = repository
+            .reject_line(&line.identifier, &user_identifier)
+            .await
+            .unwrap();
+        assert_eq!(rejected.status, BankStatementLineStatus::Unmatched);
+        assert_eq!(rejected.transaction_identifier, None);
+        assert_eq!(
+            repository
+                .fetch_review_queue(&user_identifier, &PaginationParams::default())
+                .await
+                .unwrap()
+                .total_count,
+            1
+        );
+
+        let confirmed = repository
+            .confirm_line(
+                &line.identifier,
+                &user_identifier,
+                Some(MatchTarget::Transaction(transfer.identifier)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(confirmed.status, BankStatementLineStatus::Confirmed);
+        assert_eq!(confirmed.confidence, None);
+        assert!(matches!(
+            repository
+                .confirm_line(&line.identifier, &user_identifier, None)
+                .await,
+            Err(RepositoryError::InvalidStatementLineState)
+        ));
+        assert!(
+            repository
+                .fetch_candidates(&source, &user_identifier, from, to)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_confirmed_invoice_line_records_the_payment(pool: PgPool) {
+        let repository = BankStatementRepository::new(&pool);
+        let invoices = InvoiceRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let wallet = create_wallet(&pool, &user_identifier).await;
+
+        let today = Local::now().date_naive();
+        let request = CreateInvoiceRequest {
+            customer_name: "Acme Ltd".to_string(),
+            customer_email: None,
+            customer_address: None,
+            currency_identifier: Uuid::from_str(UAE_DIRHAM).unwrap(),
+            issue_date: Some(today),
+            due_date: today + Duration::days(14),
+            notes: None,
+            line_items: vec![InvoiceLineItemRequest {
+                description: "Design work".to_string(),
+                quantity: BigDecimal::from(1),
+                unit_price: BigDecimal::from(100),
+                tax_rate_basis_points: 0,
+                discount_basis_points: 0,
+            }],
+        };
+        let line_items = vec![PricedLineItem::price(
+            &BigDecimal::from(1),
+            &BigDecimal::from(100),
+            0,
+            0,
+            2,
+        )];
+        let invoice = invoices
+            .create(
+                &request,
+                &user_identifier,
+                &line_items,
+                &InvoiceTotals::of(&line_items, 2),
+            )
+            .await
+            .unwrap();
+        invoices
+            .send(&invoice.identifier, &user_identifier)
+            .await
+            .unwrap();
+
+        let candidates = repository
+            .fetch_invoice_candidates(&wallet, &user_identifier)
+            .await
+            .unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].outstanding, BigDecimal::from(100));
+
+        let lines = vec![NewBankStatementLine {
+            line_number: 2,
+            posted_date: today,
+            amount: BigDecimal::from(100),
+            reference: None,
+            description: format!("payment {}", invoice.reference),
+        }];
+        let matches = match_lines(&lines, &[], &candidates);
+        let import = repository
+            .create_import(
+                &wallet,
+                &user_identifier,
+                "statement.csv",
+                BankStatementFormat::Csv,
+                &lines,
+                &matches,
+            )
+            .await
+            .unwrap();
+        let line = &import.lines[0];
+        assert_eq!(line.status, BankStatementLineStatus::Suggested);
+        assert_eq!(line.invoice_identifier, Some(invoice.identifier));
+        let unpaid = invoices
+            .fetch_invoice(&invoice.identifier, &user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unpaid.status, InvoiceStatus::Sent);
+
+        let confirmed = repository
+            .confirm_line(&line.identifier, &user_identifier, None)
+            .await
+            .unwrap();
+        assert_eq!(confirmed.status, BankStatementLineStatus::Confirmed);
+        let paid = invoices
+            .fetch_invoice(&invoice.identifier, &user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paid.status, InvoiceStatus::Paid);
+        assert_eq!(paid.amount_paid, BigDecimal::from(100));
+        assert!(
+            repository
+                .fetch_invoice_candidates(&wallet, &user_identifier)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let rejected = repository
+            .reject_line(&line.identifier, &user_identifier)
+            .await
+            .unwrap();
+        assert_eq!(rejected.invoice_identifier, None);
+        let reopened = invoices
+            .fetch_invoice(&invoice.identifier, &user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reopened.status, InvoiceStatus::Sent);
+        assert_eq!(reopened.amount_paid, BigDecimal::zero());
+        assert_eq!(reopened.paid_date, None);
+    }
+}
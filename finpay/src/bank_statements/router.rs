@@ -0,0 +1,26 @@
+use crate::bank_statements::handlers::{
+    confirm_bank_statement_line, fetch_bank_statement, fetch_bank_statements, fetch_review_queue,
+    import_bank_statement, reject_bank_statement_line,
+};
+use crate::state::AppState;
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+pub fn bank_statement_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/", post(import_bank_statement))
+        .route("/", get(fetch_bank_statements))
+        .route("/review", get(fetch_review_queue))
+        .route("/{identifier}", get(fetch_bank_statement))
+        .route(
+            "/lines/{identifier}/confirm",
+            post(confirm_bank_statement_line),
+        )
+        .route(
+            "/lines/{identifier}/reject",
+            post(reject_bank_statement_line),
+        )
+        .with_state(state.clone())
+}
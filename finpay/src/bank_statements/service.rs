@@ -0,0 +1,252 @@
+use crate::authentication::claims::Claims;
+use crate::bank_statements::adapters::{
+    ConfirmBankStatementLineRequest, ImportBankStatementRequest, NewBankStatementLine,
+};
+use crate::bank_statements::entities::{
+    BankStatementFormat, BankStatementImport, BankStatementLine,
+};
+use crate::bank_statements::matching::{MATCH_WINDOW_IN_DAYS, MatchTarget, match_lines};
+use crate::bank_statements::parser::{read_csv, read_ofx};
+use crate::bank_statements::repository::{BankStatementRepository, BankStatementRepositoryExt};
+use crate::errors::{RepositoryError, ServiceError};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use axum_typed_multipart::TypedMultipart;
+use chrono::Duration;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// the most lines a single uploaded statement can carry
+const MAX_STATEMENT_LINES: usize = 5000;
+
+/// OFX when the file is named or starts like one, csv otherwise
+fn detect_format(file_name: &str, contents: &[u8]) -> BankStatementFormat {
+    let file_name = file_name.to_lowercase();
+    let head = String::from_utf8_lossy(&contents[..contents.len().min(512)]).to_uppercase();
+    if file_name.ends_with(".ofx")
+        || file_name.ends_with(".qfx")
+        || head.contains("OFXHEADER")
+        || head.contains("<OFX>")
+    {
+        BankStatementFormat::Ofx
+    } else {
+        BankStatementFormat::Csv
+    }
+}
+
+fn read_statement_file(
+    format: BankStatementFormat,
+    contents: &[u8],
+) -> Result<Vec<NewBankStatementLine>, ServiceError> {
+    let lines = match format {
+        BankStatementFormat::Csv => read_csv(contents),
+        BankStatementFormat::Ofx => read_ofx(&String::from_utf8_lossy(contents)),
+    }
+    .map_err(ServiceError::UnprocessableEntity)?;
+
+    if lines.is_empty() {
+        return Err(ServiceError::UnprocessableEntity(
+            "the file has no lines".to_string(),
+        ));
+    }
+    if lines.len() > MAX_STATEMENT_LINES {
+        return Err(ServiceError::UnprocessableEntity(format!(
+            "a statement cannot have more than {MAX_STATEMENT_LINES} lines"
+        )));
+    }
+
+    Ok(lines)
+}
+
+#[derive(Clone)]
+pub struct BankStatementService {
+    repository: BankStatementRepository,
+}
+
+impl BankStatementService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: BankStatementRepository::new(pool),
+        }
+    }
+}
+
+pub trait BankStatementServiceExt {
+    /// Reads an uploaded statement and matches its lines against the completed transactions on
+    /// the wallet and the user's open invoices, lines without a sure match land in the review
+    /// queue
+    fn import_statement(
+        &self,
+        claims: &Claims,
+        request: TypedMultipart<ImportBankStatementRequest>,
+    ) -> impl std::future::Future<Output = Result<BankStatementImport, ServiceError>> + Send;
+
+    fn fetch_imports(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<BankStatementImport>, ServiceError>,
+    > + Send;
+
+    fn fetch_import(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<BankStatementImport, ServiceError>> + Send;
+
+    fn fetch_review_queue(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<BankStatementLine>, ServiceError>,
+    > + Send;
+
+    fn confirm_line(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &ConfirmBankStatementLineRequest,
+    ) -> impl std::future::Future<Output = Result<BankStatementLine, ServiceError>> + Send;
+
+    fn reject_line(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<BankStatementLine, ServiceError>> + Send;
+}
+
+impl BankStatementServiceExt for BankStatementService {
+    async fn import_statement(
+        &self,
+        claims: &Claims,
+        TypedMultipart(request): TypedMultipart<ImportBankStatementRequest>,
+    ) -> Result<BankStatementImport, ServiceError> {
+        let wallet_identifier =
+            Uuid::parse_str(&request.wallet_identifier).map_err(|_| ServiceError::BadRequest)?;
+        let file_name = request
+            .file
+            .metadata
+            .file_name
+            .clone()
+            .unwrap_or_else(|| "statement.csv".to_string());
+        let contents = tokio::fs::read(request.file.contents.path())
+            .await
+            .map_err(|err| {
+                log::error!("error reading bank statement due to {err}");
+                ServiceError::OperationFailed
+            })?;
+
+        let format = detect_format(&file_name, &contents);
+        let lines = read_statement_file(format, &contents)?;
+
+        // every transaction that could match any line, the matcher narrows it down per line
+        let window = Duration::days(MATCH_WINDOW_IN_DAYS);
+        let from = lines
+            .iter()
+            .map(|line| line.posted_date)
+            .min()
+            .unwrap_or_default()
+            - window;
+        let to = lines
+            .iter()
+            .map(|line| line.posted_date)
+            .max()
+            .unwrap_or_default()
+            + window;
+        let candidates = self
+            .repository
+            .fetch_candidates(&wallet_identifier, &claims.user_identifier, from, to)
+            .await?;
+        let invoices = self
+            .repository
+            .fetch_invoice_candidates(&wallet_identifier, &claims.user_identifier)
+            .await?;
+        let matches = match_lines(&lines, &candidates, &invoices);
+
+        let import = self
+            .repository
+            .create_import(
+                &wallet_identifier,
+                &claims.user_identifier,
+                &file_name,
+                format,
+                &lines,
+                &matches,
+            )
+            .await?;
+        Ok(import)
+    }
+
+    async fn fetch_imports(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<BankStatementImport>, ServiceError> {
+        let imports = self
+            .repository
+            .fetch_imports(&claims.user_identifier, pagination_params)
+            .await?;
+        Ok(imports)
+    }
+
+    async fn fetch_import(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<BankStatementImport, ServiceError> {
+        let import = self
+            .repository
+            .fetch_import(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(import)
+    }
+
+    async fn fetch_review_queue(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<BankStatementLine>, ServiceError> {
+        let lines = self
+            .repository
+            .fetch_review_queue(&claims.user_identifier, pagination_params)
+            .await?;
+        Ok(lines)
+    }
+
+    async fn confirm_line(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &ConfirmBankStatementLineRequest,
+    ) -> Result<BankStatementLine, ServiceError> {
+        let target = match (request.transaction_identifier, request.invoice_identifier) {
+            (Some(_), Some(_)) => {
+                return Err(ServiceError::UnprocessableEntity(
+                    "a line is matched to a transaction or an invoice, not both".to_string(),
+                ));
+            }
+            (transaction_identifier, invoice_identifier) => {
+                MatchTarget::of(transaction_identifier, invoice_identifier)
+            }
+        };
+        let line = self
+            .repository
+            .confirm_line(identifier, &claims.user_identifier, target)
+            .await?;
+        Ok(line)
+    }
+
+    async fn reject_line(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<BankStatementLine, ServiceError> {
+        let line = self
+            .repository
+            .reject_line(identifier, &claims.user_identifier)
+            .await?;
+        Ok(line)
+    }
+}
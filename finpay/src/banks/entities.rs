@@ -8,6 +8,8 @@ pub struct Bank {
     pub identifier: Uuid,
     pub bank_name: String,
     pub country_identifier: Uuid,
+    /// what a pain.001 file routes payments to the bank by, not every bank has one on record
+    pub bic: Option<String>,
     pub created_date: DateTime<Local>,
     pub updated_at: Option<DateTime<Local>>,
 }
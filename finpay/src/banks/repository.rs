@@ -54,7 +54,7 @@ impl BankRepositoryExt for BankRepository {
         &self,
         country_identifier: &Uuid,
     ) -> Result<Vec<Bank>, RepositoryError> {
-        let query = r"SELECT * FROM countries WHERE country_identifier = $1;";
+        let query = r"SELECT * FROM banks WHERE country_identifier = $1 ORDER BY bank_name;";
         let banks = sqlx::query_as::<_, Bank>(query)
             .bind(country_identifier)
             .fetch_all(&self.pool)
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBeneficiaryRequest {
+    pub bank_identifier: Uuid,
+    #[validate(length(
+        equal = 10,
+        message = "account number must be 10 digits",
+        code = "accountNumber"
+    ))]
+    pub account_number: String,
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "account name must be between 1 and 255 characters",
+        code = "accountName"
+    ))]
+    pub account_name: String,
+}
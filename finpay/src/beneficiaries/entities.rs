@@ -0,0 +1,19 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A bank account a user has saved to withdraw to
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Beneficiary {
+    pub identifier: Uuid,
+    pub user_identifier: Uuid,
+    pub bank_identifier: Uuid,
+    pub account_number: String,
+    pub account_name: String,
+    /// when compliance confirmed the account holder is a listed party, it cannot be paid since
+    pub blocked_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: Option<DateTime<Local>>,
+}
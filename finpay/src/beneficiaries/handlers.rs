@@ -0,0 +1,63 @@
+use crate::authentication::claims::Claims;
+use crate::beneficiaries::adapters::CreateBeneficiaryRequest;
+use crate::beneficiaries::entities::Beneficiary;
+use crate::beneficiaries::service::{BeneficiaryService, BeneficiaryServiceExt};
+use crate::errors::ServiceError;
+use crate::utils::{
+    ApiResponse, AuthenticatedRequest, EmptyResponseBody, PaginatedResponse, PaginationParams,
+};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+pub async fn create_beneficiary(
+    State(beneficiary_service): State<BeneficiaryService>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateBeneficiaryRequest>,
+) -> Result<ApiResponse<Beneficiary>, ServiceError> {
+    let beneficiary = beneficiary_service
+        .create_beneficiary(&claims, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(beneficiary)
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_beneficiary(
+    State(beneficiary_service): State<BeneficiaryService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<Beneficiary>, ServiceError> {
+    let beneficiary = beneficiary_service
+        .fetch_beneficiary(&claims, &identifier)
+        .await?;
+
+    Ok(ApiResponse::builder().data(beneficiary).build())
+}
+
+pub async fn fetch_beneficiaries(
+    State(beneficiary_service): State<BeneficiaryService>,
+    claims: Claims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<Beneficiary>>, ServiceError> {
+    let beneficiaries = beneficiary_service
+        .fetch_beneficiaries(&claims, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(beneficiaries).build())
+}
+
+pub async fn delete_beneficiary(
+    State(beneficiary_service): State<BeneficiaryService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<EmptyResponseBody>, ServiceError> {
+    beneficiary_service
+        .delete_beneficiary(&claims, &identifier)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .message("beneficiary removed successfully")
+        .build())
+}
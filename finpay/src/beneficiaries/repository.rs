@@ -0,0 +1,133 @@
+use crate::beneficiaries::adapters::CreateBeneficiaryRequest;
+use crate::beneficiaries::entities::Beneficiary;
+use crate::errors::RepositoryError;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct BeneficiaryRepository {
+    pool: PgPool,
+}
+
+impl BeneficiaryRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+pub trait BeneficiaryRepositoryExt {
+    fn create(
+        &self,
+        payload: &CreateBeneficiaryRequest,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Beneficiary, RepositoryError>> + Send;
+
+    fn fetch_beneficiary(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<Beneficiary>, RepositoryError>> + Send;
+
+    fn fetch_beneficiaries(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Beneficiary>, RepositoryError>> + Send;
+
+    fn delete(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<(), RepositoryError>> + Send;
+}
+
+impl BeneficiaryRepositoryExt for BeneficiaryRepository {
+    async fn create(
+        &self,
+        payload: &CreateBeneficiaryRequest,
+        user_identifier: &Uuid,
+    ) -> Result<Beneficiary, RepositoryError> {
+        let query = r#"
+        INSERT INTO beneficiaries (identifier, user_identifier, bank_identifier, account_number, account_name)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_identifier, bank_identifier, account_number) DO NOTHING
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, Beneficiary>(query)
+            .bind(Uuid::new_v4())
+            .bind(user_identifier)
+            .bind(payload.bank_identifier)
+            .bind(&payload.account_number)
+            .bind(&payload.account_name)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(RepositoryError::DuplicateRecord)
+    }
+
+    async fn fetch_beneficiary(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<Beneficiary>, RepositoryError> {
+        sqlx::query_as::<_, Beneficiary>(
+            r#"SELECT * FROM beneficiaries WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_beneficiaries(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Beneficiary>, RepositoryError> {
+        let total_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(identifier) FROM beneficiaries WHERE user_identifier = $1",
+        )
+        .bind(user_identifier)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let beneficiaries = sqlx::query_as::<_, Beneficiary>(
+            r#"SELECT * FROM beneficiaries WHERE user_identifier = $1 ORDER BY created_date DESC LIMIT $2 OFFSET $3"#,
+        )
+        .bind(user_identifier)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            beneficiaries,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn delete(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<(), RepositoryError> {
+        let result =
+            sqlx::query("DELETE FROM beneficiaries WHERE identifier = $1 AND user_identifier = $2")
+                .bind(identifier)
+                .bind(user_identifier)
+                .execute(&self.pool)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::RecordNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use crate::{
+    beneficiaries::handlers::{
+        create_beneficiary, delete_beneficiary, fetch_beneficiaries, fetch_beneficiary,
+    },
+    state::AppState,
+};
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+
+pub fn beneficiary_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/", post(create_beneficiary))
+        .route("/", get(fetch_beneficiaries))
+        .route("/{identifier}", get(fetch_beneficiary))
+        .route("/{identifier}", delete(delete_beneficiary))
+        .with_state(state.clone())
+}
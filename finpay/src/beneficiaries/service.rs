@@ -0,0 +1,129 @@
+use crate::authentication::claims::Claims;
+use crate::banks::repository::{BankRepository, BankRepositoryExt};
+use crate::beneficiaries::adapters::CreateBeneficiaryRequest;
+use crate::beneficiaries::entities::Beneficiary;
+use crate::beneficiaries::repository::{BeneficiaryRepository, BeneficiaryRepositoryExt};
+use crate::compliance::adapters::ScreeningSubject;
+use crate::compliance::entities::ScreeningSubjectType;
+use crate::compliance::service::{ComplianceService, ComplianceServiceExt};
+use crate::errors::{RepositoryError, ServiceError};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct BeneficiaryService {
+    repository: BeneficiaryRepository,
+    bank_repository: BankRepository,
+    compliance_service: ComplianceService,
+}
+
+impl BeneficiaryService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: BeneficiaryRepository::new(pool),
+            bank_repository: BankRepository::new(pool),
+            compliance_service: ComplianceService::new(pool),
+        }
+    }
+}
+
+pub trait BeneficiaryServiceExt {
+    fn create_beneficiary(
+        &self,
+        claims: &Claims,
+        request: &CreateBeneficiaryRequest,
+    ) -> impl std::future::Future<Output = Result<Beneficiary, ServiceError>> + Send;
+
+    fn fetch_beneficiary(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Beneficiary, ServiceError>> + Send;
+
+    fn fetch_beneficiaries(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Beneficiary>, ServiceError>> + Send;
+
+    fn delete_beneficiary(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<(), ServiceError>> + Send;
+}
+
+impl BeneficiaryServiceExt for BeneficiaryService {
+    async fn create_beneficiary(
+        &self,
+        claims: &Claims,
+        request: &CreateBeneficiaryRequest,
+    ) -> Result<Beneficiary, ServiceError> {
+        if !request.account_number.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ServiceError::UnprocessableEntity(
+                "account number must only contain digits".to_string(),
+            ));
+        }
+
+        // surfaces an unknown bank as a 404 rather than a foreign key violation
+        self.bank_repository
+            .find_by_identifier(&request.bank_identifier)
+            .await?;
+
+        let mut subject = ScreeningSubject {
+            subject_type: ScreeningSubjectType::Beneficiary,
+            subject_identifier: None,
+            user_identifier: Some(claims.user_identifier),
+            name: request.account_name.clone(),
+            reference: format!("{}/{}", request.bank_identifier, request.account_number),
+        };
+        let screening = self.compliance_service.guard(&subject).await?;
+
+        let beneficiary = self
+            .repository
+            .create(request, &claims.user_identifier)
+            .await?;
+        subject.subject_identifier = Some(beneficiary.identifier);
+        self.compliance_service
+            .record_screening(&subject, &screening)
+            .await?;
+        Ok(beneficiary)
+    }
+
+    async fn fetch_beneficiary(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<Beneficiary, ServiceError> {
+        let beneficiary = self
+            .repository
+            .fetch_beneficiary(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(beneficiary)
+    }
+
+    async fn fetch_beneficiaries(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Beneficiary>, ServiceError> {
+        let beneficiaries = self
+            .repository
+            .fetch_beneficiaries(&claims.user_identifier, pagination_params)
+            .await?;
+        Ok(beneficiaries)
+    }
+
+    async fn delete_beneficiary(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<(), ServiceError> {
+        self.repository
+            .delete(identifier, &claims.user_identifier)
+            .await?;
+        Ok(())
+    }
+}
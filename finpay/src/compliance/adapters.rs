@@ -0,0 +1,125 @@
+use crate::compliance::entities::{ComplianceCaseStatus, ScreeningSubjectType, normalize_name};
+use axum_typed_multipart::{FieldData, TryFromMultipart};
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+use uuid::Uuid;
+use validator::Validate;
+
+/// A watch list as a csv with a name column and optional reference and aliases columns, aliases
+/// separated by semicolons, or as a json array of entries
+#[derive(TryFromMultipart)]
+#[try_from_multipart(rename_all = "camelCase")]
+pub struct ImportWatchListRequest {
+    /// where the list came from, e.g. the issuing authority
+    pub source: Option<String>,
+    #[form_data(limit = "20MiB")]
+    pub file: FieldData<NamedTempFile>,
+}
+
+/// One line of an uploaded csv
+#[derive(Deserialize, Debug)]
+pub struct WatchListRow {
+    pub name: String,
+    pub reference: Option<String>,
+    pub aliases: Option<String>,
+}
+
+/// One entry of an uploaded json list
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchListEntryRequest {
+    pub name: String,
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+impl From<WatchListRow> for WatchListEntryRequest {
+    fn from(row: WatchListRow) -> Self {
+        Self {
+            name: row.name,
+            reference: row.reference.filter(|reference| !reference.is_empty()),
+            aliases: row
+                .aliases
+                .map(|aliases| aliases.split(';').map(str::to_string).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NewWatchListEntry {
+    pub name: String,
+    pub normalized_name: String,
+    pub reference: Option<String>,
+}
+
+impl WatchListEntryRequest {
+    /// the listed name and each of its aliases as entries of their own, blank names are dropped
+    pub fn into_entries(self) -> Vec<NewWatchListEntry> {
+        let reference = self.reference;
+        std::iter::once(self.name)
+            .chain(self.aliases)
+            .map(|name| name.trim().to_string())
+            .filter(|name| !normalize_name(name).is_empty())
+            .map(|name| NewWatchListEntry {
+                normalized_name: normalize_name(&name),
+                name,
+                reference: reference.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Who or what is being screened
+#[derive(Debug, Clone)]
+pub struct ScreeningSubject {
+    pub subject_type: ScreeningSubjectType,
+    pub subject_identifier: Option<Uuid>,
+    /// the customer the subject belongs to, the user themselves or the owner of a beneficiary
+    pub user_identifier: Option<Uuid>,
+    pub name: String,
+    pub reference: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceCaseOutcome {
+    Cleared,
+    Confirmed,
+}
+
+impl From<ComplianceCaseOutcome> for ComplianceCaseStatus {
+    fn from(outcome: ComplianceCaseOutcome) -> Self {
+        match outcome {
+            ComplianceCaseOutcome::Cleared => ComplianceCaseStatus::Cleared,
+            ComplianceCaseOutcome::Confirmed => ComplianceCaseStatus::Confirmed,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DecideComplianceCaseRequest {
+    pub outcome: ComplianceCaseOutcome,
+    #[validate(length(
+        min = 1,
+        max = 500,
+        message = "note must be between 1 and 500 characters",
+        code = "note"
+    ))]
+    pub note: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplianceCaseQuery {
+    pub status: Option<ComplianceCaseStatus>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreeningResultQuery {
+    /// only the screenings that matched the list
+    pub hits: Option<bool>,
+}
@@ -0,0 +1,203 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "screening_subject_type_enum")]
+pub enum ScreeningSubjectType {
+    User,
+    Beneficiary,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "compliance_case_status_enum")]
+pub enum ComplianceCaseStatus {
+    Open,
+    /// a false positive, the same match no longer blocks the subject
+    Cleared,
+    /// the subject is the listed party
+    Confirmed,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchListImport {
+    pub identifier: Uuid,
+    pub file_name: String,
+    pub source: Option<String>,
+    pub entry_count: i32,
+    pub uploaded_by: Option<Uuid>,
+    pub created_date: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchListEntry {
+    pub identifier: Uuid,
+    pub watch_list_import_identifier: Uuid,
+    pub name: String,
+    pub normalized_name: String,
+    pub reference: Option<String>,
+}
+
+/// The closest entry on the list to a name, a hit once the score reaches the threshold
+#[derive(Debug, Clone)]
+pub struct ScreeningMatch {
+    pub watch_list_import_identifier: Option<Uuid>,
+    pub matched_name: Option<String>,
+    pub matched_reference: Option<String>,
+    pub score: f64,
+    pub threshold: f64,
+    /// the same match was cleared as a false positive before
+    pub cleared: bool,
+}
+
+impl ScreeningMatch {
+    /// Compares a name against every entry on the list and keeps the closest one
+    pub fn against(entries: &[WatchListEntry], name: &str, threshold: f64) -> Self {
+        let normalized_name = normalize_name(name);
+        let closest = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry,
+                    name_similarity(&normalized_name, &entry.normalized_name),
+                )
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        Self {
+            watch_list_import_identifier: closest
+                .map(|(entry, _)| entry.watch_list_import_identifier),
+            matched_name: closest.map(|(entry, _)| entry.name.clone()),
+            matched_reference: closest.and_then(|(entry, _)| entry.reference.clone()),
+            score: closest.map(|(_, score)| score).unwrap_or_default(),
+            threshold,
+            cleared: false,
+        }
+    }
+
+    pub fn is_hit(&self) -> bool {
+        self.matched_name.is_some() && self.score >= self.threshold && !self.cleared
+    }
+}
+
+/// Lower cases a name, drops punctuation and sorts its parts, so that "DOE, John" and
+/// "john doe" compare equal
+pub fn normalize_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .to_lowercase();
+    let mut parts: Vec<&str> = cleaned.split_whitespace().collect();
+    parts.sort_unstable();
+    parts.join(" ")
+}
+
+/// How alike two normalized names are, from 0 for nothing in common to 1 for the same name,
+/// based on the edit distance between them
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreeningResult {
+    pub identifier: Uuid,
+    pub subject_type: ScreeningSubjectType,
+    /// the user or beneficiary screened, not set when the action was blocked before it was
+    /// created
+    pub subject_identifier: Option<Uuid>,
+    pub user_identifier: Option<Uuid>,
+    pub subject_name: String,
+    /// the email of a user, the bank and account number of a beneficiary
+    pub subject_reference: String,
+    pub watch_list_import_identifier: Option<Uuid>,
+    pub matched_name: Option<String>,
+    pub matched_reference: Option<String>,
+    pub score: f64,
+    pub is_hit: bool,
+    pub created_date: DateTime<Local>,
+}
+
+/// A hit waiting for, or given, a compliance decision
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplianceCase {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub screening_result_identifier: Uuid,
+    pub subject_type: ScreeningSubjectType,
+    pub subject_identifier: Option<Uuid>,
+    pub user_identifier: Option<Uuid>,
+    pub subject_name: String,
+    pub subject_reference: String,
+    pub matched_name: String,
+    pub score: f64,
+    pub status: ComplianceCaseStatus,
+    pub decision_note: Option<String>,
+    pub decided_by: Option<Uuid>,
+    pub decided_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> WatchListEntry {
+        WatchListEntry {
+            identifier: Uuid::new_v4(),
+            watch_list_import_identifier: Uuid::new_v4(),
+            name: name.to_string(),
+            normalized_name: normalize_name(name),
+            reference: None,
+        }
+    }
+
+    #[test]
+    fn test_names_are_compared_regardless_of_order_case_and_punctuation() {
+        assert_eq!(normalize_name("DOE, John  "), normalize_name("john doe"));
+        assert_eq!(name_similarity("john doe", "john doe"), 1.0);
+        assert!(name_similarity(&normalize_name("Jon Doe"), &normalize_name("John Doe")) > 0.85);
+        assert!(name_similarity(&normalize_name("Jane Smith"), &normalize_name("John Doe")) < 0.5);
+    }
+
+    #[test]
+    fn test_closest_entry_is_a_hit_only_at_the_threshold() {
+        let entries = vec![entry("Ivan Petrov"), entry("John Doe")];
+
+        let close = ScreeningMatch::against(&entries, "Ivan Petrof", 0.85);
+        assert_eq!(close.matched_name.as_deref(), Some("Ivan Petrov"));
+        assert!(close.is_hit());
+
+        let strict = ScreeningMatch::against(&entries, "Ivan Petrof", 0.95);
+        assert!(!strict.is_hit());
+
+        let empty_list = ScreeningMatch::against(&[], "Ivan Petrov", 0.85);
+        assert!(!empty_list.is_hit());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::compliance::adapters::{
+    ComplianceCaseQuery, DecideComplianceCaseRequest, ImportWatchListRequest, ScreeningResultQuery,
+};
+use crate::compliance::entities::{ComplianceCase, ScreeningResult, WatchListImport};
+use crate::compliance::service::{ComplianceService, ComplianceServiceExt};
+use crate::errors::ServiceError;
+use crate::shared::middlewares::admin_middleware::AdminClaims;
+use crate::shared::middlewares::validator::ValidatedRequest;
+use crate::utils::{ApiResponse, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum_typed_multipart::TypedMultipart;
+use uuid::Uuid;
+
+pub async fn import_watch_list(
+    State(compliance_service): State<ComplianceService>,
+    AdminClaims(claims): AdminClaims,
+    request: TypedMultipart<ImportWatchListRequest>,
+) -> Result<ApiResponse<WatchListImport>, ServiceError> {
+    let watch_list_import = compliance_service
+        .import_watch_list(&claims, request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(watch_list_import)
+        .message("watch list imported successfully, re-screening has started")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_watch_list_imports(
+    State(compliance_service): State<ComplianceService>,
+    _: AdminClaims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<WatchListImport>>, ServiceError> {
+    let imports = compliance_service.fetch_imports(&pagination_params).await?;
+
+    Ok(ApiResponse::builder().data(imports).build())
+}
+
+pub async fn fetch_screening_results(
+    State(compliance_service): State<ComplianceService>,
+    _: AdminClaims,
+    Query(query): Query<ScreeningResultQuery>,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<ScreeningResult>>, ServiceError> {
+    let results = compliance_service
+        .fetch_results(&query, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(results).build())
+}
+
+pub async fn fetch_compliance_cases(
+    State(compliance_service): State<ComplianceService>,
+    _: AdminClaims,
+    Query(query): Query<ComplianceCaseQuery>,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<ComplianceCase>>, ServiceError> {
+    let cases = compliance_service
+        .fetch_cases(&query, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(cases).build())
+}
+
+pub async fn decide_compliance_case(
+    State(compliance_service): State<ComplianceService>,
+    AdminClaims(claims): AdminClaims,
+    Path(identifier): Path<Uuid>,
+    ValidatedRequest(request): ValidatedRequest<DecideComplianceCaseRequest>,
+) -> Result<ApiResponse<ComplianceCase>, ServiceError> {
+    let case = compliance_service
+        .decide_case(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(case)
+        .message("compliance case decided successfully")
+        .build())
+}
@@ -0,0 +1,662 @@
+use crate::compliance::adapters::{
+    ComplianceCaseQuery, DecideComplianceCaseRequest, NewWatchListEntry, ScreeningResultQuery,
+    ScreeningSubject,
+};
+use crate::compliance::entities::{
+    ComplianceCase, ComplianceCaseStatus, ScreeningMatch, ScreeningResult, ScreeningSubjectType,
+    WatchListEntry, WatchListImport,
+};
+use crate::errors::RepositoryError;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use crate::wallet::entities::{WalletFreezeOrigin, WalletStatus};
+use finpay_utils::generate_reference;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ComplianceRepository {
+    pool: PgPool,
+}
+
+impl ComplianceRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+pub trait ComplianceRepositoryExt {
+    /// Swaps the whole watch list for the entries of a new import
+    fn replace_watch_list(
+        &self,
+        file_name: &str,
+        source: Option<&str>,
+        uploaded_by: &Uuid,
+        entries: &[NewWatchListEntry],
+    ) -> impl std::future::Future<Output = Result<WatchListImport, RepositoryError>> + Send;
+
+    fn fetch_watch_list(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<WatchListEntry>, RepositoryError>> + Send;
+
+    /// the import the current list came from, none before the first one
+    fn fetch_current_import_identifier(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Option<Uuid>, RepositoryError>> + Send;
+
+    fn fetch_imports(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<WatchListImport>, RepositoryError>,
+    > + Send;
+
+    /// whether compliance already cleared this subject against the listed name
+    fn is_cleared(
+        &self,
+        subject: &ScreeningSubject,
+        matched_name: &str,
+    ) -> impl std::future::Future<Output = Result<bool, RepositoryError>> + Send;
+
+    /// Stores a screening and opens a case for a hit, unless the same match is already open
+    fn record_screening(
+        &self,
+        subject: &ScreeningSubject,
+        screening: &ScreeningMatch,
+    ) -> impl std::future::Future<Output = Result<ScreeningResult, RepositoryError>> + Send;
+
+    /// Every user and beneficiary as they are screened
+    fn fetch_screening_subjects(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<ScreeningSubject>, RepositoryError>> + Send;
+
+    fn fetch_results(
+        &self,
+        query: &ScreeningResultQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<ScreeningResult>, RepositoryError>,
+    > + Send;
+
+    fn fetch_cases(
+        &self,
+        query: &ComplianceCaseQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<ComplianceCase>, RepositoryError>,
+    > + Send;
+
+    /// Clears or confirms an open case. Confirming it freezes every wallet of a listed user, the
+    /// way an administrator would, or blocks a listed beneficiary from being paid
+    fn decide_case(
+        &self,
+        identifier: &Uuid,
+        request: &DecideComplianceCaseRequest,
+        decided_by: &Uuid,
+    ) -> impl std::future::Future<Output = Result<ComplianceCase, RepositoryError>> + Send;
+}
+
+impl ComplianceRepositoryExt for ComplianceRepository {
+    async fn replace_watch_list(
+        &self,
+        file_name: &str,
+        source: Option<&str>,
+        uploaded_by: &Uuid,
+        entries: &[NewWatchListEntry],
+    ) -> Result<WatchListImport, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = r#"
+        INSERT INTO watch_list_imports (identifier, file_name, source, entry_count, uploaded_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#;
+        let watch_list_import = sqlx::query_as::<_, WatchListImport>(query)
+            .bind(Uuid::new_v4())
+            .bind(file_name)
+            .bind(source)
+            .bind(entries.len() as i32)
+            .bind(uploaded_by)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        sqlx::query(r#"DELETE FROM watch_list_entries WHERE watch_list_import_identifier <> $1"#)
+            .bind(watch_list_import.identifier)
+            .execute(&mut *tx)
+            .await?;
+
+        let query = r#"
+        INSERT INTO watch_list_entries (identifier, watch_list_import_identifier, name,
+                                        normalized_name, reference)
+        VALUES ($1, $2, $3, $4, $5)
+        "#;
+        for entry in entries {
+            sqlx::query(query)
+                .bind(Uuid::new_v4())
+                .bind(watch_list_import.identifier)
+                .bind(&entry.name)
+                .bind(&entry.normalized_name)
+                .bind(&entry.reference)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(watch_list_import)
+    }
+
+    async fn fetch_watch_list(&self) -> Result<Vec<WatchListEntry>, RepositoryError> {
+        let entries = sqlx::query_as::<_, WatchListEntry>(r#"SELECT * FROM watch_list_entries"#)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(entries)
+    }
+
+    async fn fetch_current_import_identifier(&self) -> Result<Option<Uuid>, RepositoryError> {
+        sqlx::query_scalar(
+            r#"SELECT identifier FROM watch_list_imports ORDER BY created_date DESC LIMIT 1"#,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_imports(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<WatchListImport>, RepositoryError> {
+        let total_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(identifier) FROM watch_list_imports")
+                .fetch_one(&self.pool)
+                .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let imports = sqlx::query_as::<_, WatchListImport>(
+            r#"SELECT * FROM watch_list_imports ORDER BY created_date DESC LIMIT $1 OFFSET $2"#,
+        )
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            imports,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn is_cleared(
+        &self,
+        subject: &ScreeningSubject,
+        matched_name: &str,
+    ) -> Result<bool, RepositoryError> {
+        let query = r#"
+        SELECT EXISTS(SELECT 1
+                      FROM compliance_cases
+                      WHERE subject_type = $1
+                        AND subject_reference = $2
+                        AND matched_name = $3
+                        AND status = $4)
+        "#;
+        let cleared: bool = sqlx::query_scalar(query)
+            .bind(subject.subject_type)
+            .bind(&subject.reference)
+            .bind(matched_name)
+            .bind(ComplianceCaseStatus::Cleared)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(cleared)
+    }
+
+    async fn record_screening(
+        &self,
+        subject: &ScreeningSubject,
+        screening: &ScreeningMatch,
+    ) -> Result<ScreeningResult, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = r#"
+        INSERT INTO screening_results (identifier, subject_type, subject_identifier, user_identifier,
+                                       subject_name, subject_reference,
+                                       watch_list_import_identifier, matched_name,
+                                       matched_reference, score, is_hit)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#;
+        let result = sqlx::query_as::<_, ScreeningResult>(query)
+            .bind(Uuid::new_v4())
+            .bind(subject.subject_type)
+            .bind(subject.subject_identifier)
+            .bind(subject.user_identifier)
+            .bind(&subject.name)
+            .bind(&subject.reference)
+            .bind(screening.watch_list_import_identifier)
+            .bind(&screening.matched_name)
+            .bind(&screening.matched_reference)
+            .bind(screening.score)
+            .bind(screening.is_hit())
+            .fetch_one(&mut *tx)
+            .await?;
+
+        // a match compliance already confirmed stays blocked without being raised again
+        if result.is_hit {
+            let query = r#"
+            INSERT INTO compliance_cases (identifier, reference, screening_result_identifier,
+                                          subject_type, subject_identifier, user_identifier,
+                                          subject_name, subject_reference, matched_name, score)
+            SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+            WHERE NOT EXISTS(SELECT 1
+                             FROM compliance_cases
+                             WHERE subject_type = $4
+                               AND subject_reference = $8
+                               AND matched_name = $9
+                               AND status = 'confirmed')
+            ON CONFLICT (subject_type, subject_reference, matched_name) WHERE status = 'open'
+                DO NOTHING
+            "#;
+            sqlx::query(query)
+                .bind(Uuid::new_v4())
+                .bind(generate_reference("CMP"))
+                .bind(result.identifier)
+                .bind(result.subject_type)
+                .bind(result.subject_identifier)
+                .bind(result.user_identifier)
+                .bind(&result.subject_name)
+                .bind(&result.subject_reference)
+                .bind(&result.matched_name)
+                .bind(result.score)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(result)
+    }
+
+    async fn fetch_screening_subjects(&self) -> Result<Vec<ScreeningSubject>, RepositoryError> {
+        let users = sqlx::query_as::<_, (Uuid, String, String)>(
+            r#"SELECT identifier, first_name || ' ' || last_name, email FROM users"#,
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|(identifier, name, email)| ScreeningSubject {
+            subject_type: ScreeningSubjectType::User,
+            subject_identifier: Some(identifier),
+            user_identifier: Some(identifier),
+            name,
+            reference: email,
+        });
+
+        let query = r#"
+        SELECT identifier, user_identifier, account_name, bank_identifier::TEXT || '/' || account_number
+        FROM beneficiaries
+        "#;
+        let beneficiaries = sqlx::query_as::<_, (Uuid, Uuid, String, String)>(query)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(
+                |(identifier, user_identifier, account_name, reference)| ScreeningSubject {
+                    subject_type: ScreeningSubjectType::Beneficiary,
+                    subject_identifier: Some(identifier),
+                    user_identifier: Some(user_identifier),
+                    name: account_name,
+                    reference,
+                },
+            );
+
+        Ok(users.chain(beneficiaries).collect())
+    }
+
+    async fn fetch_results(
+        &self,
+        query: &ScreeningResultQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<ScreeningResult>, RepositoryError> {
+        let filter = r#"WHERE ($1::BOOLEAN IS NULL OR is_hit = $1)"#;
+
+        let total_count: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(identifier) FROM screening_results {filter}"
+        ))
+        .bind(query.hits)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let results = sqlx::query_as::<_, ScreeningResult>(&format!(
+            "SELECT * FROM screening_results {filter} ORDER BY created_date DESC LIMIT $2 OFFSET $3"
+        ))
+        .bind(query.hits)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            results,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn fetch_cases(
+        &self,
+        query: &ComplianceCaseQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<ComplianceCase>, RepositoryError> {
+        let filter = r#"WHERE ($1::compliance_case_status_enum IS NULL OR status = $1)"#;
+
+        let total_count: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(identifier) FROM compliance_cases {filter}"
+        ))
+        .bind(query.status)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        // the closest matches first, they are the most likely to be the listed party
+        let cases = sqlx::query_as::<_, ComplianceCase>(&format!(
+            "SELECT * FROM compliance_cases {filter} ORDER BY score DESC, created_date LIMIT $2 OFFSET $3"
+        ))
+        .bind(query.status)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            cases,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn decide_case(
+        &self,
+        identifier: &Uuid,
+        request: &DecideComplianceCaseRequest,
+        decided_by: &Uuid,
+    ) -> Result<ComplianceCase, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = r#"
+        UPDATE compliance_cases
+        SET status        = $1,
+            decision_note = $2,
+            decided_by    = $3,
+            decided_date  = NOW()
+        WHERE identifier = $4
+          AND status = $5
+        RETURNING *
+        "#;
+        let case = sqlx::query_as::<_, ComplianceCase>(query)
+            .bind(ComplianceCaseStatus::from(request.outcome))
+            .bind(&request.note)
+            .bind(decided_by)
+            .bind(identifier)
+            .bind(ComplianceCaseStatus::Open)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        match case {
+            Some(case) => {
+                match (case.status, case.subject_type) {
+                    (ComplianceCaseStatus::Confirmed, ScreeningSubjectType::User) => {
+                        sqlx::query(
+                            r#"UPDATE wallets SET status = $1, frozen_by = $2 WHERE user_identifier = $3 AND status <> $4"#,
+                        )
+                        .bind(WalletStatus::Frozen)
+                        .bind(WalletFreezeOrigin::Admin)
+                        .bind(case.subject_identifier)
+                        .bind(WalletStatus::Closed)
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                    (ComplianceCaseStatus::Confirmed, ScreeningSubjectType::Beneficiary) => {
+                        sqlx::query(
+                            r#"UPDATE beneficiaries SET blocked_date = COALESCE(blocked_date, NOW()) WHERE identifier = $1"#,
+                        )
+                        .bind(case.subject_identifier)
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                    _ => {}
+                }
+
+                tx.commit().await?;
+                Ok(case)
+            }
+            None => {
+                sqlx::query_scalar::<_, Uuid>(
+                    r#"SELECT identifier FROM compliance_cases WHERE identifier = $1"#,
+                )
+                .bind(identifier)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or(RepositoryError::RecordNotFound)?;
+                Err(RepositoryError::InvalidCaseState)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beneficiaries::adapters::CreateBeneficiaryRequest;
+    use crate::beneficiaries::repository::{BeneficiaryRepository, BeneficiaryRepositoryExt};
+    use crate::compliance::adapters::{ComplianceCaseOutcome, WatchListEntryRequest};
+    use crate::shared::fixtures::{UAE_DIRHAM, create_user, create_wallet};
+    use chrono::{DateTime, Local};
+    use std::str::FromStr;
+
+    const THRESHOLD: f64 = 0.85;
+
+    async fn load_list(pool: &PgPool, uploaded_by: &Uuid, names: &[&str]) -> Vec<WatchListEntry> {
+        let repository = ComplianceRepository::new(pool);
+
+        let entries: Vec<NewWatchListEntry> = names
+            .iter()
+            .flat_map(|name| {
+                WatchListEntryRequest {
+                    name: name.to_string(),
+                    reference: None,
+                    aliases: vec![],
+                }
+                .into_entries()
+            })
+            .collect();
+        repository
+            .replace_watch_list("list.csv", None, uploaded_by, &entries)
+            .await
+            .expect("failed to load watch list");
+        repository.fetch_watch_list().await.unwrap()
+    }
+
+    fn subject(name: &str) -> ScreeningSubject {
+        ScreeningSubject {
+            subject_type: ScreeningSubjectType::User,
+            subject_identifier: None,
+            user_identifier: None,
+            name: name.to_string(),
+            reference: "ivan@example.com".to_string(),
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_import_replaces_the_previous_list(pool: PgPool) {
+        let admin = create_user(&pool).await;
+        load_list(&pool, &admin, &["Ivan Petrov", "John Doe"]).await;
+        let entries = load_list(&pool, &admin, &["Maria Ivanova"]).await;
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "Maria Ivanova");
+    }
+
+    #[sqlx::test]
+    async fn test_hit_opens_one_case_until_it_is_cleared(pool: PgPool) {
+        let repository = ComplianceRepository::new(&pool);
+        let officer = create_user(&pool).await;
+        let entries = load_list(&pool, &officer, &["Ivan Petrov"]).await;
+        let subject = subject("Ivan Petrof");
+
+        let screening = ScreeningMatch::against(&entries, &subject.name, THRESHOLD);
+        assert!(screening.is_hit());
+        repository
+            .record_screening(&subject, &screening)
+            .await
+            .unwrap();
+        // screening the same subject again does not open a second case
+        repository
+            .record_screening(&subject, &screening)
+            .await
+            .unwrap();
+
+        let cases = repository
+            .fetch_cases(
+                &ComplianceCaseQuery {
+                    status: Some(ComplianceCaseStatus::Open),
+                },
+                &PaginationParams::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(cases.records.len(), 1);
+        let results = repository
+            .fetch_results(
+                &ScreeningResultQuery { hits: Some(true) },
+                &PaginationParams::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.records.len(), 2);
+
+        let request = DecideComplianceCaseRequest {
+            outcome: ComplianceCaseOutcome::Cleared,
+            note: "different date of birth".to_string(),
+        };
+        let case = repository
+            .decide_case(&cases.records[0].identifier, &request, &officer)
+            .await
+            .expect("failed to clear case");
+        assert_eq!(case.status, ComplianceCaseStatus::Cleared);
+        assert!(
+            repository
+                .is_cleared(&subject, "Ivan Petrov")
+                .await
+                .unwrap()
+        );
+
+        let decided_again = repository
+            .decide_case(&case.identifier, &request, &officer)
+            .await;
+        assert!(matches!(
+            decided_again,
+            Err(RepositoryError::InvalidCaseState)
+        ));
+    }
+
+    /// Screens a subject against a list naming them and confirms the case it opens
+    async fn confirm(pool: &PgPool, officer: &Uuid, subject: &ScreeningSubject) -> ComplianceCase {
+        let repository = ComplianceRepository::new(pool);
+        let entries = load_list(pool, officer, &["Ivan Petrov"]).await;
+        let screening = ScreeningMatch::against(&entries, &subject.name, THRESHOLD);
+        repository
+            .record_screening(subject, &screening)
+            .await
+            .unwrap();
+
+        let cases = repository
+            .fetch_cases(
+                &ComplianceCaseQuery {
+                    status: Some(ComplianceCaseStatus::Open),
+                },
+                &PaginationParams::default(),
+            )
+            .await
+            .unwrap();
+        let request = DecideComplianceCaseRequest {
+            outcome: ComplianceCaseOutcome::Confirmed,
+            note: "same date of birth".to_string(),
+        };
+        repository
+            .decide_case(&cases.records[0].identifier, &request, officer)
+            .await
+            .expect("failed to confirm case")
+    }
+
+    #[sqlx::test]
+    async fn test_confirmed_user_has_their_wallets_frozen(pool: PgPool) {
+        let officer = create_user(&pool).await;
+        let user_identifier = create_user(&pool).await;
+        let wallet_identifier = create_wallet(&pool, &user_identifier).await;
+
+        let subject = ScreeningSubject {
+            subject_identifier: Some(user_identifier),
+            user_identifier: Some(user_identifier),
+            ..subject("Ivan Petrov")
+        };
+        confirm(&pool, &officer, &subject).await;
+
+        let (status, frozen_by): (WalletStatus, Option<WalletFreezeOrigin>) =
+            sqlx::query_as("SELECT status, frozen_by FROM wallets WHERE identifier = $1")
+                .bind(wallet_identifier)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, WalletStatus::Frozen);
+        assert_eq!(frozen_by, Some(WalletFreezeOrigin::Admin));
+    }
+
+    #[sqlx::test]
+    async fn test_confirmed_beneficiary_is_blocked(pool: PgPool) {
+        let officer = create_user(&pool).await;
+        let user_identifier = create_user(&pool).await;
+        let bank_identifier: Uuid = sqlx::query_scalar(
+            "SELECT identifier FROM banks WHERE country_identifier = $1 LIMIT 1",
+        )
+        .bind(Uuid::from_str(UAE_DIRHAM).unwrap())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let beneficiary = BeneficiaryRepository::new(&pool)
+            .create(
+                &CreateBeneficiaryRequest {
+                    bank_identifier,
+                    account_number: "0123456789".to_string(),
+                    account_name: "Ivan Petrov".to_string(),
+                },
+                &user_identifier,
+            )
+            .await
+            .expect("failed to create beneficiary");
+        assert!(beneficiary.blocked_date.is_none());
+
+        let subject = ScreeningSubject {
+            subject_type: ScreeningSubjectType::Beneficiary,
+            subject_identifier: Some(beneficiary.identifier),
+            user_identifier: Some(user_identifier),
+            name: beneficiary.account_name.clone(),
+            reference: format!("{}/{}", bank_identifier, beneficiary.account_number),
+        };
+        confirm(&pool, &officer, &subject).await;
+
+        let blocked_date: Option<DateTime<Local>> =
+            sqlx::query_scalar("SELECT blocked_date FROM beneficiaries WHERE identifier = $1")
+                .bind(beneficiary.identifier)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(blocked_date.is_some());
+    }
+}
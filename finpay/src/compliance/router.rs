@@ -0,0 +1,19 @@
+use crate::compliance::handlers::{
+    decide_compliance_case, fetch_compliance_cases, fetch_screening_results,
+    fetch_watch_list_imports, import_watch_list,
+};
+use crate::state::AppState;
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+pub fn admin_compliance_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/watch-list", post(import_watch_list))
+        .route("/watch-list/imports", get(fetch_watch_list_imports))
+        .route("/screenings", get(fetch_screening_results))
+        .route("/cases", get(fetch_compliance_cases))
+        .route("/cases/{identifier}/decision", post(decide_compliance_case))
+        .with_state(state.clone())
+}
@@ -0,0 +1,343 @@
+use crate::authentication::claims::Claims;
+use crate::compliance::adapters::{
+    ComplianceCaseQuery, DecideComplianceCaseRequest, ImportWatchListRequest, NewWatchListEntry,
+    ScreeningResultQuery, ScreeningSubject, WatchListEntryRequest, WatchListRow,
+};
+use crate::compliance::entities::{
+    ComplianceCase, ScreeningMatch, ScreeningResult, WatchListEntry, WatchListImport,
+};
+use crate::compliance::repository::{ComplianceRepository, ComplianceRepositoryExt};
+use crate::errors::ServiceError;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use axum_typed_multipart::TypedMultipart;
+use finpay_utils::extract_env;
+use sqlx::PgPool;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// The watch list as of one import, kept until a newer import replaces it
+struct LoadedWatchList {
+    import_identifier: Option<Uuid>,
+    entries: Arc<Vec<WatchListEntry>>,
+}
+
+#[derive(Clone)]
+pub struct ComplianceService {
+    repository: ComplianceRepository,
+    watch_list: Arc<RwLock<Option<LoadedWatchList>>>,
+}
+
+impl ComplianceService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: ComplianceRepository::new(pool),
+            watch_list: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// The current watch list, only read from the database again once a new list was imported
+    async fn watch_list(&self) -> Result<Arc<Vec<WatchListEntry>>, ServiceError> {
+        let import_identifier = self.repository.fetch_current_import_identifier().await?;
+        let cached = self
+            .watch_list
+            .read()
+            .await
+            .as_ref()
+            .filter(|loaded| loaded.import_identifier == import_identifier)
+            .map(|loaded| loaded.entries.clone());
+        if let Some(entries) = cached {
+            return Ok(entries);
+        }
+
+        let entries = Arc::new(self.repository.fetch_watch_list().await?);
+        *self.watch_list.write().await = Some(LoadedWatchList {
+            import_identifier,
+            entries: entries.clone(),
+        });
+        Ok(entries)
+    }
+
+    /// A hit on a match cleared as a false positive before is not a hit
+    async fn apply_clearance(
+        &self,
+        subject: &ScreeningSubject,
+        mut screening: ScreeningMatch,
+    ) -> Result<ScreeningMatch, ServiceError> {
+        if screening.is_hit() {
+            let matched_name = screening.matched_name.clone().unwrap_or_default();
+            screening.cleared = self.repository.is_cleared(subject, &matched_name).await?;
+        }
+        Ok(screening)
+    }
+}
+
+/// Reads an uploaded list, as json when the file says so and as csv otherwise
+fn read_watch_list_file(
+    path: &Path,
+    file_name: &str,
+) -> Result<Vec<NewWatchListEntry>, ServiceError> {
+    let requests: Vec<WatchListEntryRequest> = if file_name.to_lowercase().ends_with(".json") {
+        let contents = std::fs::read(path).map_err(|err| {
+            log::error!("error reading watch list due to {err}");
+            ServiceError::OperationFailed
+        })?;
+        serde_json::from_slice(&contents).map_err(|err| {
+            ServiceError::UnprocessableEntity(format!("the file is not a valid watch list: {err}"))
+        })?
+    } else {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)
+            .map_err(|err| {
+                log::error!("error reading watch list due to {err}");
+                ServiceError::OperationFailed
+            })?;
+        reader
+            .deserialize::<WatchListRow>()
+            .map(|row| row.map(WatchListEntryRequest::from))
+            .collect::<Result<_, _>>()
+            .map_err(|err| {
+                ServiceError::UnprocessableEntity(format!(
+                    "the file is not a valid watch list: {err}"
+                ))
+            })?
+    };
+
+    let entries: Vec<NewWatchListEntry> = requests
+        .into_iter()
+        .flat_map(WatchListEntryRequest::into_entries)
+        .collect();
+    if entries.is_empty() {
+        return Err(ServiceError::UnprocessableEntity(
+            "the file has no entries".to_string(),
+        ));
+    }
+
+    Ok(entries)
+}
+
+pub trait ComplianceServiceExt {
+    /// Screens a subject before the action creating it goes ahead. A hit is recorded, opens a
+    /// compliance case and comes back as `ScreeningHit`, a clear screening is handed back to be
+    /// recorded once the subject exists
+    fn guard(
+        &self,
+        subject: &ScreeningSubject,
+    ) -> impl std::future::Future<Output = Result<ScreeningMatch, ServiceError>> + Send;
+
+    /// Screens a subject that already exists, a hit is recorded and opens a compliance case
+    fn screen_and_record(
+        &self,
+        subject: &ScreeningSubject,
+    ) -> impl std::future::Future<Output = Result<ScreeningMatch, ServiceError>> + Send;
+
+    fn record_screening(
+        &self,
+        subject: &ScreeningSubject,
+        screening: &ScreeningMatch,
+    ) -> impl std::future::Future<Output = Result<ScreeningResult, ServiceError>> + Send;
+
+    /// Replaces the watch list with an uploaded one, everyone is re-screened in the background
+    fn import_watch_list(
+        &self,
+        claims: &Claims,
+        request: TypedMultipart<ImportWatchListRequest>,
+    ) -> impl std::future::Future<Output = Result<WatchListImport, ServiceError>> + Send;
+
+    /// Screens every user and beneficiary against the current list, returns the number of hits.
+    /// A subject that fails to be recorded is logged and skipped
+    fn rescreen_everyone(
+        &self,
+    ) -> impl std::future::Future<Output = Result<usize, ServiceError>> + Send;
+
+    fn fetch_imports(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<WatchListImport>, ServiceError>> + Send;
+
+    fn fetch_results(
+        &self,
+        query: &ScreeningResultQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<ScreeningResult>, ServiceError>> + Send;
+
+    fn fetch_cases(
+        &self,
+        query: &ComplianceCaseQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<ComplianceCase>, ServiceError>> + Send;
+
+    fn decide_case(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &DecideComplianceCaseRequest,
+    ) -> impl std::future::Future<Output = Result<ComplianceCase, ServiceError>> + Send;
+}
+
+impl ComplianceServiceExt for ComplianceService {
+    async fn guard(&self, subject: &ScreeningSubject) -> Result<ScreeningMatch, ServiceError> {
+        let screening = self.screen_and_record(subject).await?;
+        if screening.is_hit() {
+            return Err(ServiceError::ScreeningHit);
+        }
+        Ok(screening)
+    }
+
+    async fn screen_and_record(
+        &self,
+        subject: &ScreeningSubject,
+    ) -> Result<ScreeningMatch, ServiceError> {
+        let entries = self.watch_list().await?;
+        let threshold = extract_env::<f64>("WATCH_LIST_MATCH_THRESHOLD");
+        let screening = ScreeningMatch::against(&entries, &subject.name, threshold);
+        let screening = self.apply_clearance(subject, screening).await?;
+        if !screening.is_hit() {
+            return Ok(screening);
+        }
+
+        let result = self
+            .repository
+            .record_screening(subject, &screening)
+            .await?;
+        log::warn!(
+            "screening {} of {} matched a listed name with a score of {:.2}",
+            result.identifier,
+            subject.reference,
+            result.score
+        );
+        Ok(screening)
+    }
+
+    async fn record_screening(
+        &self,
+        subject: &ScreeningSubject,
+        screening: &ScreeningMatch,
+    ) -> Result<ScreeningResult, ServiceError> {
+        let result = self.repository.record_screening(subject, screening).await?;
+        Ok(result)
+    }
+
+    async fn import_watch_list(
+        &self,
+        claims: &Claims,
+        TypedMultipart(request): TypedMultipart<ImportWatchListRequest>,
+    ) -> Result<WatchListImport, ServiceError> {
+        let file_name = request
+            .file
+            .metadata
+            .file_name
+            .clone()
+            .unwrap_or_else(|| "watch_list.csv".to_string());
+        let entries = read_watch_list_file(request.file.contents.path(), &file_name)?;
+
+        let watch_list_import = self
+            .repository
+            .replace_watch_list(
+                &file_name,
+                request.source.as_deref(),
+                &claims.user_identifier,
+                &entries,
+            )
+            .await?;
+
+        let compliance_service = self.clone();
+        tokio::task::spawn(async move {
+            match compliance_service.rescreen_everyone().await {
+                Ok(hits) => log::info!("re-screening against the new watch list found {hits} hits"),
+                Err(err) => log::error!("failed to re-screen against the new watch list: {err}"),
+            }
+        });
+
+        Ok(watch_list_import)
+    }
+
+    async fn rescreen_everyone(&self) -> Result<usize, ServiceError> {
+        let entries = self.watch_list().await?;
+        let subjects = self.repository.fetch_screening_subjects().await?;
+        let threshold = extract_env::<f64>("WATCH_LIST_MATCH_THRESHOLD");
+
+        // comparing every name against every entry is cpu bound, keep it off the runtime
+        let screenings = tokio::task::spawn_blocking(move || {
+            subjects
+                .into_iter()
+                .map(|subject| {
+                    let screening = ScreeningMatch::against(&entries, &subject.name, threshold);
+                    (subject, screening)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|err| {
+            log::error!("error screening against the watch list due to {err}");
+            ServiceError::OperationFailed
+        })?;
+
+        let mut hits = 0;
+        for (subject, screening) in screenings {
+            let recorded = async {
+                let screening = self.apply_clearance(&subject, screening).await?;
+                self.repository
+                    .record_screening(&subject, &screening)
+                    .await?;
+                Ok::<_, ServiceError>(screening)
+            }
+            .await;
+
+            match recorded {
+                Ok(screening) if screening.is_hit() => hits += 1,
+                Ok(_) => {}
+                Err(err) => log::error!("failed to re-screen {} due to {err}", subject.reference),
+            }
+        }
+
+        Ok(hits)
+    }
+
+    async fn fetch_imports(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<WatchListImport>, ServiceError> {
+        let imports = self.repository.fetch_imports(pagination_params).await?;
+        Ok(imports)
+    }
+
+    async fn fetch_results(
+        &self,
+        query: &ScreeningResultQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<ScreeningResult>, ServiceError> {
+        let results = self
+            .repository
+            .fetch_results(query, pagination_params)
+            .await?;
+        Ok(results)
+    }
+
+    async fn fetch_cases(
+        &self,
+        query: &ComplianceCaseQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<ComplianceCase>, ServiceError> {
+        let cases = self
+            .repository
+            .fetch_cases(query, pagination_params)
+            .await?;
+        Ok(cases)
+    }
+
+    async fn decide_case(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &DecideComplianceCaseRequest,
+    ) -> Result<ComplianceCase, ServiceError> {
+        let case = self
+            .repository
+            .decide_case(identifier, request, &claims.user_identifier)
+            .await?;
+        Ok(case)
+    }
+}
@@ -5,4 +5,4 @@ pub mod filesystem;
 pub mod tasks;
 pub use env::*;
 pub mod logger;
-mod kafka;
+pub mod kafka;
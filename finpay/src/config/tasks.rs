@@ -1,9 +1,24 @@
+use std::time::Duration;
+
 use finpay_mailer::EmailClient;
+use finpay_utils::extract_env;
+use sqlx::PgPool;
+
+use crate::config::kafka::KafkaProducer;
+use crate::disputes::service::{DisputeService, DisputeServiceExt};
+use crate::fx::provider::MockRateProvider;
+use crate::fx::service::FxRateRefresher;
+use crate::holds::service::{HoldService, HoldServiceExt};
+use crate::invoices::service::{InvoiceService, InvoiceServiceExt};
+use crate::payouts::provider::MockPayoutProvider;
+use crate::payouts::service::PayoutProcessor;
+use crate::reconciliation::service::{ReconciliationService, ReconciliationServiceExt};
+use crate::statements::service::StatementGenerator;
 
 pub struct AppBackgroundTasks {}
 
 impl AppBackgroundTasks {
-    pub fn run() {
+    pub fn run(pool: &PgPool) {
         tokio::task::spawn(async move {
             match EmailClient::new().test_connection() {
                 Ok(true) => tracing::info!("SMTP Connection established"),
@@ -11,5 +26,145 @@ impl AppBackgroundTasks {
                 Err(e) => tracing::error!("Error testing connection: {}", e),
             };
         });
+
+        Self::reconcile_wallet_balances(pool);
+        Self::process_payouts(pool);
+        Self::expire_lapsed_holds(pool);
+        Self::refresh_exchange_rates(pool);
+        Self::generate_statements(pool);
+        Self::mark_overdue_invoices(pool);
+        Self::sweep_overdue_disputes(pool);
+    }
+
+    fn reconcile_wallet_balances(pool: &PgPool) {
+        let reconciliation_service = ReconciliationService::new(pool);
+        let interval_in_minutes = extract_env::<u64>("RECONCILIATION_INTERVAL_IN_MINUTES");
+
+        tokio::task::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_in_minutes * 60));
+            loop {
+                interval.tick().await;
+                match reconciliation_service.reconcile().await {
+                    Ok(run) => tracing::info!(
+                        "Reconciliation run {} checked {} wallet(s)",
+                        run.identifier,
+                        run.wallets_checked
+                    ),
+                    Err(e) => tracing::error!("Error reconciling wallet balances: {}", e),
+                }
+            }
+        });
+    }
+
+    fn process_payouts(pool: &PgPool) {
+        let payout_processor = PayoutProcessor::new(pool, MockPayoutProvider);
+        let interval_in_seconds = extract_env::<u64>("PAYOUT_PROCESSING_INTERVAL_IN_SECONDS");
+
+        tokio::task::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_in_seconds));
+            loop {
+                interval.tick().await;
+                match payout_processor.process_queued().await {
+                    Ok(0) => {}
+                    Ok(settled) => tracing::info!("Settled {} payout(s)", settled),
+                    Err(e) => tracing::error!("Error processing payouts: {}", e),
+                }
+            }
+        });
+    }
+
+    fn expire_lapsed_holds(pool: &PgPool) {
+        let hold_service = HoldService::new(pool);
+        let interval_in_seconds = extract_env::<u64>("HOLD_EXPIRY_INTERVAL_IN_SECONDS");
+
+        tokio::task::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_in_seconds));
+            loop {
+                interval.tick().await;
+                match hold_service.expire_lapsed_holds().await {
+                    Ok(0) => {}
+                    Ok(expired) => tracing::info!("Expired {} lapsed hold(s)", expired),
+                    Err(e) => tracing::error!("Error expiring holds: {}", e),
+                }
+            }
+        });
+    }
+
+    fn refresh_exchange_rates(pool: &PgPool) {
+        let pool = pool.clone();
+        let interval_in_minutes = extract_env::<u64>("FX_RATE_REFRESH_INTERVAL_IN_MINUTES");
+
+        tokio::task::spawn(async move {
+            let producer = match KafkaProducer::new().await {
+                Ok(producer) => Some(producer),
+                Err(e) => {
+                    tracing::error!("Rate updates will not be published: {}", e);
+                    None
+                }
+            };
+            let rate_refresher = FxRateRefresher::new(&pool, MockRateProvider, producer);
+
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_in_minutes * 60));
+            // the first tick fires immediately, keep the seeded rates until the first interval
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match rate_refresher.refresh().await {
+                    Ok(refreshed) => tracing::info!("Refreshed {} exchange rate(s)", refreshed),
+                    Err(e) => tracing::error!("Error refreshing exchange rates: {}", e),
+                }
+            }
+        });
+    }
+
+    fn generate_statements(pool: &PgPool) {
+        let statement_generator = StatementGenerator::new(pool);
+        let interval_in_seconds = extract_env::<u64>("STATEMENT_GENERATION_INTERVAL_IN_SECONDS");
+
+        tokio::task::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_in_seconds));
+            loop {
+                interval.tick().await;
+                match statement_generator.generate_pending().await {
+                    Ok(0) => {}
+                    Ok(generated) => tracing::info!("Generated {} statement(s)", generated),
+                    Err(e) => tracing::error!("Error generating statements: {}", e),
+                }
+            }
+        });
+    }
+
+    fn mark_overdue_invoices(pool: &PgPool) {
+        let invoice_service = InvoiceService::new(pool);
+        let interval_in_minutes = extract_env::<u64>("INVOICE_OVERDUE_INTERVAL_IN_MINUTES");
+
+        tokio::task::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_in_minutes * 60));
+            loop {
+                interval.tick().await;
+                match invoice_service.mark_overdue().await {
+                    Ok(0) => {}
+                    Ok(marked) => tracing::info!("Marked {} invoice(s) overdue", marked),
+                    Err(e) => tracing::error!("Error marking invoices overdue: {}", e),
+                }
+            }
+        });
+    }
+
+    fn sweep_overdue_disputes(pool: &PgPool) {
+        let dispute_service = DisputeService::new(pool);
+        let interval_in_minutes = extract_env::<u64>("DISPUTE_SWEEP_INTERVAL_IN_MINUTES");
+
+        tokio::task::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_in_minutes * 60));
+            loop {
+                interval.tick().await;
+                match dispute_service.sweep_overdue_disputes().await {
+                    Ok(0) => {}
+                    Ok(swept) => tracing::info!("Moved {} overdue dispute(s) on", swept),
+                    Err(e) => tracing::error!("Error sweeping overdue disputes: {}", e),
+                }
+            }
+        });
     }
 }
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateConversionRequest {
+    /// a quote from `POST /fx/quotes`, the conversion executes at exactly the quoted amounts
+    pub quote_identifier: Uuid,
+}
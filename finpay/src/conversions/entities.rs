@@ -0,0 +1,110 @@
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// decimal places converted amounts are rounded to
+const AMOUNT_SCALE: i64 = 2;
+/// decimal places rates are kept to, matches `currency_conversions.rate`
+const RATE_SCALE: i64 = 10;
+const BASIS_POINTS: u32 = 10_000;
+
+/// The mid rate for a currency pair together with the spread finpay charges on top of it
+#[derive(Debug, Clone)]
+pub struct ConversionPricing {
+    pub mid_rate: BigDecimal,
+    pub spread_basis_points: u32,
+    pub rate_source: String,
+}
+
+impl ConversionPricing {
+    /// the rate the customer gets, the mid rate less the spread
+    pub fn rate(&self) -> BigDecimal {
+        let retained = BASIS_POINTS.saturating_sub(self.spread_basis_points);
+        (&self.mid_rate * BigDecimal::from(retained) / BigDecimal::from(BASIS_POINTS))
+            .with_scale_round(RATE_SCALE, RoundingMode::Down)
+    }
+
+    /// what `amount` buys at the customer rate, rounded down so the ledger never pays out more
+    /// than the position received
+    pub fn convert(&self, amount: &BigDecimal) -> BigDecimal {
+        (amount * self.rate()).with_scale_round(AMOUNT_SCALE, RoundingMode::Down)
+    }
+
+    /// what `amount` is worth at the mid rate, the difference to `convert` is the spread earned
+    pub fn convert_at_mid_rate(&self, amount: &BigDecimal) -> BigDecimal {
+        (amount * &self.mid_rate).with_scale_round(AMOUNT_SCALE, RoundingMode::Down)
+    }
+}
+
+/// Both legs of a completed conversion and the pricing they were executed at
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyConversion {
+    pub identifier: Uuid,
+    pub transaction_identifier: Uuid,
+    pub quote_identifier: Option<Uuid>,
+    pub user_identifier: Uuid,
+    pub source_wallet_identifier: Uuid,
+    pub destination_wallet_identifier: Uuid,
+    pub source_amount: BigDecimal,
+    pub source_currency_identifier: Uuid,
+    pub destination_amount: BigDecimal,
+    pub destination_currency_identifier: Uuid,
+    pub fee_amount: BigDecimal,
+    pub mid_rate: BigDecimal,
+    pub rate: BigDecimal,
+    pub spread_basis_points: i32,
+    pub rate_source: String,
+    pub created_date: DateTime<Local>,
+}
+
+impl CurrencyConversion {
+    /// the part of the destination leg that the first `source_amount` of the source leg bought,
+    /// the difference between two shares is what a partial refund takes back so that refunds in
+    /// full always add up to the destination amount
+    pub fn destination_share(&self, source_amount: &BigDecimal) -> BigDecimal {
+        (&self.destination_amount * source_amount / &self.source_amount)
+            .with_scale_round(AMOUNT_SCALE, RoundingMode::Down)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn pricing(mid_rate: &str, spread_basis_points: u32) -> ConversionPricing {
+        ConversionPricing {
+            mid_rate: BigDecimal::from_str(mid_rate).unwrap(),
+            spread_basis_points,
+            rate_source: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_spread_is_taken_off_the_mid_rate() {
+        let pricing = pricing("3.6725", 100);
+
+        assert_eq!(pricing.rate(), BigDecimal::from_str("3.635775").unwrap());
+        assert_eq!(
+            pricing.convert(&BigDecimal::from(100)),
+            BigDecimal::from_str("363.57").unwrap()
+        );
+        assert_eq!(
+            pricing.convert_at_mid_rate(&BigDecimal::from(100)),
+            BigDecimal::from_str("367.25").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_converted_amounts_round_down() {
+        let pricing = pricing("0.333333", 0);
+
+        assert_eq!(
+            pricing.convert(&BigDecimal::from(1)),
+            BigDecimal::from_str("0.33").unwrap()
+        );
+    }
+}
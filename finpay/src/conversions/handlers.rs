@@ -0,0 +1,20 @@
+use crate::conversions::adapters::CreateConversionRequest;
+use crate::conversions::entities::CurrencyConversion;
+use crate::conversions::service::{ConversionService, ConversionServiceExt};
+use crate::errors::ServiceError;
+use crate::utils::{ApiResponse, AuthenticatedRequest};
+use axum::extract::State;
+use axum::http::StatusCode;
+
+pub async fn convert(
+    State(conversion_service): State<ConversionService>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateConversionRequest>,
+) -> Result<ApiResponse<CurrencyConversion>, ServiceError> {
+    let conversion = conversion_service.convert(&claims, &request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(conversion)
+        .message("conversion completed successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
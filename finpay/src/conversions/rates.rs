@@ -0,0 +1,111 @@
+use crate::fx::repository::{FxRepository, FxRepositoryExt};
+use bigdecimal::BigDecimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RateSourceError {
+    #[error("no exchange rate is available between the selected currencies")]
+    UnsupportedPair,
+    #[error("the exchange rate source could not be reached: {0}")]
+    Unavailable(String),
+}
+
+/// A mid-market rate, one unit of the source currency buys `rate` units of the destination
+#[derive(Debug, Clone)]
+pub struct MidRate {
+    pub rate: BigDecimal,
+    pub source: String,
+}
+
+/// Where conversions get their rates from. Currencies are the `countries` identifiers wallets
+/// are denominated in
+pub trait RateSource: Send + Sync {
+    fn mid_rate(
+        &self,
+        source_currency_identifier: &Uuid,
+        destination_currency_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<MidRate, RateSourceError>> + Send;
+}
+
+/// Reads the latest rates from the FX rate store, which ships seeded so that conversions work
+/// offline
+#[derive(Clone)]
+pub struct DatabaseRateSource {
+    pool: PgPool,
+}
+
+impl DatabaseRateSource {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl RateSource for DatabaseRateSource {
+    async fn mid_rate(
+        &self,
+        source_currency_identifier: &Uuid,
+        destination_currency_identifier: &Uuid,
+    ) -> Result<MidRate, RateSourceError> {
+        let currency_codes: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"SELECT identifier, currency_code FROM countries WHERE identifier = ANY($1)"#,
+        )
+        .bind(vec![
+            *source_currency_identifier,
+            *destination_currency_identifier,
+        ])
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| RateSourceError::Unavailable(err.to_string()))?;
+        let currency_code = |currency_identifier: &Uuid| {
+            currency_codes
+                .iter()
+                .find(|(identifier, _)| identifier == currency_identifier)
+                .map(|(_, currency_code)| currency_code.as_str())
+                .ok_or(RateSourceError::UnsupportedPair)
+        };
+
+        let cross_rate = FxRepository::new(&self.pool)
+            .fetch_cross_rate(
+                currency_code(source_currency_identifier)?,
+                currency_code(destination_currency_identifier)?,
+                None,
+            )
+            .await
+            .map_err(|err| RateSourceError::Unavailable(err.to_string()))?
+            .ok_or(RateSourceError::UnsupportedPair)?;
+
+        Ok(MidRate {
+            rate: cross_rate.rate,
+            source: cross_rate.source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::fixtures::{AFGHAN_AFGHANI, UAE_DIRHAM};
+    use bigdecimal::RoundingMode;
+    use std::str::FromStr;
+
+    #[sqlx::test]
+    async fn test_cross_rate_is_derived_through_the_base_currency(pool: PgPool) {
+        let rate_source = DatabaseRateSource::new(&pool);
+
+        let mid_rate = rate_source
+            .mid_rate(
+                &Uuid::from_str(UAE_DIRHAM).unwrap(),
+                &Uuid::from_str(AFGHAN_AFGHANI).unwrap(),
+            )
+            .await
+            .expect("failed to derive cross rate");
+
+        // 68.5 AFN per USD over 3.6725 AED per USD
+        assert_eq!(
+            mid_rate.rate.with_scale_round(4, RoundingMode::HalfEven),
+            BigDecimal::from_str("18.6521").unwrap()
+        );
+        assert_eq!(mid_rate.source, "seed");
+    }
+}
@@ -0,0 +1,386 @@
+use crate::conversions::adapters::CreateConversionRequest;
+use crate::conversions::entities::CurrencyConversion;
+use crate::errors::RepositoryError;
+use crate::fees::repository::FeeRepository;
+use crate::fx::entities::FxQuote;
+use crate::fx::repository::FxRepository;
+use crate::holds::repository::HoldRepository;
+use crate::ledger::adapters::NewJournalEntry;
+use crate::ledger::entities::SystemAccount;
+use crate::ledger::repository::LedgerRepository;
+use crate::transactions::adapters::NewTransaction;
+use crate::transactions::entities::{Transaction, TransactionStatus, TransactionType};
+use crate::transactions::repository::TransactionRepository;
+use crate::wallet::entities::{Wallet, WalletStatus};
+use bigdecimal::{BigDecimal, Zero};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ConversionRepository {
+    pool: PgPool,
+}
+
+impl ConversionRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Books both legs of a locked quote and its fee and marks it used. The caller has locked and checked
+    /// the quote's wallets and opened `transaction` for it
+    pub async fn execute_quote(
+        connection: &mut PgConnection,
+        quote: &FxQuote,
+        transaction: &Transaction,
+    ) -> Result<CurrencyConversion, RepositoryError> {
+        let source_account =
+            LedgerRepository::wallet_account(connection, &quote.source_wallet_identifier).await?;
+        let destination_account =
+            LedgerRepository::wallet_account(connection, &quote.destination_wallet_identifier)
+                .await?;
+        let source_position = LedgerRepository::system_account(
+            connection,
+            SystemAccount::FxPosition,
+            &quote.source_currency_identifier,
+        )
+        .await?;
+        let destination_position = LedgerRepository::system_account(
+            connection,
+            SystemAccount::FxPosition,
+            &quote.destination_currency_identifier,
+        )
+        .await?;
+
+        // each leg balances within its own currency, the position pays out at the mid rate and
+        // the difference to what was quoted is booked as spread revenue
+        let mid_amount = quote.pricing().convert_at_mid_rate(&quote.source_amount);
+        let spread = &mid_amount - &quote.destination_amount;
+        let mut entry = NewJournalEntry::new(&format!("conversion {}", transaction.reference))
+            .transaction(&transaction.identifier)
+            .debit(&source_account, &quote.source_amount)
+            .credit(&source_position, &quote.source_amount)
+            .debit(&destination_position, &mid_amount)
+            .credit(&destination_account, &quote.destination_amount);
+        if spread > BigDecimal::zero() {
+            let spread_revenue = LedgerRepository::system_account(
+                connection,
+                SystemAccount::FxSpreadRevenue,
+                &quote.destination_currency_identifier,
+            )
+            .await?;
+            entry = entry.credit(&spread_revenue, &spread);
+        }
+        LedgerRepository::record_entry(connection, &entry).await?;
+        FeeRepository::charge(
+            connection,
+            transaction,
+            &quote.source_wallet_identifier,
+            &quote.fee(),
+        )
+        .await?;
+
+        let quote = FxRepository::redeem_quote(connection, quote, &transaction.identifier).await?;
+
+        let query = r#"
+        INSERT INTO currency_conversions (identifier, transaction_identifier, quote_identifier,
+                                          user_identifier, source_wallet_identifier,
+                                          destination_wallet_identifier, source_amount,
+                                          source_currency_identifier, destination_amount,
+                                          destination_currency_identifier, fee_amount, mid_rate,
+                                          rate, spread_basis_points, rate_source)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, CurrencyConversion>(query)
+            .bind(Uuid::new_v4())
+            .bind(transaction.identifier)
+            .bind(quote.identifier)
+            .bind(quote.user_identifier)
+            .bind(quote.source_wallet_identifier)
+            .bind(quote.destination_wallet_identifier)
+            .bind(&quote.source_amount)
+            .bind(quote.source_currency_identifier)
+            .bind(&quote.destination_amount)
+            .bind(quote.destination_currency_identifier)
+            .bind(&quote.fee_amount)
+            .bind(&quote.mid_rate)
+            .bind(&quote.rate)
+            .bind(quote.spread_basis_points)
+            .bind(&quote.rate_source)
+            .fetch_one(connection)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    /// The conversion a transaction was executed through, none when it stayed in one currency
+    pub async fn fetch_for_transaction(
+        connection: &mut PgConnection,
+        transaction_identifier: &Uuid,
+    ) -> Result<Option<CurrencyConversion>, RepositoryError> {
+        sqlx::query_as::<_, CurrencyConversion>(
+            r#"SELECT * FROM currency_conversions WHERE transaction_identifier = $1"#,
+        )
+        .bind(transaction_identifier)
+        .fetch_optional(connection)
+        .await
+        .map_err(RepositoryError::from)
+    }
+}
+
+pub trait ConversionRepositoryExt {
+    fn convert(
+        &self,
+        payload: &CreateConversionRequest,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<CurrencyConversion, RepositoryError>> + Send;
+}
+
+impl ConversionRepositoryExt for ConversionRepository {
+    async fn convert(
+        &self,
+        payload: &CreateConversionRequest,
+        user_identifier: &Uuid,
+    ) -> Result<CurrencyConversion, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        // the quote is locked before the wallets, cross-currency transfers take them in the
+        // same order
+        let quote =
+            FxRepository::lock_quote(&mut tx, &payload.quote_identifier, user_identifier).await?;
+
+        let wallets = sqlx::query_as::<_, Wallet>(
+            r#"SELECT * FROM wallets WHERE identifier = ANY($1) ORDER BY identifier FOR UPDATE"#,
+        )
+        .bind(vec![
+            quote.source_wallet_identifier,
+            quote.destination_wallet_identifier,
+        ])
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // conversions only move value between the user's own wallets
+        let source_wallet = wallets
+            .iter()
+            .find(|wallet| {
+                wallet.identifier == quote.source_wallet_identifier
+                    && wallet.user_identifier == *user_identifier
+            })
+            .ok_or(RepositoryError::RecordNotFound)?;
+        let destination_wallet = wallets
+            .iter()
+            .find(|wallet| {
+                wallet.identifier == quote.destination_wallet_identifier
+                    && wallet.user_identifier == *user_identifier
+            })
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        let held_amount = HoldRepository::held_amount(&mut tx, &source_wallet.identifier).await?;
+
+        let failure = if source_wallet.status != WalletStatus::Active
+            || destination_wallet.status != WalletStatus::Active
+        {
+            Some(RepositoryError::InactiveWallet)
+        } else if &source_wallet.balance - held_amount < quote.total_debit() {
+            Some(RepositoryError::InsufficientFunds)
+        } else {
+            None
+        };
+
+        let mut transaction = TransactionRepository::open(
+            &mut tx,
+            &NewTransaction {
+                transaction_type: TransactionType::Conversion,
+                source_wallet_identifier: Some(source_wallet.identifier),
+                destination_wallet_identifier: Some(destination_wallet.identifier),
+                amount: quote.source_amount.clone(),
+                currency_identifier: source_wallet.currency_identifier,
+                description: Some(format!("conversion to {}", destination_wallet.name)),
+                initiated_by: Some(*user_identifier),
+                external_reference: None,
+            },
+        )
+        .await?;
+
+        if let Some(err) = failure {
+            TransactionRepository::transition(
+                &mut tx,
+                &mut transaction,
+                TransactionStatus::Failed,
+                Some(&err.to_string()),
+            )
+            .await?;
+            tx.commit().await?;
+            return Err(err);
+        }
+
+        let conversion = Self::execute_quote(&mut tx, &quote, &transaction).await?;
+
+        TransactionRepository::transition(
+            &mut tx,
+            &mut transaction,
+            TransactionStatus::Completed,
+            None,
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(conversion)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversions::entities::ConversionPricing;
+    use crate::fx::adapters::NewFxQuote;
+    use crate::fx::repository::FxRepositoryExt;
+    use crate::shared::fixtures::{
+        AFGHAN_AFGHANI, UAE_DIRHAM, balance, create_user, create_wallet_in, fund,
+    };
+    use chrono::{DateTime, Duration, Local};
+    use std::str::FromStr;
+
+    async fn create_quote(
+        pool: &PgPool,
+        user_identifier: &Uuid,
+        source: &Uuid,
+        destination: &Uuid,
+        amount: i32,
+        expires_at: DateTime<Local>,
+    ) -> FxQuote {
+        FxRepository::new(pool)
+            .create_quote(&NewFxQuote {
+                user_identifier: *user_identifier,
+                source_wallet_identifier: *source,
+                destination_wallet_identifier: *destination,
+                source_currency_identifier: Uuid::from_str(UAE_DIRHAM).unwrap(),
+                destination_currency_identifier: Uuid::from_str(AFGHAN_AFGHANI).unwrap(),
+                source_amount: BigDecimal::from(amount),
+                pricing: ConversionPricing {
+                    mid_rate: BigDecimal::from_str("18.65").unwrap(),
+                    spread_basis_points: 100,
+                    rate_source: "test".to_string(),
+                },
+                expires_at,
+            })
+            .await
+            .expect("failed to create quote")
+    }
+
+    #[sqlx::test]
+    async fn test_convert_books_both_legs_and_the_spread(pool: PgPool) {
+        let repository = ConversionRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let source = create_wallet_in(&pool, &user_identifier, UAE_DIRHAM).await;
+        let destination = create_wallet_in(&pool, &user_identifier, AFGHAN_AFGHANI).await;
+        fund(&pool, &source, "100").await;
+        let quote = create_quote(
+            &pool,
+            &user_identifier,
+            &source,
+            &destination,
+            10,
+            Local::now() + Duration::minutes(1),
+        )
+        .await;
+
+        let request = CreateConversionRequest {
+            quote_identifier: quote.identifier,
+        };
+        let conversion = repository
+            .convert(&request, &user_identifier)
+            .await
+            .expect("failed to convert");
+
+        // 10 AED at 18.65 less 1% spread
+        assert_eq!(
+            conversion.destination_amount,
+            BigDecimal::from_str("184.63").unwrap()
+        );
+        assert_eq!(conversion.quote_identifier, Some(quote.identifier));
+        assert_eq!(balance(&pool, &source).await, BigDecimal::from(90));
+        assert_eq!(
+            balance(&pool, &destination).await,
+            BigDecimal::from_str("184.63").unwrap()
+        );
+
+        let spread: BigDecimal = sqlx::query_scalar(
+            r#"
+            SELECT postings.amount
+            FROM postings
+                     JOIN ledger_accounts ON ledger_accounts.identifier = postings.ledger_account_identifier
+            WHERE ledger_accounts.system_code = $1
+              AND ledger_accounts.currency_identifier = $2
+            "#,
+        )
+        .bind(SystemAccount::FxSpreadRevenue.code())
+        .bind(Uuid::from_str(AFGHAN_AFGHANI).unwrap())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(spread, BigDecimal::from_str("1.87").unwrap());
+
+        let replayed = repository.convert(&request, &user_identifier).await;
+        assert!(matches!(replayed, Err(RepositoryError::InvalidQuote)));
+    }
+
+    #[sqlx::test]
+    async fn test_convert_rejects_expired_quote(pool: PgPool) {
+        let repository = ConversionRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let source = create_wallet_in(&pool, &user_identifier, UAE_DIRHAM).await;
+        let destination = create_wallet_in(&pool, &user_identifier, AFGHAN_AFGHANI).await;
+        fund(&pool, &source, "100").await;
+        let quote = create_quote(
+            &pool,
+            &user_identifier,
+            &source,
+            &destination,
+            10,
+            Local::now() - Duration::seconds(1),
+        )
+        .await;
+
+        let result = repository
+            .convert(
+                &CreateConversionRequest {
+                    quote_identifier: quote.identifier,
+                },
+                &user_identifier,
+            )
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::InvalidQuote)));
+        assert_eq!(balance(&pool, &source).await, BigDecimal::from(100));
+    }
+
+    #[sqlx::test]
+    async fn test_convert_rejects_overdraft(pool: PgPool) {
+        let repository = ConversionRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let source = create_wallet_in(&pool, &user_identifier, UAE_DIRHAM).await;
+        let destination = create_wallet_in(&pool, &user_identifier, AFGHAN_AFGHANI).await;
+        fund(&pool, &source, "5").await;
+        let quote = create_quote(
+            &pool,
+            &user_identifier,
+            &source,
+            &destination,
+            10,
+            Local::now() + Duration::minutes(1),
+        )
+        .await;
+
+        let result = repository
+            .convert(
+                &CreateConversionRequest {
+                    quote_identifier: quote.identifier,
+                },
+                &user_identifier,
+            )
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::InsufficientFunds)));
+        assert_eq!(balance(&pool, &destination).await, BigDecimal::zero());
+    }
+}
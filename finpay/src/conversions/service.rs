@@ -0,0 +1,41 @@
+use crate::authentication::claims::Claims;
+use crate::conversions::adapters::CreateConversionRequest;
+use crate::conversions::entities::CurrencyConversion;
+use crate::conversions::repository::{ConversionRepository, ConversionRepositoryExt};
+use crate::errors::ServiceError;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct ConversionService {
+    repository: ConversionRepository,
+}
+
+impl ConversionService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: ConversionRepository::new(pool),
+        }
+    }
+}
+
+pub trait ConversionServiceExt {
+    fn convert(
+        &self,
+        claims: &Claims,
+        request: &CreateConversionRequest,
+    ) -> impl std::future::Future<Output = Result<CurrencyConversion, ServiceError>> + Send;
+}
+
+impl ConversionServiceExt for ConversionService {
+    async fn convert(
+        &self,
+        claims: &Claims,
+        request: &CreateConversionRequest,
+    ) -> Result<CurrencyConversion, ServiceError> {
+        let conversion = self
+            .repository
+            .convert(request, &claims.user_identifier)
+            .await?;
+        Ok(conversion)
+    }
+}
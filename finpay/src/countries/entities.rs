@@ -9,5 +9,7 @@ pub struct Country {
     pub currency_code: String,
     pub currency: String,
     pub country: String,
-    pub flag: Option<String>
+    pub flag: Option<String>,
+    /// the decimal places of the currency's minor unit, 0 for the yen and 3 for the dinar
+    pub currency_exponent: i16
 }
@@ -0,0 +1,79 @@
+use axum_typed_multipart::{FieldData, TryFromMultipart};
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+use validator::Validate;
+
+use crate::disputes::entities::DisputeStatus;
+
+/// Opens a dispute against a transfer, the evidence files are optional and can be added later
+#[derive(TryFromMultipart, Validate)]
+#[try_from_multipart(rename_all = "camelCase")]
+pub struct OpenDisputeRequest {
+    #[validate(length(
+        equal = 36,
+        message = "a valid transaction identifier is required",
+        code = "transactionIdentifier"
+    ))]
+    pub transaction_identifier: String,
+    #[validate(length(
+        min = 1,
+        max = 500,
+        message = "reason must be between 1 and 500 characters",
+        code = "reason"
+    ))]
+    pub reason: String,
+    #[form_data(limit = "5MiB")]
+    pub evidence: Vec<FieldData<NamedTempFile>>,
+}
+
+#[derive(TryFromMultipart)]
+#[try_from_multipart(rename_all = "camelCase")]
+pub struct UploadDisputeEvidenceRequest {
+    #[form_data(limit = "5MiB")]
+    pub evidence: Vec<FieldData<NamedTempFile>>,
+}
+
+/// An evidence file once it has been uploaded
+pub struct NewDisputeEvidence {
+    /// what imagekit knows the upload by, used to remove it again if it is never recorded
+    pub file_id: String,
+    pub file_name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeOutcome {
+    Won,
+    Lost,
+}
+
+impl From<DisputeOutcome> for DisputeStatus {
+    fn from(outcome: DisputeOutcome) -> Self {
+        match outcome {
+            DisputeOutcome::Won => DisputeStatus::Won,
+            DisputeOutcome::Lost => DisputeStatus::Lost,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DecideDisputeRequest {
+    pub outcome: DisputeOutcome,
+    #[validate(length(
+        min = 1,
+        max = 500,
+        message = "note must be between 1 and 500 characters",
+        code = "note"
+    ))]
+    pub note: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeQuery {
+    pub status: Option<DisputeStatus>,
+    /// only undecided cases past their deadline
+    pub overdue: Option<bool>,
+}
@@ -0,0 +1,151 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "dispute_status_enum")]
+pub enum DisputeStatus {
+    Open,
+    UnderReview,
+    Won,
+    Lost,
+}
+
+impl DisputeStatus {
+    /// open -> under_review -> won | lost; won and lost are final
+    pub fn can_transition_to(&self, next: DisputeStatus) -> bool {
+        matches!(
+            (self, next),
+            (DisputeStatus::Open, DisputeStatus::UnderReview)
+                | (DisputeStatus::UnderReview, DisputeStatus::Won)
+                | (DisputeStatus::UnderReview, DisputeStatus::Lost)
+        )
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            DisputeStatus::Open => "open",
+            DisputeStatus::UnderReview => "under_review",
+            DisputeStatus::Won => "won",
+            DisputeStatus::Lost => "lost",
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self, DisputeStatus::Open | DisputeStatus::UnderReview)
+    }
+}
+
+impl Display for DisputeStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisputeStatus::Open => write!(f, "open"),
+            DisputeStatus::UnderReview => write!(f, "under review"),
+            DisputeStatus::Won => write!(f, "won"),
+            DisputeStatus::Lost => write!(f, "lost"),
+        }
+    }
+}
+
+/// A case raised by the payer of a transfer against the payee
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Dispute {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub transaction_identifier: Uuid,
+    pub user_identifier: Uuid,
+    pub reason: String,
+    pub status: DisputeStatus,
+    /// the deadline for the stage the case is in
+    pub due_date: DateTime<Local>,
+    pub decision_note: Option<String>,
+    pub decided_by: Option<Uuid>,
+    pub decided_date: Option<DateTime<Local>>,
+    /// the refund booked when the case was won
+    pub refund_identifier: Option<Uuid>,
+    /// when the case went past its decision deadline without being decided
+    pub escalated_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+    #[sqlx(skip)]
+    pub evidence: Vec<DisputeEvidence>,
+}
+
+impl Dispute {
+    /// an undecided case that has gone past its deadline
+    pub fn is_overdue(&self) -> bool {
+        self.status.is_active() && self.due_date <= Local::now()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeEvidence {
+    pub identifier: Uuid,
+    pub dispute_identifier: Uuid,
+    pub file_name: String,
+    pub url: String,
+    pub uploaded_by: Option<Uuid>,
+    pub created_date: DateTime<Local>,
+}
+
+/// The user a dispute email goes to
+#[derive(Debug, FromRow)]
+pub struct DisputeRecipient {
+    pub email: String,
+    pub first_name: String,
+    pub transaction_reference: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn dispute(status: DisputeStatus, due_date: DateTime<Local>) -> Dispute {
+        Dispute {
+            identifier: Uuid::new_v4(),
+            reference: "DSP-TEST".to_string(),
+            transaction_identifier: Uuid::new_v4(),
+            user_identifier: Uuid::new_v4(),
+            reason: "item never arrived".to_string(),
+            status,
+            due_date,
+            decision_note: None,
+            decided_by: None,
+            decided_date: None,
+            refund_identifier: None,
+            escalated_date: None,
+            created_date: Local::now(),
+            updated_at: Local::now(),
+            evidence: vec![],
+        }
+    }
+
+    #[test]
+    fn test_dispute_status_transitions() {
+        assert!(DisputeStatus::Open.can_transition_to(DisputeStatus::UnderReview));
+        assert!(DisputeStatus::UnderReview.can_transition_to(DisputeStatus::Won));
+        assert!(DisputeStatus::UnderReview.can_transition_to(DisputeStatus::Lost));
+
+        assert!(!DisputeStatus::Open.can_transition_to(DisputeStatus::Won));
+        assert!(!DisputeStatus::Won.can_transition_to(DisputeStatus::Lost));
+        assert!(!DisputeStatus::Lost.can_transition_to(DisputeStatus::UnderReview));
+    }
+
+    #[test]
+    fn test_only_undecided_disputes_are_overdue() {
+        let yesterday = Local::now() - Duration::days(1);
+        let tomorrow = Local::now() + Duration::days(1);
+
+        assert!(dispute(DisputeStatus::Open, yesterday).is_overdue());
+        assert!(dispute(DisputeStatus::UnderReview, yesterday).is_overdue());
+        assert!(!dispute(DisputeStatus::UnderReview, tomorrow).is_overdue());
+        assert!(!dispute(DisputeStatus::Won, yesterday).is_overdue());
+    }
+}
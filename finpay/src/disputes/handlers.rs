@@ -0,0 +1,109 @@
+use crate::authentication::claims::Claims;
+use crate::disputes::adapters::{
+    DecideDisputeRequest, DisputeQuery, OpenDisputeRequest, UploadDisputeEvidenceRequest,
+};
+use crate::disputes::entities::Dispute;
+use crate::disputes::service::{DisputeService, DisputeServiceExt};
+use crate::errors::ServiceError;
+use crate::shared::middlewares::admin_middleware::AdminClaims;
+use crate::shared::middlewares::validator::ValidatedRequest;
+use crate::utils::{ApiResponse, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum_typed_multipart::TypedMultipart;
+use uuid::Uuid;
+
+pub async fn open_dispute(
+    State(dispute_service): State<DisputeService>,
+    claims: Claims,
+    request: TypedMultipart<OpenDisputeRequest>,
+) -> Result<ApiResponse<Dispute>, ServiceError> {
+    let dispute = dispute_service.open_dispute(&claims, request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(dispute)
+        .message("dispute opened successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn upload_dispute_evidence(
+    State(dispute_service): State<DisputeService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+    request: TypedMultipart<UploadDisputeEvidenceRequest>,
+) -> Result<ApiResponse<Dispute>, ServiceError> {
+    let dispute = dispute_service
+        .upload_evidence(&claims, &identifier, request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(dispute)
+        .message("evidence uploaded successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_dispute(
+    State(dispute_service): State<DisputeService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<Dispute>, ServiceError> {
+    let dispute = dispute_service.fetch_dispute(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder().data(dispute).build())
+}
+
+pub async fn fetch_disputes(
+    State(dispute_service): State<DisputeService>,
+    claims: Claims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<Dispute>>, ServiceError> {
+    let disputes = dispute_service
+        .fetch_disputes(&claims, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(disputes).build())
+}
+
+pub async fn fetch_all_disputes(
+    State(dispute_service): State<DisputeService>,
+    _: AdminClaims,
+    Query(query): Query<DisputeQuery>,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<Dispute>>, ServiceError> {
+    let disputes = dispute_service
+        .fetch_all_disputes(&query, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(disputes).build())
+}
+
+pub async fn review_dispute(
+    State(dispute_service): State<DisputeService>,
+    _: AdminClaims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<Dispute>, ServiceError> {
+    let dispute = dispute_service.review_dispute(&identifier).await?;
+
+    Ok(ApiResponse::builder()
+        .data(dispute)
+        .message("dispute is now under review")
+        .build())
+}
+
+pub async fn decide_dispute(
+    State(dispute_service): State<DisputeService>,
+    AdminClaims(claims): AdminClaims,
+    Path(identifier): Path<Uuid>,
+    ValidatedRequest(request): ValidatedRequest<DecideDisputeRequest>,
+) -> Result<ApiResponse<Dispute>, ServiceError> {
+    let dispute = dispute_service
+        .decide_dispute(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(dispute)
+        .message("dispute decided successfully")
+        .build())
+}
@@ -0,0 +1,769 @@
+use crate::disputes::adapters::{DecideDisputeRequest, DisputeQuery, NewDisputeEvidence};
+use crate::disputes::entities::{Dispute, DisputeEvidence, DisputeRecipient, DisputeStatus};
+use crate::errors::RepositoryError;
+use crate::transactions::adapters::CreateRefundRequest;
+use crate::transactions::entities::{Transaction, TransactionType};
+use crate::transactions::repository::TransactionRepository;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use bigdecimal::Zero;
+use chrono::{DateTime, Local};
+use finpay_utils::generate_reference;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct DisputeRepository {
+    pool: PgPool,
+}
+
+impl DisputeRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    async fn lock_dispute(
+        connection: &mut PgConnection,
+        identifier: &Uuid,
+    ) -> Result<Dispute, RepositoryError> {
+        sqlx::query_as::<_, Dispute>(r#"SELECT * FROM disputes WHERE identifier = $1 FOR UPDATE"#)
+            .bind(identifier)
+            .fetch_optional(connection)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)
+    }
+
+    async fn attach_evidence(
+        connection: &mut PgConnection,
+        dispute_identifier: &Uuid,
+        uploaded_by: &Uuid,
+        evidence: &[NewDisputeEvidence],
+    ) -> Result<(), RepositoryError> {
+        let query = r#"
+        INSERT INTO dispute_evidence (identifier, dispute_identifier, file_name, url, uploaded_by)
+        VALUES ($1, $2, $3, $4, $5)
+        "#;
+        for file in evidence {
+            sqlx::query(query)
+                .bind(Uuid::new_v4())
+                .bind(dispute_identifier)
+                .bind(&file.file_name)
+                .bind(&file.url)
+                .bind(uploaded_by)
+                .execute(&mut *connection)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn with_evidence(
+        &self,
+        mut disputes: Vec<Dispute>,
+    ) -> Result<Vec<Dispute>, RepositoryError> {
+        let dispute_identifiers: Vec<Uuid> =
+            disputes.iter().map(|dispute| dispute.identifier).collect();
+        let evidence = sqlx::query_as::<_, DisputeEvidence>(
+            r#"SELECT * FROM dispute_evidence WHERE dispute_identifier = ANY($1) ORDER BY created_date"#,
+        )
+        .bind(dispute_identifiers)
+        .fetch_all(&self.pool)
+        .await?;
+        for file in evidence {
+            if let Some(dispute) = disputes
+                .iter_mut()
+                .find(|dispute| dispute.identifier == file.dispute_identifier)
+            {
+                dispute.evidence.push(file);
+            }
+        }
+
+        Ok(disputes)
+    }
+
+    async fn dispute_with_evidence(&self, dispute: Dispute) -> Result<Dispute, RepositoryError> {
+        let mut disputes = self.with_evidence(vec![dispute]).await?;
+        disputes.pop().ok_or(RepositoryError::RecordNotFound)
+    }
+}
+
+pub trait DisputeRepositoryExt {
+    /// Opens a case against a transfer the user paid, only one case per transaction can be in
+    /// progress at a time
+    fn open(
+        &self,
+        transaction_identifier: &Uuid,
+        user_identifier: &Uuid,
+        reason: &str,
+        due_date: &DateTime<Local>,
+        evidence: &[NewDisputeEvidence],
+    ) -> impl std::future::Future<Output = Result<Dispute, RepositoryError>> + Send;
+
+    fn add_evidence(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        evidence: &[NewDisputeEvidence],
+    ) -> impl std::future::Future<Output = Result<Dispute, RepositoryError>> + Send;
+
+    fn fetch_dispute(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<Dispute>, RepositoryError>> + Send;
+
+    fn fetch_disputes(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Dispute>, RepositoryError>> + Send;
+
+    /// Lists every user's cases for support, optionally narrowed to a status or to overdue cases
+    fn fetch_all_disputes(
+        &self,
+        query: &DisputeQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Dispute>, RepositoryError>> + Send;
+
+    /// Takes an open case up for review and moves its deadline to `due_date`
+    fn review(
+        &self,
+        identifier: &Uuid,
+        due_date: &DateTime<Local>,
+    ) -> impl std::future::Future<Output = Result<Dispute, RepositoryError>> + Send;
+
+    /// Decides a case under review. Winning it charges what is left of the transaction back to
+    /// the payer in the same database transaction, so the case stays under review if that fails.
+    /// The chargeback goes through even when the payee cannot cover it
+    fn decide(
+        &self,
+        identifier: &Uuid,
+        request: &DecideDisputeRequest,
+        decided_by: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Dispute, RepositoryError>> + Send;
+
+    /// Takes open cases past their review deadline up for review, due by `due_date`, and
+    /// escalates cases under review past their decision deadline. Returns the cases it moved
+    fn sweep_overdue(
+        &self,
+        due_date: &DateTime<Local>,
+    ) -> impl std::future::Future<Output = Result<Vec<Dispute>, RepositoryError>> + Send;
+
+    fn fetch_recipient(
+        &self,
+        dispute: &Dispute,
+    ) -> impl std::future::Future<Output = Result<DisputeRecipient, RepositoryError>> + Send;
+}
+
+impl DisputeRepositoryExt for DisputeRepository {
+    async fn open(
+        &self,
+        transaction_identifier: &Uuid,
+        user_identifier: &Uuid,
+        reason: &str,
+        due_date: &DateTime<Local>,
+        evidence: &[NewDisputeEvidence],
+    ) -> Result<Dispute, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        // only the payer can dispute a transfer
+        let query = r#"
+        SELECT transactions.*
+        FROM transactions
+                 JOIN wallets ON wallets.identifier = transactions.source_wallet_identifier
+        WHERE transactions.identifier = $1
+          AND wallets.user_identifier = $2
+        "#;
+        let transaction = sqlx::query_as::<_, Transaction>(query)
+            .bind(transaction_identifier)
+            .bind(user_identifier)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        if !matches!(
+            transaction.transaction_type,
+            TransactionType::Transfer | TransactionType::HoldCapture
+        ) || transaction.destination_wallet_identifier.is_none()
+            || transaction.refundable_amount().is_zero()
+        {
+            return Err(RepositoryError::OperationFailed(
+                "only completed transfers that have not been fully refunded can be disputed".into(),
+            ));
+        }
+
+        let query = r#"
+        INSERT INTO disputes (identifier, reference, transaction_identifier, user_identifier, reason,
+                              status, due_date)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#;
+        let dispute = sqlx::query_as::<_, Dispute>(query)
+            .bind(Uuid::new_v4())
+            .bind(generate_reference("DSP"))
+            .bind(transaction.identifier)
+            .bind(user_identifier)
+            .bind(reason)
+            .bind(DisputeStatus::Open)
+            .bind(due_date)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|err| match err {
+                sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                    RepositoryError::DuplicateRecord
+                }
+                err => RepositoryError::from(err),
+            })?;
+
+        Self::attach_evidence(&mut tx, &dispute.identifier, user_identifier, evidence).await?;
+
+        tx.commit().await?;
+        self.dispute_with_evidence(dispute).await
+    }
+
+    async fn add_evidence(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        evidence: &[NewDisputeEvidence],
+    ) -> Result<Dispute, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let dispute = Self::lock_dispute(&mut tx, identifier).await?;
+        if dispute.user_identifier != *user_identifier {
+            return Err(RepositoryError::RecordNotFound);
+        }
+        if !dispute.status.is_active() {
+            return Err(RepositoryError::InvalidDisputeState);
+        }
+
+        Self::attach_evidence(&mut tx, &dispute.identifier, user_identifier, evidence).await?;
+
+        tx.commit().await?;
+        self.dispute_with_evidence(dispute).await
+    }
+
+    async fn fetch_dispute(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<Dispute>, RepositoryError> {
+        let dispute = sqlx::query_as::<_, Dispute>(
+            r#"SELECT * FROM disputes WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match dispute {
+            Some(dispute) => Ok(Some(self.dispute_with_evidence(dispute).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn fetch_disputes(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Dispute>, RepositoryError> {
+        let total_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(identifier) FROM disputes WHERE user_identifier = $1")
+                .bind(user_identifier)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let disputes = sqlx::query_as::<_, Dispute>(
+            r#"SELECT * FROM disputes WHERE user_identifier = $1 ORDER BY created_date DESC LIMIT $2 OFFSET $3"#,
+        )
+        .bind(user_identifier)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            self.with_evidence(disputes).await?,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn fetch_all_disputes(
+        &self,
+        query: &DisputeQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Dispute>, RepositoryError> {
+        let filter = r#"
+        WHERE ($1::dispute_status_enum IS NULL OR status = $1)
+          AND (NOT $2 OR (status IN ('open', 'under_review') AND due_date <= NOW()))
+        "#;
+        let overdue = query.overdue.unwrap_or(false);
+
+        let total_count: i64 =
+            sqlx::query_scalar(&format!("SELECT COUNT(identifier) FROM disputes {filter}"))
+                .bind(query.status)
+                .bind(overdue)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        // the closest deadlines first, that is the order support works through them
+        let disputes = sqlx::query_as::<_, Dispute>(&format!(
+            "SELECT * FROM disputes {filter} ORDER BY due_date LIMIT $3 OFFSET $4"
+        ))
+        .bind(query.status)
+        .bind(overdue)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            self.with_evidence(disputes).await?,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn review(
+        &self,
+        identifier: &Uuid,
+        due_date: &DateTime<Local>,
+    ) -> Result<Dispute, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let dispute = Self::lock_dispute(&mut tx, identifier).await?;
+        if !dispute.status.can_transition_to(DisputeStatus::UnderReview) {
+            return Err(RepositoryError::InvalidDisputeState);
+        }
+
+        let dispute = sqlx::query_as::<_, Dispute>(
+            r#"UPDATE disputes SET status = $1, due_date = $2 WHERE identifier = $3 RETURNING *"#,
+        )
+        .bind(DisputeStatus::UnderReview)
+        .bind(due_date)
+        .bind(identifier)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.dispute_with_evidence(dispute).await
+    }
+
+    async fn decide(
+        &self,
+        identifier: &Uuid,
+        request: &DecideDisputeRequest,
+        decided_by: &Uuid,
+    ) -> Result<Dispute, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let dispute = Self::lock_dispute(&mut tx, identifier).await?;
+        let status = DisputeStatus::from(request.outcome);
+        if !dispute.status.can_transition_to(status) {
+            return Err(RepositoryError::InvalidDisputeState);
+        }
+
+        let refund_identifier = if status == DisputeStatus::Won {
+            let refund = TransactionRepository::book_refund(
+                &mut tx,
+                &dispute.transaction_identifier,
+                &CreateRefundRequest {
+                    amount: None,
+                    reason: format!("dispute {} won", dispute.reference),
+                },
+                decided_by,
+                true,
+            )
+            .await?;
+            Some(refund.identifier)
+        } else {
+            None
+        };
+
+        let query = r#"
+        UPDATE disputes
+        SET status            = $1,
+            decision_note     = $2,
+            decided_by        = $3,
+            decided_date      = NOW(),
+            refund_identifier = $4
+        WHERE identifier = $5
+        RETURNING *
+        "#;
+        let dispute = sqlx::query_as::<_, Dispute>(query)
+            .bind(status)
+            .bind(&request.note)
+            .bind(decided_by)
+            .bind(refund_identifier)
+            .bind(identifier)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        self.dispute_with_evidence(dispute).await
+    }
+
+    async fn sweep_overdue(
+        &self,
+        due_date: &DateTime<Local>,
+    ) -> Result<Vec<Dispute>, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        // escalate first so cases taken up for review below are not escalated in the same sweep
+        let mut disputes = sqlx::query_as::<_, Dispute>(
+            r#"
+            UPDATE disputes
+            SET escalated_date = NOW()
+            WHERE status = $1
+              AND due_date <= NOW()
+              AND escalated_date IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(DisputeStatus::UnderReview)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let reviewed = sqlx::query_as::<_, Dispute>(
+            r#"UPDATE disputes SET status = $1, due_date = $2 WHERE status = $3 AND due_date <= NOW() RETURNING *"#,
+        )
+        .bind(DisputeStatus::UnderReview)
+        .bind(due_date)
+        .bind(DisputeStatus::Open)
+        .fetch_all(&mut *tx)
+        .await?;
+        disputes.extend(reviewed);
+
+        tx.commit().await?;
+        Ok(disputes)
+    }
+
+    async fn fetch_recipient(
+        &self,
+        dispute: &Dispute,
+    ) -> Result<DisputeRecipient, RepositoryError> {
+        let query = r#"
+        SELECT users.email, users.first_name, transactions.reference AS transaction_reference
+        FROM disputes
+                 JOIN users ON users.identifier = disputes.user_identifier
+                 JOIN transactions ON transactions.identifier = disputes.transaction_identifier
+        WHERE disputes.identifier = $1
+        "#;
+        sqlx::query_as::<_, DisputeRecipient>(query)
+            .bind(dispute.identifier)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disputes::adapters::DisputeOutcome;
+    use crate::shared::fixtures::{balance, create_user, create_wallet, fund};
+    use crate::transactions::adapters::CreateTransferRequest;
+    use crate::transactions::entities::TransactionStatus;
+    use crate::wallet::repository::{WalletRepository, WalletRepositoryExt};
+    use bigdecimal::BigDecimal;
+    use chrono::Duration;
+
+    struct Fixture {
+        payer: Uuid,
+        payee: Uuid,
+        source: Uuid,
+        destination: Uuid,
+        transfer: Transaction,
+    }
+
+    /// transfers 40 out of a wallet funded with 100
+    async fn setup(pool: &PgPool) -> Fixture {
+        let payer = create_user(pool).await;
+        let payee = create_user(pool).await;
+        let source = create_wallet(pool, &payer).await;
+        let destination = create_wallet(pool, &payee).await;
+
+        fund(pool, &source, "100").await;
+
+        let transfer = WalletRepository::new(pool.clone())
+            .transfer(
+                &CreateTransferRequest {
+                    source_wallet_identifier: source,
+                    destination_wallet_identifier: destination,
+                    amount: BigDecimal::from(40),
+                    description: None,
+                    quote_identifier: None,
+                },
+                &payer,
+            )
+            .await
+            .expect("failed to transfer");
+
+        Fixture {
+            payer,
+            payee,
+            source,
+            destination,
+            transfer,
+        }
+    }
+
+    fn due_date() -> DateTime<Local> {
+        Local::now() + Duration::hours(48)
+    }
+
+    fn evidence() -> Vec<NewDisputeEvidence> {
+        vec![NewDisputeEvidence {
+            file_id: "receipt".to_string(),
+            file_name: "receipt.png".to_string(),
+            url: "https://ik.imagekit.io/finpay/receipt.png".to_string(),
+        }]
+    }
+
+    fn decision(outcome: DisputeOutcome) -> DecideDisputeRequest {
+        DecideDisputeRequest {
+            outcome,
+            note: "reviewed the evidence".to_string(),
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_won_dispute_reverses_the_transfer(pool: PgPool) {
+        let repository = DisputeRepository::new(&pool);
+        let fixture = setup(&pool).await;
+
+        let dispute = repository
+            .open(
+                &fixture.transfer.identifier,
+                &fixture.payer,
+                "item never arrived",
+                &due_date(),
+                &evidence(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Open);
+        assert_eq!(dispute.evidence.len(), 1);
+
+        let result = repository
+            .open(
+                &fixture.transfer.identifier,
+                &fixture.payer,
+                "item never arrived",
+                &due_date(),
+                &[],
+            )
+            .await;
+        assert!(matches!(result, Err(RepositoryError::DuplicateRecord)));
+
+        // a case has to be reviewed before it is decided
+        let result = repository
+            .decide(
+                &dispute.identifier,
+                &decision(DisputeOutcome::Won),
+                &fixture.payee,
+            )
+            .await;
+        assert!(matches!(result, Err(RepositoryError::InvalidDisputeState)));
+
+        repository
+            .review(&dispute.identifier, &due_date())
+            .await
+            .unwrap();
+        let dispute = repository
+            .decide(
+                &dispute.identifier,
+                &decision(DisputeOutcome::Won),
+                &fixture.payee,
+            )
+            .await
+            .unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Won);
+        assert!(dispute.refund_identifier.is_some());
+        assert!(dispute.decided_date.is_some());
+
+        let balance: BigDecimal =
+            sqlx::query_scalar("SELECT balance FROM wallets WHERE identifier = $1")
+                .bind(fixture.source)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(balance, BigDecimal::from(100));
+
+        let status: TransactionStatus =
+            sqlx::query_scalar("SELECT status FROM transactions WHERE identifier = $1")
+                .bind(fixture.transfer.identifier)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, TransactionStatus::Reversed);
+    }
+
+    #[sqlx::test]
+    async fn test_lost_dispute_leaves_the_transfer_alone(pool: PgPool) {
+        let repository = DisputeRepository::new(&pool);
+        let fixture = setup(&pool).await;
+
+        // only the payer can raise a case
+        let result = repository
+            .open(
+                &fixture.transfer.identifier,
+                &fixture.payee,
+                "item never arrived",
+                &due_date(),
+                &[],
+            )
+            .await;
+        assert!(matches!(result, Err(RepositoryError::RecordNotFound)));
+
+        let dispute = repository
+            .open(
+                &fixture.transfer.identifier,
+                &fixture.payer,
+                "item never arrived",
+                &due_date(),
+                &[],
+            )
+            .await
+            .unwrap();
+        repository
+            .review(&dispute.identifier, &due_date())
+            .await
+            .unwrap();
+        let dispute = repository
+            .decide(
+                &dispute.identifier,
+                &decision(DisputeOutcome::Lost),
+                &fixture.payee,
+            )
+            .await
+            .unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Lost);
+        assert!(dispute.refund_identifier.is_none());
+
+        // evidence cannot be added once the case is decided
+        let result = repository
+            .add_evidence(&dispute.identifier, &fixture.payer, &evidence())
+            .await;
+        assert!(matches!(result, Err(RepositoryError::InvalidDisputeState)));
+
+        let status: TransactionStatus =
+            sqlx::query_scalar("SELECT status FROM transactions WHERE identifier = $1")
+                .bind(fixture.transfer.identifier)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, TransactionStatus::Completed);
+    }
+
+    #[sqlx::test]
+    async fn test_won_dispute_is_charged_back_when_the_payee_has_spent_the_money(pool: PgPool) {
+        let repository = DisputeRepository::new(&pool);
+        let fixture = setup(&pool).await;
+
+        // the payee moves 30 of the 40 on before the case is decided
+        let savings = create_wallet(&pool, &fixture.payee).await;
+        WalletRepository::new(pool.clone())
+            .transfer(
+                &CreateTransferRequest {
+                    source_wallet_identifier: fixture.destination,
+                    destination_wallet_identifier: savings,
+                    amount: BigDecimal::from(30),
+                    description: None,
+                    quote_identifier: None,
+                },
+                &fixture.payee,
+            )
+            .await
+            .unwrap();
+
+        let dispute = repository
+            .open(
+                &fixture.transfer.identifier,
+                &fixture.payer,
+                "item never arrived",
+                &due_date(),
+                &[],
+            )
+            .await
+            .unwrap();
+        repository
+            .review(&dispute.identifier, &due_date())
+            .await
+            .unwrap();
+        let dispute = repository
+            .decide(
+                &dispute.identifier,
+                &decision(DisputeOutcome::Won),
+                &fixture.payee,
+            )
+            .await
+            .unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Won);
+
+        assert_eq!(balance(&pool, &fixture.source).await, BigDecimal::from(100));
+        assert_eq!(
+            balance(&pool, &fixture.destination).await,
+            BigDecimal::from(0)
+        );
+        let receivable_amount: BigDecimal =
+            sqlx::query_scalar("SELECT receivable_amount FROM refunds WHERE identifier = $1")
+                .bind(dispute.refund_identifier)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(receivable_amount, BigDecimal::from(30));
+    }
+
+    #[sqlx::test]
+    async fn test_sweep_moves_cases_past_their_deadline(pool: PgPool) {
+        let repository = DisputeRepository::new(&pool);
+        let fixture = setup(&pool).await;
+        let yesterday = Local::now() - Duration::days(1);
+
+        let open = repository
+            .open(
+                &fixture.transfer.identifier,
+                &fixture.payer,
+                "item never arrived",
+                &yesterday,
+                &[],
+            )
+            .await
+            .unwrap();
+
+        let swept = repository.sweep_overdue(&due_date()).await.unwrap();
+        assert_eq!(swept.len(), 1);
+        assert_eq!(swept[0].identifier, open.identifier);
+        assert_eq!(swept[0].status, DisputeStatus::UnderReview);
+        assert!(swept[0].escalated_date.is_none());
+
+        // a case under review that misses its decision deadline is escalated once
+        sqlx::query("UPDATE disputes SET due_date = $1 WHERE identifier = $2")
+            .bind(yesterday)
+            .bind(open.identifier)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let swept = repository.sweep_overdue(&due_date()).await.unwrap();
+        assert_eq!(swept.len(), 1);
+        assert_eq!(swept[0].status, DisputeStatus::UnderReview);
+        assert!(swept[0].escalated_date.is_some());
+
+        assert!(
+            repository
+                .sweep_overdue(&due_date())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use crate::disputes::handlers::{
+    decide_dispute, fetch_all_disputes, fetch_dispute, fetch_disputes, open_dispute,
+    review_dispute, upload_dispute_evidence,
+};
+use crate::shared::middlewares::idempotency::{IdempotencyStore, enforce_idempotency};
+use crate::state::AppState;
+use axum::{
+    Router,
+    extract::FromRef,
+    middleware::from_fn_with_state,
+    routing::{get, post},
+};
+
+pub fn dispute_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/", post(open_dispute))
+        .route("/", get(fetch_disputes))
+        .route("/{identifier}", get(fetch_dispute))
+        .route("/{identifier}/evidence", post(upload_dispute_evidence))
+        .with_state(state.clone())
+}
+
+pub fn admin_dispute_routes(state: &AppState) -> Router {
+    let idempotency_store = IdempotencyStore::from_ref(state);
+
+    Router::new()
+        .route("/", get(fetch_all_disputes))
+        .route("/{identifier}/review", post(review_dispute))
+        .route(
+            "/{identifier}/decision",
+            post(decide_dispute).layer(from_fn_with_state(idempotency_store, enforce_idempotency)),
+        )
+        .with_state(state.clone())
+}
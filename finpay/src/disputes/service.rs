@@ -0,0 +1,340 @@
+use crate::authentication::claims::Claims;
+use crate::config::AppConfig;
+use crate::disputes::adapters::{
+    DecideDisputeRequest, DisputeQuery, NewDisputeEvidence, OpenDisputeRequest,
+    UploadDisputeEvidenceRequest,
+};
+use crate::disputes::entities::Dispute;
+use crate::disputes::repository::{DisputeRepository, DisputeRepositoryExt};
+use crate::errors::{RepositoryError, ServiceError};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use axum_typed_multipart::{FieldData, TypedMultipart};
+use chrono::{Duration, Local};
+use finpay_imagekit::ImagekitClient;
+use finpay_mailer::{DisputeTemplate, EmailClient, EmailClientExt};
+use finpay_utils::{extract_env, generate_file_name};
+use sqlx::PgPool;
+use std::path::Path;
+use tempfile::NamedTempFile;
+use uuid::Uuid;
+use validator::Validate;
+
+/// the most files a single request can carry
+const MAX_EVIDENCE_FILES: usize = 5;
+
+#[derive(Clone)]
+pub struct DisputeService {
+    repository: DisputeRepository,
+}
+
+impl DisputeService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: DisputeRepository::new(pool),
+        }
+    }
+
+    /// Emails the user who raised the case about where it stands, failing to reach them must not
+    /// undo the change
+    async fn notify(&self, dispute: &Dispute) {
+        let recipient = match self.repository.fetch_recipient(dispute).await {
+            Ok(recipient) => recipient,
+            Err(error) => {
+                log::error!("Failed to fetch dispute recipient: {error}");
+                return;
+            }
+        };
+
+        let template = DisputeTemplate::new(
+            &recipient.first_name,
+            &dispute.reference,
+            &recipient.transaction_reference,
+            dispute.status.code(),
+            &dispute.due_date.format("%d %B %Y, %H:%M").to_string(),
+            dispute.decision_note.as_deref().unwrap_or_default(),
+        );
+        tokio::task::spawn(async move {
+            if let Err(error) = EmailClient::new()
+                .send_dispute_email(&recipient.email, template)
+                .await
+            {
+                log::error!("Failed to send dispute email: {error}");
+            }
+        });
+    }
+}
+
+/// Uploads evidence files to imagekit the same way profile pictures are
+async fn upload_evidence_files(
+    files: Vec<FieldData<NamedTempFile>>,
+) -> Result<Vec<NewDisputeEvidence>, ServiceError> {
+    if files.len() > MAX_EVIDENCE_FILES {
+        return Err(ServiceError::UnprocessableEntity(format!(
+            "no more than {MAX_EVIDENCE_FILES} evidence files can be uploaded at once"
+        )));
+    }
+
+    let config = AppConfig::from_env()?;
+    let private_key = extract_env::<String>("IMAGEKIT_PRIVATE_KEY");
+    let public_key = extract_env::<String>("IMAGEKIT_PUBLIC_KEY");
+
+    let mut evidence: Vec<NewDisputeEvidence> = Vec::with_capacity(files.len());
+    for file in files {
+        let file_name = file
+            .metadata
+            .file_name
+            .clone()
+            .unwrap_or(generate_file_name());
+        let file_path = Path::new(&config.upload_path).join(format!(
+            "{time_stamp}_{file_name}",
+            time_stamp = Local::now().timestamp()
+        ));
+
+        if let Err(err) = file.contents.persist(&file_path) {
+            log::error!("error processing file due to {err}");
+            discard_evidence_files(&evidence).await;
+            return Err(ServiceError::OperationFailed);
+        }
+
+        let upload_response = match ImagekitClient::new(&public_key, &private_key) {
+            Ok(client) => client.upload_file(&file_path, &file_name).await,
+            Err(err) => Err(err),
+        };
+        let upload_response = match upload_response {
+            Ok(upload_response) => upload_response,
+            Err(err) => {
+                log::error!("error uploading evidence due to {err}");
+                discard_evidence_files(&evidence).await;
+                return Err(ServiceError::OperationFailed);
+            }
+        };
+
+        evidence.push(NewDisputeEvidence {
+            file_id: upload_response.file_id,
+            file_name,
+            url: upload_response.url,
+        });
+    }
+
+    Ok(evidence)
+}
+
+/// Removes uploads that never made it onto a case, failing to remove one is only logged
+async fn discard_evidence_files(evidence: &[NewDisputeEvidence]) {
+    if evidence.is_empty() {
+        return;
+    }
+
+    let private_key = extract_env::<String>("IMAGEKIT_PRIVATE_KEY");
+    let public_key = extract_env::<String>("IMAGEKIT_PUBLIC_KEY");
+    let client = match ImagekitClient::new(&public_key, &private_key) {
+        Ok(client) => client,
+        Err(err) => {
+            log::error!("error creating client due to {err}");
+            return;
+        }
+    };
+    for file in evidence {
+        if let Err(err) = client.delete_file(&file.file_id).await {
+            log::error!("error removing evidence {} due to {err}", file.file_id);
+        }
+    }
+}
+
+pub trait DisputeServiceExt {
+    /// Opens a case against a transfer, it has to be picked up within `DISPUTE_REVIEW_SLA_IN_HOURS`
+    fn open_dispute(
+        &self,
+        claims: &Claims,
+        request: TypedMultipart<OpenDisputeRequest>,
+    ) -> impl std::future::Future<Output = Result<Dispute, ServiceError>> + Send;
+
+    fn upload_evidence(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: TypedMultipart<UploadDisputeEvidenceRequest>,
+    ) -> impl std::future::Future<Output = Result<Dispute, ServiceError>> + Send;
+
+    fn fetch_dispute(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Dispute, ServiceError>> + Send;
+
+    fn fetch_disputes(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Dispute>, ServiceError>> + Send;
+
+    fn fetch_all_disputes(
+        &self,
+        query: &DisputeQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Dispute>, ServiceError>> + Send;
+
+    /// Takes a case up for review, a decision is due within `DISPUTE_DECISION_SLA_IN_HOURS`
+    fn review_dispute(
+        &self,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Dispute, ServiceError>> + Send;
+
+    fn decide_dispute(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &DecideDisputeRequest,
+    ) -> impl std::future::Future<Output = Result<Dispute, ServiceError>> + Send;
+
+    /// Moves cases that have missed their deadline on, returns how many were moved
+    fn sweep_overdue_disputes(
+        &self,
+    ) -> impl std::future::Future<Output = Result<usize, ServiceError>> + Send;
+}
+
+impl DisputeServiceExt for DisputeService {
+    async fn open_dispute(
+        &self,
+        claims: &Claims,
+        TypedMultipart(request): TypedMultipart<OpenDisputeRequest>,
+    ) -> Result<Dispute, ServiceError> {
+        request.validate()?;
+        let transaction_identifier = Uuid::parse_str(&request.transaction_identifier)
+            .map_err(|_| ServiceError::BadRequest)?;
+
+        let evidence = upload_evidence_files(request.evidence).await?;
+        let due_date =
+            Local::now() + Duration::hours(extract_env::<i64>("DISPUTE_REVIEW_SLA_IN_HOURS"));
+
+        let dispute = match self
+            .repository
+            .open(
+                &transaction_identifier,
+                &claims.user_identifier,
+                &request.reason,
+                &due_date,
+                &evidence,
+            )
+            .await
+        {
+            Ok(dispute) => dispute,
+            Err(err) => {
+                discard_evidence_files(&evidence).await;
+                return Err(err.into());
+            }
+        };
+
+        self.notify(&dispute).await;
+        Ok(dispute)
+    }
+
+    async fn upload_evidence(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        TypedMultipart(request): TypedMultipart<UploadDisputeEvidenceRequest>,
+    ) -> Result<Dispute, ServiceError> {
+        if request.evidence.is_empty() {
+            return Err(ServiceError::UnprocessableEntity(
+                "at least one evidence file is required".to_string(),
+            ));
+        }
+
+        // check the case is the user's before anything is uploaded
+        self.fetch_dispute(claims, identifier).await?;
+
+        let evidence = upload_evidence_files(request.evidence).await?;
+        let dispute = match self
+            .repository
+            .add_evidence(identifier, &claims.user_identifier, &evidence)
+            .await
+        {
+            Ok(dispute) => dispute,
+            Err(err) => {
+                discard_evidence_files(&evidence).await;
+                return Err(err.into());
+            }
+        };
+        Ok(dispute)
+    }
+
+    async fn fetch_dispute(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<Dispute, ServiceError> {
+        let dispute = self
+            .repository
+            .fetch_dispute(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(dispute)
+    }
+
+    async fn fetch_disputes(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Dispute>, ServiceError> {
+        let disputes = self
+            .repository
+            .fetch_disputes(&claims.user_identifier, pagination_params)
+            .await?;
+        Ok(disputes)
+    }
+
+    async fn fetch_all_disputes(
+        &self,
+        query: &DisputeQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Dispute>, ServiceError> {
+        let disputes = self
+            .repository
+            .fetch_all_disputes(query, pagination_params)
+            .await?;
+        Ok(disputes)
+    }
+
+    async fn review_dispute(&self, identifier: &Uuid) -> Result<Dispute, ServiceError> {
+        let due_date =
+            Local::now() + Duration::hours(extract_env::<i64>("DISPUTE_DECISION_SLA_IN_HOURS"));
+        let dispute = self.repository.review(identifier, &due_date).await?;
+
+        self.notify(&dispute).await;
+        Ok(dispute)
+    }
+
+    async fn decide_dispute(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &DecideDisputeRequest,
+    ) -> Result<Dispute, ServiceError> {
+        let dispute = self
+            .repository
+            .decide(identifier, request, &claims.user_identifier)
+            .await?;
+
+        self.notify(&dispute).await;
+        Ok(dispute)
+    }
+
+    async fn sweep_overdue_disputes(&self) -> Result<usize, ServiceError> {
+        let due_date =
+            Local::now() + Duration::hours(extract_env::<i64>("DISPUTE_DECISION_SLA_IN_HOURS"));
+        let disputes = self.repository.sweep_overdue(&due_date).await?;
+
+        for dispute in &disputes {
+            match dispute.escalated_date {
+                Some(_) => log::warn!(
+                    "Dispute {} is past its decision deadline and has been escalated",
+                    dispute.reference
+                ),
+                // the user hears about the new deadline the same way a manual review tells them
+                None => self.notify(dispute).await,
+            }
+        }
+        Ok(disputes.len())
+    }
+}
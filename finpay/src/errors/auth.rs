@@ -20,6 +20,8 @@ pub enum AuthenticationError {
     ValidationError(String),
     #[error("Unauthenticated! Please login and retry")]
     Unauthenticated,
+    #[error("You do not have permission to perform this action")]
+    Forbidden,
 }
 
 impl AuthenticationError {
@@ -32,6 +34,7 @@ impl AuthenticationError {
             AuthenticationError::InvalidOtp => StatusCode::UNAUTHORIZED,
             AuthenticationError::AppError(err) => err.status_code(),
             AuthenticationError::JwtError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AuthenticationError::Forbidden => StatusCode::FORBIDDEN,
             _ => StatusCode::UNAUTHORIZED,
         }
     }
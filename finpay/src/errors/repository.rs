@@ -3,6 +3,7 @@ use axum::{
     response::{IntoResponse, Response},
 };
 
+use crate::transactions::entities::IllegalTransition;
 use crate::utils::ApiResponseBuilder;
 
 #[derive(Debug, thiserror::Error)]
@@ -13,6 +14,36 @@ pub enum RepositoryError {
     DuplicateRecord,
     #[error("Operation failed due to {0}")]
     OperationFailed(String),
+    #[error("Insufficient funds to complete the transaction")]
+    InsufficientFunds,
+    #[error("The wallets involved must share the same currency")]
+    CurrencyMismatch,
+    #[error("The wallet is frozen or closed and cannot send or receive funds")]
+    InactiveWallet,
+    #[error("The wallet was frozen by an administrator and can only be unfrozen by one")]
+    AdministrativeFreeze,
+    #[error("The beneficiary has been blocked and cannot be paid")]
+    BlockedBeneficiary,
+    #[error("Journal entry debits and credits do not balance")]
+    UnbalancedJournalEntry,
+    #[error(transparent)]
+    IllegalTransition(#[from] IllegalTransition),
+    #[error("The hold has already been captured, voided or has expired")]
+    InactiveHold,
+    #[error("The quote has expired or has already been used")]
+    InvalidQuote,
+    #[error("The refund is more than what is left to refund on the transaction")]
+    RefundExceedsOriginal,
+    #[error("The dispute has already been decided or is not at that stage yet")]
+    InvalidDisputeState,
+    #[error("The review has already been decided")]
+    InvalidReviewState,
+    #[error("The compliance case has already been decided")]
+    InvalidCaseState,
+    #[error("The statement line has already been confirmed or has no match")]
+    InvalidStatementLineState,
+    #[error("The invoice is not in a status that allows this change")]
+    InvalidInvoiceState,
     #[error(transparent)]
     SqlxError(#[from] sqlx::Error),
 }
@@ -24,6 +55,21 @@ impl RepositoryError {
             RepositoryError::DuplicateRecord => StatusCode::CONFLICT,
             RepositoryError::SqlxError(_) => StatusCode::UNPROCESSABLE_ENTITY,
             RepositoryError::OperationFailed(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            RepositoryError::InsufficientFunds => StatusCode::UNPROCESSABLE_ENTITY,
+            RepositoryError::CurrencyMismatch => StatusCode::UNPROCESSABLE_ENTITY,
+            RepositoryError::InactiveWallet => StatusCode::UNPROCESSABLE_ENTITY,
+            RepositoryError::AdministrativeFreeze => StatusCode::FORBIDDEN,
+            RepositoryError::BlockedBeneficiary => StatusCode::FORBIDDEN,
+            RepositoryError::UnbalancedJournalEntry => StatusCode::INTERNAL_SERVER_ERROR,
+            RepositoryError::IllegalTransition(_) => StatusCode::CONFLICT,
+            RepositoryError::InactiveHold => StatusCode::CONFLICT,
+            RepositoryError::InvalidQuote => StatusCode::UNPROCESSABLE_ENTITY,
+            RepositoryError::RefundExceedsOriginal => StatusCode::UNPROCESSABLE_ENTITY,
+            RepositoryError::InvalidDisputeState => StatusCode::CONFLICT,
+            RepositoryError::InvalidReviewState => StatusCode::CONFLICT,
+            RepositoryError::InvalidCaseState => StatusCode::CONFLICT,
+            RepositoryError::InvalidStatementLineState => StatusCode::CONFLICT,
+            RepositoryError::InvalidInvoiceState => StatusCode::CONFLICT,
         }
     }
 }
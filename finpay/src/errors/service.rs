@@ -4,6 +4,7 @@ use axum::{http::StatusCode, response::IntoResponse};
 
 use finpay_redis::RedisClientError;
 
+use crate::conversions::rates::RateSourceError;
 use crate::errors::{AppError, AuthenticationError, RepositoryError};
 use crate::utils::ApiResponseBuilder;
 
@@ -35,6 +36,18 @@ pub enum ServiceError {
     RedisClientError(#[from] RedisClientError),
     #[error("unprocessable entity: {0}")]
     UnprocessableEntity(String),
+    #[error("the idempotency key has already been used for a different request")]
+    IdempotencyKeyReused,
+    #[error("a request with this idempotency key is still being processed")]
+    IdempotentRequestInProgress,
+    #[error(transparent)]
+    RateSourceError(#[from] RateSourceError),
+    #[error("the transaction was blocked by our risk checks")]
+    TransactionBlocked,
+    #[error("the transaction has been held for review under {0}")]
+    TransactionHeldForReview(String),
+    #[error("the request could not be completed and has been referred for a compliance review")]
+    ScreeningHit,
 }
 
 impl ServiceError {
@@ -52,6 +65,19 @@ impl ServiceError {
             ServiceError::RedisClientError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ServiceError::SerdeJsonError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ServiceError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ServiceError::IdempotencyKeyReused => StatusCode::UNPROCESSABLE_ENTITY,
+            ServiceError::IdempotentRequestInProgress => StatusCode::CONFLICT,
+            ServiceError::RateSourceError(RateSourceError::UnsupportedPair) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ServiceError::RateSourceError(RateSourceError::Unavailable(_)) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            ServiceError::TransactionBlocked => StatusCode::FORBIDDEN,
+            // nothing went wrong, the request was accepted but will only be carried out once
+            // it is approved
+            ServiceError::TransactionHeldForReview(_) => StatusCode::ACCEPTED,
+            ServiceError::ScreeningHit => StatusCode::FORBIDDEN,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
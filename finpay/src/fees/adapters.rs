@@ -0,0 +1,117 @@
+use crate::fees::entities::FeeMethod;
+use crate::transactions::entities::TransactionType;
+use crate::users::enums::AccountType;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFeeScheduleRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "name must be between 1 and 255 characters",
+        code = "name"
+    ))]
+    pub name: String,
+    pub transaction_type: TransactionType,
+    /// applies to every currency when left out
+    pub currency_identifier: Option<Uuid>,
+    /// applies to every account type when left out
+    pub account_type: Option<AccountType>,
+    /// the band of amounts the schedule applies to, from `minimumAmount` up to but excluding
+    /// `maximumAmount`
+    #[validate(custom(
+        function = "crate::utils::validate_non_negative_amount",
+        message = "minimum amount cannot be negative",
+        code = "minimumAmount"
+    ))]
+    pub minimum_amount: Option<BigDecimal>,
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "maximum amount must be greater than zero",
+        code = "maximumAmount"
+    ))]
+    pub maximum_amount: Option<BigDecimal>,
+    pub fee_method: FeeMethod,
+    #[validate(custom(
+        function = "crate::utils::validate_non_negative_amount",
+        message = "flat amount cannot be negative",
+        code = "flatAmount"
+    ))]
+    pub flat_amount: Option<BigDecimal>,
+    #[validate(range(
+        max = 10000,
+        message = "percentage cannot be more than 10000 basis points",
+        code = "percentageBasisPoints"
+    ))]
+    pub percentage_basis_points: Option<u32>,
+    #[validate(custom(
+        function = "crate::utils::validate_non_negative_amount",
+        message = "minimum fee cannot be negative",
+        code = "minimumFee"
+    ))]
+    pub minimum_fee: Option<BigDecimal>,
+    /// caps the fee
+    #[validate(custom(
+        function = "crate::utils::validate_non_negative_amount",
+        message = "maximum fee cannot be negative",
+        code = "maximumFee"
+    ))]
+    pub maximum_fee: Option<BigDecimal>,
+    /// required for, and only allowed on, tiered schedules
+    #[serde(default)]
+    #[validate(nested)]
+    pub tiers: Vec<CreateFeeTierRequest>,
+    /// defaults to now, a schedule cannot take effect in the past
+    pub effective_from: Option<DateTime<Local>>,
+    pub effective_to: Option<DateTime<Local>>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFeeTierRequest {
+    #[validate(custom(
+        function = "crate::utils::validate_non_negative_amount",
+        message = "lower bound cannot be negative",
+        code = "lowerBound"
+    ))]
+    pub lower_bound: BigDecimal,
+    #[validate(custom(
+        function = "crate::utils::validate_non_negative_amount",
+        message = "flat amount cannot be negative",
+        code = "flatAmount"
+    ))]
+    pub flat_amount: Option<BigDecimal>,
+    #[validate(range(
+        max = 10000,
+        message = "percentage cannot be more than 10000 basis points",
+        code = "percentageBasisPoints"
+    ))]
+    pub percentage_basis_points: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FeePreviewQuery {
+    pub transaction_type: TransactionType,
+    pub wallet_identifier: Uuid,
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: BigDecimal,
+}
+
+/// What a fee is looked up by, the user's account type is read from `users`
+#[derive(Debug)]
+pub struct FeeQuery {
+    pub transaction_type: TransactionType,
+    pub currency_identifier: Uuid,
+    pub user_identifier: Uuid,
+    pub amount: BigDecimal,
+}
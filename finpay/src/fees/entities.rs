@@ -0,0 +1,209 @@
+use crate::transactions::entities::TransactionType;
+use crate::users::enums::AccountType;
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// decimal places fees are rounded to
+const FEE_SCALE: i64 = 2;
+const BASIS_POINTS: u32 = 10_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "fee_method_enum")]
+pub enum FeeMethod {
+    /// `flat_amount` regardless of the amount
+    Flat,
+    /// `percentage_basis_points` of the amount on top of `flat_amount`
+    Percentage,
+    /// priced by the tier the amount falls in
+    Tiered,
+}
+
+/// How a type of transaction is charged between `effective_from` and `effective_to`, a null
+/// currency or account type matches any
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeSchedule {
+    pub identifier: Uuid,
+    pub name: String,
+    pub transaction_type: TransactionType,
+    pub currency_identifier: Option<Uuid>,
+    pub account_type: Option<AccountType>,
+    pub minimum_amount: BigDecimal,
+    pub maximum_amount: Option<BigDecimal>,
+    pub fee_method: FeeMethod,
+    pub flat_amount: BigDecimal,
+    pub percentage_basis_points: i32,
+    pub minimum_fee: Option<BigDecimal>,
+    pub maximum_fee: Option<BigDecimal>,
+    pub effective_from: DateTime<Local>,
+    pub effective_to: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+    #[sqlx(skip)]
+    pub tiers: Vec<FeeTier>,
+}
+
+impl FeeSchedule {
+    /// the fee charged on `amount`, clamped to the schedule's minimum and maximum fee
+    pub fn fee_for(&self, amount: &BigDecimal) -> BigDecimal {
+        let (flat_amount, percentage_basis_points) = match self.fee_method {
+            FeeMethod::Flat => (self.flat_amount.clone(), 0),
+            FeeMethod::Percentage => (self.flat_amount.clone(), self.percentage_basis_points),
+            FeeMethod::Tiered => self
+                .tiers
+                .iter()
+                .filter(|tier| &tier.lower_bound <= amount)
+                .max_by(|a, b| a.lower_bound.cmp(&b.lower_bound))
+                .map(|tier| (tier.flat_amount.clone(), tier.percentage_basis_points))
+                .unwrap_or((BigDecimal::zero(), 0)),
+        };
+
+        let mut fee = flat_amount
+            + amount * BigDecimal::from(percentage_basis_points) / BigDecimal::from(BASIS_POINTS);
+        if let Some(minimum_fee) = &self.minimum_fee {
+            fee = fee.max(minimum_fee.clone());
+        }
+        if let Some(maximum_fee) = &self.maximum_fee {
+            fee = fee.min(maximum_fee.clone());
+        }
+        fee.with_scale_round(FEE_SCALE, RoundingMode::HalfUp)
+    }
+}
+
+/// A band of a tiered schedule, it prices every amount from `lower_bound` up to the next tier
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeTier {
+    pub identifier: Uuid,
+    pub fee_schedule_identifier: Uuid,
+    pub lower_bound: BigDecimal,
+    pub flat_amount: BigDecimal,
+    pub percentage_basis_points: i32,
+}
+
+/// The fee a transaction would be charged and the schedule it came from, no schedule means no fee
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeAssessment {
+    pub fee_schedule_identifier: Option<Uuid>,
+    pub fee_amount: BigDecimal,
+}
+
+/// A fee taken from a wallet for a transaction, it is refunded if the transaction is undone
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeCharge {
+    pub identifier: Uuid,
+    pub transaction_identifier: Uuid,
+    pub fee_schedule_identifier: Option<Uuid>,
+    pub wallet_identifier: Uuid,
+    pub amount: BigDecimal,
+    pub currency_identifier: Uuid,
+    pub refunded_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeePreview {
+    pub transaction_type: TransactionType,
+    pub wallet_identifier: Uuid,
+    pub currency_identifier: Uuid,
+    pub amount: BigDecimal,
+    pub fee_amount: BigDecimal,
+    /// what the wallet would be debited, the amount plus the fee
+    pub total_debit: BigDecimal,
+    pub fee_schedule_identifier: Option<Uuid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn schedule(
+        fee_method: FeeMethod,
+        flat_amount: &str,
+        percentage_basis_points: i32,
+    ) -> FeeSchedule {
+        FeeSchedule {
+            identifier: Uuid::new_v4(),
+            name: "test".to_string(),
+            transaction_type: TransactionType::Transfer,
+            currency_identifier: None,
+            account_type: None,
+            minimum_amount: BigDecimal::zero(),
+            maximum_amount: None,
+            fee_method,
+            flat_amount: BigDecimal::from_str(flat_amount).unwrap(),
+            percentage_basis_points,
+            minimum_fee: None,
+            maximum_fee: None,
+            effective_from: Local::now(),
+            effective_to: None,
+            created_date: Local::now(),
+            tiers: vec![],
+        }
+    }
+
+    fn tier(lower_bound: i32, flat_amount: &str, percentage_basis_points: i32) -> FeeTier {
+        FeeTier {
+            identifier: Uuid::new_v4(),
+            fee_schedule_identifier: Uuid::new_v4(),
+            lower_bound: BigDecimal::from(lower_bound),
+            flat_amount: BigDecimal::from_str(flat_amount).unwrap(),
+            percentage_basis_points,
+        }
+    }
+
+    #[test]
+    fn test_flat_and_percentage_fees() {
+        let flat = schedule(FeeMethod::Flat, "2.5", 100);
+        assert_eq!(
+            flat.fee_for(&BigDecimal::from(1000)),
+            BigDecimal::from_str("2.50").unwrap()
+        );
+
+        let percentage = schedule(FeeMethod::Percentage, "0.3", 290);
+        assert_eq!(
+            percentage.fee_for(&BigDecimal::from_str("123.45").unwrap()),
+            BigDecimal::from_str("3.88").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tiered_fee_prices_by_the_highest_tier_reached() {
+        let mut tiered = schedule(FeeMethod::Tiered, "0", 0);
+        tiered.tiers = vec![tier(0, "1", 0), tier(500, "0", 100), tier(5000, "0", 50)];
+
+        assert_eq!(tiered.fee_for(&BigDecimal::from(100)), BigDecimal::from(1));
+        assert_eq!(
+            tiered.fee_for(&BigDecimal::from(1000)),
+            BigDecimal::from(10)
+        );
+        assert_eq!(
+            tiered.fee_for(&BigDecimal::from(10000)),
+            BigDecimal::from(50)
+        );
+    }
+
+    #[test]
+    fn test_fee_is_clamped_to_minimum_and_cap() {
+        let mut capped = schedule(FeeMethod::Percentage, "0", 150);
+        capped.minimum_fee = Some(BigDecimal::from(1));
+        capped.maximum_fee = Some(BigDecimal::from(20));
+
+        assert_eq!(capped.fee_for(&BigDecimal::from(10)), BigDecimal::from(1));
+        assert_eq!(
+            capped.fee_for(&BigDecimal::from(1000)),
+            BigDecimal::from(15)
+        );
+        assert_eq!(
+            capped.fee_for(&BigDecimal::from(100000)),
+            BigDecimal::from(20)
+        );
+    }
+}
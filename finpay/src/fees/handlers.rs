@@ -0,0 +1,58 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::fees::adapters::{CreateFeeScheduleRequest, FeePreviewQuery};
+use crate::fees::entities::{FeePreview, FeeSchedule};
+use crate::fees::service::{FeeService, FeeServiceExt};
+use crate::shared::middlewares::admin_middleware::AdminClaims;
+use crate::shared::middlewares::validator::ValidatedRequest;
+use crate::utils::{ApiResponse, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+pub async fn preview_fee(
+    State(fee_service): State<FeeService>,
+    claims: Claims,
+    Query(query): Query<FeePreviewQuery>,
+) -> Result<ApiResponse<FeePreview>, ServiceError> {
+    let preview = fee_service.preview_fee(&claims, &query).await?;
+
+    Ok(ApiResponse::builder().data(preview).build())
+}
+
+pub async fn create_fee_schedule(
+    State(fee_service): State<FeeService>,
+    _: AdminClaims,
+    ValidatedRequest(request): ValidatedRequest<CreateFeeScheduleRequest>,
+) -> Result<ApiResponse<FeeSchedule>, ServiceError> {
+    let schedule = fee_service.create_schedule(&request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(schedule)
+        .message("fee schedule created successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_fee_schedules(
+    State(fee_service): State<FeeService>,
+    _: AdminClaims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<FeeSchedule>>, ServiceError> {
+    let schedules = fee_service.fetch_schedules(&pagination_params).await?;
+
+    Ok(ApiResponse::builder().data(schedules).build())
+}
+
+pub async fn retire_fee_schedule(
+    State(fee_service): State<FeeService>,
+    _: AdminClaims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<FeeSchedule>, ServiceError> {
+    let schedule = fee_service.retire_schedule(&identifier).await?;
+
+    Ok(ApiResponse::builder()
+        .data(schedule)
+        .message("fee schedule retired successfully")
+        .build())
+}
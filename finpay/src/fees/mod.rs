@@ -0,0 +1,6 @@
+pub mod adapters;
+pub mod entities;
+pub mod handlers;
+pub mod repository;
+pub mod router;
+pub mod service;
@@ -0,0 +1,457 @@
+use crate::errors::RepositoryError;
+use crate::fees::adapters::{CreateFeeScheduleRequest, FeePreviewQuery, FeeQuery};
+use crate::fees::entities::{
+    FeeAssessment, FeeCharge, FeeMethod, FeePreview, FeeSchedule, FeeTier,
+};
+use crate::ledger::adapters::NewJournalEntry;
+use crate::ledger::entities::SystemAccount;
+use crate::ledger::repository::LedgerRepository;
+use crate::transactions::entities::Transaction;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::Local;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct FeeRepository {
+    pool: PgPool,
+}
+
+impl FeeRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Prices a transaction against the schedule in effect now. When several match, the one
+    /// scoped to the currency, then to the account type, then the most recent wins
+    pub async fn assess(
+        connection: &mut PgConnection,
+        query: &FeeQuery,
+    ) -> Result<FeeAssessment, RepositoryError> {
+        let schedule = sqlx::query_as::<_, FeeSchedule>(
+            r#"
+            SELECT *
+            FROM fee_schedules
+            WHERE transaction_type = $1
+              AND (currency_identifier IS NULL OR currency_identifier = $2)
+              AND (account_type IS NULL OR
+                   account_type = (SELECT account_type FROM users WHERE identifier = $3))
+              AND minimum_amount <= $4
+              AND (maximum_amount IS NULL OR maximum_amount > $4)
+              AND effective_from <= NOW()
+              AND (effective_to IS NULL OR effective_to > NOW())
+            ORDER BY currency_identifier IS NULL, account_type IS NULL, effective_from DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(query.transaction_type)
+        .bind(query.currency_identifier)
+        .bind(query.user_identifier)
+        .bind(&query.amount)
+        .fetch_optional(&mut *connection)
+        .await?;
+
+        let Some(mut schedule) = schedule else {
+            return Ok(FeeAssessment::default());
+        };
+        if schedule.fee_method == FeeMethod::Tiered {
+            schedule.tiers = sqlx::query_as::<_, FeeTier>(
+                r#"SELECT * FROM fee_schedule_tiers WHERE fee_schedule_identifier = $1"#,
+            )
+            .bind(schedule.identifier)
+            .fetch_all(&mut *connection)
+            .await?;
+        }
+
+        Ok(FeeAssessment {
+            fee_schedule_identifier: Some(schedule.identifier),
+            fee_amount: schedule.fee_for(&query.amount),
+        })
+    }
+
+    /// Takes an assessed fee from the wallet into fee revenue, in the transaction's currency.
+    /// A zero fee is not recorded
+    pub async fn charge(
+        connection: &mut PgConnection,
+        transaction: &Transaction,
+        wallet_identifier: &Uuid,
+        assessment: &FeeAssessment,
+    ) -> Result<Option<FeeCharge>, RepositoryError> {
+        if assessment.fee_amount <= BigDecimal::zero() {
+            return Ok(None);
+        }
+
+        let wallet_account =
+            LedgerRepository::wallet_account(connection, wallet_identifier).await?;
+        let fee_revenue = LedgerRepository::system_account(
+            connection,
+            SystemAccount::FeeRevenue,
+            &transaction.currency_identifier,
+        )
+        .await?;
+        let entry = NewJournalEntry::new(&format!("fee {}", transaction.reference))
+            .transaction(&transaction.identifier)
+            .debit(&wallet_account, &assessment.fee_amount)
+            .credit(&fee_revenue, &assessment.fee_amount);
+        LedgerRepository::record_entry(connection, &entry).await?;
+
+        let query = r#"
+        INSERT INTO fee_charges (identifier, transaction_identifier, fee_schedule_identifier,
+                                 wallet_identifier, amount, currency_identifier)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#;
+        let charge = sqlx::query_as::<_, FeeCharge>(query)
+            .bind(Uuid::new_v4())
+            .bind(transaction.identifier)
+            .bind(assessment.fee_schedule_identifier)
+            .bind(wallet_identifier)
+            .bind(&assessment.fee_amount)
+            .bind(transaction.currency_identifier)
+            .fetch_one(&mut *connection)
+            .await?;
+        Ok(Some(charge))
+    }
+
+    /// Hands the fee charged for a transaction back to the wallet it was taken from
+    pub async fn refund(
+        connection: &mut PgConnection,
+        transaction: &Transaction,
+    ) -> Result<Option<FeeCharge>, RepositoryError> {
+        let charge = sqlx::query_as::<_, FeeCharge>(
+            r#"
+            SELECT *
+            FROM fee_charges
+            WHERE transaction_identifier = $1
+              AND refunded_date IS NULL
+            FOR UPDATE
+            "#,
+        )
+        .bind(transaction.identifier)
+        .fetch_optional(&mut *connection)
+        .await?;
+        let Some(charge) = charge else {
+            return Ok(None);
+        };
+
+        let wallet_account =
+            LedgerRepository::wallet_account(connection, &charge.wallet_identifier).await?;
+        let fee_revenue = LedgerRepository::system_account(
+            connection,
+            SystemAccount::FeeRevenue,
+            &charge.currency_identifier,
+        )
+        .await?;
+        let entry = NewJournalEntry::new(&format!("fee {} refunded", transaction.reference))
+            .transaction(&transaction.identifier)
+            .debit(&fee_revenue, &charge.amount)
+            .credit(&wallet_account, &charge.amount);
+        LedgerRepository::record_entry(connection, &entry).await?;
+
+        sqlx::query_as::<_, FeeCharge>(
+            r#"UPDATE fee_charges SET refunded_date = NOW() WHERE identifier = $1 RETURNING *"#,
+        )
+        .bind(charge.identifier)
+        .fetch_one(&mut *connection)
+        .await
+        .map(Some)
+        .map_err(RepositoryError::from)
+    }
+}
+
+pub trait FeeRepositoryExt {
+    fn create_schedule(
+        &self,
+        request: &CreateFeeScheduleRequest,
+    ) -> impl std::future::Future<Output = Result<FeeSchedule, RepositoryError>> + Send;
+
+    fn fetch_schedules(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<FeeSchedule>, RepositoryError>> + Send;
+
+    /// Ends a schedule now, or cancels it if it has not taken effect yet. Fees already charged
+    /// and quotes already priced keep what they were given
+    fn retire_schedule(
+        &self,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<FeeSchedule, RepositoryError>> + Send;
+
+    fn preview(
+        &self,
+        query: &FeePreviewQuery,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<FeePreview, RepositoryError>> + Send;
+}
+
+impl FeeRepositoryExt for FeeRepository {
+    async fn create_schedule(
+        &self,
+        request: &CreateFeeScheduleRequest,
+    ) -> Result<FeeSchedule, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = r#"
+        INSERT INTO fee_schedules (identifier, name, transaction_type, currency_identifier,
+                                   account_type, minimum_amount, maximum_amount, fee_method,
+                                   flat_amount, percentage_basis_points, minimum_fee, maximum_fee,
+                                   effective_from, effective_to)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        RETURNING *
+        "#;
+        let mut schedule = sqlx::query_as::<_, FeeSchedule>(query)
+            .bind(Uuid::new_v4())
+            .bind(&request.name)
+            .bind(request.transaction_type)
+            .bind(request.currency_identifier)
+            .bind(request.account_type)
+            .bind(
+                request
+                    .minimum_amount
+                    .clone()
+                    .unwrap_or_else(BigDecimal::zero),
+            )
+            .bind(&request.maximum_amount)
+            .bind(request.fee_method)
+            .bind(request.flat_amount.clone().unwrap_or_else(BigDecimal::zero))
+            .bind(request.percentage_basis_points.unwrap_or_default() as i32)
+            .bind(&request.minimum_fee)
+            .bind(&request.maximum_fee)
+            .bind(request.effective_from.unwrap_or_else(Local::now))
+            .bind(request.effective_to)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let query = r#"
+        INSERT INTO fee_schedule_tiers (identifier, fee_schedule_identifier, lower_bound,
+                                        flat_amount, percentage_basis_points)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#;
+        for tier in &request.tiers {
+            let tier = sqlx::query_as::<_, FeeTier>(query)
+                .bind(Uuid::new_v4())
+                .bind(schedule.identifier)
+                .bind(&tier.lower_bound)
+                .bind(tier.flat_amount.clone().unwrap_or_else(BigDecimal::zero))
+                .bind(tier.percentage_basis_points.unwrap_or_default() as i32)
+                .fetch_one(&mut *tx)
+                .await?;
+            schedule.tiers.push(tier);
+        }
+
+        tx.commit().await?;
+        Ok(schedule)
+    }
+
+    async fn fetch_schedules(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<FeeSchedule>, RepositoryError> {
+        let total_count: i64 = sqlx::query_scalar("SELECT COUNT(identifier) FROM fee_schedules")
+            .fetch_one(&self.pool)
+            .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let mut schedules = sqlx::query_as::<_, FeeSchedule>(
+            r#"SELECT * FROM fee_schedules ORDER BY effective_from DESC LIMIT $1 OFFSET $2"#,
+        )
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let schedule_identifiers: Vec<Uuid> = schedules
+            .iter()
+            .map(|schedule| schedule.identifier)
+            .collect();
+        let tiers = sqlx::query_as::<_, FeeTier>(
+            r#"
+            SELECT *
+            FROM fee_schedule_tiers
+            WHERE fee_schedule_identifier = ANY($1)
+            ORDER BY lower_bound
+            "#,
+        )
+        .bind(schedule_identifiers)
+        .fetch_all(&self.pool)
+        .await?;
+        for tier in tiers {
+            if let Some(schedule) = schedules
+                .iter_mut()
+                .find(|schedule| schedule.identifier == tier.fee_schedule_identifier)
+            {
+                schedule.tiers.push(tier);
+            }
+        }
+
+        Ok(PaginatedResponse::new(
+            schedules,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn retire_schedule(&self, identifier: &Uuid) -> Result<FeeSchedule, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let schedule = sqlx::query_as::<_, FeeSchedule>(
+            r#"SELECT * FROM fee_schedules WHERE identifier = $1 FOR UPDATE"#,
+        )
+        .bind(identifier)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+        if schedule
+            .effective_to
+            .is_some_and(|effective_to| effective_to <= Local::now())
+        {
+            return Err(RepositoryError::OperationFailed(
+                "the fee schedule has already ended".into(),
+            ));
+        }
+
+        // a schedule that has not started yet ends the moment it starts, so it never applies
+        let schedule = sqlx::query_as::<_, FeeSchedule>(
+            r#"
+            UPDATE fee_schedules
+            SET effective_to = GREATEST(NOW(), effective_from)
+            WHERE identifier = $1
+            RETURNING *
+            "#,
+        )
+        .bind(schedule.identifier)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(schedule)
+    }
+
+    async fn preview(
+        &self,
+        query: &FeePreviewQuery,
+        user_identifier: &Uuid,
+    ) -> Result<FeePreview, RepositoryError> {
+        let mut connection = self.pool.acquire().await?;
+
+        let currency_identifier: Uuid = sqlx::query_scalar(
+            r#"SELECT currency_identifier FROM wallets WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(query.wallet_identifier)
+        .bind(user_identifier)
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+
+        let assessment = Self::assess(
+            &mut connection,
+            &FeeQuery {
+                transaction_type: query.transaction_type,
+                currency_identifier,
+                user_identifier: *user_identifier,
+                amount: query.amount.clone(),
+            },
+        )
+        .await?;
+
+        Ok(FeePreview {
+            transaction_type: query.transaction_type,
+            wallet_identifier: query.wallet_identifier,
+            currency_identifier,
+            total_debit: &query.amount + &assessment.fee_amount,
+            amount: query.amount.clone(),
+            fee_amount: assessment.fee_amount,
+            fee_schedule_identifier: assessment.fee_schedule_identifier,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::fixtures::{UAE_DIRHAM, create_user};
+    use crate::transactions::entities::TransactionType;
+    use crate::users::entities::User;
+    use crate::users::repositories::{UsersRepository, UsersRepositoryExt};
+    use chrono::Duration;
+    use fake::{Fake, Faker};
+    use std::str::FromStr;
+
+    fn flat_schedule(flat_amount: i32) -> CreateFeeScheduleRequest {
+        CreateFeeScheduleRequest {
+            name: Faker.fake(),
+            transaction_type: TransactionType::Transfer,
+            currency_identifier: None,
+            account_type: None,
+            minimum_amount: None,
+            maximum_amount: None,
+            fee_method: FeeMethod::Flat,
+            flat_amount: Some(BigDecimal::from(flat_amount)),
+            percentage_basis_points: None,
+            minimum_fee: None,
+            maximum_fee: None,
+            tiers: vec![],
+            effective_from: None,
+            effective_to: None,
+        }
+    }
+
+    async fn assessed_fee(pool: &PgPool, user: &User, amount: i32) -> BigDecimal {
+        let mut connection = pool.acquire().await.unwrap();
+        FeeRepository::assess(
+            &mut connection,
+            &FeeQuery {
+                transaction_type: TransactionType::Transfer,
+                currency_identifier: Uuid::from_str(UAE_DIRHAM).unwrap(),
+                user_identifier: user.identifier,
+                amount: BigDecimal::from(amount),
+            },
+        )
+        .await
+        .unwrap()
+        .fee_amount
+    }
+
+    #[sqlx::test]
+    async fn test_assess_uses_the_most_specific_schedule_in_effect(pool: PgPool) {
+        let repository = FeeRepository::new(&pool);
+        let user = UsersRepository::new(&pool)
+            .find_user_by_pk(&create_user(&pool).await)
+            .await
+            .unwrap()
+            .expect("failed to load user");
+        assert_eq!(assessed_fee(&pool, &user, 100).await, BigDecimal::zero());
+
+        repository.create_schedule(&flat_schedule(1)).await.unwrap();
+        let mut in_currency = flat_schedule(2);
+        in_currency.currency_identifier = Some(Uuid::from_str(UAE_DIRHAM).unwrap());
+        repository.create_schedule(&in_currency).await.unwrap();
+        let mut large_amounts = flat_schedule(9);
+        large_amounts.minimum_amount = Some(BigDecimal::from(1000));
+        repository.create_schedule(&large_amounts).await.unwrap();
+        let mut upcoming = flat_schedule(5);
+        upcoming.currency_identifier = Some(Uuid::from_str(UAE_DIRHAM).unwrap());
+        upcoming.account_type = Some(user.account_type);
+        upcoming.effective_from = Some(Local::now() + Duration::hours(1));
+        repository.create_schedule(&upcoming).await.unwrap();
+
+        assert_eq!(assessed_fee(&pool, &user, 100).await, BigDecimal::from(2));
+
+        let mut for_account_type = flat_schedule(3);
+        for_account_type.currency_identifier = Some(Uuid::from_str(UAE_DIRHAM).unwrap());
+        for_account_type.account_type = Some(user.account_type);
+        let for_account_type = repository.create_schedule(&for_account_type).await.unwrap();
+        assert_eq!(assessed_fee(&pool, &user, 100).await, BigDecimal::from(3));
+
+        repository
+            .retire_schedule(&for_account_type.identifier)
+            .await
+            .unwrap();
+        assert_eq!(assessed_fee(&pool, &user, 100).await, BigDecimal::from(2));
+    }
+}
@@ -0,0 +1,22 @@
+use crate::fees::handlers::{
+    create_fee_schedule, fetch_fee_schedules, preview_fee, retire_fee_schedule,
+};
+use crate::state::AppState;
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+
+pub fn fee_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/preview", get(preview_fee))
+        .with_state(state.clone())
+}
+
+pub fn admin_fee_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/schedules", post(create_fee_schedule))
+        .route("/schedules", get(fetch_fee_schedules))
+        .route("/schedules/{identifier}", delete(retire_fee_schedule))
+        .with_state(state.clone())
+}
@@ -0,0 +1,131 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::fees::adapters::{CreateFeeScheduleRequest, FeePreviewQuery};
+use crate::fees::entities::{FeeMethod, FeePreview, FeeSchedule};
+use crate::fees::repository::{FeeRepository, FeeRepositoryExt};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::Local;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Clone)]
+pub struct FeeService {
+    repository: FeeRepository,
+}
+
+impl FeeService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: FeeRepository::new(pool),
+        }
+    }
+}
+
+pub trait FeeServiceExt {
+    fn create_schedule(
+        &self,
+        request: &CreateFeeScheduleRequest,
+    ) -> impl std::future::Future<Output = Result<FeeSchedule, ServiceError>> + Send;
+
+    fn fetch_schedules(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<FeeSchedule>, ServiceError>> + Send;
+
+    fn retire_schedule(
+        &self,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<FeeSchedule, ServiceError>> + Send;
+
+    /// The fee a transaction would be charged if it were confirmed now
+    fn preview_fee(
+        &self,
+        claims: &Claims,
+        query: &FeePreviewQuery,
+    ) -> impl std::future::Future<Output = Result<FeePreview, ServiceError>> + Send;
+}
+
+impl FeeServiceExt for FeeService {
+    async fn create_schedule(
+        &self,
+        request: &CreateFeeScheduleRequest,
+    ) -> Result<FeeSchedule, ServiceError> {
+        if (request.fee_method == FeeMethod::Tiered) == request.tiers.is_empty() {
+            return Err(ServiceError::UnprocessableEntity(
+                "tiers are required for, and only allowed on, tiered schedules".to_string(),
+            ));
+        }
+
+        let minimum_amount = request
+            .minimum_amount
+            .clone()
+            .unwrap_or_else(BigDecimal::zero);
+        if request
+            .maximum_amount
+            .as_ref()
+            .is_some_and(|maximum_amount| maximum_amount <= &minimum_amount)
+        {
+            return Err(ServiceError::UnprocessableEntity(
+                "maximum amount must be greater than the minimum amount".to_string(),
+            ));
+        }
+
+        let fees_inverted = match (&request.minimum_fee, &request.maximum_fee) {
+            (Some(minimum_fee), Some(maximum_fee)) => maximum_fee < minimum_fee,
+            _ => false,
+        };
+        if fees_inverted {
+            return Err(ServiceError::UnprocessableEntity(
+                "maximum fee cannot be less than the minimum fee".to_string(),
+            ));
+        }
+
+        // schedules only ever apply going forward, so that nothing already priced changes
+        let effective_from = request.effective_from.unwrap_or_else(Local::now);
+        if request
+            .effective_from
+            .is_some_and(|from| from < Local::now())
+        {
+            return Err(ServiceError::UnprocessableEntity(
+                "a fee schedule cannot take effect in the past".to_string(),
+            ));
+        }
+        if request.effective_to.is_some_and(|to| to <= effective_from) {
+            return Err(ServiceError::UnprocessableEntity(
+                "a fee schedule must end after it takes effect".to_string(),
+            ));
+        }
+
+        let schedule = self.repository.create_schedule(request).await?;
+        Ok(schedule)
+    }
+
+    async fn fetch_schedules(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<FeeSchedule>, ServiceError> {
+        let schedules = self.repository.fetch_schedules(pagination_params).await?;
+        Ok(schedules)
+    }
+
+    async fn retire_schedule(&self, identifier: &Uuid) -> Result<FeeSchedule, ServiceError> {
+        let schedule = self.repository.retire_schedule(identifier).await?;
+        Ok(schedule)
+    }
+
+    async fn preview_fee(
+        &self,
+        claims: &Claims,
+        query: &FeePreviewQuery,
+    ) -> Result<FeePreview, ServiceError> {
+        query.validate()?;
+
+        let preview = self
+            .repository
+            .preview(query, &claims.user_identifier)
+            .await?;
+        Ok(preview)
+    }
+}
@@ -0,0 +1,61 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::conversions::entities::ConversionPricing;
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FxConvertQuery {
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: BigDecimal,
+    /// ISO 4217 code of the currency being sold
+    #[validate(length(equal = 3, message = "invalid currency code", code = "from"))]
+    pub from: String,
+    #[validate(length(equal = 3, message = "invalid currency code", code = "to"))]
+    pub to: String,
+    /// converts at the rates that stood at this instant, the latest rates when left out
+    pub as_of: Option<DateTime<Local>>,
+}
+
+/// A rate to store against the base currency
+#[derive(Debug, Clone)]
+pub struct NewExchangeRate {
+    pub quote_currency_code: String,
+    pub rate: BigDecimal,
+    pub source: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFxQuoteRequest {
+    pub source_wallet_identifier: Uuid,
+    /// one of the user's own wallets for a conversion, any wallet for a cross-currency transfer
+    pub destination_wallet_identifier: Uuid,
+    /// how much to sell, in the source wallet's currency
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: BigDecimal,
+}
+
+/// A priced quote ready to be stored, its fee is assessed when it is stored
+#[derive(Debug)]
+pub struct NewFxQuote {
+    pub user_identifier: Uuid,
+    pub source_wallet_identifier: Uuid,
+    pub destination_wallet_identifier: Uuid,
+    pub source_currency_identifier: Uuid,
+    pub destination_currency_identifier: Uuid,
+    pub source_amount: BigDecimal,
+    pub pricing: ConversionPricing,
+    pub expires_at: DateTime<Local>,
+}
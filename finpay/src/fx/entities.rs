@@ -0,0 +1,137 @@
+use crate::conversions::entities::ConversionPricing;
+use crate::fees::entities::FeeAssessment;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt::Display;
+use uuid::Uuid;
+
+/// the currency every stored rate is quoted against, cross rates are derived through it
+pub const BASE_CURRENCY_CODE: &str = "USD";
+
+/// the topic `ExchangeRateUpdated` events are published to
+pub const EXCHANGE_RATE_UPDATED_TOPIC: &str = "fx.exchange_rate_updated";
+
+/// How many units of the quote currency one unit of the base currency bought at `created_date`
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRate {
+    pub identifier: Uuid,
+    pub base_currency_code: String,
+    pub quote_currency_code: String,
+    pub rate: BigDecimal,
+    pub source: String,
+    pub created_date: DateTime<Local>,
+}
+
+/// A rate between any two currencies, derived through the base currency
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossRate {
+    pub from_currency_code: String,
+    pub to_currency_code: String,
+    pub rate: BigDecimal,
+    pub source: String,
+    /// when the older of the two underlying rates was recorded
+    pub as_of: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FxConversion {
+    pub from_currency_code: String,
+    pub to_currency_code: String,
+    pub amount: BigDecimal,
+    pub rate: BigDecimal,
+    pub converted_amount: BigDecimal,
+    pub as_of: DateTime<Local>,
+}
+
+/// A rate, markup and fee locked for a pair of wallets until `expires_at`, it can be executed once
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FxQuote {
+    pub identifier: Uuid,
+    pub user_identifier: Uuid,
+    pub source_wallet_identifier: Uuid,
+    pub destination_wallet_identifier: Uuid,
+    pub source_currency_identifier: Uuid,
+    pub destination_currency_identifier: Uuid,
+    pub source_amount: BigDecimal,
+    /// charged on top of `source_amount`, in the source currency
+    pub fee_amount: BigDecimal,
+    /// the schedule the fee was priced from when the quote was created
+    pub fee_schedule_identifier: Option<Uuid>,
+    pub destination_amount: BigDecimal,
+    pub mid_rate: BigDecimal,
+    pub rate: BigDecimal,
+    pub spread_basis_points: i32,
+    pub rate_source: String,
+    pub transaction_identifier: Option<Uuid>,
+    pub expires_at: DateTime<Local>,
+    pub used_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+}
+
+impl FxQuote {
+    /// what the source wallet is debited, the amount sold plus the fee
+    pub fn total_debit(&self) -> BigDecimal {
+        &self.source_amount + &self.fee_amount
+    }
+
+    pub fn fee(&self) -> FeeAssessment {
+        FeeAssessment {
+            fee_schedule_identifier: self.fee_schedule_identifier,
+            fee_amount: self.fee_amount.clone(),
+        }
+    }
+
+    /// a quote can only be executed once and only before it expires
+    pub fn is_redeemable(&self) -> bool {
+        self.used_date.is_none() && self.expires_at > Local::now()
+    }
+
+    pub fn pricing(&self) -> ConversionPricing {
+        ConversionPricing {
+            mid_rate: self.mid_rate.clone(),
+            spread_basis_points: self.spread_basis_points as u32,
+            rate_source: self.rate_source.clone(),
+        }
+    }
+}
+
+/// Published whenever a new rate is stored
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRateUpdated {
+    pub base_currency_code: String,
+    pub quote_currency_code: String,
+    pub rate: BigDecimal,
+    pub previous_rate: Option<BigDecimal>,
+    pub source: String,
+    pub created_date: DateTime<Local>,
+}
+
+impl From<&ExchangeRate> for ExchangeRateUpdated {
+    fn from(rate: &ExchangeRate) -> Self {
+        Self {
+            base_currency_code: rate.base_currency_code.clone(),
+            quote_currency_code: rate.quote_currency_code.clone(),
+            rate: rate.rate.clone(),
+            previous_rate: None,
+            source: rate.source.clone(),
+            created_date: rate.created_date,
+        }
+    }
+}
+
+impl Display for ExchangeRateUpdated {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} = {} ({})",
+            self.base_currency_code, self.quote_currency_code, self.rate, self.source
+        )
+    }
+}
@@ -0,0 +1,61 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::fx::adapters::{CreateFxQuoteRequest, FxConvertQuery};
+use crate::fx::entities::{ExchangeRate, FxConversion, FxQuote};
+use crate::fx::service::{FxService, FxServiceExt};
+use crate::utils::{ApiResponse, AuthenticatedRequest, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+pub async fn fetch_latest_rates(
+    State(fx_service): State<FxService>,
+) -> Result<ApiResponse<Vec<ExchangeRate>>, ServiceError> {
+    let rates = fx_service.fetch_latest_rates().await?;
+
+    Ok(ApiResponse::builder().data(rates).build())
+}
+
+pub async fn convert(
+    State(fx_service): State<FxService>,
+    Query(query): Query<FxConvertQuery>,
+) -> Result<ApiResponse<FxConversion>, ServiceError> {
+    let conversion = fx_service.convert(&query).await?;
+
+    Ok(ApiResponse::builder().data(conversion).build())
+}
+
+pub async fn fetch_rate_history(
+    State(fx_service): State<FxService>,
+    Path(currency_code): Path<String>,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<ExchangeRate>>, ServiceError> {
+    let history = fx_service
+        .fetch_rate_history(&currency_code, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(history).build())
+}
+
+pub async fn create_quote(
+    State(fx_service): State<FxService>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateFxQuoteRequest>,
+) -> Result<ApiResponse<FxQuote>, ServiceError> {
+    let quote = fx_service.create_quote(&claims, &request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(quote)
+        .message("quote created successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_quote(
+    State(fx_service): State<FxService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<FxQuote>, ServiceError> {
+    let quote = fx_service.fetch_quote(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder().data(quote).build())
+}
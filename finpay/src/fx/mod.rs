@@ -0,0 +1,7 @@
+pub mod adapters;
+pub mod entities;
+pub mod handlers;
+pub mod provider;
+pub mod repository;
+pub mod router;
+pub mod service;
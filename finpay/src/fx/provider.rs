@@ -0,0 +1,52 @@
+use bigdecimal::{BigDecimal, RoundingMode};
+use rand::Rng;
+
+use crate::fx::adapters::NewExchangeRate;
+use crate::fx::entities::ExchangeRate;
+
+/// the furthest a mock refresh moves a rate, in basis points either way
+const MAX_DRIFT_BASIS_POINTS: i64 = 50;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RateProviderError {
+    #[error("the rate provider could not be reached: {0}")]
+    Unavailable(String),
+}
+
+/// Where fresh rates against the base currency come from
+pub trait RateProvider: Send + Sync {
+    fn fetch_rates(
+        &self,
+        current_rates: &[ExchangeRate],
+    ) -> impl std::future::Future<Output = Result<Vec<NewExchangeRate>, RateProviderError>> + Send;
+}
+
+/// Nudges every current rate by a small random amount, stands in for a market data feed
+#[derive(Clone, Default)]
+pub struct MockRateProvider;
+
+impl RateProvider for MockRateProvider {
+    async fn fetch_rates(
+        &self,
+        current_rates: &[ExchangeRate],
+    ) -> Result<Vec<NewExchangeRate>, RateProviderError> {
+        let mut rng = rand::rng();
+
+        let rates = current_rates
+            .iter()
+            .map(|current| {
+                let drift = rng.random_range(-MAX_DRIFT_BASIS_POINTS..=MAX_DRIFT_BASIS_POINTS);
+                let rate =
+                    &current.rate * BigDecimal::from(10_000 + drift) / BigDecimal::from(10_000);
+
+                NewExchangeRate {
+                    quote_currency_code: current.quote_currency_code.clone(),
+                    rate: rate.with_scale_round(10, RoundingMode::HalfEven),
+                    source: "mock".to_string(),
+                }
+            })
+            .collect();
+
+        Ok(rates)
+    }
+}
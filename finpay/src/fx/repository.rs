@@ -0,0 +1,386 @@
+use crate::errors::RepositoryError;
+use crate::fees::adapters::FeeQuery;
+use crate::fees::repository::FeeRepository;
+use crate::fx::adapters::{NewExchangeRate, NewFxQuote};
+use crate::fx::entities::{BASE_CURRENCY_CODE, CrossRate, ExchangeRate, FxQuote};
+use crate::transactions::entities::TransactionType;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use chrono::{DateTime, Local};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct FxRepository {
+    pool: PgPool,
+}
+
+impl FxRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Locks one of the user's quotes for execution, it must be unused and unexpired
+    pub async fn lock_quote(
+        connection: &mut PgConnection,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<FxQuote, RepositoryError> {
+        let quote = sqlx::query_as::<_, FxQuote>(
+            r#"SELECT * FROM fx_quotes WHERE identifier = $1 AND user_identifier = $2 FOR UPDATE"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(connection)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+
+        if !quote.is_redeemable() {
+            return Err(RepositoryError::InvalidQuote);
+        }
+        Ok(quote)
+    }
+
+    /// Marks a locked quote as used by the transaction that executed it
+    pub async fn redeem_quote(
+        connection: &mut PgConnection,
+        quote: &FxQuote,
+        transaction_identifier: &Uuid,
+    ) -> Result<FxQuote, RepositoryError> {
+        sqlx::query_as::<_, FxQuote>(
+            r#"
+            UPDATE fx_quotes
+            SET transaction_identifier = $1,
+                used_date              = NOW()
+            WHERE identifier = $2
+              AND used_date IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(transaction_identifier)
+        .bind(quote.identifier)
+        .fetch_optional(connection)
+        .await?
+        .ok_or(RepositoryError::InvalidQuote)
+    }
+}
+
+pub trait FxRepositoryExt {
+    /// The most recent rate of every currency against the base currency
+    fn fetch_latest_rates(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<ExchangeRate>, RepositoryError>> + Send;
+
+    /// Derives the rate between two currencies from their rates against the base currency, as
+    /// they stood at `as_of` or as they stand now
+    fn fetch_cross_rate(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+        as_of: Option<&DateTime<Local>>,
+    ) -> impl std::future::Future<Output = Result<Option<CrossRate>, RepositoryError>> + Send;
+
+    fn fetch_rate_history(
+        &self,
+        currency_code: &str,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<ExchangeRate>, RepositoryError>> + Send;
+
+    fn record_rates(
+        &self,
+        rates: &[NewExchangeRate],
+    ) -> impl std::future::Future<Output = Result<Vec<ExchangeRate>, RepositoryError>> + Send;
+
+    /// Resolves the currencies of a quote's wallets, the source wallet must belong to the user
+    fn fetch_wallet_currencies(
+        &self,
+        source_wallet_identifier: &Uuid,
+        destination_wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<(Uuid, Uuid), RepositoryError>> + Send;
+
+    fn create_quote(
+        &self,
+        quote: &NewFxQuote,
+    ) -> impl std::future::Future<Output = Result<FxQuote, RepositoryError>> + Send;
+
+    fn fetch_quote(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<FxQuote>, RepositoryError>> + Send;
+}
+
+impl FxRepositoryExt for FxRepository {
+    async fn fetch_latest_rates(&self) -> Result<Vec<ExchangeRate>, RepositoryError> {
+        let query = r#"
+        SELECT DISTINCT ON (quote_currency_code) *
+        FROM exchange_rates
+        WHERE base_currency_code = $1
+        ORDER BY quote_currency_code, created_date DESC
+        "#;
+        sqlx::query_as::<_, ExchangeRate>(query)
+            .bind(BASE_CURRENCY_CODE)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_cross_rate(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+        as_of: Option<&DateTime<Local>>,
+    ) -> Result<Option<CrossRate>, RepositoryError> {
+        // the base currency has no row of its own, it trades at 1 against itself
+        let query = r#"
+        WITH point_in_time AS (SELECT COALESCE($4::TIMESTAMPTZ, NOW()) AS as_of),
+             latest_rates AS (SELECT DISTINCT ON (quote_currency_code) quote_currency_code, rate, source, created_date
+                              FROM exchange_rates,
+                                   point_in_time
+                              WHERE base_currency_code = $3
+                                AND created_date <= point_in_time.as_of
+                              ORDER BY quote_currency_code, created_date DESC),
+             rates AS (SELECT quote_currency_code, rate, source, created_date
+                       FROM latest_rates
+                       UNION ALL
+                       SELECT $3, 1, 'base', point_in_time.as_of
+                       FROM point_in_time)
+        SELECT from_rate.quote_currency_code                         AS from_currency_code,
+               to_rate.quote_currency_code                           AS to_currency_code,
+               to_rate.rate / from_rate.rate                         AS rate,
+               CASE
+                   WHEN from_rate.source IN ('base', to_rate.source) THEN to_rate.source
+                   WHEN to_rate.source = 'base' THEN from_rate.source
+                   ELSE from_rate.source || '/' || to_rate.source END AS source,
+               LEAST(from_rate.created_date, to_rate.created_date)   AS as_of
+        FROM rates from_rate,
+             rates to_rate
+        WHERE from_rate.quote_currency_code = $1
+          AND to_rate.quote_currency_code = $2
+        "#;
+        sqlx::query_as::<_, CrossRate>(query)
+            .bind(from_currency_code)
+            .bind(to_currency_code)
+            .bind(BASE_CURRENCY_CODE)
+            .bind(as_of)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_rate_history(
+        &self,
+        currency_code: &str,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<ExchangeRate>, RepositoryError> {
+        let total_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(identifier) FROM exchange_rates WHERE base_currency_code = $1 AND quote_currency_code = $2",
+        )
+        .bind(BASE_CURRENCY_CODE)
+        .bind(currency_code)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let query = r#"
+        SELECT *
+        FROM exchange_rates
+        WHERE base_currency_code = $1
+          AND quote_currency_code = $2
+        ORDER BY created_date DESC
+        LIMIT $3 OFFSET $4
+        "#;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let rates = sqlx::query_as::<_, ExchangeRate>(query)
+            .bind(BASE_CURRENCY_CODE)
+            .bind(currency_code)
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(PaginatedResponse::new(
+            rates,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn record_rates(
+        &self,
+        rates: &[NewExchangeRate],
+    ) -> Result<Vec<ExchangeRate>, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = r#"
+        INSERT INTO exchange_rates (identifier, base_currency_code, quote_currency_code, rate, source)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#;
+        let mut recorded = Vec::with_capacity(rates.len());
+        for rate in rates {
+            let exchange_rate = sqlx::query_as::<_, ExchangeRate>(query)
+                .bind(Uuid::new_v4())
+                .bind(BASE_CURRENCY_CODE)
+                .bind(&rate.quote_currency_code)
+                .bind(&rate.rate)
+                .bind(&rate.source)
+                .fetch_one(&mut *tx)
+                .await?;
+            recorded.push(exchange_rate);
+        }
+
+        tx.commit().await?;
+        Ok(recorded)
+    }
+
+    async fn fetch_wallet_currencies(
+        &self,
+        source_wallet_identifier: &Uuid,
+        destination_wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<(Uuid, Uuid), RepositoryError> {
+        let query = r#"
+        SELECT source_wallet.currency_identifier, destination_wallet.currency_identifier
+        FROM wallets source_wallet,
+             wallets destination_wallet
+        WHERE source_wallet.identifier = $1
+          AND source_wallet.user_identifier = $3
+          AND destination_wallet.identifier = $2
+        "#;
+        sqlx::query_as::<_, (Uuid, Uuid)>(query)
+            .bind(source_wallet_identifier)
+            .bind(destination_wallet_identifier)
+            .bind(user_identifier)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)
+    }
+
+    async fn create_quote(&self, quote: &NewFxQuote) -> Result<FxQuote, RepositoryError> {
+        let mut connection = self.pool.acquire().await?;
+
+        // the fee is fixed on the quote, a schedule that changes before it is executed does not
+        // reprice it
+        let fee = FeeRepository::assess(
+            &mut connection,
+            &FeeQuery {
+                transaction_type: TransactionType::Conversion,
+                currency_identifier: quote.source_currency_identifier,
+                user_identifier: quote.user_identifier,
+                amount: quote.source_amount.clone(),
+            },
+        )
+        .await?;
+
+        let query = r#"
+        INSERT INTO fx_quotes (identifier, user_identifier, source_wallet_identifier,
+                               destination_wallet_identifier, source_currency_identifier,
+                               destination_currency_identifier, source_amount, fee_amount,
+                               fee_schedule_identifier, destination_amount, mid_rate, rate,
+                               spread_basis_points, rate_source, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, FxQuote>(query)
+            .bind(Uuid::new_v4())
+            .bind(quote.user_identifier)
+            .bind(quote.source_wallet_identifier)
+            .bind(quote.destination_wallet_identifier)
+            .bind(quote.source_currency_identifier)
+            .bind(quote.destination_currency_identifier)
+            .bind(&quote.source_amount)
+            .bind(&fee.fee_amount)
+            .bind(fee.fee_schedule_identifier)
+            .bind(quote.pricing.convert(&quote.source_amount))
+            .bind(&quote.pricing.mid_rate)
+            .bind(quote.pricing.rate())
+            .bind(quote.pricing.spread_basis_points as i32)
+            .bind(&quote.pricing.rate_source)
+            .bind(quote.expires_at)
+            .fetch_one(&mut *connection)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_quote(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<FxQuote>, RepositoryError> {
+        sqlx::query_as::<_, FxQuote>(
+            r#"SELECT * FROM fx_quotes WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::{BigDecimal, RoundingMode};
+    use std::str::FromStr;
+
+    #[sqlx::test]
+    async fn test_cross_rate_follows_the_latest_recorded_rates(pool: PgPool) {
+        let repository = FxRepository::new(&pool);
+        let before_refresh: DateTime<Local> = sqlx::query_scalar("SELECT NOW()")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        repository
+            .record_rates(&[NewExchangeRate {
+                quote_currency_code: "NGN".to_string(),
+                rate: BigDecimal::from(1500),
+                source: "test".to_string(),
+            }])
+            .await
+            .unwrap();
+
+        let latest = repository
+            .fetch_cross_rate("GBP", "NGN", None)
+            .await
+            .unwrap()
+            .unwrap();
+        let historical = repository
+            .fetch_cross_rate("GBP", "NGN", Some(&before_refresh))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(latest.rate, BigDecimal::from(2000));
+        assert_eq!(latest.source, "seed/test");
+        assert_eq!(
+            historical.rate.with_scale_round(4, RoundingMode::HalfEven),
+            BigDecimal::from_str("1953.3333").unwrap()
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_cross_rate_from_the_base_currency(pool: PgPool) {
+        let repository = FxRepository::new(&pool);
+
+        let rate = repository
+            .fetch_cross_rate("USD", "AED", None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(rate.rate, BigDecimal::from_str("3.6725").unwrap());
+        assert!(
+            repository
+                .fetch_cross_rate("USD", "XXX", None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use crate::{
+    fx::handlers::{convert, create_quote, fetch_latest_rates, fetch_quote, fetch_rate_history},
+    state::AppState,
+};
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+pub fn fx_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/rates", get(fetch_latest_rates))
+        .route("/rates/{currency_code}/history", get(fetch_rate_history))
+        .route("/convert", get(convert))
+        .route("/quotes", post(create_quote))
+        .route("/quotes/{identifier}", get(fetch_quote))
+        .with_state(state.clone())
+}
@@ -0,0 +1,241 @@
+use crate::authentication::claims::Claims;
+use crate::config::kafka::{KafkaMessage, KafkaProducer};
+use crate::conversions::entities::ConversionPricing;
+use crate::conversions::rates::{DatabaseRateSource, RateSource};
+use crate::errors::{RepositoryError, ServiceError};
+use crate::fx::adapters::{CreateFxQuoteRequest, FxConvertQuery, NewFxQuote};
+use crate::fx::entities::{
+    EXCHANGE_RATE_UPDATED_TOPIC, ExchangeRate, ExchangeRateUpdated, FxConversion, FxQuote,
+};
+use crate::fx::provider::RateProvider;
+use crate::fx::repository::{FxRepository, FxRepositoryExt};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::{Duration, Local};
+use finpay_utils::extract_env;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Clone)]
+pub struct FxService<R = DatabaseRateSource> {
+    repository: FxRepository,
+    rate_source: R,
+}
+
+impl FxService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self::with_rate_source(pool, DatabaseRateSource::new(pool))
+    }
+}
+
+impl<R: RateSource> FxService<R> {
+    pub fn with_rate_source(pool: &PgPool, rate_source: R) -> Self {
+        Self {
+            repository: FxRepository::new(pool),
+            rate_source,
+        }
+    }
+}
+
+pub trait FxServiceExt {
+    fn fetch_latest_rates(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<ExchangeRate>, ServiceError>> + Send;
+
+    fn convert(
+        &self,
+        query: &FxConvertQuery,
+    ) -> impl std::future::Future<Output = Result<FxConversion, ServiceError>> + Send;
+
+    fn fetch_rate_history(
+        &self,
+        currency_code: &str,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<ExchangeRate>, ServiceError>> + Send;
+
+    /// Prices a conversion and locks the rate, markup and fee for `FX_QUOTE_TTL_IN_SECONDS`
+    fn create_quote(
+        &self,
+        claims: &Claims,
+        request: &CreateFxQuoteRequest,
+    ) -> impl std::future::Future<Output = Result<FxQuote, ServiceError>> + Send;
+
+    fn fetch_quote(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<FxQuote, ServiceError>> + Send;
+}
+
+impl<R: RateSource> FxServiceExt for FxService<R> {
+    async fn fetch_latest_rates(&self) -> Result<Vec<ExchangeRate>, ServiceError> {
+        let rates = self.repository.fetch_latest_rates().await?;
+        Ok(rates)
+    }
+
+    async fn convert(&self, query: &FxConvertQuery) -> Result<FxConversion, ServiceError> {
+        query.validate()?;
+        if query.as_of.is_some_and(|as_of| as_of > Local::now()) {
+            return Err(ServiceError::UnprocessableEntity(
+                "asOf cannot be in the future".to_string(),
+            ));
+        }
+
+        let cross_rate = self
+            .repository
+            .fetch_cross_rate(
+                &query.from.to_uppercase(),
+                &query.to.to_uppercase(),
+                query.as_of.as_ref(),
+            )
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        Ok(FxConversion {
+            converted_amount: (&query.amount * &cross_rate.rate)
+                .with_scale_round(2, RoundingMode::HalfEven),
+            amount: query.amount.clone(),
+            from_currency_code: cross_rate.from_currency_code,
+            to_currency_code: cross_rate.to_currency_code,
+            rate: cross_rate.rate,
+            as_of: cross_rate.as_of,
+        })
+    }
+
+    async fn fetch_rate_history(
+        &self,
+        currency_code: &str,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<ExchangeRate>, ServiceError> {
+        let history = self
+            .repository
+            .fetch_rate_history(&currency_code.to_uppercase(), pagination_params)
+            .await?;
+        Ok(history)
+    }
+
+    async fn create_quote(
+        &self,
+        claims: &Claims,
+        request: &CreateFxQuoteRequest,
+    ) -> Result<FxQuote, ServiceError> {
+        if request.source_wallet_identifier == request.destination_wallet_identifier {
+            return Err(ServiceError::UnprocessableEntity(
+                "source and destination wallets must be different".to_string(),
+            ));
+        }
+
+        let (source_currency, destination_currency) = self
+            .repository
+            .fetch_wallet_currencies(
+                &request.source_wallet_identifier,
+                &request.destination_wallet_identifier,
+                &claims.user_identifier,
+            )
+            .await?;
+        if source_currency == destination_currency {
+            return Err(ServiceError::UnprocessableEntity(
+                "both wallets hold the same currency, no quote is needed".to_string(),
+            ));
+        }
+
+        let mid_rate = self
+            .rate_source
+            .mid_rate(&source_currency, &destination_currency)
+            .await?;
+        let pricing = ConversionPricing {
+            mid_rate: mid_rate.rate,
+            spread_basis_points: extract_env::<u32>("CONVERSION_SPREAD_IN_BASIS_POINTS"),
+            rate_source: mid_rate.source,
+        };
+        if pricing.convert(&request.amount) <= BigDecimal::zero() {
+            return Err(ServiceError::UnprocessableEntity(
+                "the amount is too small to convert".to_string(),
+            ));
+        }
+
+        let ttl_in_seconds = extract_env::<i64>("FX_QUOTE_TTL_IN_SECONDS");
+        let quote = self
+            .repository
+            .create_quote(&NewFxQuote {
+                user_identifier: claims.user_identifier,
+                source_wallet_identifier: request.source_wallet_identifier,
+                destination_wallet_identifier: request.destination_wallet_identifier,
+                source_currency_identifier: source_currency,
+                destination_currency_identifier: destination_currency,
+                source_amount: request.amount.clone(),
+                pricing,
+                expires_at: Local::now() + Duration::seconds(ttl_in_seconds),
+            })
+            .await?;
+        Ok(quote)
+    }
+
+    async fn fetch_quote(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<FxQuote, ServiceError> {
+        let quote = self
+            .repository
+            .fetch_quote(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(quote)
+    }
+}
+
+/// Pulls fresh rates from a provider, stores them and announces each one
+pub struct FxRateRefresher<P: RateProvider> {
+    repository: FxRepository,
+    provider: P,
+    producer: Option<KafkaProducer>,
+}
+
+impl<P: RateProvider> FxRateRefresher<P> {
+    /// rates are still stored when there is no producer, they are just not announced
+    pub fn new(pool: &PgPool, provider: P, producer: Option<KafkaProducer>) -> Self {
+        Self {
+            repository: FxRepository::new(pool),
+            provider,
+            producer,
+        }
+    }
+
+    /// Returns how many rates were refreshed
+    pub async fn refresh(&self) -> Result<usize, ServiceError> {
+        let current_rates = self.repository.fetch_latest_rates().await?;
+        let fresh_rates = self
+            .provider
+            .fetch_rates(&current_rates)
+            .await
+            .map_err(|err| ServiceError::UnprocessableEntity(err.to_string()))?;
+        let recorded = self.repository.record_rates(&fresh_rates).await?;
+
+        if let Some(producer) = &self.producer {
+            for rate in &recorded {
+                let mut event = ExchangeRateUpdated::from(rate);
+                event.previous_rate = current_rates
+                    .iter()
+                    .find(|current| current.quote_currency_code == rate.quote_currency_code)
+                    .map(|current| current.rate.clone());
+
+                let message = KafkaMessage {
+                    topic: EXCHANGE_RATE_UPDATED_TOPIC.to_string(),
+                    message: event,
+                };
+                // the rate is already stored, a lost event must not undo the refresh
+                if let Err(err) = producer.send(message).await {
+                    tracing::warn!(
+                        "failed to publish rate update for {}: {}",
+                        rate.quote_currency_code,
+                        err
+                    );
+                }
+            }
+        }
+
+        Ok(recorded.len())
+    }
+}
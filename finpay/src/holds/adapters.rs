@@ -0,0 +1,43 @@
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateHoldRequest {
+    pub wallet_identifier: Uuid,
+    pub destination_wallet_identifier: Uuid,
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: BigDecimal,
+    #[validate(length(
+        max = 255,
+        message = "description cannot be more than 255 characters",
+        code = "description"
+    ))]
+    pub description: Option<String>,
+    /// defaults to `HOLD_DEFAULT_TTL_IN_MINUTES`
+    #[validate(range(
+        min = 1,
+        max = 43200,
+        message = "a hold can last between 1 minute and 30 days",
+        code = "expiresInMinutes"
+    ))]
+    pub expires_in_minutes: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureHoldRequest {
+    /// captures the full held amount when left out, the uncaptured remainder is released
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: Option<BigDecimal>,
+}
@@ -0,0 +1,42 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "hold_status_enum")]
+pub enum HoldStatus {
+    Active,
+    Captured,
+    Voided,
+    Expired,
+}
+
+/// Funds reserved on a wallet for a later capture to the destination wallet
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletHold {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub wallet_identifier: Uuid,
+    pub destination_wallet_identifier: Uuid,
+    pub user_identifier: Uuid,
+    pub amount: BigDecimal,
+    pub captured_amount: BigDecimal,
+    pub currency_identifier: Uuid,
+    pub status: HoldStatus,
+    pub description: Option<String>,
+    pub transaction_identifier: Option<Uuid>,
+    pub expires_at: DateTime<Local>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl WalletHold {
+    /// an active hold stops reserving funds once it passes its expiry, even before it is swept
+    pub fn is_lapsed(&self) -> bool {
+        self.expires_at <= Local::now()
+    }
+}
@@ -0,0 +1,72 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::holds::adapters::{CaptureHoldRequest, CreateHoldRequest};
+use crate::holds::entities::WalletHold;
+use crate::holds::service::{HoldService, HoldServiceExt};
+use crate::utils::{ApiResponse, AuthenticatedRequest, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+pub async fn create_hold(
+    State(hold_service): State<HoldService>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateHoldRequest>,
+) -> Result<ApiResponse<WalletHold>, ServiceError> {
+    let hold = hold_service.create_hold(&claims, &request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(hold)
+        .message("funds held successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_hold(
+    State(hold_service): State<HoldService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<WalletHold>, ServiceError> {
+    let hold = hold_service.fetch_hold(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder().data(hold).build())
+}
+
+pub async fn fetch_holds(
+    State(hold_service): State<HoldService>,
+    claims: Claims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<WalletHold>>, ServiceError> {
+    let holds = hold_service
+        .fetch_holds(&claims, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(holds).build())
+}
+
+pub async fn capture_hold(
+    State(hold_service): State<HoldService>,
+    Path(identifier): Path<Uuid>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CaptureHoldRequest>,
+) -> Result<ApiResponse<WalletHold>, ServiceError> {
+    let hold = hold_service
+        .capture_hold(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(hold)
+        .message("hold captured successfully")
+        .build())
+}
+
+pub async fn void_hold(
+    State(hold_service): State<HoldService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<WalletHold>, ServiceError> {
+    let hold = hold_service.void_hold(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder()
+        .data(hold)
+        .message("hold voided successfully")
+        .build())
+}
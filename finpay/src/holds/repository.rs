@@ -0,0 +1,500 @@
+use crate::errors::RepositoryError;
+use crate::holds::adapters::CreateHoldRequest;
+use crate::holds::entities::{HoldStatus, WalletHold};
+use crate::ledger::adapters::NewJournalEntry;
+use crate::ledger::repository::LedgerRepository;
+use crate::transactions::adapters::NewTransaction;
+use crate::transactions::entities::{TransactionStatus, TransactionType};
+use crate::transactions::repository::TransactionRepository;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use crate::wallet::entities::{Wallet, WalletStatus};
+use bigdecimal::BigDecimal;
+use finpay_utils::generate_reference;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct HoldRepository {
+    pool: PgPool,
+}
+
+impl HoldRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Sums the active holds on a wallet, every debit must check against the balance less this
+    /// amount. Lock the wallet row first so that no hold is placed in between
+    pub async fn held_amount(
+        connection: &mut PgConnection,
+        wallet_identifier: &Uuid,
+    ) -> Result<BigDecimal, RepositoryError> {
+        let query = r#"
+        SELECT COALESCE(SUM(amount), 0)
+        FROM wallet_holds
+        WHERE wallet_identifier = $1
+          AND status = $2
+          AND expires_at > NOW()
+        "#;
+        sqlx::query_scalar(query)
+            .bind(wallet_identifier)
+            .bind(HoldStatus::Active)
+            .fetch_one(connection)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn lock_hold(
+        connection: &mut PgConnection,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<WalletHold, RepositoryError> {
+        let hold = sqlx::query_as::<_, WalletHold>(
+            r#"SELECT * FROM wallet_holds WHERE identifier = $1 AND user_identifier = $2 FOR UPDATE"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+
+        if hold.status != HoldStatus::Active {
+            return Err(RepositoryError::InactiveHold);
+        }
+
+        Ok(hold)
+    }
+
+    async fn update_status(
+        connection: &mut PgConnection,
+        identifier: &Uuid,
+        status: HoldStatus,
+    ) -> Result<WalletHold, RepositoryError> {
+        sqlx::query_as::<_, WalletHold>(
+            r#"UPDATE wallet_holds SET status = $1 WHERE identifier = $2 RETURNING *"#,
+        )
+        .bind(status)
+        .bind(identifier)
+        .fetch_one(connection)
+        .await
+        .map_err(RepositoryError::from)
+    }
+}
+
+pub trait HoldRepositoryExt {
+    fn create(
+        &self,
+        payload: &CreateHoldRequest,
+        expires_in_minutes: i64,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<WalletHold, RepositoryError>> + Send;
+
+    fn fetch_hold(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<WalletHold>, RepositoryError>> + Send;
+
+    fn fetch_holds(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<WalletHold>, RepositoryError>> + Send;
+
+    fn capture(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        amount: Option<&BigDecimal>,
+    ) -> impl std::future::Future<Output = Result<WalletHold, RepositoryError>> + Send;
+
+    fn void(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<WalletHold, RepositoryError>> + Send;
+
+    /// Marks every lapsed hold as expired, returns how many were swept
+    fn expire_lapsed(
+        &self,
+    ) -> impl std::future::Future<Output = Result<u64, RepositoryError>> + Send;
+}
+
+impl HoldRepositoryExt for HoldRepository {
+    async fn create(
+        &self,
+        payload: &CreateHoldRequest,
+        expires_in_minutes: i64,
+        user_identifier: &Uuid,
+    ) -> Result<WalletHold, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let wallets = sqlx::query_as::<_, Wallet>(
+            r#"SELECT * FROM wallets WHERE identifier = ANY($1) ORDER BY identifier FOR UPDATE"#,
+        )
+        .bind(vec![
+            payload.wallet_identifier,
+            payload.destination_wallet_identifier,
+        ])
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let wallet = wallets
+            .iter()
+            .find(|wallet| {
+                wallet.identifier == payload.wallet_identifier
+                    && wallet.user_identifier == *user_identifier
+            })
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        let destination_wallet = wallets
+            .iter()
+            .find(|wallet| wallet.identifier == payload.destination_wallet_identifier)
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        if wallet.status != WalletStatus::Active
+            || destination_wallet.status != WalletStatus::Active
+        {
+            return Err(RepositoryError::InactiveWallet);
+        }
+
+        if wallet.currency_identifier != destination_wallet.currency_identifier {
+            return Err(RepositoryError::CurrencyMismatch);
+        }
+
+        let held_amount = Self::held_amount(&mut tx, &wallet.identifier).await?;
+        if &wallet.balance - held_amount < payload.amount {
+            return Err(RepositoryError::InsufficientFunds);
+        }
+
+        let query = r#"
+        INSERT INTO wallet_holds (
+            identifier,
+            reference,
+            wallet_identifier,
+            destination_wallet_identifier,
+            user_identifier,
+            amount,
+            currency_identifier,
+            description,
+            expires_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW() + make_interval(mins => $9)) RETURNING *
+        "#;
+        let hold = sqlx::query_as::<_, WalletHold>(query)
+            .bind(Uuid::new_v4())
+            .bind(generate_reference("HLD"))
+            .bind(wallet.identifier)
+            .bind(destination_wallet.identifier)
+            .bind(user_identifier)
+            .bind(&payload.amount)
+            .bind(wallet.currency_identifier)
+            .bind(&payload.description)
+            .bind(expires_in_minutes as i32)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(hold)
+    }
+
+    async fn fetch_hold(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<WalletHold>, RepositoryError> {
+        sqlx::query_as::<_, WalletHold>(
+            r#"SELECT * FROM wallet_holds WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_holds(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<WalletHold>, RepositoryError> {
+        let total_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(identifier) FROM wallet_holds WHERE user_identifier = $1",
+        )
+        .bind(user_identifier)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let holds = sqlx::query_as::<_, WalletHold>(
+            r#"SELECT * FROM wallet_holds WHERE user_identifier = $1 ORDER BY created_date DESC LIMIT $2 OFFSET $3"#,
+        )
+        .bind(user_identifier)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            holds,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn capture(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        amount: Option<&BigDecimal>,
+    ) -> Result<WalletHold, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let hold = Self::lock_hold(&mut tx, identifier, user_identifier).await?;
+        if hold.is_lapsed() {
+            Self::update_status(&mut tx, &hold.identifier, HoldStatus::Expired).await?;
+            tx.commit().await?;
+            return Err(RepositoryError::InactiveHold);
+        }
+
+        let amount = amount.unwrap_or(&hold.amount);
+        if amount > &hold.amount {
+            return Err(RepositoryError::OperationFailed(
+                "the capture amount exceeds the held amount".into(),
+            ));
+        }
+
+        let wallets = sqlx::query_as::<_, Wallet>(
+            r#"SELECT * FROM wallets WHERE identifier = ANY($1) ORDER BY identifier FOR UPDATE"#,
+        )
+        .bind(vec![
+            hold.wallet_identifier,
+            hold.destination_wallet_identifier,
+        ])
+        .fetch_all(&mut *tx)
+        .await?;
+        if wallets
+            .iter()
+            .any(|wallet| wallet.status != WalletStatus::Active)
+        {
+            return Err(RepositoryError::InactiveWallet);
+        }
+
+        let mut transaction = TransactionRepository::open(
+            &mut tx,
+            &NewTransaction {
+                transaction_type: TransactionType::HoldCapture,
+                source_wallet_identifier: Some(hold.wallet_identifier),
+                destination_wallet_identifier: Some(hold.destination_wallet_identifier),
+                amount: amount.clone(),
+                currency_identifier: hold.currency_identifier,
+                description: hold.description.clone(),
+                initiated_by: Some(*user_identifier),
+                external_reference: Some(hold.reference.clone()),
+            },
+        )
+        .await?;
+
+        // the held funds never left the wallet, capturing moves them like a transfer would
+        let source_account =
+            LedgerRepository::wallet_account(&mut tx, &hold.wallet_identifier).await?;
+        let destination_account =
+            LedgerRepository::wallet_account(&mut tx, &hold.destination_wallet_identifier).await?;
+        let entry = NewJournalEntry::new(&format!("capture {}", hold.reference))
+            .transaction(&transaction.identifier)
+            .debit(&source_account, amount)
+            .credit(&destination_account, amount);
+        LedgerRepository::record_entry(&mut tx, &entry).await?;
+
+        TransactionRepository::transition(
+            &mut tx,
+            &mut transaction,
+            TransactionStatus::Completed,
+            None,
+        )
+        .await?;
+
+        let query = r#"
+        UPDATE wallet_holds
+        SET status                 = $1,
+            captured_amount        = $2,
+            transaction_identifier = $3
+        WHERE identifier = $4
+        RETURNING *
+        "#;
+        let captured = sqlx::query_as::<_, WalletHold>(query)
+            .bind(HoldStatus::Captured)
+            .bind(amount)
+            .bind(transaction.identifier)
+            .bind(hold.identifier)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(captured)
+    }
+
+    async fn void(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<WalletHold, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        Self::lock_hold(&mut tx, identifier, user_identifier).await?;
+        let voided = Self::update_status(&mut tx, identifier, HoldStatus::Voided).await?;
+
+        tx.commit().await?;
+        Ok(voided)
+    }
+
+    async fn expire_lapsed(&self) -> Result<u64, RepositoryError> {
+        let result = sqlx::query(
+            "UPDATE wallet_holds SET status = $1 WHERE status = $2 AND expires_at <= NOW()",
+        )
+        .bind(HoldStatus::Expired)
+        .bind(HoldStatus::Active)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::fixtures::{create_user, create_wallet, fund};
+    use crate::wallet::repository::{WalletRepository, WalletRepositoryExt};
+
+    struct Fixture {
+        user_identifier: Uuid,
+        source: Uuid,
+        destination: Uuid,
+    }
+
+    async fn setup(pool: &PgPool, balance: &str) -> Fixture {
+        let user_identifier = create_user(pool).await;
+        let source = create_wallet(pool, &user_identifier).await;
+        let destination = create_wallet(pool, &user_identifier).await;
+        fund(pool, &source, balance).await;
+
+        Fixture {
+            user_identifier,
+            source,
+            destination,
+        }
+    }
+
+    fn hold_request(fixture: &Fixture, amount: i64) -> CreateHoldRequest {
+        CreateHoldRequest {
+            wallet_identifier: fixture.source,
+            destination_wallet_identifier: fixture.destination,
+            amount: BigDecimal::from(amount),
+            description: None,
+            expires_in_minutes: None,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_hold_reduces_available_balance(pool: PgPool) {
+        let repository = HoldRepository::new(&pool);
+        let fixture = setup(&pool, "100").await;
+
+        repository
+            .create(&hold_request(&fixture, 70), 60, &fixture.user_identifier)
+            .await
+            .expect("failed to place hold");
+
+        let wallet = WalletRepository::new(pool.clone())
+            .fetch_wallet(&fixture.source, &fixture.user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(wallet.wallet.balance, BigDecimal::from(100));
+        assert_eq!(wallet.held_balance, BigDecimal::from(70));
+        assert_eq!(wallet.available_balance, BigDecimal::from(30));
+
+        let second = repository
+            .create(&hold_request(&fixture, 40), 60, &fixture.user_identifier)
+            .await;
+        assert!(matches!(second, Err(RepositoryError::InsufficientFunds)));
+    }
+
+    #[sqlx::test]
+    async fn test_partial_capture_releases_the_remainder(pool: PgPool) {
+        let repository = HoldRepository::new(&pool);
+        let fixture = setup(&pool, "100").await;
+
+        let hold = repository
+            .create(&hold_request(&fixture, 70), 60, &fixture.user_identifier)
+            .await
+            .unwrap();
+        let captured = repository
+            .capture(
+                &hold.identifier,
+                &fixture.user_identifier,
+                Some(&BigDecimal::from(50)),
+            )
+            .await
+            .expect("failed to capture hold");
+
+        assert_eq!(captured.status, HoldStatus::Captured);
+        assert_eq!(captured.captured_amount, BigDecimal::from(50));
+
+        let wallet_repository = WalletRepository::new(pool.clone());
+        let source = wallet_repository
+            .fetch_wallet(&fixture.source, &fixture.user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        let destination = wallet_repository
+            .fetch_wallet(&fixture.destination, &fixture.user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(source.available_balance, BigDecimal::from(50));
+        assert_eq!(destination.wallet.balance, BigDecimal::from(50));
+
+        let again = repository
+            .capture(&hold.identifier, &fixture.user_identifier, None)
+            .await;
+        assert!(matches!(again, Err(RepositoryError::InactiveHold)));
+    }
+
+    #[sqlx::test]
+    async fn test_voided_and_lapsed_holds_free_funds(pool: PgPool) {
+        let repository = HoldRepository::new(&pool);
+        let fixture = setup(&pool, "100").await;
+
+        let hold = repository
+            .create(&hold_request(&fixture, 60), 60, &fixture.user_identifier)
+            .await
+            .unwrap();
+        let voided = repository
+            .void(&hold.identifier, &fixture.user_identifier)
+            .await
+            .unwrap();
+        assert_eq!(voided.status, HoldStatus::Voided);
+
+        let lapsing = repository
+            .create(&hold_request(&fixture, 60), 60, &fixture.user_identifier)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE wallet_holds SET expires_at = NOW() - INTERVAL '1 minute' WHERE identifier = $1")
+            .bind(lapsing.identifier)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut connection = pool.acquire().await.unwrap();
+        let held = HoldRepository::held_amount(&mut connection, &fixture.source)
+            .await
+            .unwrap();
+        assert_eq!(held, BigDecimal::from(0));
+        assert_eq!(repository.expire_lapsed().await.unwrap(), 1);
+    }
+}
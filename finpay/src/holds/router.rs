@@ -0,0 +1,32 @@
+use crate::{
+    holds::handlers::{capture_hold, create_hold, fetch_hold, fetch_holds, void_hold},
+    shared::middlewares::idempotency::{IdempotencyStore, enforce_idempotency},
+    state::AppState,
+};
+use axum::{
+    Router,
+    extract::FromRef,
+    middleware::from_fn_with_state,
+    routing::{get, post},
+};
+
+pub fn hold_routes(state: &AppState) -> Router {
+    let idempotency_store = IdempotencyStore::from_ref(state);
+
+    Router::new()
+        .route(
+            "/",
+            post(create_hold).layer(from_fn_with_state(
+                idempotency_store.clone(),
+                enforce_idempotency,
+            )),
+        )
+        .route("/", get(fetch_holds))
+        .route("/{identifier}", get(fetch_hold))
+        .route(
+            "/{identifier}/capture",
+            post(capture_hold).layer(from_fn_with_state(idempotency_store, enforce_idempotency)),
+        )
+        .route("/{identifier}/void", post(void_hold))
+        .with_state(state.clone())
+}
@@ -0,0 +1,139 @@
+use crate::authentication::claims::Claims;
+use crate::errors::{RepositoryError, ServiceError};
+use crate::holds::adapters::{CaptureHoldRequest, CreateHoldRequest};
+use crate::holds::entities::WalletHold;
+use crate::holds::repository::{HoldRepository, HoldRepositoryExt};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use finpay_utils::extract_env;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct HoldService {
+    repository: HoldRepository,
+}
+
+impl HoldService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: HoldRepository::new(pool),
+        }
+    }
+}
+
+pub trait HoldServiceExt {
+    fn create_hold(
+        &self,
+        claims: &Claims,
+        request: &CreateHoldRequest,
+    ) -> impl std::future::Future<Output = Result<WalletHold, ServiceError>> + Send;
+
+    fn fetch_hold(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<WalletHold, ServiceError>> + Send;
+
+    fn fetch_holds(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<WalletHold>, ServiceError>> + Send;
+
+    fn capture_hold(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &CaptureHoldRequest,
+    ) -> impl std::future::Future<Output = Result<WalletHold, ServiceError>> + Send;
+
+    fn void_hold(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<WalletHold, ServiceError>> + Send;
+
+    fn expire_lapsed_holds(
+        &self,
+    ) -> impl std::future::Future<Output = Result<u64, ServiceError>> + Send;
+}
+
+impl HoldServiceExt for HoldService {
+    async fn create_hold(
+        &self,
+        claims: &Claims,
+        request: &CreateHoldRequest,
+    ) -> Result<WalletHold, ServiceError> {
+        if request.wallet_identifier == request.destination_wallet_identifier {
+            return Err(ServiceError::UnprocessableEntity(
+                "source and destination wallets must be different".to_string(),
+            ));
+        }
+
+        let expires_in_minutes = match request.expires_in_minutes {
+            Some(expires_in_minutes) => expires_in_minutes,
+            None => extract_env::<i64>("HOLD_DEFAULT_TTL_IN_MINUTES"),
+        };
+
+        let hold = self
+            .repository
+            .create(request, expires_in_minutes, &claims.user_identifier)
+            .await?;
+        Ok(hold)
+    }
+
+    async fn fetch_hold(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<WalletHold, ServiceError> {
+        let hold = self
+            .repository
+            .fetch_hold(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(hold)
+    }
+
+    async fn fetch_holds(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<WalletHold>, ServiceError> {
+        let holds = self
+            .repository
+            .fetch_holds(&claims.user_identifier, pagination_params)
+            .await?;
+        Ok(holds)
+    }
+
+    async fn capture_hold(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &CaptureHoldRequest,
+    ) -> Result<WalletHold, ServiceError> {
+        let hold = self
+            .repository
+            .capture(identifier, &claims.user_identifier, request.amount.as_ref())
+            .await?;
+        Ok(hold)
+    }
+
+    async fn void_hold(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<WalletHold, ServiceError> {
+        let hold = self
+            .repository
+            .void(identifier, &claims.user_identifier)
+            .await?;
+        Ok(hold)
+    }
+
+    async fn expire_lapsed_holds(&self) -> Result<u64, ServiceError> {
+        let expired = self.repository.expire_lapsed().await?;
+        Ok(expired)
+    }
+}
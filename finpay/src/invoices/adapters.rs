@@ -0,0 +1,105 @@
+use crate::invoices::entities::InvoiceStatus;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Creates a draft invoice, a draft is updated by sending it whole again
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInvoiceRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "customer name must be between 1 and 255 characters",
+        code = "customerName"
+    ))]
+    pub customer_name: String,
+    #[validate(email(message = "please provide a valid customer email"))]
+    pub customer_email: Option<String>,
+    #[validate(length(
+        max = 500,
+        message = "customer address cannot be more than 500 characters",
+        code = "customerAddress"
+    ))]
+    pub customer_address: Option<String>,
+    /// the country whose currency the invoice is billed in
+    pub currency_identifier: Uuid,
+    /// defaults to today
+    pub issue_date: Option<NaiveDate>,
+    pub due_date: NaiveDate,
+    #[validate(length(
+        max = 2000,
+        message = "notes cannot be more than 2000 characters",
+        code = "notes"
+    ))]
+    pub notes: Option<String>,
+    #[validate(
+        length(
+            min = 1,
+            max = 100,
+            message = "an invoice must have between 1 and 100 line items",
+            code = "lineItems"
+        ),
+        nested
+    )]
+    pub line_items: Vec<InvoiceLineItemRequest>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceLineItemRequest {
+    #[validate(length(
+        min = 1,
+        max = 500,
+        message = "description must be between 1 and 500 characters",
+        code = "description"
+    ))]
+    pub description: String,
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "quantity must be greater than zero",
+        code = "quantity"
+    ))]
+    pub quantity: BigDecimal,
+    #[validate(custom(
+        function = "crate::utils::validate_non_negative_amount",
+        message = "unit price cannot be negative",
+        code = "unitPrice"
+    ))]
+    pub unit_price: BigDecimal,
+    #[serde(default)]
+    #[validate(range(
+        max = 10000,
+        message = "tax rate cannot be more than 10000 basis points",
+        code = "taxRateBasisPoints"
+    ))]
+    pub tax_rate_basis_points: u32,
+    /// taken off the line before tax
+    #[serde(default)]
+    #[validate(range(
+        max = 10000,
+        message = "discount cannot be more than 10000 basis points",
+        code = "discountBasisPoints"
+    ))]
+    pub discount_basis_points: u32,
+}
+
+/// A payment the customer made outside finpay, recorded against what is owed
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordInvoicePaymentRequest {
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceQuery {
+    pub status: Option<InvoiceStatus>,
+}
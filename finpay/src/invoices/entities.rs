@@ -0,0 +1,207 @@
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+const BASIS_POINTS: u32 = 10_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "invoice_status_enum")]
+pub enum InvoiceStatus {
+    /// still being written, the only status an invoice can be edited or deleted in
+    Draft,
+    /// issued to the customer, nothing paid yet
+    Sent,
+    /// some but not all of the total has been paid
+    PartiallyPaid,
+    Paid,
+    /// past its due date with money still owed
+    Overdue,
+    /// cancelled before anything was paid
+    Void,
+}
+
+/// An invoice a user bills a customer with
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Invoice {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub user_identifier: Uuid,
+    pub customer_name: String,
+    pub customer_email: Option<String>,
+    pub customer_address: Option<String>,
+    pub currency_identifier: Uuid,
+    pub status: InvoiceStatus,
+    pub issue_date: NaiveDate,
+    pub due_date: NaiveDate,
+    pub notes: Option<String>,
+    /// the line items before discounts and tax
+    pub subtotal: BigDecimal,
+    pub discount_total: BigDecimal,
+    pub tax_total: BigDecimal,
+    pub total: BigDecimal,
+    pub amount_paid: BigDecimal,
+    pub sent_date: Option<DateTime<Local>>,
+    pub paid_date: Option<DateTime<Local>>,
+    pub voided_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+    #[sqlx(skip)]
+    pub line_items: Vec<InvoiceLineItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceLineItem {
+    pub identifier: Uuid,
+    pub invoice_identifier: Uuid,
+    pub position: i32,
+    pub description: String,
+    pub quantity: BigDecimal,
+    pub unit_price: BigDecimal,
+    pub tax_rate_basis_points: i32,
+    pub discount_basis_points: i32,
+    /// quantity times unit price
+    pub subtotal: BigDecimal,
+    pub discount_amount: BigDecimal,
+    /// charged on the subtotal less the discount
+    pub tax_amount: BigDecimal,
+    pub total: BigDecimal,
+    pub created_date: DateTime<Local>,
+}
+
+/// Rounds half up to `scale` decimal places, the exponent of the invoice's currency
+fn round(amount: BigDecimal, scale: i64) -> BigDecimal {
+    amount.with_scale_round(scale, RoundingMode::HalfUp)
+}
+
+fn rate_of(amount: &BigDecimal, basis_points: u32, scale: i64) -> BigDecimal {
+    round(
+        amount * BigDecimal::from(basis_points) / BigDecimal::from(BASIS_POINTS),
+        scale,
+    )
+}
+
+/// What a line item comes to. Each amount is rounded on its own, half up to the currency's
+/// decimal places, so the line adds up exactly as printed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PricedLineItem {
+    pub subtotal: BigDecimal,
+    pub discount_amount: BigDecimal,
+    pub tax_amount: BigDecimal,
+    pub total: BigDecimal,
+}
+
+impl PricedLineItem {
+    pub fn price(
+        quantity: &BigDecimal,
+        unit_price: &BigDecimal,
+        discount_basis_points: u32,
+        tax_rate_basis_points: u32,
+        scale: i64,
+    ) -> Self {
+        let subtotal = round(quantity * unit_price, scale);
+        let discount_amount = rate_of(&subtotal, discount_basis_points, scale);
+        let taxable = &subtotal - &discount_amount;
+        let tax_amount = rate_of(&taxable, tax_rate_basis_points, scale);
+        let total = taxable + &tax_amount;
+
+        Self {
+            subtotal,
+            discount_amount,
+            tax_amount,
+            total,
+        }
+    }
+}
+
+/// The totals of an invoice, the sums of its already rounded line items so they always agree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvoiceTotals {
+    pub subtotal: BigDecimal,
+    pub discount_total: BigDecimal,
+    pub tax_total: BigDecimal,
+    pub total: BigDecimal,
+}
+
+impl InvoiceTotals {
+    pub fn of(line_items: &[PricedLineItem], scale: i64) -> Self {
+        let sum = |amount: fn(&PricedLineItem) -> &BigDecimal| {
+            line_items
+                .iter()
+                .map(amount)
+                .fold(BigDecimal::zero(), |sum, amount| sum + amount)
+                .with_scale(scale)
+        };
+
+        Self {
+            subtotal: sum(|item| &item.subtotal),
+            discount_total: sum(|item| &item.discount_amount),
+            tax_total: sum(|item| &item.tax_amount),
+            total: sum(|item| &item.total),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn amount(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_line_items_are_discounted_before_tax_and_rounded_half_up() {
+        // 3 x 19.99 = 59.97, 12.5% off is 7.49625, 7.5% tax on 52.47 is 3.93525
+        let item = PricedLineItem::price(&amount("3"), &amount("19.99"), 1250, 750, 2);
+
+        assert_eq!(item.subtotal, amount("59.97"));
+        assert_eq!(item.discount_amount, amount("7.50"));
+        assert_eq!(item.tax_amount, amount("3.94"));
+        assert_eq!(item.total, amount("56.41"));
+        assert_eq!(item.total.to_string(), "56.41");
+    }
+
+    #[test]
+    fn test_invoice_totals_add_up_the_rounded_line_items() {
+        // each line's tax is 0.005 before rounding, the invoice charges what the lines show
+        let items = vec![
+            PricedLineItem::price(&amount("1"), &amount("0.10"), 0, 500, 2),
+            PricedLineItem::price(&amount("1"), &amount("0.10"), 0, 500, 2),
+            PricedLineItem::price(&amount("0.5"), &amount("2.005"), 10000, 500, 2),
+        ];
+
+        let totals = InvoiceTotals::of(&items, 2);
+
+        assert_eq!(items[0].tax_amount, amount("0.01"));
+        assert_eq!(items[2].total, BigDecimal::zero());
+        assert_eq!(totals.subtotal, amount("1.20"));
+        assert_eq!(totals.discount_total, amount("1.00"));
+        assert_eq!(totals.tax_total, amount("0.02"));
+        assert_eq!(totals.total, amount("0.22"));
+        assert_eq!(
+            &totals.subtotal - &totals.discount_total + &totals.tax_total,
+            totals.total
+        );
+        assert_eq!(InvoiceTotals::of(&[], 2).total, BigDecimal::zero());
+    }
+
+    #[test]
+    fn test_amounts_are_rounded_to_the_currency_exponent() {
+        // 3 x 333.3333 = 999.9999 rounds up to 1000, 8.75% tax on that is 87.5
+        let yen = PricedLineItem::price(&amount("3"), &amount("333.3333"), 0, 875, 0);
+        let dinar = PricedLineItem::price(&amount("3"), &amount("333.3333"), 0, 875, 3);
+
+        assert_eq!(yen.subtotal.to_string(), "1000");
+        assert_eq!(yen.tax_amount, amount("88"));
+        assert_eq!(dinar.subtotal.to_string(), "1000.000");
+        assert_eq!(dinar.tax_amount, amount("87.500"));
+        assert_eq!(InvoiceTotals::of(&[yen], 0).total.to_string(), "1088");
+        assert_eq!(InvoiceTotals::of(&[dinar], 3).total.to_string(), "1087.500");
+    }
+}
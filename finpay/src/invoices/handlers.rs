@@ -0,0 +1,115 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::invoices::adapters::{CreateInvoiceRequest, InvoiceQuery, RecordInvoicePaymentRequest};
+use crate::invoices::entities::Invoice;
+use crate::invoices::service::{InvoiceService, InvoiceServiceExt};
+use crate::utils::{
+    ApiResponse, AuthenticatedRequest, EmptyResponseBody, PaginatedResponse, PaginationParams,
+};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+pub async fn create_invoice(
+    State(invoice_service): State<InvoiceService>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateInvoiceRequest>,
+) -> Result<ApiResponse<Invoice>, ServiceError> {
+    let invoice = invoice_service.create_invoice(&claims, &request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(invoice)
+        .message("invoice drafted successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_invoices(
+    State(invoice_service): State<InvoiceService>,
+    claims: Claims,
+    Query(query): Query<InvoiceQuery>,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<Invoice>>, ServiceError> {
+    let invoices = invoice_service
+        .fetch_invoices(&claims, &query, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(invoices).build())
+}
+
+pub async fn fetch_invoice(
+    State(invoice_service): State<InvoiceService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<Invoice>, ServiceError> {
+    let invoice = invoice_service.fetch_invoice(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder().data(invoice).build())
+}
+
+pub async fn update_invoice(
+    State(invoice_service): State<InvoiceService>,
+    Path(identifier): Path<Uuid>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateInvoiceRequest>,
+) -> Result<ApiResponse<Invoice>, ServiceError> {
+    let invoice = invoice_service
+        .update_invoice(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(invoice)
+        .message("invoice updated successfully")
+        .build())
+}
+
+pub async fn delete_invoice(
+    State(invoice_service): State<InvoiceService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<EmptyResponseBody>, ServiceError> {
+    invoice_service.delete_invoice(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder()
+        .message("invoice deleted successfully")
+        .build())
+}
+
+pub async fn send_invoice(
+    State(invoice_service): State<InvoiceService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<Invoice>, ServiceError> {
+    let invoice = invoice_service.send_invoice(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder()
+        .data(invoice)
+        .message("invoice sent successfully")
+        .build())
+}
+
+pub async fn record_invoice_payment(
+    State(invoice_service): State<InvoiceService>,
+    Path(identifier): Path<Uuid>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<RecordInvoicePaymentRequest>,
+) -> Result<ApiResponse<Invoice>, ServiceError> {
+    let invoice = invoice_service
+        .record_payment(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(invoice)
+        .message("invoice payment recorded successfully")
+        .build())
+}
+
+pub async fn void_invoice(
+    State(invoice_service): State<InvoiceService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<Invoice>, ServiceError> {
+    let invoice = invoice_service.void_invoice(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder()
+        .data(invoice)
+        .message("invoice voided successfully")
+        .build())
+}
@@ -0,0 +1,7 @@
+pub mod adapters;
+pub mod entities;
+pub mod handlers;
+pub mod middleware;
+pub mod repository;
+pub mod router;
+pub mod service;
@@ -0,0 +1,738 @@
+use crate::errors::RepositoryError;
+use crate::invoices::adapters::{CreateInvoiceRequest, InvoiceQuery};
+use crate::invoices::entities::{
+    Invoice, InvoiceLineItem, InvoiceStatus, InvoiceTotals, PricedLineItem,
+};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use bigdecimal::BigDecimal;
+use chrono::Local;
+use finpay_utils::generate_reference;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct InvoiceRepository {
+    pool: PgPool,
+}
+
+impl InvoiceRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    async fn insert_line_items(
+        connection: &mut PgConnection,
+        invoice_identifier: &Uuid,
+        request: &CreateInvoiceRequest,
+        line_items: &[PricedLineItem],
+    ) -> Result<Vec<InvoiceLineItem>, RepositoryError> {
+        let query = r#"
+        INSERT INTO invoice_line_items (identifier, invoice_identifier, position, description, quantity, unit_price, tax_rate_basis_points, discount_basis_points, subtotal, discount_amount, tax_amount, total)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING *
+        "#;
+
+        let mut inserted = vec![];
+        for (position, (item, priced)) in request.line_items.iter().zip(line_items).enumerate() {
+            let line_item = sqlx::query_as::<_, InvoiceLineItem>(query)
+                .bind(Uuid::new_v4())
+                .bind(invoice_identifier)
+                .bind(position as i32 + 1)
+                .bind(&item.description)
+                .bind(&item.quantity)
+                .bind(&item.unit_price)
+                .bind(item.tax_rate_basis_points as i32)
+                .bind(item.discount_basis_points as i32)
+                .bind(&priced.subtotal)
+                .bind(&priced.discount_amount)
+                .bind(&priced.tax_amount)
+                .bind(&priced.total)
+                .fetch_one(&mut *connection)
+                .await?;
+            inserted.push(line_item);
+        }
+
+        Ok(inserted)
+    }
+
+    async fn fetch_line_items(
+        &self,
+        invoice_identifier: &Uuid,
+    ) -> Result<Vec<InvoiceLineItem>, RepositoryError> {
+        sqlx::query_as::<_, InvoiceLineItem>(
+            r#"SELECT * FROM invoice_line_items WHERE invoice_identifier = $1 ORDER BY position"#,
+        )
+        .bind(invoice_identifier)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    /// Why a change guarded on the invoice's status touched nothing
+    async fn state_error(&self, identifier: &Uuid, user_identifier: &Uuid) -> RepositoryError {
+        let exists = sqlx::query_scalar::<_, Uuid>(
+            r#"SELECT identifier FROM invoices WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await;
+
+        match exists {
+            Ok(Some(_)) => RepositoryError::InvalidInvoiceState,
+            Ok(None) => RepositoryError::RecordNotFound,
+            Err(err) => RepositoryError::from(err),
+        }
+    }
+
+    async fn with_line_items(&self, mut invoice: Invoice) -> Result<Invoice, RepositoryError> {
+        invoice.line_items = self.fetch_line_items(&invoice.identifier).await?;
+        Ok(invoice)
+    }
+
+    /// Adds a payment to a sent invoice inside the caller's transaction, it cannot be more than
+    /// what is still owed
+    pub async fn apply_payment(
+        connection: &mut PgConnection,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        amount: &BigDecimal,
+    ) -> Result<Invoice, RepositoryError> {
+        let invoice = sqlx::query_as::<_, Invoice>(
+            r#"SELECT * FROM invoices WHERE identifier = $1 AND user_identifier = $2 FOR UPDATE"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+        if !matches!(
+            invoice.status,
+            InvoiceStatus::Sent | InvoiceStatus::PartiallyPaid | InvoiceStatus::Overdue
+        ) {
+            return Err(RepositoryError::InvalidInvoiceState);
+        }
+
+        let amount_paid = &invoice.amount_paid + amount;
+        if amount_paid > invoice.total {
+            return Err(RepositoryError::OperationFailed(
+                "the payment is more than what is owed on the invoice".to_string(),
+            ));
+        }
+
+        // a payment that leaves money owed past the due date keeps the invoice overdue
+        let query = r#"
+        UPDATE invoices
+        SET amount_paid = $1,
+            status      = CASE
+                              WHEN $1 = total THEN $2
+                              WHEN due_date < CURRENT_DATE THEN $3
+                              ELSE $4
+                END,
+            paid_date   = CASE WHEN $1 = total THEN NOW() END
+        WHERE identifier = $5
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, Invoice>(query)
+            .bind(&amount_paid)
+            .bind(InvoiceStatus::Paid)
+            .bind(InvoiceStatus::Overdue)
+            .bind(InvoiceStatus::PartiallyPaid)
+            .bind(identifier)
+            .fetch_one(&mut *connection)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    /// Takes back a payment recorded on an invoice inside the caller's transaction, the invoice
+    /// goes back to what it was owed before it
+    pub async fn reverse_payment(
+        connection: &mut PgConnection,
+        identifier: &Uuid,
+        amount: &BigDecimal,
+    ) -> Result<Invoice, RepositoryError> {
+        let query = r#"
+        UPDATE invoices
+        SET amount_paid = amount_paid - $1,
+            status      = CASE
+                              WHEN due_date < CURRENT_DATE THEN $2
+                              WHEN amount_paid - $1 = 0 THEN $3
+                              ELSE $4
+                END,
+            paid_date   = NULL
+        WHERE identifier = $5
+          AND status IN ($4, $6, $2)
+          AND amount_paid >= $1
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, Invoice>(query)
+            .bind(amount)
+            .bind(InvoiceStatus::Overdue)
+            .bind(InvoiceStatus::Sent)
+            .bind(InvoiceStatus::PartiallyPaid)
+            .bind(identifier)
+            .bind(InvoiceStatus::Paid)
+            .fetch_optional(&mut *connection)
+            .await?
+            .ok_or(RepositoryError::InvalidInvoiceState)
+    }
+}
+
+pub trait InvoiceRepositoryExt {
+    /// Records a draft invoice with its priced line items
+    fn create(
+        &self,
+        request: &CreateInvoiceRequest,
+        user_identifier: &Uuid,
+        line_items: &[PricedLineItem],
+        totals: &InvoiceTotals,
+    ) -> impl std::future::Future<Output = Result<Invoice, RepositoryError>> + Send;
+
+    fn fetch_invoice(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<Invoice>, RepositoryError>> + Send;
+
+    fn fetch_invoices(
+        &self,
+        user_identifier: &Uuid,
+        query: &InvoiceQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Invoice>, RepositoryError>> + Send;
+
+    /// Replaces a draft with the request, its line items included
+    fn update_draft(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        request: &CreateInvoiceRequest,
+        line_items: &[PricedLineItem],
+        totals: &InvoiceTotals,
+    ) -> impl std::future::Future<Output = Result<Invoice, RepositoryError>> + Send;
+
+    fn delete_draft(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<(), RepositoryError>> + Send;
+
+    /// Issues a draft, an invoice with nothing to pay is paid as soon as it is sent
+    fn send(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Invoice, RepositoryError>> + Send;
+
+    /// Adds a payment to a sent invoice, it cannot be more than what is still owed
+    fn record_payment(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        amount: &BigDecimal,
+    ) -> impl std::future::Future<Output = Result<Invoice, RepositoryError>> + Send;
+
+    /// Cancels an invoice nothing has been paid on
+    fn void(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Invoice, RepositoryError>> + Send;
+
+    /// Moves the sent and partially paid invoices past their due date to overdue, returns how
+    /// many were moved
+    fn mark_overdue(
+        &self,
+    ) -> impl std::future::Future<Output = Result<u64, RepositoryError>> + Send;
+}
+
+impl InvoiceRepositoryExt for InvoiceRepository {
+    async fn create(
+        &self,
+        request: &CreateInvoiceRequest,
+        user_identifier: &Uuid,
+        line_items: &[PricedLineItem],
+        totals: &InvoiceTotals,
+    ) -> Result<Invoice, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = r#"
+        INSERT INTO invoices (identifier, reference, user_identifier, customer_name, customer_email, customer_address, currency_identifier, status, issue_date, due_date, notes, subtotal, discount_total, tax_total, total)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        RETURNING *
+        "#;
+        let mut invoice = sqlx::query_as::<_, Invoice>(query)
+            .bind(Uuid::new_v4())
+            .bind(generate_reference("INV"))
+            .bind(user_identifier)
+            .bind(&request.customer_name)
+            .bind(&request.customer_email)
+            .bind(&request.customer_address)
+            .bind(request.currency_identifier)
+            .bind(InvoiceStatus::Draft)
+            .bind(
+                request
+                    .issue_date
+                    .unwrap_or_else(|| Local::now().date_naive()),
+            )
+            .bind(request.due_date)
+            .bind(&request.notes)
+            .bind(&totals.subtotal)
+            .bind(&totals.discount_total)
+            .bind(&totals.tax_total)
+            .bind(&totals.total)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|err| match err {
+                sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                    RepositoryError::DuplicateRecord
+                }
+                err => RepositoryError::from(err),
+            })?;
+
+        invoice.line_items =
+            Self::insert_line_items(&mut tx, &invoice.identifier, request, line_items).await?;
+
+        tx.commit().await?;
+        Ok(invoice)
+    }
+
+    async fn fetch_invoice(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<Invoice>, RepositoryError> {
+        let invoice = sqlx::query_as::<_, Invoice>(
+            r#"SELECT * FROM invoices WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match invoice {
+            Some(invoice) => Ok(Some(self.with_line_items(invoice).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn fetch_invoices(
+        &self,
+        user_identifier: &Uuid,
+        query: &InvoiceQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Invoice>, RepositoryError> {
+        let filter =
+            r#"WHERE user_identifier = $1 AND ($2::invoice_status_enum IS NULL OR status = $2)"#;
+
+        let total_count: i64 =
+            sqlx::query_scalar(&format!("SELECT COUNT(identifier) FROM invoices {filter}"))
+                .bind(user_identifier)
+                .bind(query.status)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let mut invoices = sqlx::query_as::<_, Invoice>(&format!(
+            "SELECT * FROM invoices {filter} ORDER BY created_date DESC LIMIT $3 OFFSET $4"
+        ))
+        .bind(user_identifier)
+        .bind(query.status)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // the line items of the whole page in one query, handed out to their invoices
+        let identifiers: Vec<Uuid> = invoices.iter().map(|invoice| invoice.identifier).collect();
+        let line_items = sqlx::query_as::<_, InvoiceLineItem>(
+            r#"SELECT * FROM invoice_line_items WHERE invoice_identifier = ANY($1) ORDER BY position"#,
+        )
+        .bind(&identifiers)
+        .fetch_all(&self.pool)
+        .await?;
+        let mut line_items_by_invoice: HashMap<Uuid, Vec<InvoiceLineItem>> = HashMap::new();
+        for line_item in line_items {
+            line_items_by_invoice
+                .entry(line_item.invoice_identifier)
+                .or_default()
+                .push(line_item);
+        }
+        for invoice in &mut invoices {
+            invoice.line_items = line_items_by_invoice
+                .remove(&invoice.identifier)
+                .unwrap_or_default();
+        }
+
+        Ok(PaginatedResponse::new(
+            invoices,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn update_draft(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        request: &CreateInvoiceRequest,
+        line_items: &[PricedLineItem],
+        totals: &InvoiceTotals,
+    ) -> Result<Invoice, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = r#"
+        UPDATE invoices
+        SET customer_name       = $1,
+            customer_email      = $2,
+            customer_address    = $3,
+            currency_identifier = $4,
+            issue_date          = COALESCE($5, issue_date),
+            due_date            = $6,
+            notes               = $7,
+            subtotal            = $8,
+            discount_total      = $9,
+            tax_total           = $10,
+            total               = $11
+        WHERE identifier = $12
+          AND user_identifier = $13
+          AND status = $14
+        RETURNING *
+        "#;
+        let invoice = sqlx::query_as::<_, Invoice>(query)
+            .bind(&request.customer_name)
+            .bind(&request.customer_email)
+            .bind(&request.customer_address)
+            .bind(request.currency_identifier)
+            .bind(request.issue_date)
+            .bind(request.due_date)
+            .bind(&request.notes)
+            .bind(&totals.subtotal)
+            .bind(&totals.discount_total)
+            .bind(&totals.tax_total)
+            .bind(&totals.total)
+            .bind(identifier)
+            .bind(user_identifier)
+            .bind(InvoiceStatus::Draft)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(mut invoice) = invoice else {
+            return Err(self.state_error(identifier, user_identifier).await);
+        };
+
+        sqlx::query(r#"DELETE FROM invoice_line_items WHERE invoice_identifier = $1"#)
+            .bind(identifier)
+            .execute(&mut *tx)
+            .await?;
+        invoice.line_items =
+            Self::insert_line_items(&mut tx, identifier, request, line_items).await?;
+
+        tx.commit().await?;
+        Ok(invoice)
+    }
+
+    async fn delete_draft(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"DELETE FROM invoices WHERE identifier = $1 AND user_identifier = $2 AND status = $3"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .bind(InvoiceStatus::Draft)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(self.state_error(identifier, user_identifier).await);
+        }
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Invoice, RepositoryError> {
+        let query = r#"
+        UPDATE invoices
+        SET status    = CASE WHEN total = 0 THEN $1 ELSE $2 END,
+            sent_date = NOW(),
+            paid_date = CASE WHEN total = 0 THEN NOW() END
+        WHERE identifier = $3
+          AND user_identifier = $4
+          AND status = $5
+        RETURNING *
+        "#;
+        let invoice = sqlx::query_as::<_, Invoice>(query)
+            .bind(InvoiceStatus::Paid)
+            .bind(InvoiceStatus::Sent)
+            .bind(identifier)
+            .bind(user_identifier)
+            .bind(InvoiceStatus::Draft)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match invoice {
+            Some(invoice) => self.with_line_items(invoice).await,
+            None => Err(self.state_error(identifier, user_identifier).await),
+        }
+    }
+
+    async fn record_payment(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        amount: &BigDecimal,
+    ) -> Result<Invoice, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let invoice = Self::apply_payment(&mut tx, identifier, user_identifier, amount).await?;
+        tx.commit().await?;
+        self.with_line_items(invoice).await
+    }
+
+    async fn void(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Invoice, RepositoryError> {
+        let query = r#"
+        UPDATE invoices
+        SET status      = $1,
+            voided_date = NOW()
+        WHERE identifier = $2
+          AND user_identifier = $3
+          AND status IN ($4, $5, $6)
+          AND amount_paid = 0
+        RETURNING *
+        "#;
+        let invoice = sqlx::query_as::<_, Invoice>(query)
+            .bind(InvoiceStatus::Void)
+            .bind(identifier)
+            .bind(user_identifier)
+            .bind(InvoiceStatus::Draft)
+            .bind(InvoiceStatus::Sent)
+            .bind(InvoiceStatus::Overdue)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match invoice {
+            Some(invoice) => self.with_line_items(invoice).await,
+            None => Err(self.state_error(identifier, user_identifier).await),
+        }
+    }
+
+    async fn mark_overdue(&self) -> Result<u64, RepositoryError> {
+        let result = sqlx::query(
+            r#"UPDATE invoices SET status = $1 WHERE status IN ($2, $3) AND due_date < CURRENT_DATE"#,
+        )
+        .bind(InvoiceStatus::Overdue)
+        .bind(InvoiceStatus::Sent)
+        .bind(InvoiceStatus::PartiallyPaid)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invoices::adapters::InvoiceLineItemRequest;
+    use crate::shared::fixtures::{UAE_DIRHAM, create_user};
+    use chrono::Duration;
+    use std::str::FromStr;
+
+    fn invoice_request(due_in_days: i64, unit_price: &str) -> CreateInvoiceRequest {
+        let today = Local::now().date_naive();
+        CreateInvoiceRequest {
+            customer_name: "Acme Ltd".to_string(),
+            customer_email: None,
+            customer_address: None,
+            currency_identifier: Uuid::from_str(UAE_DIRHAM).unwrap(),
+            issue_date: Some(today - Duration::days(30)),
+            due_date: today + Duration::days(due_in_days),
+            notes: None,
+            line_items: vec![InvoiceLineItemRequest {
+                description: "Design work".to_string(),
+                quantity: BigDecimal::from(2),
+                unit_price: BigDecimal::from_str(unit_price).unwrap(),
+                tax_rate_basis_points: 500,
+                discount_basis_points: 0,
+            }],
+        }
+    }
+
+    async fn create(
+        repository: &InvoiceRepository,
+        request: &CreateInvoiceRequest,
+        user_identifier: &Uuid,
+    ) -> Invoice {
+        let line_items: Vec<PricedLineItem> = request
+            .line_items
+            .iter()
+            .map(|item| {
+                PricedLineItem::price(
+                    &item.quantity,
+                    &item.unit_price,
+                    item.discount_basis_points,
+                    item.tax_rate_basis_points,
+                    2,
+                )
+            })
+            .collect();
+        repository
+            .create(
+                request,
+                user_identifier,
+                &line_items,
+                &InvoiceTotals::of(&line_items, 2),
+            )
+            .await
+            .expect("failed to create invoice")
+    }
+
+    #[sqlx::test]
+    async fn test_invoice_is_paid_in_parts_and_then_locked(pool: PgPool) {
+        let repository = InvoiceRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let invoice = create(&repository, &invoice_request(14, "50"), &user_identifier).await;
+        assert_eq!(invoice.status, InvoiceStatus::Draft);
+        assert_eq!(invoice.total, BigDecimal::from(105));
+        assert_eq!(invoice.line_items.len(), 1);
+
+        assert!(matches!(
+            repository
+                .record_payment(&invoice.identifier, &user_identifier, &BigDecimal::from(5))
+                .await,
+            Err(RepositoryError::InvalidInvoiceState)
+        ));
+        let sent = repository
+            .send(&invoice.identifier, &user_identifier)
+            .await
+            .unwrap();
+        assert_eq!(sent.status, InvoiceStatus::Sent);
+        assert!(matches!(
+            repository
+                .delete_draft(&invoice.identifier, &user_identifier)
+                .await,
+            Err(RepositoryError::InvalidInvoiceState)
+        ));
+
+        let partially_paid = repository
+            .record_payment(
+                &invoice.identifier,
+                &user_identifier,
+                &BigDecimal::from(100),
+            )
+            .await
+            .unwrap();
+        assert_eq!(partially_paid.status, InvoiceStatus::PartiallyPaid);
+        assert!(matches!(
+            repository
+                .record_payment(&invoice.identifier, &user_identifier, &BigDecimal::from(6))
+                .await,
+            Err(RepositoryError::OperationFailed(_))
+        ));
+        assert!(matches!(
+            repository.void(&invoice.identifier, &user_identifier).await,
+            Err(RepositoryError::InvalidInvoiceState)
+        ));
+
+        let paid = repository
+            .record_payment(&invoice.identifier, &user_identifier, &BigDecimal::from(5))
+            .await
+            .unwrap();
+        assert_eq!(paid.status, InvoiceStatus::Paid);
+        assert!(paid.paid_date.is_some());
+        assert!(matches!(
+            repository.send(&invoice.identifier, &Uuid::new_v4()).await,
+            Err(RepositoryError::RecordNotFound)
+        ));
+    }
+
+    #[sqlx::test]
+    async fn test_only_unpaid_invoices_past_due_turn_overdue(pool: PgPool) {
+        let repository = InvoiceRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let late = create(&repository, &invoice_request(-1, "10"), &user_identifier).await;
+        let on_time = create(&repository, &invoice_request(1, "10"), &user_identifier).await;
+        let draft = create(&repository, &invoice_request(-1, "10"), &user_identifier).await;
+        let free = create(&repository, &invoice_request(-1, "0"), &user_identifier).await;
+        for invoice in [&late, &on_time, &free] {
+            repository
+                .send(&invoice.identifier, &user_identifier)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(repository.mark_overdue().await.unwrap(), 1);
+
+        for (invoice, status) in [
+            (&late, InvoiceStatus::Overdue),
+            (&on_time, InvoiceStatus::Sent),
+            (&draft, InvoiceStatus::Draft),
+            (&free, InvoiceStatus::Paid),
+        ] {
+            let stored = repository
+                .fetch_invoice(&invoice.identifier, &user_identifier)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored.status, status);
+        }
+
+        let voided = repository
+            .void(&late.identifier, &user_identifier)
+            .await
+            .unwrap();
+        assert_eq!(voided.status, InvoiceStatus::Void);
+    }
+
+    #[sqlx::test]
+    async fn test_listed_invoices_carry_their_line_items(pool: PgPool) {
+        let repository = InvoiceRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let mut request = invoice_request(14, "50");
+        request.line_items.push(InvoiceLineItemRequest {
+            description: "Hosting".to_string(),
+            quantity: BigDecimal::from(1),
+            unit_price: BigDecimal::from(20),
+            tax_rate_basis_points: 0,
+            discount_basis_points: 0,
+        });
+        let first = create(&repository, &request, &user_identifier).await;
+        let second = create(&repository, &invoice_request(14, "10"), &user_identifier).await;
+
+        let invoices = repository
+            .fetch_invoices(
+                &user_identifier,
+                &InvoiceQuery { status: None },
+                &PaginationParams::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(invoices.total_count, 2);
+        for invoice in &invoices.records {
+            let expected = if invoice.identifier == first.identifier {
+                vec!["Design work", "Hosting"]
+            } else {
+                assert_eq!(invoice.identifier, second.identifier);
+                vec!["Design work"]
+            };
+            let descriptions: Vec<&str> = invoice
+                .line_items
+                .iter()
+                .map(|item| item.description.as_str())
+                .collect();
+            assert_eq!(descriptions, expected);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::invoices::handlers::{
+    create_invoice, delete_invoice, fetch_invoice, fetch_invoices, record_invoice_payment,
+    send_invoice, update_invoice, void_invoice,
+};
+use crate::shared::middlewares::idempotency::{IdempotencyStore, enforce_idempotency};
+use crate::state::AppState;
+use axum::{
+    Router,
+    extract::FromRef,
+    middleware::from_fn_with_state,
+    routing::{delete, get, post, put},
+};
+
+pub fn invoice_routes(state: &AppState) -> Router {
+    let idempotency_store = IdempotencyStore::from_ref(state);
+
+    Router::new()
+        .route("/", post(create_invoice))
+        .route("/", get(fetch_invoices))
+        .route("/{identifier}", get(fetch_invoice))
+        .route("/{identifier}", put(update_invoice))
+        .route("/{identifier}", delete(delete_invoice))
+        .route("/{identifier}/send", post(send_invoice))
+        .route(
+            "/{identifier}/payments",
+            post(record_invoice_payment)
+                .layer(from_fn_with_state(idempotency_store, enforce_idempotency)),
+        )
+        .route("/{identifier}/void", post(void_invoice))
+        .with_state(state.clone())
+}
@@ -0,0 +1,261 @@
+use crate::authentication::claims::Claims;
+use crate::countries::repository::{CountryRepository, CountryRepositoryExt};
+use crate::errors::{RepositoryError, ServiceError};
+use crate::invoices::adapters::{CreateInvoiceRequest, InvoiceQuery, RecordInvoicePaymentRequest};
+use crate::invoices::entities::{Invoice, InvoiceTotals, PricedLineItem};
+use crate::invoices::repository::{InvoiceRepository, InvoiceRepositoryExt};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use chrono::{Local, NaiveDate};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct InvoiceService {
+    repository: InvoiceRepository,
+    country_repository: CountryRepository,
+}
+
+impl InvoiceService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: InvoiceRepository::new(pool),
+            country_repository: CountryRepository::new(pool),
+        }
+    }
+
+    /// The decimal places amounts in the currency are rounded to. An unknown currency surfaces
+    /// as a 404 rather than a foreign key violation
+    async fn currency_scale(&self, currency_identifier: &Uuid) -> Result<i64, ServiceError> {
+        let country = self
+            .country_repository
+            .find_by_identifier(currency_identifier)
+            .await?;
+        Ok(i64::from(country.currency_exponent))
+    }
+
+    /// Checks the request and prices its line items, the client's own totals are never trusted.
+    /// `issue_date` is the date the invoice will carry, the stored one when an update leaves it
+    /// out
+    async fn price(
+        &self,
+        request: &CreateInvoiceRequest,
+        issue_date: NaiveDate,
+    ) -> Result<(Vec<PricedLineItem>, InvoiceTotals), ServiceError> {
+        if request.due_date < issue_date {
+            return Err(ServiceError::UnprocessableEntity(
+                "the due date cannot be before the issue date".to_string(),
+            ));
+        }
+
+        let scale = self.currency_scale(&request.currency_identifier).await?;
+
+        let line_items: Vec<PricedLineItem> = request
+            .line_items
+            .iter()
+            .map(|item| {
+                PricedLineItem::price(
+                    &item.quantity,
+                    &item.unit_price,
+                    item.discount_basis_points,
+                    item.tax_rate_basis_points,
+                    scale,
+                )
+            })
+            .collect();
+        let totals = InvoiceTotals::of(&line_items, scale);
+        Ok((line_items, totals))
+    }
+}
+
+pub trait InvoiceServiceExt {
+    fn create_invoice(
+        &self,
+        claims: &Claims,
+        request: &CreateInvoiceRequest,
+    ) -> impl std::future::Future<Output = Result<Invoice, ServiceError>> + Send;
+
+    fn fetch_invoice(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Invoice, ServiceError>> + Send;
+
+    fn fetch_invoices(
+        &self,
+        claims: &Claims,
+        query: &InvoiceQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Invoice>, ServiceError>> + Send;
+
+    /// Replaces a draft, invoices that have been sent can no longer change
+    fn update_invoice(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &CreateInvoiceRequest,
+    ) -> impl std::future::Future<Output = Result<Invoice, ServiceError>> + Send;
+
+    fn delete_invoice(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<(), ServiceError>> + Send;
+
+    fn send_invoice(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Invoice, ServiceError>> + Send;
+
+    fn record_payment(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &RecordInvoicePaymentRequest,
+    ) -> impl std::future::Future<Output = Result<Invoice, ServiceError>> + Send;
+
+    fn void_invoice(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Invoice, ServiceError>> + Send;
+
+    /// Marks the unpaid invoices past their due date overdue, returns how many were marked
+    fn mark_overdue(&self) -> impl std::future::Future<Output = Result<u64, ServiceError>> + Send;
+}
+
+impl InvoiceServiceExt for InvoiceService {
+    async fn create_invoice(
+        &self,
+        claims: &Claims,
+        request: &CreateInvoiceRequest,
+    ) -> Result<Invoice, ServiceError> {
+        let issue_date = request
+            .issue_date
+            .unwrap_or_else(|| Local::now().date_naive());
+        let (line_items, totals) = self.price(request, issue_date).await?;
+
+        let invoice = self
+            .repository
+            .create(request, &claims.user_identifier, &line_items, &totals)
+            .await?;
+        Ok(invoice)
+    }
+
+    async fn fetch_invoice(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<Invoice, ServiceError> {
+        let invoice = self
+            .repository
+            .fetch_invoice(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(invoice)
+    }
+
+    async fn fetch_invoices(
+        &self,
+        claims: &Claims,
+        query: &InvoiceQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Invoice>, ServiceError> {
+        let invoices = self
+            .repository
+            .fetch_invoices(&claims.user_identifier, query, pagination_params)
+            .await?;
+        Ok(invoices)
+    }
+
+    async fn update_invoice(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &CreateInvoiceRequest,
+    ) -> Result<Invoice, ServiceError> {
+        let stored = self
+            .repository
+            .fetch_invoice(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        let issue_date = request.issue_date.unwrap_or(stored.issue_date);
+        let (line_items, totals) = self.price(request, issue_date).await?;
+
+        let invoice = self
+            .repository
+            .update_draft(
+                identifier,
+                &claims.user_identifier,
+                request,
+                &line_items,
+                &totals,
+            )
+            .await?;
+        Ok(invoice)
+    }
+
+    async fn delete_invoice(&self, claims: &Claims, identifier: &Uuid) -> Result<(), ServiceError> {
+        self.repository
+            .delete_draft(identifier, &claims.user_identifier)
+            .await?;
+        Ok(())
+    }
+
+    async fn send_invoice(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<Invoice, ServiceError> {
+        let invoice = self
+            .repository
+            .send(identifier, &claims.user_identifier)
+            .await?;
+        Ok(invoice)
+    }
+
+    async fn record_payment(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &RecordInvoicePaymentRequest,
+    ) -> Result<Invoice, ServiceError> {
+        let invoice = self
+            .repository
+            .fetch_invoice(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        // invoice totals are in the currency's minor unit, a payment finer than that could never
+        // settle one
+        let scale = self.currency_scale(&invoice.currency_identifier).await?;
+        if request.amount.with_scale(scale) != request.amount {
+            return Err(ServiceError::UnprocessableEntity(format!(
+                "a payment cannot have more than {scale} decimal places"
+            )));
+        }
+
+        let invoice = self
+            .repository
+            .record_payment(identifier, &claims.user_identifier, &request.amount)
+            .await?;
+        Ok(invoice)
+    }
+
+    async fn void_invoice(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<Invoice, ServiceError> {
+        let invoice = self
+            .repository
+            .void(identifier, &claims.user_identifier)
+            .await?;
+        Ok(invoice)
+    }
+
+    async fn mark_overdue(&self) -> Result<u64, ServiceError> {
+        let marked = self.repository.mark_overdue().await?;
+        Ok(marked)
+    }
+}
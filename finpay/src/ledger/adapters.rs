@@ -0,0 +1,132 @@
+use crate::ledger::entities::{LedgerAccount, PostingDirection};
+use bigdecimal::{BigDecimal, Zero};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct NewPosting {
+    pub ledger_account_identifier: Uuid,
+    pub currency_identifier: Uuid,
+    pub direction: PostingDirection,
+    pub amount: BigDecimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewJournalEntry {
+    pub transaction_identifier: Option<Uuid>,
+    pub description: String,
+    pub postings: Vec<NewPosting>,
+}
+
+impl NewJournalEntry {
+    pub fn new(description: &str) -> Self {
+        Self {
+            transaction_identifier: None,
+            description: description.to_string(),
+            postings: Vec::new(),
+        }
+    }
+
+    pub fn transaction(mut self, transaction_identifier: &Uuid) -> Self {
+        self.transaction_identifier = Some(*transaction_identifier);
+        self
+    }
+
+    pub fn debit(self, account: &LedgerAccount, amount: &BigDecimal) -> Self {
+        self.posting(account, PostingDirection::Debit, amount)
+    }
+
+    pub fn credit(self, account: &LedgerAccount, amount: &BigDecimal) -> Self {
+        self.posting(account, PostingDirection::Credit, amount)
+    }
+
+    fn posting(
+        mut self,
+        account: &LedgerAccount,
+        direction: PostingDirection,
+        amount: &BigDecimal,
+    ) -> Self {
+        self.postings.push(NewPosting {
+            ledger_account_identifier: account.identifier,
+            currency_identifier: account.currency_identifier,
+            direction,
+            amount: amount.clone(),
+        });
+        self
+    }
+
+    /// debits and credits must cancel out within every currency the entry touches
+    pub fn is_balanced(&self) -> bool {
+        if self.postings.is_empty() {
+            return false;
+        }
+
+        let mut totals: HashMap<Uuid, BigDecimal> = HashMap::new();
+        for posting in &self.postings {
+            if posting.amount <= BigDecimal::zero() {
+                return false;
+            }
+
+            let total = totals.entry(posting.currency_identifier).or_default();
+            match posting.direction {
+                PostingDirection::Debit => *total += &posting.amount,
+                PostingDirection::Credit => *total -= &posting.amount,
+            }
+        }
+
+        totals.values().all(|total| total.is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::entities::LedgerAccountType;
+    use chrono::Local;
+
+    fn account(currency_identifier: Uuid) -> LedgerAccount {
+        LedgerAccount {
+            identifier: Uuid::new_v4(),
+            name: "test".to_string(),
+            account_type: LedgerAccountType::Liability,
+            currency_identifier,
+            wallet_identifier: Some(Uuid::new_v4()),
+            system_code: None,
+            created_date: Local::now(),
+        }
+    }
+
+    #[test]
+    fn test_balanced_entry() {
+        let currency = Uuid::new_v4();
+        let entry = NewJournalEntry::new("transfer")
+            .debit(&account(currency), &BigDecimal::from(25))
+            .credit(&account(currency), &BigDecimal::from(25));
+
+        assert!(entry.is_balanced());
+    }
+
+    #[test]
+    fn test_unbalanced_entry() {
+        let currency = Uuid::new_v4();
+        let entry = NewJournalEntry::new("transfer")
+            .debit(&account(currency), &BigDecimal::from(25))
+            .credit(&account(currency), &BigDecimal::from(20));
+
+        assert!(!entry.is_balanced());
+    }
+
+    #[test]
+    fn test_entry_must_balance_per_currency() {
+        let entry = NewJournalEntry::new("conversion")
+            .debit(&account(Uuid::new_v4()), &BigDecimal::from(25))
+            .credit(&account(Uuid::new_v4()), &BigDecimal::from(25));
+
+        assert!(!entry.is_balanced());
+    }
+
+    #[test]
+    fn test_empty_entry_is_not_balanced() {
+        assert!(!NewJournalEntry::new("nothing").is_balanced());
+    }
+}
@@ -0,0 +1,125 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "ledger_account_type_enum")]
+pub enum LedgerAccountType {
+    Asset,
+    Liability,
+    Equity,
+    Revenue,
+    Expense,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "posting_direction_enum")]
+pub enum PostingDirection {
+    Debit,
+    Credit,
+}
+
+/// Accounts owned by finpay rather than by a wallet, there is one per currency
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SystemAccount {
+    OpeningBalances,
+    /// money held at partner banks, deposits arrive through it
+    BankSettlement,
+    /// withdrawals that have left the wallet but have not been paid out by the bank yet
+    PayoutsInTransit,
+    /// the currency finpay has bought or sold through conversions
+    FxPosition,
+    /// the spread earned on conversions over the mid rate
+    FxSpreadRevenue,
+    /// fees charged on transfers, withdrawals and conversions
+    FeeRevenue,
+    /// chargebacks paid to payers that the payee's wallet could not cover, owed by the payee
+    ChargebackReceivable,
+}
+
+impl SystemAccount {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SystemAccount::OpeningBalances => "opening_balances",
+            SystemAccount::BankSettlement => "bank_settlement",
+            SystemAccount::PayoutsInTransit => "payouts_in_transit",
+            SystemAccount::FxPosition => "fx_position",
+            SystemAccount::FxSpreadRevenue => "fx_spread_revenue",
+            SystemAccount::FeeRevenue => "fee_revenue",
+            SystemAccount::ChargebackReceivable => "chargeback_receivable",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SystemAccount::OpeningBalances => "opening balances",
+            SystemAccount::BankSettlement => "bank settlement",
+            SystemAccount::PayoutsInTransit => "payouts in transit",
+            SystemAccount::FxPosition => "fx position",
+            SystemAccount::FxSpreadRevenue => "fx spread revenue",
+            SystemAccount::FeeRevenue => "fee revenue",
+            SystemAccount::ChargebackReceivable => "chargeback receivable",
+        }
+    }
+
+    pub fn account_type(&self) -> LedgerAccountType {
+        match self {
+            SystemAccount::OpeningBalances => LedgerAccountType::Equity,
+            SystemAccount::BankSettlement => LedgerAccountType::Asset,
+            SystemAccount::PayoutsInTransit => LedgerAccountType::Liability,
+            SystemAccount::FxPosition => LedgerAccountType::Asset,
+            SystemAccount::FxSpreadRevenue => LedgerAccountType::Revenue,
+            SystemAccount::FeeRevenue => LedgerAccountType::Revenue,
+            SystemAccount::ChargebackReceivable => LedgerAccountType::Asset,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerAccount {
+    pub identifier: Uuid,
+    pub name: String,
+    pub account_type: LedgerAccountType,
+    pub currency_identifier: Uuid,
+    pub wallet_identifier: Option<Uuid>,
+    pub system_code: Option<String>,
+    pub created_date: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub identifier: Uuid,
+    pub transaction_identifier: Option<Uuid>,
+    pub description: String,
+    pub created_date: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Posting {
+    pub identifier: Uuid,
+    pub journal_entry_identifier: Uuid,
+    pub ledger_account_identifier: Uuid,
+    pub direction: PostingDirection,
+    pub amount: BigDecimal,
+    pub created_date: DateTime<Local>,
+}
+
+/// A posting on a wallet account, together with the entry it belongs to
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletPosting {
+    pub identifier: Uuid,
+    pub journal_entry_identifier: Uuid,
+    pub transaction_identifier: Option<Uuid>,
+    pub description: String,
+    pub direction: PostingDirection,
+    pub amount: BigDecimal,
+    pub created_date: DateTime<Local>,
+}
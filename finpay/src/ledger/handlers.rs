@@ -0,0 +1,20 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::ledger::entities::WalletPosting;
+use crate::ledger::service::{LedgerService, LedgerServiceExt};
+use crate::utils::{ApiResponse, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use uuid::Uuid;
+
+pub async fn fetch_wallet_postings(
+    State(ledger_service): State<LedgerService>,
+    claims: Claims,
+    Path(wallet_identifier): Path<Uuid>,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<WalletPosting>>, ServiceError> {
+    let postings = ledger_service
+        .fetch_wallet_postings(&claims, &wallet_identifier, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(postings).build())
+}
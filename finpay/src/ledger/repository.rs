@@ -0,0 +1,174 @@
+use crate::errors::RepositoryError;
+use crate::ledger::adapters::NewJournalEntry;
+use crate::ledger::entities::{JournalEntry, LedgerAccount, SystemAccount, WalletPosting};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct LedgerRepository {
+    pool: PgPool,
+}
+
+impl LedgerRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Fetches the liability account that backs a wallet
+    pub async fn wallet_account(
+        connection: &mut PgConnection,
+        wallet_identifier: &Uuid,
+    ) -> Result<LedgerAccount, RepositoryError> {
+        sqlx::query_as::<_, LedgerAccount>(
+            r#"SELECT * FROM ledger_accounts WHERE wallet_identifier = $1"#,
+        )
+        .bind(wallet_identifier)
+        .fetch_optional(connection)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)
+    }
+
+    /// Fetches a finpay owned account, creating it the first time the currency is used
+    pub async fn system_account(
+        connection: &mut PgConnection,
+        system_account: SystemAccount,
+        currency_identifier: &Uuid,
+    ) -> Result<LedgerAccount, RepositoryError> {
+        let query = r#"
+        INSERT INTO ledger_accounts (identifier, name, account_type, currency_identifier, system_code)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (system_code, currency_identifier) DO NOTHING
+        "#;
+        sqlx::query(query)
+            .bind(Uuid::new_v4())
+            .bind(system_account.name())
+            .bind(system_account.account_type())
+            .bind(currency_identifier)
+            .bind(system_account.code())
+            .execute(&mut *connection)
+            .await?;
+
+        sqlx::query_as::<_, LedgerAccount>(
+            r#"SELECT * FROM ledger_accounts WHERE system_code = $1 AND currency_identifier = $2"#,
+        )
+        .bind(system_account.code())
+        .bind(currency_identifier)
+        .fetch_one(&mut *connection)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    /// Appends a journal entry and its postings, this must run inside the same database
+    /// transaction as the money movement it records
+    pub async fn record_entry(
+        connection: &mut PgConnection,
+        entry: &NewJournalEntry,
+    ) -> Result<JournalEntry, RepositoryError> {
+        if !entry.is_balanced() {
+            return Err(RepositoryError::UnbalancedJournalEntry);
+        }
+
+        let journal_entry = sqlx::query_as::<_, JournalEntry>(
+            r#"INSERT INTO journal_entries (identifier, transaction_identifier, description) VALUES ($1, $2, $3) RETURNING *"#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(entry.transaction_identifier)
+        .bind(&entry.description)
+        .fetch_one(&mut *connection)
+        .await?;
+
+        for posting in &entry.postings {
+            let query = r#"
+            INSERT INTO postings (identifier, journal_entry_identifier, ledger_account_identifier, direction, amount)
+            VALUES ($1, $2, $3, $4, $5)
+            "#;
+            sqlx::query(query)
+                .bind(Uuid::new_v4())
+                .bind(journal_entry.identifier)
+                .bind(posting.ledger_account_identifier)
+                .bind(posting.direction)
+                .bind(&posting.amount)
+                .execute(&mut *connection)
+                .await?;
+        }
+
+        Ok(journal_entry)
+    }
+}
+
+pub trait LedgerRepositoryExt {
+    fn fetch_wallet_postings(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<WalletPosting>, RepositoryError>> + Send;
+}
+
+impl LedgerRepositoryExt for LedgerRepository {
+    async fn fetch_wallet_postings(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<WalletPosting>, RepositoryError> {
+        let wallet_exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM wallets WHERE identifier = $1 AND user_identifier = $2)",
+        )
+        .bind(wallet_identifier)
+        .bind(user_identifier)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if !wallet_exists {
+            return Err(RepositoryError::RecordNotFound);
+        }
+
+        let total_count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(postings.identifier)
+            FROM postings
+                     JOIN ledger_accounts ON ledger_accounts.identifier = postings.ledger_account_identifier
+            WHERE ledger_accounts.wallet_identifier = $1
+            "#,
+        )
+        .bind(wallet_identifier)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let query = r#"
+        SELECT
+            postings.identifier,
+            postings.journal_entry_identifier,
+            journal_entries.transaction_identifier,
+            journal_entries.description,
+            postings.direction,
+            postings.amount,
+            postings.created_date
+        FROM postings
+                 JOIN journal_entries ON journal_entries.identifier = postings.journal_entry_identifier
+                 JOIN ledger_accounts ON ledger_accounts.identifier = postings.ledger_account_identifier
+        WHERE ledger_accounts.wallet_identifier = $1
+        ORDER BY postings.created_date DESC
+        LIMIT $2 OFFSET $3
+        "#;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let postings = sqlx::query_as::<_, WalletPosting>(query)
+            .bind(wallet_identifier)
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(PaginatedResponse::new(
+            postings,
+            pagination_params,
+            total_count,
+        ))
+    }
+}
@@ -0,0 +1,8 @@
+use crate::{ledger::handlers::fetch_wallet_postings, state::AppState};
+use axum::{Router, routing::get};
+
+pub fn ledger_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/wallets/{wallet_identifier}/postings", get(fetch_wallet_postings))
+        .with_state(state.clone())
+}
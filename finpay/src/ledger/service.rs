@@ -0,0 +1,44 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::ledger::entities::WalletPosting;
+use crate::ledger::repository::{LedgerRepository, LedgerRepositoryExt};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct LedgerService {
+    repository: LedgerRepository,
+}
+
+impl LedgerService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: LedgerRepository::new(pool),
+        }
+    }
+}
+
+pub trait LedgerServiceExt {
+    fn fetch_wallet_postings(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<WalletPosting>, ServiceError>> + Send;
+}
+
+impl LedgerServiceExt for LedgerService {
+    async fn fetch_wallet_postings(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<WalletPosting>, ServiceError> {
+        let postings = self
+            .repository
+            .fetch_wallet_postings(wallet_identifier, &claims.user_identifier, pagination_params)
+            .await?;
+        Ok(postings)
+    }
+}
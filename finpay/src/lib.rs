@@ -1,14 +1,26 @@
 pub mod accounts;
 pub mod authentication;
+pub mod bank_statements;
+pub mod beneficiaries;
+pub mod compliance;
 pub mod config;
+pub mod conversions;
 pub mod countries;
+pub mod disputes;
 pub mod errors;
+pub mod fees;
+pub mod fx;
+pub mod holds;
 pub mod invoices;
 pub mod ledger;
+pub mod payouts;
+pub mod reconciliation;
+pub mod risk;
 pub mod router;
 pub mod security;
 pub mod shared;
 pub mod state;
+pub mod statements;
 pub mod transactions;
 pub mod users;
 pub mod utils;
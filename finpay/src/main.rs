@@ -32,7 +32,7 @@ async fn main() -> Result<(), AppError> {
     let db_pool = AppDatabase::init(&config).await?;
     let shared_db_pool = std::sync::Arc::new(db_pool);
 
-    AppBackgroundTasks::run();
+    AppBackgroundTasks::run(&shared_db_pool);
     tracing::info!("Background tasks initialized");
 
     let body_limit_bytes = config.body_limit_mb * 1024 * 1024;
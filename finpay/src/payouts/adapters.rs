@@ -0,0 +1,267 @@
+use std::str::FromStr;
+
+use axum_typed_multipart::{FieldData, TryFromMultipart};
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+use uuid::Uuid;
+use validator::{Validate, ValidationErrors};
+
+use crate::beneficiaries::adapters::CreateBeneficiaryRequest;
+use crate::payouts::provider::PayoutOutcome;
+
+#[derive(Serialize, Deserialize, Validate, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePayoutRequest {
+    pub wallet_identifier: Uuid,
+    pub beneficiary_identifier: Uuid,
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: BigDecimal,
+    #[validate(length(
+        max = 255,
+        message = "narration cannot be more than 255 characters",
+        code = "narration"
+    ))]
+    pub narration: Option<String>,
+}
+
+/// What the partner bank did with a payout sent to it in a pain.001 file
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportedPayoutOutcome {
+    Paid,
+    Rejected,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmExportedPayoutRequest {
+    pub outcome: ExportedPayoutOutcome,
+    /// the bank's reference for a paid payout, the reason it gave for a rejected one
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "note must be between 1 and 255 characters",
+        code = "note"
+    ))]
+    pub note: String,
+}
+
+impl From<&ConfirmExportedPayoutRequest> for PayoutOutcome {
+    fn from(request: &ConfirmExportedPayoutRequest) -> Self {
+        match request.outcome {
+            ExportedPayoutOutcome::Paid => PayoutOutcome::Paid {
+                provider_reference: request.note.clone(),
+            },
+            ExportedPayoutOutcome::Rejected => PayoutOutcome::Failed {
+                reason: request.note.clone(),
+            },
+        }
+    }
+}
+
+/// A csv of payouts with the columns bank_identifier, account_number, account_name, amount and
+/// an optional narration
+#[derive(TryFromMultipart)]
+#[try_from_multipart(rename_all = "camelCase")]
+pub struct CreatePayoutBatchRequest {
+    pub wallet_identifier: String,
+    #[form_data(limit = "2MiB")]
+    pub file: FieldData<NamedTempFile>,
+}
+
+/// One line of the csv as it was uploaded
+#[derive(Deserialize, Debug)]
+pub struct PayoutBatchRow {
+    pub bank_identifier: String,
+    pub account_number: String,
+    pub account_name: String,
+    pub amount: String,
+    pub narration: Option<String>,
+}
+
+/// A row of the csv once it has been checked against the beneficiary and payout rules, the bank
+/// is checked by the repository
+#[derive(Debug)]
+pub struct NewPayoutBatchItem {
+    pub row_number: i32,
+    pub bank_identifier: Option<Uuid>,
+    pub account_number: String,
+    pub account_name: String,
+    pub amount: Option<BigDecimal>,
+    pub narration: Option<String>,
+    pub errors: Vec<String>,
+}
+
+impl NewPayoutBatchItem {
+    pub fn from_row(row_number: i32, row: PayoutBatchRow) -> Self {
+        let mut errors = vec![];
+
+        let bank_identifier = Uuid::from_str(row.bank_identifier.trim()).ok();
+        if bank_identifier.is_none() {
+            errors.push("bank identifier is not valid".to_string());
+        }
+        let amount = BigDecimal::from_str(row.amount.trim()).ok();
+        if amount.is_none() {
+            errors.push("amount is not a number".to_string());
+        }
+        let narration = row
+            .narration
+            .filter(|narration| !narration.trim().is_empty());
+
+        let beneficiary = CreateBeneficiaryRequest {
+            bank_identifier: bank_identifier.unwrap_or_default(),
+            account_number: row.account_number.trim().to_string(),
+            account_name: row.account_name.trim().to_string(),
+        };
+        if let Err(err) = beneficiary.validate() {
+            errors.extend(validation_messages(&err));
+        }
+        if let Some(amount) = &amount {
+            let payout = CreatePayoutRequest {
+                wallet_identifier: Uuid::nil(),
+                beneficiary_identifier: Uuid::nil(),
+                amount: amount.clone(),
+                narration: narration.clone(),
+            };
+            if let Err(err) = payout.validate() {
+                errors.extend(validation_messages(&err));
+            }
+        }
+
+        Self {
+            row_number,
+            bank_identifier,
+            account_number: beneficiary.account_number,
+            account_name: beneficiary.account_name,
+            amount,
+            narration,
+            errors,
+        }
+    }
+
+    /// a line that could not be read at all
+    pub fn malformed(row_number: i32, reason: &str) -> Self {
+        Self {
+            row_number,
+            bank_identifier: None,
+            account_number: String::new(),
+            account_name: String::new(),
+            amount: None,
+            narration: None,
+            errors: vec![format!("the row could not be read: {reason}")],
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Flags every row that pays the same amount into the same account as an earlier one, a line
+/// pasted twice by mistake would otherwise be paid twice
+pub fn flag_duplicate_rows(items: &mut [NewPayoutBatchItem]) {
+    for index in 1..items.len() {
+        let (earlier, rest) = items.split_at_mut(index);
+        let item = &mut rest[0];
+        if item.bank_identifier.is_none() || item.amount.is_none() {
+            continue;
+        }
+        if let Some(original) = earlier.iter().find(|original| {
+            original.bank_identifier == item.bank_identifier
+                && original.account_number == item.account_number
+                && original.amount == item.amount
+        }) {
+            item.errors.push(format!(
+                "row {} already pays this amount into this account",
+                original.row_number
+            ));
+        }
+    }
+}
+
+fn validation_messages(errors: &ValidationErrors) -> Vec<String> {
+    let mut field_errors: Vec<_> = errors.field_errors().into_iter().collect();
+    field_errors.sort_by(|a, b| a.0.cmp(&b.0));
+    field_errors
+        .into_iter()
+        .flat_map(|(field, errors)| {
+            errors.iter().map(move |error| {
+                error
+                    .message
+                    .as_ref()
+                    .map(|message| message.to_string())
+                    .unwrap_or_else(|| format!("{field} is not valid"))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(bank_identifier: &str, account_number: &str, amount: &str) -> PayoutBatchRow {
+        PayoutBatchRow {
+            bank_identifier: bank_identifier.to_string(),
+            account_number: account_number.to_string(),
+            account_name: "Ada Lovelace".to_string(),
+            amount: amount.to_string(),
+            narration: Some(" ".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_valid_row() {
+        let item = NewPayoutBatchItem::from_row(
+            2,
+            row(
+                "e829463e-a7f0-461c-b094-da566ad82801",
+                "0123456789",
+                "150.50",
+            ),
+        );
+
+        assert!(item.is_valid());
+        assert_eq!(item.amount, Some(BigDecimal::from_str("150.50").unwrap()));
+        assert_eq!(item.narration, None);
+    }
+
+    #[test]
+    fn test_repeated_rows_are_flagged() {
+        let bank_identifier = "e829463e-a7f0-461c-b094-da566ad82801";
+        let mut items = vec![
+            NewPayoutBatchItem::from_row(2, row(bank_identifier, "0123456789", "150.50")),
+            NewPayoutBatchItem::from_row(3, row(bank_identifier, "0123456789", "150.5")),
+            NewPayoutBatchItem::from_row(4, row(bank_identifier, "0123456789", "99")),
+        ];
+
+        flag_duplicate_rows(&mut items);
+
+        assert!(items[0].is_valid());
+        assert_eq!(
+            items[1].errors,
+            vec!["row 2 already pays this amount into this account".to_string()]
+        );
+        assert!(items[2].is_valid());
+    }
+
+    #[test]
+    fn test_every_broken_rule_is_reported() {
+        let item = NewPayoutBatchItem::from_row(3, row("not-a-bank", "12345", "-4"));
+
+        assert!(!item.is_valid());
+        assert_eq!(
+            item.errors,
+            vec![
+                "bank identifier is not valid".to_string(),
+                "account number must be 10 digits".to_string(),
+                "amount must be greater than zero".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "payout_status_enum")]
+pub enum PayoutStatus {
+    Queued,
+    Processing,
+    Paid,
+    Failed,
+    /// sent to a partner bank in a pain.001 file, the payout processor never picks it up
+    Exported,
+}
+
+/// A withdrawal from a wallet to a bank account, the funds sit in transit until the provider
+/// settles it
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Payout {
+    pub identifier: Uuid,
+    pub transaction_identifier: Uuid,
+    pub user_identifier: Uuid,
+    pub wallet_identifier: Uuid,
+    pub beneficiary_identifier: Option<Uuid>,
+    pub bank_identifier: Uuid,
+    pub account_number: String,
+    pub account_name: String,
+    pub amount: BigDecimal,
+    pub currency_identifier: Uuid,
+    pub status: PayoutStatus,
+    pub provider_reference: Option<String>,
+    pub failure_reason: Option<String>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "payout_batch_status_enum")]
+pub enum PayoutBatchStatus {
+    /// every row passed validation, the batch is waiting to be committed
+    Validated,
+    /// at least one row failed validation, nothing will be paid out
+    Rejected,
+    Executed,
+    /// handed to a partner bank as a pain.001 file instead of being paid out row by row
+    Exported,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "payout_batch_item_status_enum")]
+pub enum PayoutBatchItemStatus {
+    Valid,
+    Invalid,
+    /// a payout was queued for the row, its own status tracks it from there
+    Queued,
+    /// the row was valid but the payout could not be queued when the batch was executed
+    Failed,
+    /// the row was booked and written to the batch's pain.001 file
+    Exported,
+}
+
+/// Payouts uploaded together as a csv by a company account
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PayoutBatch {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub user_identifier: Uuid,
+    pub wallet_identifier: Uuid,
+    pub file_name: String,
+    pub status: PayoutBatchStatus,
+    pub item_count: i32,
+    /// the sum of the valid rows
+    pub total_amount: BigDecimal,
+    #[serde(skip)]
+    pub result_file: Option<String>,
+    #[serde(skip)]
+    pub export_file: Option<String>,
+    pub executed_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+    #[sqlx(skip)]
+    pub items: Vec<PayoutBatchItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PayoutBatchItem {
+    pub identifier: Uuid,
+    pub batch_identifier: Uuid,
+    /// the line in the csv, the header is line 1
+    pub row_number: i32,
+    pub bank_identifier: Option<Uuid>,
+    pub account_number: String,
+    pub account_name: String,
+    pub amount: Option<BigDecimal>,
+    pub narration: Option<String>,
+    pub status: PayoutBatchItemStatus,
+    pub errors: Vec<String>,
+    pub payout_identifier: Option<Uuid>,
+    /// the status of the queued payout, if there is one
+    pub payout_status: Option<PayoutStatus>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+/// The company a pain.001 file pays out for, and the bank account behind the wallet the payouts
+/// are booked against. A wallet without a virtual account has no account to debit
+#[derive(Debug, FromRow)]
+pub struct PaymentDebtor {
+    pub name: String,
+    pub address: String,
+    pub country: String,
+    pub wallet_identifier: Uuid,
+    pub currency_code: String,
+    pub account_number: Option<String>,
+    pub bic: Option<String>,
+}
+
+/// An exported row of a batch with the payout booked for it and the bank it goes to
+#[derive(Debug, FromRow)]
+pub struct ExportedPayout {
+    pub item_identifier: Uuid,
+    pub payout_identifier: Uuid,
+    pub amount: BigDecimal,
+    pub account_number: String,
+    pub account_name: String,
+    pub bank_name: String,
+    pub bic: Option<String>,
+    pub narration: Option<String>,
+}
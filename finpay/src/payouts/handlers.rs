@@ -0,0 +1,172 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::payouts::adapters::{
+    ConfirmExportedPayoutRequest, CreatePayoutBatchRequest, CreatePayoutRequest,
+};
+use crate::payouts::entities::{Payout, PayoutBatch};
+use crate::payouts::service::{PayoutService, PayoutServiceExt};
+use crate::shared::middlewares::admin_middleware::AdminClaims;
+use crate::shared::middlewares::validator::ValidatedRequest;
+use crate::utils::{ApiResponse, AuthenticatedRequest, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use axum_typed_multipart::TypedMultipart;
+use uuid::Uuid;
+
+pub async fn create_payout(
+    State(payout_service): State<PayoutService>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreatePayoutRequest>,
+) -> Result<ApiResponse<Payout>, ServiceError> {
+    let payout = payout_service.create_payout(&claims, &request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(payout)
+        .message("payout queued successfully")
+        .status_code(StatusCode::ACCEPTED)
+        .build())
+}
+
+pub async fn fetch_payout(
+    State(payout_service): State<PayoutService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<Payout>, ServiceError> {
+    let payout = payout_service.fetch_payout(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder().data(payout).build())
+}
+
+pub async fn fetch_payouts(
+    State(payout_service): State<PayoutService>,
+    claims: Claims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<Payout>>, ServiceError> {
+    let payouts = payout_service
+        .fetch_payouts(&claims, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(payouts).build())
+}
+
+pub async fn create_payout_batch(
+    State(payout_service): State<PayoutService>,
+    claims: Claims,
+    request: TypedMultipart<CreatePayoutBatchRequest>,
+) -> Result<ApiResponse<PayoutBatch>, ServiceError> {
+    let batch = payout_service.create_batch(&claims, request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(batch)
+        .message("payout batch uploaded successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_payout_batch(
+    State(payout_service): State<PayoutService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<PayoutBatch>, ServiceError> {
+    let batch = payout_service.fetch_batch(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder().data(batch).build())
+}
+
+pub async fn fetch_payout_batches(
+    State(payout_service): State<PayoutService>,
+    claims: Claims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<PayoutBatch>>, ServiceError> {
+    let batches = payout_service
+        .fetch_batches(&claims, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(batches).build())
+}
+
+pub async fn execute_payout_batch(
+    State(payout_service): State<PayoutService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<PayoutBatch>, ServiceError> {
+    let batch = payout_service.execute_batch(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder()
+        .data(batch)
+        .message("payout batch executed successfully")
+        .status_code(StatusCode::ACCEPTED)
+        .build())
+}
+
+pub async fn download_payout_batch_result(
+    State(payout_service): State<PayoutService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<impl IntoResponse, ServiceError> {
+    let (file_name, contents) = payout_service
+        .fetch_batch_result(&claims, &identifier)
+        .await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        contents,
+    ))
+}
+
+pub async fn export_payout_batch(
+    State(payout_service): State<PayoutService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<PayoutBatch>, ServiceError> {
+    let batch = payout_service.export_batch(&claims, &identifier).await?;
+
+    Ok(ApiResponse::builder()
+        .data(batch)
+        .message("payout batch exported successfully")
+        .status_code(StatusCode::ACCEPTED)
+        .build())
+}
+
+pub async fn download_payout_batch_export(
+    State(payout_service): State<PayoutService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<impl IntoResponse, ServiceError> {
+    let (file_name, contents) = payout_service
+        .fetch_batch_export(&claims, &identifier)
+        .await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/xml".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        contents,
+    ))
+}
+
+pub async fn confirm_exported_payout(
+    State(payout_service): State<PayoutService>,
+    AdminClaims(_): AdminClaims,
+    Path(identifier): Path<Uuid>,
+    ValidatedRequest(request): ValidatedRequest<ConfirmExportedPayoutRequest>,
+) -> Result<ApiResponse<Payout>, ServiceError> {
+    let payout = payout_service
+        .confirm_exported_payout(&identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(payout)
+        .message("payout confirmed successfully")
+        .build())
+}
@@ -0,0 +1,8 @@
+pub mod adapters;
+pub mod entities;
+pub mod handlers;
+pub mod pain;
+pub mod provider;
+pub mod repository;
+pub mod router;
+pub mod service;
@@ -0,0 +1,779 @@
+//! ISO 20022 pain.001.001.09 customer credit transfer initiation, the file an exported payout
+//! batch is sent to a partner bank in. It is checked against the parts of the schema finpay
+//! writes before it is handed out
+
+use crate::payouts::entities::{ExportedPayout, PaymentDebtor, PayoutBatch};
+use crate::utils::xml::{XmlElement, XmlWriter, descendants, parse, required};
+use crate::utils::{is_bic, is_currency_code, is_iban};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+const PAIN_001_NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:pain.001.001.09";
+
+/// an address runs over at most this many lines of at most 70 characters
+const ADDRESS_LINES: usize = 7;
+
+const UNBOUNDED: usize = usize::MAX;
+
+/// elements that hold exactly one of the children the schema lists for them
+const CHOICES: &[&str] = &[
+    "CstmrCdtTrfInitn/PmtInf/ReqdExctnDt",
+    "CstmrCdtTrfInitn/PmtInf/DbtrAcct/Id",
+    "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/Amt",
+    "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/CdtrAcct/Id",
+];
+
+/// An element's children in the order the schema puts them, with how often each may appear
+type Children = &'static [(&'static str, usize, usize)];
+
+const PARTY: Children = &[
+    ("Nm", 0, 1),
+    ("PstlAdr", 0, 1),
+    ("Id", 0, 1),
+    ("CtryOfRes", 0, 1),
+    ("CtctDtls", 0, 1),
+];
+
+const POSTAL_ADDRESS: Children = &[
+    ("AdrTp", 0, 1),
+    ("Dept", 0, 1),
+    ("SubDept", 0, 1),
+    ("StrtNm", 0, 1),
+    ("BldgNb", 0, 1),
+    ("BldgNm", 0, 1),
+    ("Flr", 0, 1),
+    ("PstBx", 0, 1),
+    ("Room", 0, 1),
+    ("PstCd", 0, 1),
+    ("TwnNm", 0, 1),
+    ("TwnLctnNm", 0, 1),
+    ("DstrctNm", 0, 1),
+    ("CtrySubDvsn", 0, 1),
+    ("Ctry", 0, 1),
+    ("AdrLine", 0, ADDRESS_LINES),
+];
+
+const ACCOUNT: Children = &[
+    ("Id", 1, 1),
+    ("Tp", 0, 1),
+    ("Ccy", 0, 1),
+    ("Nm", 0, 1),
+    ("Prxy", 0, 1),
+];
+
+const ACCOUNT_IDENTIFICATION: Children = &[("IBAN", 0, 1), ("Othr", 0, 1)];
+
+const OTHER_IDENTIFICATION: Children = &[("Id", 1, 1), ("SchmeNm", 0, 1), ("Issr", 0, 1)];
+
+const AGENT: Children = &[("FinInstnId", 1, 1), ("BrnchId", 0, 1)];
+
+const FINANCIAL_INSTITUTION: Children = &[
+    ("BICFI", 0, 1),
+    ("ClrSysMmbId", 0, 1),
+    ("LEI", 0, 1),
+    ("Nm", 0, 1),
+    ("PstlAdr", 0, 1),
+    ("Othr", 0, 1),
+];
+
+/// The part of the pain.001.001.09 schema finpay writes, keyed by path below `Document`. Any
+/// other element must hold text only
+const SCHEMA: &[(&str, Children)] = &[
+    ("", &[("CstmrCdtTrfInitn", 1, 1)]),
+    (
+        "CstmrCdtTrfInitn",
+        &[
+            ("GrpHdr", 1, 1),
+            ("PmtInf", 1, UNBOUNDED),
+            ("SplmtryData", 0, UNBOUNDED),
+        ],
+    ),
+    (
+        "CstmrCdtTrfInitn/GrpHdr",
+        &[
+            ("MsgId", 1, 1),
+            ("CreDtTm", 1, 1),
+            ("Authstn", 0, 2),
+            ("NbOfTxs", 1, 1),
+            ("CtrlSum", 0, 1),
+            ("InitgPty", 1, 1),
+            ("FwdgAgt", 0, 1),
+        ],
+    ),
+    ("CstmrCdtTrfInitn/GrpHdr/InitgPty", PARTY),
+    (
+        "CstmrCdtTrfInitn/PmtInf",
+        &[
+            ("PmtInfId", 1, 1),
+            ("PmtMtd", 1, 1),
+            ("BtchBookg", 0, 1),
+            ("NbOfTxs", 0, 1),
+            ("CtrlSum", 0, 1),
+            ("PmtTpInf", 0, 1),
+            ("ReqdExctnDt", 1, 1),
+            ("PoolgAdjstmntDt", 0, 1),
+            ("Dbtr", 1, 1),
+            ("DbtrAcct", 1, 1),
+            ("DbtrAgt", 1, 1),
+            ("DbtrAgtAcct", 0, 1),
+            ("InstrForDbtrAgt", 0, 1),
+            ("UltmtDbtr", 0, 1),
+            ("ChrgBr", 0, 1),
+            ("ChrgsAcct", 0, 1),
+            ("ChrgsAcctAgt", 0, 1),
+            ("CdtTrfTxInf", 1, UNBOUNDED),
+        ],
+    ),
+    (
+        "CstmrCdtTrfInitn/PmtInf/ReqdExctnDt",
+        &[("Dt", 0, 1), ("DtTm", 0, 1)],
+    ),
+    ("CstmrCdtTrfInitn/PmtInf/Dbtr", PARTY),
+    ("CstmrCdtTrfInitn/PmtInf/Dbtr/PstlAdr", POSTAL_ADDRESS),
+    ("CstmrCdtTrfInitn/PmtInf/DbtrAcct", ACCOUNT),
+    (
+        "CstmrCdtTrfInitn/PmtInf/DbtrAcct/Id",
+        ACCOUNT_IDENTIFICATION,
+    ),
+    (
+        "CstmrCdtTrfInitn/PmtInf/DbtrAcct/Id/Othr",
+        OTHER_IDENTIFICATION,
+    ),
+    ("CstmrCdtTrfInitn/PmtInf/DbtrAgt", AGENT),
+    (
+        "CstmrCdtTrfInitn/PmtInf/DbtrAgt/FinInstnId",
+        FINANCIAL_INSTITUTION,
+    ),
+    (
+        "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf",
+        &[
+            ("PmtId", 1, 1),
+            ("PmtTpInf", 0, 1),
+            ("Amt", 1, 1),
+            ("XchgRateInf", 0, 1),
+            ("ChrgBr", 0, 1),
+            ("ChqInstr", 0, 1),
+            ("UltmtDbtr", 0, 1),
+            ("IntrmyAgt1", 0, 1),
+            ("CdtrAgt", 0, 1),
+            ("Cdtr", 0, 1),
+            ("CdtrAcct", 0, 1),
+            ("UltmtCdtr", 0, 1),
+            ("InstrForCdtrAgt", 0, UNBOUNDED),
+            ("Purp", 0, 1),
+            ("RmtInf", 0, 1),
+        ],
+    ),
+    (
+        "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/PmtId",
+        &[("InstrId", 0, 1), ("EndToEndId", 1, 1), ("UETR", 0, 1)],
+    ),
+    (
+        "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/Amt",
+        &[("InstdAmt", 0, 1), ("EqvtAmt", 0, 1)],
+    ),
+    ("CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/CdtrAgt", AGENT),
+    (
+        "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/CdtrAgt/FinInstnId",
+        FINANCIAL_INSTITUTION,
+    ),
+    (
+        "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/CdtrAgt/FinInstnId/Othr",
+        OTHER_IDENTIFICATION,
+    ),
+    ("CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/Cdtr", PARTY),
+    ("CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/CdtrAcct", ACCOUNT),
+    (
+        "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/CdtrAcct/Id",
+        ACCOUNT_IDENTIFICATION,
+    ),
+    (
+        "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/CdtrAcct/Id/Othr",
+        OTHER_IDENTIFICATION,
+    ),
+    (
+        "CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf/RmtInf",
+        &[("Ustrd", 0, UNBOUNDED), ("Strd", 0, UNBOUNDED)],
+    ),
+];
+
+/// The longest text the schema allows for an element, matched on the end of its path. The
+/// first match wins, an account's `Othr/Id` is one shorter than the other identifiers
+const TEXT_LENGTHS: &[(&str, usize)] = &[
+    ("Acct/Id/Othr/Id", 34),
+    ("Acct/Id/IBAN", 34),
+    ("BICFI", 11),
+    ("Id", 35),
+    ("MsgId", 35),
+    ("PmtInfId", 35),
+    ("InstrId", 35),
+    ("EndToEndId", 35),
+    ("Nm", 140),
+    ("AdrLine", 70),
+    ("Ustrd", 140),
+];
+
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+/// Amounts are written with two decimals unless they carry more, the schema allows up to five
+fn amount(value: &BigDecimal) -> Result<String, String> {
+    let (_, scale) = value.normalized().as_bigint_and_exponent();
+    if scale > 5 {
+        return Err(format!("{value} has more than five decimals"));
+    }
+    Ok(value.with_scale(scale.max(2)).to_string())
+}
+
+fn iso_date_time(date: &DateTime<Local>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+/// An IBAN is written as one, any other account number as the account's proprietary id
+fn write_account_identification(writer: &mut XmlWriter, account: &str) {
+    writer.open("Id");
+    if is_iban(account) {
+        writer.element("IBAN", account);
+    } else {
+        writer.open("Othr");
+        writer.element("Id", account);
+        writer.close();
+    }
+    writer.close();
+}
+
+/// Writes the pain.001 file for an exported batch, one payment information block debiting the
+/// batch's wallet with a transaction per exported row
+pub fn render_pain001(
+    batch: &PayoutBatch,
+    debtor: &PaymentDebtor,
+    payouts: &[ExportedPayout],
+) -> Result<String, String> {
+    if payouts.is_empty() {
+        return Err("the batch has no exported rows".to_string());
+    }
+    // a bank can only act on a file that names the accounts and banks by their real ids
+    let (Some(debtor_account), Some(debtor_bic)) = (&debtor.account_number, &debtor.bic) else {
+        return Err("the wallet has no bank account to pay the batch from".to_string());
+    };
+    if let Some(payout) = payouts.iter().find(|payout| payout.bic.is_none()) {
+        return Err(format!(
+            "{} has no BIC to route the payment to",
+            payout.bank_name
+        ));
+    }
+
+    let amounts = payouts
+        .iter()
+        .map(|payout| amount(&payout.amount))
+        .collect::<Result<Vec<String>, String>>()?;
+    let control_sum: BigDecimal = payouts.iter().map(|payout| payout.amount.clone()).sum();
+    let control_sum = amount(&control_sum)?;
+    let number_of_transactions = payouts.len().to_string();
+    // the batch's own dates keep the file the same however often it is written
+    let created_date = batch.executed_date.unwrap_or(batch.updated_at);
+    let debtor_name = truncate(&debtor.name, 140);
+
+    let mut writer = XmlWriter::new();
+    writer.open_with("Document", &[("xmlns", PAIN_001_NAMESPACE)]);
+    writer.open("CstmrCdtTrfInitn");
+
+    writer.open("GrpHdr");
+    writer.element("MsgId", &batch.reference);
+    writer.element("CreDtTm", &iso_date_time(&created_date));
+    writer.element("NbOfTxs", &number_of_transactions);
+    writer.element("CtrlSum", &control_sum);
+    writer.open("InitgPty");
+    writer.element("Nm", &debtor_name);
+    writer.close();
+    writer.close();
+
+    writer.open("PmtInf");
+    writer.element("PmtInfId", &batch.reference);
+    writer.element("PmtMtd", "TRF");
+    writer.element("BtchBookg", "true");
+    writer.element("NbOfTxs", &number_of_transactions);
+    writer.element("CtrlSum", &control_sum);
+    writer.open("ReqdExctnDt");
+    writer.element("Dt", &created_date.format("%Y-%m-%d").to_string());
+    writer.close();
+
+    writer.open("Dbtr");
+    writer.element("Nm", &debtor_name);
+    let address_lines: Vec<String> = [debtor.address.as_str(), debtor.country.as_str()]
+        .into_iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| truncate(line, 70))
+        .collect();
+    if !address_lines.is_empty() {
+        writer.open("PstlAdr");
+        for line in &address_lines {
+            writer.element("AdrLine", line);
+        }
+        writer.close();
+    }
+    writer.close();
+
+    writer.open("DbtrAcct");
+    write_account_identification(&mut writer, debtor_account);
+    writer.element("Ccy", &debtor.currency_code);
+    writer.close();
+    writer.open("DbtrAgt");
+    writer.open("FinInstnId");
+    writer.element("BICFI", debtor_bic);
+    writer.close();
+    writer.close();
+
+    for (payout, amount) in payouts.iter().zip(&amounts) {
+        writer.open("CdtTrfTxInf");
+        writer.open("PmtId");
+        writer.element("InstrId", &payout.payout_identifier.simple().to_string());
+        writer.element("EndToEndId", &payout.item_identifier.simple().to_string());
+        writer.close();
+        writer.open("Amt");
+        writer.element_with("InstdAmt", &[("Ccy", &debtor.currency_code)], amount);
+        writer.close();
+
+        writer.open("CdtrAgt");
+        writer.open("FinInstnId");
+        writer.element("BICFI", payout.bic.as_deref().unwrap_or_default());
+        writer.element("Nm", &truncate(&payout.bank_name, 140));
+        writer.close();
+        writer.close();
+
+        writer.open("Cdtr");
+        writer.element("Nm", &truncate(&payout.account_name, 140));
+        writer.close();
+        writer.open("CdtrAcct");
+        write_account_identification(&mut writer, &payout.account_number);
+        writer.close();
+
+        let narration = payout.narration.as_deref().unwrap_or_default().trim();
+        if !narration.is_empty() {
+            writer.open("RmtInf");
+            writer.element("Ustrd", &truncate(narration, 140));
+            writer.close();
+        }
+        writer.close();
+    }
+
+    let pain = writer.finish();
+    validate_pain001(&pain)?;
+    Ok(pain)
+}
+
+/// The name of an element, the last part of its path
+fn name(element: &XmlElement) -> &str {
+    element.path.rsplit('/').next().unwrap_or_default()
+}
+
+/// Checks every element the schema describes holds the children it allows, in its order and as
+/// often as it allows them, and that every other element holds text only
+fn check_structure(elements: &[XmlElement]) -> Result<(), String> {
+    if elements[0].path != "Document" {
+        return Err(format!(
+            "the root element is {}, not Document",
+            elements[0].path
+        ));
+    }
+
+    for (index, element) in elements.iter().enumerate() {
+        let relative = element
+            .path
+            .strip_prefix("Document")
+            .unwrap_or_default()
+            .trim_start_matches('/');
+        let depth = element.path.matches('/').count() + 1;
+        let children: Vec<&str> = descendants(elements, index)
+            .iter()
+            .filter(|child| child.path.matches('/').count() == depth)
+            .map(name)
+            .collect();
+
+        let Some((_, allowed)) = SCHEMA.iter().find(|(path, _)| *path == relative) else {
+            if let Some(child) = children.first() {
+                return Err(format!("{} cannot hold {child}", element.path));
+            }
+            if element.text.is_empty() {
+                return Err(format!("{} is empty", element.path));
+            }
+            continue;
+        };
+
+        if CHOICES.contains(&relative) && children.len() != 1 {
+            return Err(format!("{} must hold exactly one element", element.path));
+        }
+        let mut position = 0;
+        for child in &children {
+            let at = allowed
+                .iter()
+                .position(|(name, _, _)| name == child)
+                .ok_or_else(|| format!("{} cannot hold {child}", element.path))?;
+            if at < position {
+                return Err(format!("{} holds {child} out of order", element.path));
+            }
+            position = at;
+        }
+        for (child, min_occurs, max_occurs) in allowed.iter() {
+            let count = children.iter().filter(|name| *name == child).count();
+            if count < *min_occurs {
+                return Err(format!("{}/{child} is missing", element.path));
+            }
+            if count > *max_occurs {
+                return Err(format!(
+                    "{}/{child} appears more than {max_occurs} times",
+                    element.path
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_text_length(element: &XmlElement) -> Result<(), String> {
+    let limit = TEXT_LENGTHS
+        .iter()
+        .find(|(suffix, _)| element.path.ends_with(&format!("/{suffix}")));
+    match limit {
+        Some((_, limit)) if element.text.chars().count() > *limit => Err(format!(
+            "{} is longer than {limit} characters",
+            element.path
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// A decimal without a sign or exponent, with at most `fraction_digits` decimals and 18 digits
+fn read_decimal(element: &XmlElement, fraction_digits: usize) -> Result<BigDecimal, String> {
+    let text = element.text.as_str();
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let well_formed = !whole.is_empty()
+        && whole.chars().all(|c| c.is_ascii_digit())
+        && fraction.chars().all(|c| c.is_ascii_digit())
+        && fraction.len() <= fraction_digits
+        && whole.len() + fraction.len() <= 18;
+    if !well_formed {
+        return Err(format!("{} is not a valid amount: {text}", element.path));
+    }
+    BigDecimal::from_str(text)
+        .map_err(|err| format!("{} is not a valid amount: {err}", element.path))
+}
+
+fn read_count(element: &XmlElement) -> Result<usize, String> {
+    let text = element.text.as_str();
+    if text.is_empty() || text.len() > 15 || !text.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("{} is not a valid count: {text}", element.path));
+    }
+    text.parse()
+        .map_err(|err| format!("{} is not a valid count: {err}", element.path))
+}
+
+fn optional<'a>(elements: &'a [XmlElement], path: &str) -> Option<&'a XmlElement> {
+    elements.iter().find(|element| element.path == path)
+}
+
+/// Checks a block's declared transaction count and control sum against its transactions
+fn check_totals(
+    elements: &[XmlElement],
+    path: &str,
+    count: usize,
+    sum: &BigDecimal,
+) -> Result<(), String> {
+    if let Some(declared) = optional(elements, &format!("{path}/NbOfTxs")) {
+        let declared_count = read_count(declared)?;
+        if declared_count != count {
+            return Err(format!(
+                "{path}/NbOfTxs says {declared_count} but there are {count} transactions"
+            ));
+        }
+    }
+    if let Some(declared) = optional(elements, &format!("{path}/CtrlSum")) {
+        let declared_sum = read_decimal(declared, 17)?;
+        if declared_sum != *sum {
+            return Err(format!(
+                "{path}/CtrlSum says {declared_sum} but the transactions add up to {sum}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_pain001(pain: &str) -> Result<(), String> {
+    let elements = parse(pain)?;
+    check_structure(&elements)?;
+    if elements[0].attribute("xmlns") != Some(PAIN_001_NAMESPACE) {
+        return Err(format!(
+            "the document is not in the {PAIN_001_NAMESPACE} namespace"
+        ));
+    }
+    for element in &elements {
+        check_text_length(element)?;
+        match name(element) {
+            "BICFI" if !is_bic(&element.text) => {
+                return Err(format!("{} is not a BIC: {}", element.path, element.text));
+            }
+            "IBAN" if !is_iban(&element.text) => {
+                return Err(format!("{} is not an IBAN: {}", element.path, element.text));
+            }
+            _ => {}
+        }
+    }
+
+    let header = "Document/CstmrCdtTrfInitn/GrpHdr";
+    let created = required(&elements, &format!("{header}/CreDtTm"))?;
+    DateTime::parse_from_rfc3339(&created.text)
+        .map_err(|err| format!("{} is not a date and time: {err}", created.path))?;
+
+    let payment_path = "Document/CstmrCdtTrfInitn/PmtInf";
+    let mut end_to_end_identifiers = HashSet::new();
+    let mut total_count = 0;
+    let mut total_sum = BigDecimal::from(0);
+
+    for (index, payment) in elements.iter().enumerate() {
+        if payment.path != payment_path {
+            continue;
+        }
+        let block = descendants(&elements, index);
+
+        let method = required(block, &format!("{payment_path}/PmtMtd"))?;
+        if !["TRF", "TRA", "CHK"].contains(&method.text.as_str()) {
+            return Err(format!("{} is not a payment method", method.text));
+        }
+        let booking = optional(block, &format!("{payment_path}/BtchBookg"));
+        if booking.is_some_and(|booking| !["true", "false"].contains(&booking.text.as_str())) {
+            return Err(format!("{payment_path}/BtchBookg is not a boolean"));
+        }
+        if let Some(date) = optional(block, &format!("{payment_path}/ReqdExctnDt/Dt")) {
+            NaiveDate::parse_from_str(&date.text, "%Y-%m-%d")
+                .map_err(|err| format!("{} is not a date: {err}", date.path))?;
+        }
+        if let Some(date) = optional(block, &format!("{payment_path}/ReqdExctnDt/DtTm")) {
+            DateTime::parse_from_rfc3339(&date.text)
+                .map_err(|err| format!("{} is not a date and time: {err}", date.path))?;
+        }
+        let currency = optional(block, &format!("{payment_path}/DbtrAcct/Ccy"));
+        if currency.is_some_and(|currency| !is_currency_code(&currency.text)) {
+            return Err(format!(
+                "{payment_path}/DbtrAcct/Ccy is not an ISO 4217 currency code"
+            ));
+        }
+
+        let mut count = 0;
+        let mut sum = BigDecimal::from(0);
+        for transaction in block.iter().filter(|element| {
+            element
+                .path
+                .starts_with(&format!("{payment_path}/CdtTrfTxInf/"))
+        }) {
+            match name(transaction) {
+                "EndToEndId" if !end_to_end_identifiers.insert(transaction.text.clone()) => {
+                    return Err(format!("end to end id {} is repeated", transaction.text));
+                }
+                "InstdAmt" => {
+                    let currency = transaction
+                        .attribute("Ccy")
+                        .ok_or_else(|| format!("{} has no currency", transaction.path))?;
+                    if !is_currency_code(currency) {
+                        return Err(format!("{currency} is not an ISO 4217 currency code"));
+                    }
+                    sum += read_decimal(transaction, 5)?;
+                    count += 1;
+                }
+                _ => {}
+            }
+        }
+        let transactions = block
+            .iter()
+            .filter(|element| element.path == format!("{payment_path}/CdtTrfTxInf"))
+            .count();
+        if count != transactions {
+            return Err("every transaction must carry an instructed amount".to_string());
+        }
+
+        check_totals(block, payment_path, count, &sum)?;
+        total_count += count;
+        total_sum += sum;
+    }
+
+    check_totals(&elements, header, total_count, &total_sum)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payouts::entities::PayoutBatchStatus;
+    use uuid::Uuid;
+
+    fn batch() -> PayoutBatch {
+        PayoutBatch {
+            identifier: Uuid::new_v4(),
+            reference: "PBT-TEST".to_string(),
+            user_identifier: Uuid::new_v4(),
+            wallet_identifier: Uuid::new_v4(),
+            file_name: "contractors.csv".to_string(),
+            status: PayoutBatchStatus::Exported,
+            item_count: 2,
+            total_amount: BigDecimal::from_str("160.50").unwrap(),
+            result_file: None,
+            export_file: None,
+            executed_date: Some(Local::now()),
+            created_date: Local::now(),
+            updated_at: Local::now(),
+            items: vec![],
+        }
+    }
+
+    fn debtor() -> PaymentDebtor {
+        PaymentDebtor {
+            name: "Acme & Sons".to_string(),
+            address: "12 Marina Road".to_string(),
+            country: "United Arab Emirates".to_string(),
+            wallet_identifier: Uuid::new_v4(),
+            currency_code: "AED".to_string(),
+            account_number: Some("AE070331234567890123456".to_string()),
+            bic: Some("NBADAEAA".to_string()),
+        }
+    }
+
+    fn payout(amount: &str, narration: Option<&str>) -> ExportedPayout {
+        ExportedPayout {
+            item_identifier: Uuid::new_v4(),
+            payout_identifier: Uuid::new_v4(),
+            amount: BigDecimal::from_str(amount).unwrap(),
+            account_number: "0123456789".to_string(),
+            account_name: "Contractor".to_string(),
+            bank_name: "Marina Bank".to_string(),
+            bic: Some("EBILAEADXXX".to_string()),
+            narration: narration.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_header_carries_the_count_and_sum_of_the_transactions() {
+        let payouts = vec![payout("100.5", Some("march invoice")), payout("60", None)];
+        let pain = render_pain001(&batch(), &debtor(), &payouts).unwrap();
+        let elements = parse(&pain).unwrap();
+
+        let header = "Document/CstmrCdtTrfInitn/GrpHdr";
+        assert_eq!(
+            required(&elements, &format!("{header}/NbOfTxs"))
+                .unwrap()
+                .text,
+            "2"
+        );
+        assert_eq!(
+            required(&elements, &format!("{header}/CtrlSum"))
+                .unwrap()
+                .text,
+            "160.50"
+        );
+        assert_eq!(
+            required(&elements, &format!("{header}/InitgPty/Nm"))
+                .unwrap()
+                .text,
+            "Acme & Sons"
+        );
+        let amounts: Vec<&str> = elements
+            .iter()
+            .filter(|element| element.path.ends_with("/InstdAmt"))
+            .map(|element| element.text.as_str())
+            .collect();
+        assert_eq!(amounts, vec!["100.50", "60.00"]);
+    }
+
+    #[test]
+    fn test_files_that_break_the_schema_are_refused() {
+        let payouts = vec![payout("100.50", None), payout("60", None)];
+        let pain = render_pain001(&batch(), &debtor(), &payouts).unwrap();
+        assert!(validate_pain001(&pain).is_ok());
+
+        let wrong_sum = pain.replacen("<CtrlSum>160.50</CtrlSum>", "<CtrlSum>161.50</CtrlSum>", 1);
+        assert!(validate_pain001(&wrong_sum).is_err());
+
+        let wrong_count = pain.replacen("<NbOfTxs>2</NbOfTxs>", "<NbOfTxs>3</NbOfTxs>", 1);
+        assert!(validate_pain001(&wrong_count).is_err());
+
+        let out_of_order = pain.replacen(
+            "<PmtMtd>TRF</PmtMtd>\n      <BtchBookg>true</BtchBookg>",
+            "<BtchBookg>true</BtchBookg>\n      <PmtMtd>TRF</PmtMtd>",
+            1,
+        );
+        assert!(validate_pain001(&out_of_order).is_err());
+
+        let unknown = pain.replacen("<PmtMtd>TRF</PmtMtd>", "<PmtMtd>XYZ</PmtMtd>", 1);
+        assert!(validate_pain001(&unknown).is_err());
+
+        let unknown_element = pain.replace("DbtrAgt>", "DbtrAgnt>");
+        assert!(validate_pain001(&unknown_element).is_err());
+    }
+
+    #[test]
+    fn test_accounts_and_banks_are_named_by_their_real_ids() {
+        let payouts = vec![payout("60", None)];
+        let pain = render_pain001(&batch(), &debtor(), &payouts).unwrap();
+        let elements = parse(&pain).unwrap();
+
+        let payment = "Document/CstmrCdtTrfInitn/PmtInf";
+        assert_eq!(
+            required(&elements, &format!("{payment}/DbtrAcct/Id/IBAN"))
+                .unwrap()
+                .text,
+            "AE070331234567890123456"
+        );
+        assert_eq!(
+            required(&elements, &format!("{payment}/DbtrAgt/FinInstnId/BICFI"))
+                .unwrap()
+                .text,
+            "NBADAEAA"
+        );
+        assert_eq!(
+            required(
+                &elements,
+                &format!("{payment}/CdtTrfTxInf/CdtrAgt/FinInstnId/BICFI")
+            )
+            .unwrap()
+            .text,
+            "EBILAEADXXX"
+        );
+        assert_eq!(
+            required(
+                &elements,
+                &format!("{payment}/CdtTrfTxInf/CdtrAcct/Id/Othr/Id")
+            )
+            .unwrap()
+            .text,
+            "0123456789"
+        );
+
+        let wrong_check_digits = pain.replace("AE070331234567890123456", "AE080331234567890123456");
+        assert!(validate_pain001(&wrong_check_digits).is_err());
+        assert!(validate_pain001(&pain.replace("EBILAEADXXX", "EBIL-AE")).is_err());
+    }
+
+    #[test]
+    fn test_files_without_a_debtor_account_or_a_bic_are_refused() {
+        let without_account = PaymentDebtor {
+            account_number: None,
+            ..debtor()
+        };
+        assert!(render_pain001(&batch(), &without_account, &[payout("60", None)]).is_err());
+
+        let without_bic = ExportedPayout {
+            bic: None,
+            ..payout("60", None)
+        };
+        assert!(render_pain001(&batch(), &debtor(), &[payout("10", None), without_bic]).is_err());
+    }
+
+    #[test]
+    fn test_amounts_beyond_five_decimals_are_refused() {
+        let payouts = vec![payout("10.123456", None)];
+        assert!(render_pain001(&batch(), &debtor(), &payouts).is_err());
+        assert!(render_pain001(&batch(), &debtor(), &[]).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use finpay_utils::generate_reference;
+
+use crate::payouts::entities::Payout;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PayoutProviderError {
+    #[error("the payout provider could not be reached: {0}")]
+    Unavailable(String),
+}
+
+/// How the provider settled a payout
+#[derive(Debug, PartialEq, Eq)]
+pub enum PayoutOutcome {
+    Paid { provider_reference: String },
+    Failed { reason: String },
+}
+
+/// The rail payouts are sent over. An `Err` means the outcome is unknown and the payout is
+/// retried, a definitive rejection is reported as `PayoutOutcome::Failed`. A payout can reach
+/// the provider more than once, so its identifier has to be treated as an idempotency key
+pub trait PayoutProvider: Send + Sync {
+    fn submit(
+        &self,
+        payout: &Payout,
+    ) -> impl std::future::Future<Output = Result<PayoutOutcome, PayoutProviderError>> + Send;
+}
+
+/// Settles payouts in process, account numbers starting with `000` are rejected so failures
+/// can be exercised locally
+#[derive(Clone, Default)]
+pub struct MockPayoutProvider;
+
+impl PayoutProvider for MockPayoutProvider {
+    async fn submit(&self, payout: &Payout) -> Result<PayoutOutcome, PayoutProviderError> {
+        if payout.account_number.starts_with("000") {
+            return Ok(PayoutOutcome::Failed {
+                reason: "the beneficiary account could not be credited".to_string(),
+            });
+        }
+
+        Ok(PayoutOutcome::Paid {
+            provider_reference: generate_reference("MOCK"),
+        })
+    }
+}
@@ -0,0 +1,1664 @@
+use crate::beneficiaries::entities::Beneficiary;
+use crate::errors::RepositoryError;
+use crate::fees::adapters::FeeQuery;
+use crate::fees::repository::FeeRepository;
+use crate::holds::repository::HoldRepository;
+use crate::ledger::adapters::NewJournalEntry;
+use crate::ledger::entities::SystemAccount;
+use crate::ledger::repository::LedgerRepository;
+use crate::payouts::adapters::{CreatePayoutRequest, NewPayoutBatchItem};
+use crate::payouts::entities::{
+    ExportedPayout, PaymentDebtor, Payout, PayoutBatch, PayoutBatchItem, PayoutBatchItemStatus,
+    PayoutBatchStatus, PayoutStatus,
+};
+use crate::payouts::provider::PayoutOutcome;
+use crate::transactions::adapters::NewTransaction;
+use crate::transactions::entities::{Transaction, TransactionStatus, TransactionType};
+use crate::transactions::repository::TransactionRepository;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use crate::wallet::entities::{Wallet, WalletStatus};
+use bigdecimal::BigDecimal;
+use finpay_utils::generate_reference;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// a payout left processing this long is assumed abandoned by a worker that went away mid-submit
+const STALE_PROCESSING_IN_MINUTES: i32 = 15;
+
+#[derive(Clone)]
+pub struct PayoutRepository {
+    pool: PgPool,
+}
+
+impl PayoutRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    async fn fetch_batch_items(
+        &self,
+        batch_identifier: &Uuid,
+    ) -> Result<Vec<PayoutBatchItem>, RepositoryError> {
+        let query = r#"
+        SELECT payout_batch_items.*, payouts.status AS payout_status
+        FROM payout_batch_items
+                 LEFT JOIN payouts ON payouts.identifier = payout_batch_items.payout_identifier
+        WHERE payout_batch_items.batch_identifier = $1
+        ORDER BY payout_batch_items.row_number
+        "#;
+        sqlx::query_as::<_, PayoutBatchItem>(query)
+            .bind(batch_identifier)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    /// Finds the user's beneficiary for a batch row, saving one the first time the account is paid.
+    /// A saved beneficiary is only reused when the row names the same account holder
+    async fn beneficiary_for_item(
+        connection: &mut PgConnection,
+        item: &PayoutBatchItem,
+        user_identifier: &Uuid,
+    ) -> Result<Uuid, RepositoryError> {
+        let bank_identifier = item
+            .bank_identifier
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        let query = r#"
+        INSERT INTO beneficiaries (identifier, user_identifier, bank_identifier, account_number, account_name)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_identifier, bank_identifier, account_number) DO NOTHING
+        "#;
+        sqlx::query(query)
+            .bind(Uuid::new_v4())
+            .bind(user_identifier)
+            .bind(bank_identifier)
+            .bind(&item.account_number)
+            .bind(&item.account_name)
+            .execute(&mut *connection)
+            .await?;
+
+        let beneficiary = sqlx::query_as::<_, Beneficiary>(
+            r#"SELECT * FROM beneficiaries WHERE user_identifier = $1 AND bank_identifier = $2 AND account_number = $3"#,
+        )
+        .bind(user_identifier)
+        .bind(bank_identifier)
+        .bind(&item.account_number)
+        .fetch_one(&mut *connection)
+        .await?;
+
+        if !beneficiary
+            .account_name
+            .trim()
+            .eq_ignore_ascii_case(item.account_name.trim())
+        {
+            return Err(RepositoryError::OperationFailed(format!(
+                "the account is saved for {}, not {}",
+                beneficiary.account_name, item.account_name
+            )));
+        }
+        Ok(beneficiary.identifier)
+    }
+
+    /// Takes a payout's amount and fee out of the wallet and saves the payout with `status`, a
+    /// queued payout is sent by the payout processor while an exported one leaves in a file. A
+    /// payout that cannot be paid is recorded as a failed transaction before the error is
+    /// returned, it is up to the caller to commit it
+    async fn book(
+        tx: &mut PgConnection,
+        payload: &CreatePayoutRequest,
+        user_identifier: &Uuid,
+        status: PayoutStatus,
+    ) -> Result<Payout, RepositoryError> {
+        let wallet = sqlx::query_as::<_, Wallet>(
+            r#"SELECT * FROM wallets WHERE identifier = $1 AND user_identifier = $2 FOR UPDATE"#,
+        )
+        .bind(payload.wallet_identifier)
+        .bind(user_identifier)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+
+        let beneficiary = sqlx::query_as::<_, Beneficiary>(
+            r#"SELECT * FROM beneficiaries WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(payload.beneficiary_identifier)
+        .bind(user_identifier)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+        if beneficiary.blocked_date.is_some() {
+            return Err(RepositoryError::BlockedBeneficiary);
+        }
+
+        let bank_country_identifier: Uuid =
+            sqlx::query_scalar(r#"SELECT country_identifier FROM banks WHERE identifier = $1"#)
+                .bind(beneficiary.bank_identifier)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        let held_amount = HoldRepository::held_amount(&mut *tx, &wallet.identifier).await?;
+        let fee = FeeRepository::assess(
+            &mut *tx,
+            &FeeQuery {
+                transaction_type: TransactionType::Withdrawal,
+                currency_identifier: wallet.currency_identifier,
+                user_identifier: *user_identifier,
+                amount: payload.amount.clone(),
+            },
+        )
+        .await?;
+
+        let failure = if wallet.status != WalletStatus::Active {
+            Some(RepositoryError::InactiveWallet)
+        } else if bank_country_identifier != wallet.currency_identifier {
+            Some(RepositoryError::CurrencyMismatch)
+        } else if &wallet.balance - held_amount < &payload.amount + &fee.fee_amount {
+            Some(RepositoryError::InsufficientFunds)
+        } else {
+            None
+        };
+
+        let mut transaction = TransactionRepository::open(
+            &mut *tx,
+            &NewTransaction {
+                transaction_type: TransactionType::Withdrawal,
+                source_wallet_identifier: Some(wallet.identifier),
+                destination_wallet_identifier: None,
+                amount: payload.amount.clone(),
+                currency_identifier: wallet.currency_identifier,
+                description: payload.narration.clone(),
+                initiated_by: Some(*user_identifier),
+                external_reference: None,
+            },
+        )
+        .await?;
+
+        if let Some(err) = failure {
+            TransactionRepository::transition(
+                &mut *tx,
+                &mut transaction,
+                TransactionStatus::Failed,
+                Some(&err.to_string()),
+            )
+            .await?;
+            return Err(err);
+        }
+
+        // the funds leave the wallet straight away and wait in transit until the bank pays out,
+        // so they cannot be spent twice while the payout is processing
+        let wallet_account = LedgerRepository::wallet_account(&mut *tx, &wallet.identifier).await?;
+        let in_transit_account = LedgerRepository::system_account(
+            &mut *tx,
+            SystemAccount::PayoutsInTransit,
+            &wallet.currency_identifier,
+        )
+        .await?;
+        let entry = NewJournalEntry::new(&format!("payout {}", transaction.reference))
+            .transaction(&transaction.identifier)
+            .debit(&wallet_account, &payload.amount)
+            .credit(&in_transit_account, &payload.amount);
+        LedgerRepository::record_entry(&mut *tx, &entry).await?;
+        FeeRepository::charge(&mut *tx, &transaction, &wallet.identifier, &fee).await?;
+
+        let query = r#"
+        INSERT INTO payouts (
+            identifier,
+            transaction_identifier,
+            user_identifier,
+            wallet_identifier,
+            beneficiary_identifier,
+            bank_identifier,
+            account_number,
+            account_name,
+            amount,
+            currency_identifier,
+            status
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING *
+        "#;
+        let payout = sqlx::query_as::<_, Payout>(query)
+            .bind(Uuid::new_v4())
+            .bind(transaction.identifier)
+            .bind(user_identifier)
+            .bind(wallet.identifier)
+            .bind(beneficiary.identifier)
+            .bind(beneficiary.bank_identifier)
+            .bind(&beneficiary.account_number)
+            .bind(&beneficiary.account_name)
+            .bind(&payload.amount)
+            .bind(wallet.currency_identifier)
+            .bind(status)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        Ok(payout)
+    }
+
+    /// the refusals `book` records as a failed transaction, they are committed like a payout would
+    /// be so that the transaction history is complete
+    fn records_failure(err: &RepositoryError) -> bool {
+        matches!(
+            err,
+            RepositoryError::InactiveWallet
+                | RepositoryError::CurrencyMismatch
+                | RepositoryError::InsufficientFunds
+        )
+    }
+
+    /// Fails the rows still waiting to be paid to a bank without a BIC, a pain.001 file has no
+    /// way to route them
+    async fn fail_unroutable_items(
+        connection: &mut PgConnection,
+        batch_identifier: &Uuid,
+    ) -> Result<(), RepositoryError> {
+        let query = r#"
+        UPDATE payout_batch_items
+        SET status = $1,
+            errors = array_append(errors, $2)
+        FROM banks
+        WHERE banks.identifier = payout_batch_items.bank_identifier
+          AND banks.bic IS NULL
+          AND payout_batch_items.batch_identifier = $3
+          AND payout_batch_items.status = $4
+        "#;
+        sqlx::query(query)
+            .bind(PayoutBatchItemStatus::Failed)
+            .bind("the bank has no BIC to route the payment to")
+            .bind(batch_identifier)
+            .bind(PayoutBatchItemStatus::Valid)
+            .execute(connection)
+            .await?;
+        Ok(())
+    }
+
+    /// Books a payout for every valid row of a validated batch. An executed batch queues them for
+    /// the payout processor, an exported one keeps them out of the queue for the bank file. Each
+    /// row is booked together with its outcome, so a run that stops half way is picked up again
+    /// from the rows that are still valid
+    async fn run_batch(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        batch_status: PayoutBatchStatus,
+    ) -> Result<PayoutBatch, RepositoryError> {
+        let (payout_status, item_status) = match batch_status {
+            PayoutBatchStatus::Exported => {
+                (PayoutStatus::Exported, PayoutBatchItemStatus::Exported)
+            }
+            _ => (PayoutStatus::Queued, PayoutBatchItemStatus::Queued),
+        };
+
+        // claiming the batch up front means a second commit or export cannot pay the rows out twice,
+        // a batch already claimed the same way is only taken again while rows are left to book
+        let query = r#"
+        UPDATE payout_batches
+        SET status        = $1,
+            executed_date = COALESCE(executed_date, NOW())
+        WHERE identifier = $2
+          AND user_identifier = $3
+          AND (status = $4
+            OR (status = $1 AND EXISTS (SELECT 1
+                                        FROM payout_batch_items
+                                        WHERE payout_batch_items.batch_identifier = payout_batches.identifier
+                                          AND payout_batch_items.status = $5)))
+        RETURNING *
+        "#;
+        let mut tx = self.pool.begin().await?;
+        let batch = sqlx::query_as::<_, PayoutBatch>(query)
+            .bind(batch_status)
+            .bind(identifier)
+            .bind(user_identifier)
+            .bind(PayoutBatchStatus::Validated)
+            .bind(PayoutBatchItemStatus::Valid)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(mut batch) = batch else {
+            tx.rollback().await?;
+            self.fetch_batch(identifier, user_identifier)
+                .await?
+                .ok_or(RepositoryError::RecordNotFound)?;
+            return Err(RepositoryError::OperationFailed(
+                "only a validated batch that has not been executed or exported can be paid out"
+                    .into(),
+            ));
+        };
+        if batch_status == PayoutBatchStatus::Exported {
+            Self::fail_unroutable_items(&mut tx, &batch.identifier).await?;
+        }
+        tx.commit().await?;
+
+        let items = self.fetch_batch_items(&batch.identifier).await?;
+        for item in items
+            .iter()
+            .filter(|item| item.status == PayoutBatchItemStatus::Valid)
+        {
+            let mut tx = self.pool.begin().await?;
+
+            // another run may have booked the row since it was read
+            let item = sqlx::query_as::<_, PayoutBatchItem>(
+                r#"SELECT *, NULL::payout_status_enum AS payout_status FROM payout_batch_items WHERE identifier = $1 AND status = $2 FOR UPDATE"#,
+            )
+            .bind(item.identifier)
+            .bind(PayoutBatchItemStatus::Valid)
+            .fetch_optional(&mut *tx)
+            .await?;
+            let Some(item) = item else {
+                continue;
+            };
+
+            let booked = match Self::beneficiary_for_item(&mut tx, &item, user_identifier).await {
+                Ok(beneficiary_identifier) => {
+                    let request = CreatePayoutRequest {
+                        wallet_identifier: batch.wallet_identifier,
+                        beneficiary_identifier,
+                        amount: item.amount.clone().unwrap_or_default(),
+                        narration: item.narration.clone(),
+                    };
+                    Self::book(&mut tx, &request, user_identifier, payout_status).await
+                }
+                Err(err) => Err(err),
+            };
+            let (status, payout_identifier, errors) = match booked {
+                Ok(payout) => (item_status, Some(payout.identifier), vec![]),
+                Err(err @ RepositoryError::SqlxError(_)) => return Err(err),
+                Err(err) => {
+                    if !Self::records_failure(&err) {
+                        // nothing of the attempt is worth keeping, only the row's outcome
+                        tx.rollback().await?;
+                        tx = self.pool.begin().await?;
+                    }
+                    (PayoutBatchItemStatus::Failed, None, vec![err.to_string()])
+                }
+            };
+            sqlx::query(
+                r#"UPDATE payout_batch_items SET status = $1, payout_identifier = $2, errors = $3 WHERE identifier = $4"#,
+            )
+            .bind(status)
+            .bind(payout_identifier)
+            .bind(errors)
+            .bind(item.identifier)
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+        }
+
+        batch.items = self.fetch_batch_items(&batch.identifier).await?;
+        Ok(batch)
+    }
+}
+
+pub trait PayoutRepositoryExt {
+    fn queue(
+        &self,
+        payload: &CreatePayoutRequest,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Payout, RepositoryError>> + Send;
+
+    fn fetch_payout(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<Payout>, RepositoryError>> + Send;
+
+    fn fetch_payouts(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Payout>, RepositoryError>> + Send;
+
+    /// Moves the oldest queued payout to processing, concurrent workers never claim the same one.
+    /// A payout stuck in processing is claimed again, the provider sees it a second time the
+    /// same way it sees a retry
+    fn claim_next(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Option<Payout>, RepositoryError>> + Send;
+
+    /// Puts a processing payout back in the queue when its outcome could not be determined
+    fn requeue(
+        &self,
+        payout: &Payout,
+    ) -> impl std::future::Future<Output = Result<(), RepositoryError>> + Send;
+
+    fn settle(
+        &self,
+        payout: &Payout,
+        outcome: &PayoutOutcome,
+    ) -> impl std::future::Future<Output = Result<Payout, RepositoryError>> + Send;
+
+    /// Settles a payout that left in a pain.001 file once the bank has said what it did with it
+    fn confirm_export(
+        &self,
+        identifier: &Uuid,
+        outcome: &PayoutOutcome,
+    ) -> impl std::future::Future<Output = Result<Payout, RepositoryError>> + Send;
+
+    /// Saves an uploaded batch after checking each row's bank exists and pays out in the wallet's
+    /// currency. The batch is rejected if any row is invalid
+    fn create_batch(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+        file_name: &str,
+        items: Vec<NewPayoutBatchItem>,
+    ) -> impl std::future::Future<Output = Result<PayoutBatch, RepositoryError>> + Send;
+
+    fn fetch_batch(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<PayoutBatch>, RepositoryError>> + Send;
+
+    fn fetch_batches(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<PayoutBatch>, RepositoryError>> + Send;
+
+    /// Marks a row that is still waiting to be paid as failed, so that no run books it
+    fn fail_batch_item(
+        &self,
+        identifier: &Uuid,
+        error: &str,
+    ) -> impl std::future::Future<Output = Result<(), RepositoryError>> + Send;
+
+    /// Queues a payout for every row of a validated batch. Each row is queued on its own, a row
+    /// that cannot be paid is marked failed without stopping the rest
+    fn execute_batch(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<PayoutBatch, RepositoryError>> + Send;
+
+    fn set_batch_result_file(
+        &self,
+        identifier: &Uuid,
+        result_file: &str,
+    ) -> impl std::future::Future<Output = Result<(), RepositoryError>> + Send;
+
+    /// Books a payout for every row of a validated batch to go out in a pain.001 file. The payouts
+    /// are never queued, so the bank file is the only way the rows are paid. Rows to a bank
+    /// without a BIC are failed in the same transaction that claims the batch
+    fn export_batch(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<PayoutBatch, RepositoryError>> + Send;
+
+    fn fetch_payment_debtor(
+        &self,
+        batch: &PayoutBatch,
+    ) -> impl std::future::Future<Output = Result<PaymentDebtor, RepositoryError>> + Send;
+
+    /// The rows of a batch an export would book, in the order they were uploaded. Nothing is
+    /// booked for them yet, so each row stands in for its own payout
+    fn fetch_exportable_payouts(
+        &self,
+        batch: &PayoutBatch,
+    ) -> impl std::future::Future<Output = Result<Vec<ExportedPayout>, RepositoryError>> + Send;
+
+    /// The exported rows of a batch in the order they were uploaded
+    fn fetch_exported_payouts(
+        &self,
+        batch: &PayoutBatch,
+    ) -> impl std::future::Future<Output = Result<Vec<ExportedPayout>, RepositoryError>> + Send;
+
+    fn set_batch_export_file(
+        &self,
+        identifier: &Uuid,
+        export_file: &str,
+    ) -> impl std::future::Future<Output = Result<(), RepositoryError>> + Send;
+}
+
+impl PayoutRepositoryExt for PayoutRepository {
+    async fn queue(
+        &self,
+        payload: &CreatePayoutRequest,
+        user_identifier: &Uuid,
+    ) -> Result<Payout, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        match Self::book(&mut tx, payload, user_identifier, PayoutStatus::Queued).await {
+            Ok(payout) => {
+                tx.commit().await?;
+                Ok(payout)
+            }
+            // failed attempts are committed too, so that the transaction history is complete
+            Err(err) if Self::records_failure(&err) => {
+                tx.commit().await?;
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn fetch_payout(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<Payout>, RepositoryError> {
+        sqlx::query_as::<_, Payout>(
+            r#"SELECT * FROM payouts WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_payouts(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Payout>, RepositoryError> {
+        let total_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(identifier) FROM payouts WHERE user_identifier = $1")
+                .bind(user_identifier)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let payouts = sqlx::query_as::<_, Payout>(
+            r#"SELECT * FROM payouts WHERE user_identifier = $1 ORDER BY created_date DESC LIMIT $2 OFFSET $3"#,
+        )
+        .bind(user_identifier)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            payouts,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn claim_next(&self) -> Result<Option<Payout>, RepositoryError> {
+        let query = r#"
+        UPDATE payouts
+        SET status = $1
+        WHERE identifier = (SELECT identifier
+                            FROM payouts
+                            WHERE status = $2
+                               OR (status = $1 AND updated_at < NOW() - make_interval(mins => $3))
+                            ORDER BY created_date
+                            LIMIT 1 FOR UPDATE SKIP LOCKED)
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, Payout>(query)
+            .bind(PayoutStatus::Processing)
+            .bind(PayoutStatus::Queued)
+            .bind(STALE_PROCESSING_IN_MINUTES)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn requeue(&self, payout: &Payout) -> Result<(), RepositoryError> {
+        sqlx::query("UPDATE payouts SET status = $1 WHERE identifier = $2 AND status = $3")
+            .bind(PayoutStatus::Queued)
+            .bind(payout.identifier)
+            .bind(PayoutStatus::Processing)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn settle(
+        &self,
+        payout: &Payout,
+        outcome: &PayoutOutcome,
+    ) -> Result<Payout, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let (status, provider_reference, failure_reason) = match outcome {
+            PayoutOutcome::Paid { provider_reference } => {
+                (PayoutStatus::Paid, Some(provider_reference.as_str()), None)
+            }
+            PayoutOutcome::Failed { reason } => (PayoutStatus::Failed, None, Some(reason.as_str())),
+        };
+
+        let query = r#"
+        UPDATE payouts
+        SET status             = $1,
+            provider_reference = $2,
+            failure_reason     = $3
+        WHERE identifier = $4
+          AND status IN ($5, $6)
+        RETURNING *
+        "#;
+        let settled = sqlx::query_as::<_, Payout>(query)
+            .bind(status)
+            .bind(provider_reference)
+            .bind(failure_reason)
+            .bind(payout.identifier)
+            .bind(PayoutStatus::Processing)
+            .bind(PayoutStatus::Exported)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(RepositoryError::OperationFailed(
+                "the payout is no longer processing".into(),
+            ))?;
+
+        let mut transaction = sqlx::query_as::<_, Transaction>(
+            r#"SELECT * FROM transactions WHERE identifier = $1 FOR UPDATE"#,
+        )
+        .bind(settled.transaction_identifier)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let in_transit_account = LedgerRepository::system_account(
+            &mut tx,
+            SystemAccount::PayoutsInTransit,
+            &settled.currency_identifier,
+        )
+        .await?;
+
+        match outcome {
+            PayoutOutcome::Paid { .. } => {
+                let settlement_account = LedgerRepository::system_account(
+                    &mut tx,
+                    SystemAccount::BankSettlement,
+                    &settled.currency_identifier,
+                )
+                .await?;
+                let entry = NewJournalEntry::new(&format!("payout {} paid", transaction.reference))
+                    .transaction(&transaction.identifier)
+                    .debit(&in_transit_account, &settled.amount)
+                    .credit(&settlement_account, &settled.amount);
+                LedgerRepository::record_entry(&mut tx, &entry).await?;
+
+                TransactionRepository::transition(
+                    &mut tx,
+                    &mut transaction,
+                    TransactionStatus::Completed,
+                    None,
+                )
+                .await?;
+            }
+            PayoutOutcome::Failed { reason } => {
+                // hand the held funds and the fee back to the wallet
+                let wallet_account =
+                    LedgerRepository::wallet_account(&mut tx, &settled.wallet_identifier).await?;
+                let entry =
+                    NewJournalEntry::new(&format!("payout {} returned", transaction.reference))
+                        .transaction(&transaction.identifier)
+                        .debit(&in_transit_account, &settled.amount)
+                        .credit(&wallet_account, &settled.amount);
+                LedgerRepository::record_entry(&mut tx, &entry).await?;
+                FeeRepository::refund(&mut tx, &transaction).await?;
+
+                TransactionRepository::transition(
+                    &mut tx,
+                    &mut transaction,
+                    TransactionStatus::Failed,
+                    Some(reason),
+                )
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(settled)
+    }
+
+    async fn confirm_export(
+        &self,
+        identifier: &Uuid,
+        outcome: &PayoutOutcome,
+    ) -> Result<Payout, RepositoryError> {
+        let payout = sqlx::query_as::<_, Payout>(r#"SELECT * FROM payouts WHERE identifier = $1"#)
+            .bind(identifier)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        if payout.status != PayoutStatus::Exported {
+            return Err(RepositoryError::OperationFailed(
+                "only a payout waiting on the bank can be confirmed".into(),
+            ));
+        }
+
+        self.settle(&payout, outcome).await
+    }
+
+    async fn create_batch(
+        &self,
+        wallet_identifier: &Uuid,
+        user_identifier: &Uuid,
+        file_name: &str,
+        mut items: Vec<NewPayoutBatchItem>,
+    ) -> Result<PayoutBatch, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let wallet = sqlx::query_as::<_, Wallet>(
+            r#"SELECT * FROM wallets WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(wallet_identifier)
+        .bind(user_identifier)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+
+        let bank_identifiers: Vec<Uuid> = items
+            .iter()
+            .filter_map(|item| item.bank_identifier)
+            .collect();
+        let banks: Vec<(Uuid, Uuid)> = sqlx::query_as(
+            r#"SELECT identifier, country_identifier FROM banks WHERE identifier = ANY($1)"#,
+        )
+        .bind(bank_identifiers)
+        .fetch_all(&mut *tx)
+        .await?;
+        for item in items.iter_mut() {
+            let Some(bank_identifier) = item.bank_identifier else {
+                continue;
+            };
+            match banks
+                .iter()
+                .find(|(identifier, _)| *identifier == bank_identifier)
+            {
+                None => item.errors.push("bank does not exist".to_string()),
+                Some((_, country_identifier))
+                    if *country_identifier != wallet.currency_identifier =>
+                {
+                    item.errors
+                        .push("bank does not pay out in the wallet's currency".to_string())
+                }
+                Some(_) => {}
+            }
+        }
+
+        // the rows are stored with six decimals, more than the currency has would be rounded away
+        let currency_exponent: i16 =
+            sqlx::query_scalar(r#"SELECT currency_exponent FROM countries WHERE identifier = $1"#)
+                .bind(wallet.currency_identifier)
+                .fetch_one(&mut *tx)
+                .await?;
+        for item in items.iter_mut() {
+            let Some(amount) = &item.amount else {
+                continue;
+            };
+            let (_, scale) = amount.normalized().as_bigint_and_exponent();
+            if scale > i64::from(currency_exponent) {
+                item.errors.push(format!(
+                    "amount cannot have more than {currency_exponent} decimal places"
+                ));
+            }
+        }
+
+        let status = if items.iter().all(NewPayoutBatchItem::is_valid) {
+            PayoutBatchStatus::Validated
+        } else {
+            PayoutBatchStatus::Rejected
+        };
+        let total_amount: BigDecimal = items
+            .iter()
+            .filter(|item| item.is_valid())
+            .filter_map(|item| item.amount.clone())
+            .sum();
+
+        let query = r#"
+        INSERT INTO payout_batches (identifier, reference, user_identifier, wallet_identifier, file_name,
+                                    status, item_count, total_amount)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#;
+        let mut batch = sqlx::query_as::<_, PayoutBatch>(query)
+            .bind(Uuid::new_v4())
+            .bind(generate_reference("PBT"))
+            .bind(user_identifier)
+            .bind(wallet.identifier)
+            .bind(file_name)
+            .bind(status)
+            .bind(items.len() as i32)
+            .bind(&total_amount)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let query = r#"
+        INSERT INTO payout_batch_items (identifier, batch_identifier, row_number, bank_identifier,
+                                        account_number, account_name, amount, narration, status, errors)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#;
+        for item in &items {
+            let item_status = if item.is_valid() {
+                PayoutBatchItemStatus::Valid
+            } else {
+                PayoutBatchItemStatus::Invalid
+            };
+            sqlx::query(query)
+                .bind(Uuid::new_v4())
+                .bind(batch.identifier)
+                .bind(item.row_number)
+                .bind(item.bank_identifier)
+                .bind(&item.account_number)
+                .bind(&item.account_name)
+                .bind(&item.amount)
+                .bind(&item.narration)
+                .bind(item_status)
+                .bind(&item.errors)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        batch.items = self.fetch_batch_items(&batch.identifier).await?;
+        Ok(batch)
+    }
+
+    async fn fetch_batch(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<PayoutBatch>, RepositoryError> {
+        let batch = sqlx::query_as::<_, PayoutBatch>(
+            r#"SELECT * FROM payout_batches WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(mut batch) = batch else {
+            return Ok(None);
+        };
+        batch.items = self.fetch_batch_items(&batch.identifier).await?;
+        Ok(Some(batch))
+    }
+
+    async fn fetch_batches(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<PayoutBatch>, RepositoryError> {
+        let total_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(identifier) FROM payout_batches WHERE user_identifier = $1",
+        )
+        .bind(user_identifier)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        // the rows are left out of the listing, they come with the batch itself
+        let batches = sqlx::query_as::<_, PayoutBatch>(
+            r#"SELECT * FROM payout_batches WHERE user_identifier = $1 ORDER BY created_date DESC LIMIT $2 OFFSET $3"#,
+        )
+        .bind(user_identifier)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            batches,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn fail_batch_item(&self, identifier: &Uuid, error: &str) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"UPDATE payout_batch_items SET status = $1, errors = array_append(errors, $2) WHERE identifier = $3 AND status = $4"#,
+        )
+        .bind(PayoutBatchItemStatus::Failed)
+        .bind(error)
+        .bind(identifier)
+        .bind(PayoutBatchItemStatus::Valid)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn execute_batch(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<PayoutBatch, RepositoryError> {
+        self.run_batch(identifier, user_identifier, PayoutBatchStatus::Executed)
+            .await
+    }
+
+    async fn export_batch(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<PayoutBatch, RepositoryError> {
+        self.run_batch(identifier, user_identifier, PayoutBatchStatus::Exported)
+            .await
+    }
+
+    async fn set_batch_result_file(
+        &self,
+        identifier: &Uuid,
+        result_file: &str,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query("UPDATE payout_batches SET result_file = $1 WHERE identifier = $2")
+            .bind(result_file)
+            .bind(identifier)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn fetch_payment_debtor(
+        &self,
+        batch: &PayoutBatch,
+    ) -> Result<PaymentDebtor, RepositoryError> {
+        let query = r#"
+        SELECT TRIM(CONCAT_WS(' ', users.first_name, users.last_name)) AS name,
+               COALESCE(users.address, '')                            AS address,
+               COALESCE(users.country, '')                            AS country,
+               wallets.identifier                                     AS wallet_identifier,
+               countries.currency_code,
+               virtual_accounts.account_number,
+               banks.bic
+        FROM wallets
+                 JOIN users ON users.identifier = wallets.user_identifier
+                 JOIN countries ON countries.identifier = wallets.currency_identifier
+                 LEFT JOIN virtual_accounts ON virtual_accounts.wallet_identifier = wallets.identifier
+                 LEFT JOIN banks ON banks.identifier = virtual_accounts.bank_identifier
+        WHERE wallets.identifier = $1
+        "#;
+        sqlx::query_as::<_, PaymentDebtor>(query)
+            .bind(batch.wallet_identifier)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)
+    }
+
+    async fn fetch_exportable_payouts(
+        &self,
+        batch: &PayoutBatch,
+    ) -> Result<Vec<ExportedPayout>, RepositoryError> {
+        let query = r#"
+        SELECT payout_batch_items.identifier AS item_identifier,
+               payout_batch_items.identifier AS payout_identifier,
+               payout_batch_items.amount,
+               payout_batch_items.account_number,
+               payout_batch_items.account_name,
+               banks.bank_name,
+               banks.bic,
+               payout_batch_items.narration
+        FROM payout_batch_items
+                 JOIN banks ON banks.identifier = payout_batch_items.bank_identifier
+        WHERE payout_batch_items.batch_identifier = $1
+          AND payout_batch_items.status = $2
+          AND banks.bic IS NOT NULL
+        ORDER BY payout_batch_items.row_number
+        "#;
+        sqlx::query_as::<_, ExportedPayout>(query)
+            .bind(batch.identifier)
+            .bind(PayoutBatchItemStatus::Valid)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_exported_payouts(
+        &self,
+        batch: &PayoutBatch,
+    ) -> Result<Vec<ExportedPayout>, RepositoryError> {
+        let query = r#"
+        SELECT payout_batch_items.identifier AS item_identifier,
+               payouts.identifier            AS payout_identifier,
+               payouts.amount,
+               payouts.account_number,
+               payouts.account_name,
+               banks.bank_name,
+               banks.bic,
+               payout_batch_items.narration
+        FROM payout_batch_items
+                 JOIN payouts ON payouts.identifier = payout_batch_items.payout_identifier
+                 JOIN banks ON banks.identifier = payouts.bank_identifier
+        WHERE payout_batch_items.batch_identifier = $1
+          AND payout_batch_items.status = $2
+        ORDER BY payout_batch_items.row_number
+        "#;
+        sqlx::query_as::<_, ExportedPayout>(query)
+            .bind(batch.identifier)
+            .bind(PayoutBatchItemStatus::Exported)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn set_batch_export_file(
+        &self,
+        identifier: &Uuid,
+        export_file: &str,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query("UPDATE payout_batches SET export_file = $1 WHERE identifier = $2")
+            .bind(export_file)
+            .bind(identifier)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::adapters::AccountCreationParams;
+    use crate::accounts::repository::{AccountRepository, AccountRepositoryExt};
+    use crate::beneficiaries::adapters::CreateBeneficiaryRequest;
+    use crate::beneficiaries::repository::{BeneficiaryRepository, BeneficiaryRepositoryExt};
+    use crate::fees::adapters::CreateFeeScheduleRequest;
+    use crate::fees::entities::FeeMethod;
+    use crate::fees::repository::FeeRepositoryExt;
+    use crate::payouts::adapters::PayoutBatchRow;
+    use crate::payouts::pain::render_pain001;
+    use crate::shared::fixtures::{UAE_DIRHAM, create_user, create_wallet, fund};
+    use crate::wallet::adapters::DeleteWalletRequest;
+    use crate::wallet::repository::{WalletRepository, WalletRepositoryExt};
+    use bigdecimal::BigDecimal;
+    use std::str::FromStr;
+
+    struct Fixture {
+        user_identifier: Uuid,
+        wallet_identifier: Uuid,
+        beneficiary_identifier: Uuid,
+    }
+
+    async fn setup(pool: &PgPool, balance: &str, account_number: &str) -> Fixture {
+        let user_identifier = create_user(pool).await;
+        let wallet_identifier = create_wallet(pool, &user_identifier).await;
+        fund(pool, &wallet_identifier, balance).await;
+
+        let bank_identifier: Uuid = sqlx::query_scalar(
+            "SELECT identifier FROM banks WHERE country_identifier = $1 LIMIT 1",
+        )
+        .bind(Uuid::from_str(UAE_DIRHAM).unwrap())
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let beneficiary_identifier = BeneficiaryRepository::new(pool)
+            .create(
+                &CreateBeneficiaryRequest {
+                    bank_identifier,
+                    account_number: account_number.to_string(),
+                    account_name: "Test Beneficiary".to_string(),
+                },
+                &user_identifier,
+            )
+            .await
+            .expect("failed to create beneficiary")
+            .identifier;
+
+        Fixture {
+            user_identifier,
+            wallet_identifier,
+            beneficiary_identifier,
+        }
+    }
+
+    async fn wallet_balance(pool: &PgPool, fixture: &Fixture) -> BigDecimal {
+        WalletRepository::new(pool.clone())
+            .fetch_wallet(&fixture.wallet_identifier, &fixture.user_identifier)
+            .await
+            .unwrap()
+            .unwrap()
+            .wallet
+            .balance
+    }
+
+    fn payout_request(fixture: &Fixture, amount: i64) -> CreatePayoutRequest {
+        CreatePayoutRequest {
+            wallet_identifier: fixture.wallet_identifier,
+            beneficiary_identifier: fixture.beneficiary_identifier,
+            amount: BigDecimal::from(amount),
+            narration: None,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_paid_payout_keeps_funds_out_of_wallet(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0123456789").await;
+
+        let payout = repository
+            .queue(&payout_request(&fixture, 30), &fixture.user_identifier)
+            .await
+            .expect("failed to queue payout");
+        assert_eq!(payout.status, PayoutStatus::Queued);
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(70));
+
+        let claimed = repository.claim_next().await.unwrap().unwrap();
+        assert_eq!(claimed.status, PayoutStatus::Processing);
+
+        let outcome = PayoutOutcome::Paid {
+            provider_reference: "REF".to_string(),
+        };
+        let settled = repository.settle(&claimed, &outcome).await.unwrap();
+
+        assert_eq!(settled.status, PayoutStatus::Paid);
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(70));
+    }
+
+    #[sqlx::test]
+    async fn test_payout_abandoned_mid_submit_is_claimed_again(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0123456789").await;
+        repository
+            .queue(&payout_request(&fixture, 30), &fixture.user_identifier)
+            .await
+            .expect("failed to queue payout");
+
+        let claimed = repository.claim_next().await.unwrap().unwrap();
+        assert!(repository.claim_next().await.unwrap().is_none());
+
+        // the worker went away without settling or requeueing the payout
+        sqlx::query("ALTER TABLE payouts DISABLE TRIGGER update_payouts_updated_at")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE payouts SET updated_at = NOW() - make_interval(mins => $1) WHERE identifier = $2")
+            .bind(STALE_PROCESSING_IN_MINUTES + 1)
+            .bind(claimed.identifier)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("ALTER TABLE payouts ENABLE TRIGGER update_payouts_updated_at")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let reclaimed = repository.claim_next().await.unwrap().unwrap();
+        assert_eq!(reclaimed.identifier, claimed.identifier);
+        assert_eq!(reclaimed.status, PayoutStatus::Processing);
+        assert!(repository.claim_next().await.unwrap().is_none());
+    }
+
+    #[sqlx::test]
+    async fn test_failed_payout_releases_held_funds(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0003456789").await;
+
+        repository
+            .queue(&payout_request(&fixture, 30), &fixture.user_identifier)
+            .await
+            .expect("failed to queue payout");
+        let claimed = repository.claim_next().await.unwrap().unwrap();
+
+        let outcome = PayoutOutcome::Failed {
+            reason: "rejected".to_string(),
+        };
+        let settled = repository.settle(&claimed, &outcome).await.unwrap();
+
+        assert_eq!(settled.status, PayoutStatus::Failed);
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(100));
+
+        let transaction_status: TransactionStatus =
+            sqlx::query_scalar("SELECT status FROM transactions WHERE identifier = $1")
+                .bind(settled.transaction_identifier)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(transaction_status, TransactionStatus::Failed);
+    }
+
+    #[sqlx::test]
+    async fn test_withdrawal_fee_is_charged_and_refunded_on_failure(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0012345678").await;
+        FeeRepository::new(&pool)
+            .create_schedule(&CreateFeeScheduleRequest {
+                name: "withdrawals".to_string(),
+                transaction_type: TransactionType::Withdrawal,
+                currency_identifier: None,
+                account_type: None,
+                minimum_amount: None,
+                maximum_amount: None,
+                fee_method: FeeMethod::Flat,
+                flat_amount: Some(BigDecimal::from(2)),
+                percentage_basis_points: None,
+                minimum_fee: None,
+                maximum_fee: None,
+                tiers: vec![],
+                effective_from: None,
+                effective_to: None,
+            })
+            .await
+            .unwrap();
+
+        repository
+            .queue(&payout_request(&fixture, 30), &fixture.user_identifier)
+            .await
+            .expect("failed to queue payout");
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(68));
+
+        let claimed = repository.claim_next().await.unwrap().unwrap();
+        let outcome = PayoutOutcome::Failed {
+            reason: "rejected".to_string(),
+        };
+        repository.settle(&claimed, &outcome).await.unwrap();
+
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(100));
+    }
+
+    #[sqlx::test]
+    async fn test_payout_rejects_overdraft(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "10", "0123456789").await;
+
+        let result = repository
+            .queue(&payout_request(&fixture, 30), &fixture.user_identifier)
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::InsufficientFunds)));
+        assert!(repository.claim_next().await.unwrap().is_none());
+    }
+
+    #[sqlx::test]
+    async fn test_blocked_beneficiary_is_not_paid(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0123456789").await;
+        sqlx::query("UPDATE beneficiaries SET blocked_date = NOW() WHERE identifier = $1")
+            .bind(fixture.beneficiary_identifier)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let result = repository
+            .queue(&payout_request(&fixture, 30), &fixture.user_identifier)
+            .await;
+        assert!(matches!(result, Err(RepositoryError::BlockedBeneficiary)));
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(100));
+    }
+
+    async fn uae_bank(pool: &PgPool) -> Uuid {
+        sqlx::query_scalar("SELECT identifier FROM banks WHERE country_identifier = $1 LIMIT 1")
+            .bind(Uuid::from_str(UAE_DIRHAM).unwrap())
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn batch_item(row_number: i32, bank_identifier: &Uuid, amount: &str) -> NewPayoutBatchItem {
+        NewPayoutBatchItem::from_row(
+            row_number,
+            PayoutBatchRow {
+                bank_identifier: bank_identifier.to_string(),
+                account_number: format!("00000000{row_number:02}"),
+                account_name: "Contractor".to_string(),
+                amount: amount.to_string(),
+                narration: None,
+            },
+        )
+    }
+
+    #[sqlx::test]
+    async fn test_batch_with_an_unknown_bank_is_rejected(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0123456789").await;
+        let bank_identifier = uae_bank(&pool).await;
+
+        let batch = repository
+            .create_batch(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                "contractors.csv",
+                vec![
+                    batch_item(2, &bank_identifier, "10"),
+                    batch_item(3, &Uuid::new_v4(), "10"),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(batch.status, PayoutBatchStatus::Rejected);
+        assert_eq!(batch.total_amount, BigDecimal::from(10));
+        assert_eq!(batch.items[0].status, PayoutBatchItemStatus::Valid);
+        assert_eq!(batch.items[1].status, PayoutBatchItemStatus::Invalid);
+        assert_eq!(
+            batch.items[1].errors,
+            vec!["bank does not exist".to_string()]
+        );
+
+        let result = repository
+            .execute_batch(&batch.identifier, &fixture.user_identifier)
+            .await;
+        assert!(matches!(result, Err(RepositoryError::OperationFailed(_))));
+        assert!(repository.claim_next().await.unwrap().is_none());
+    }
+
+    #[sqlx::test]
+    async fn test_batch_amount_with_more_decimals_than_the_currency_is_rejected(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0123456789").await;
+        let bank_identifier = uae_bank(&pool).await;
+
+        let batch = repository
+            .create_batch(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                "contractors.csv",
+                vec![
+                    batch_item(2, &bank_identifier, "10.50"),
+                    batch_item(3, &bank_identifier, "10.123456"),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(batch.status, PayoutBatchStatus::Rejected);
+        assert_eq!(batch.items[0].status, PayoutBatchItemStatus::Valid);
+        assert_eq!(batch.items[1].status, PayoutBatchItemStatus::Invalid);
+        assert_eq!(
+            batch.items[1].errors,
+            vec!["amount cannot have more than 2 decimal places".to_string()]
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_executed_batch_reports_each_row(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0123456789").await;
+        let bank_identifier = uae_bank(&pool).await;
+
+        let batch = repository
+            .create_batch(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                "contractors.csv",
+                vec![
+                    batch_item(2, &bank_identifier, "60"),
+                    batch_item(3, &bank_identifier, "60"),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(batch.status, PayoutBatchStatus::Validated);
+
+        let batch = repository
+            .execute_batch(&batch.identifier, &fixture.user_identifier)
+            .await
+            .unwrap();
+        assert_eq!(batch.status, PayoutBatchStatus::Executed);
+
+        // the second row no longer fits in what is left of the wallet
+        assert_eq!(batch.items[0].status, PayoutBatchItemStatus::Queued);
+        assert_eq!(batch.items[0].payout_status, Some(PayoutStatus::Queued));
+        assert_eq!(batch.items[1].status, PayoutBatchItemStatus::Failed);
+        assert_eq!(
+            batch.items[1].errors,
+            vec![RepositoryError::InsufficientFunds.to_string()]
+        );
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(40));
+
+        let result = repository
+            .execute_batch(&batch.identifier, &fixture.user_identifier)
+            .await;
+        assert!(matches!(result, Err(RepositoryError::OperationFailed(_))));
+    }
+
+    #[sqlx::test]
+    async fn test_exported_batch_is_never_queued_or_paid_again(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0123456789").await;
+        let bank_identifier = uae_bank(&pool).await;
+        sqlx::query("UPDATE banks SET bic = 'EBILAEADXXX' WHERE identifier = $1")
+            .bind(bank_identifier)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let batch = repository
+            .create_batch(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                "contractors.csv",
+                vec![
+                    batch_item(2, &bank_identifier, "30"),
+                    batch_item(3, &bank_identifier, "20.5"),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let batch = repository
+            .export_batch(&batch.identifier, &fixture.user_identifier)
+            .await
+            .unwrap();
+        assert_eq!(batch.status, PayoutBatchStatus::Exported);
+        assert!(batch.items.iter().all(|item| {
+            item.status == PayoutBatchItemStatus::Exported
+                && item.payout_status == Some(PayoutStatus::Exported)
+        }));
+        assert_eq!(
+            wallet_balance(&pool, &fixture).await,
+            BigDecimal::from_str("49.5").unwrap()
+        );
+
+        let exported = repository.fetch_exported_payouts(&batch).await.unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[0].item_identifier, batch.items[0].identifier);
+
+        // the payout processor only picks up queued payouts
+        assert!(repository.claim_next().await.unwrap().is_none());
+        for result in [
+            repository
+                .export_batch(&batch.identifier, &fixture.user_identifier)
+                .await,
+            repository
+                .execute_batch(&batch.identifier, &fixture.user_identifier)
+                .await,
+        ] {
+            assert!(matches!(result, Err(RepositoryError::OperationFailed(_))));
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_batch_left_half_way_is_picked_up_again(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0123456789").await;
+        let bank_identifier = uae_bank(&pool).await;
+
+        let batch = repository
+            .create_batch(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                "contractors.csv",
+                vec![
+                    batch_item(2, &bank_identifier, "30"),
+                    batch_item(3, &bank_identifier, "20"),
+                ],
+            )
+            .await
+            .unwrap();
+
+        // a run that claimed the batch and went away before booking any row
+        sqlx::query(
+            "UPDATE payout_batches SET status = $1, executed_date = NOW() WHERE identifier = $2",
+        )
+        .bind(PayoutBatchStatus::Executed)
+        .bind(batch.identifier)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let batch = repository
+            .execute_batch(&batch.identifier, &fixture.user_identifier)
+            .await
+            .unwrap();
+        assert!(
+            batch
+                .items
+                .iter()
+                .all(|item| item.status == PayoutBatchItemStatus::Queued)
+        );
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(50));
+    }
+
+    #[sqlx::test]
+    async fn test_batch_row_naming_someone_else_fails(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        // the account of row 2 is already saved as "Test Beneficiary"
+        let fixture = setup(&pool, "100", "0000000002").await;
+        let bank_identifier = uae_bank(&pool).await;
+
+        let batch = repository
+            .create_batch(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                "contractors.csv",
+                vec![
+                    batch_item(2, &bank_identifier, "30"),
+                    batch_item(3, &bank_identifier, "20"),
+                ],
+            )
+            .await
+            .unwrap();
+        let batch = repository
+            .execute_batch(&batch.identifier, &fixture.user_identifier)
+            .await
+            .unwrap();
+
+        assert_eq!(batch.items[0].status, PayoutBatchItemStatus::Failed);
+        assert_eq!(batch.items[0].payout_identifier, None);
+        assert_eq!(batch.items[1].status, PayoutBatchItemStatus::Queued);
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(80));
+    }
+
+    #[sqlx::test]
+    async fn test_bank_confirmation_settles_exported_payouts(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let wallet_repository = WalletRepository::new(pool.clone());
+        let fixture = setup(&pool, "100", "0123456789").await;
+        let bank_identifier = uae_bank(&pool).await;
+        sqlx::query("UPDATE banks SET bic = 'EBILAEADXXX' WHERE identifier = $1")
+            .bind(bank_identifier)
+            .execute(&pool)
+            .await
+            .unwrap();
+        AccountRepository::new(&pool)
+            .create_account(&AccountCreationParams {
+                user_identifier: fixture.user_identifier,
+                wallet_identifier: fixture.wallet_identifier,
+                bank_identifier: Some(bank_identifier),
+            })
+            .await
+            .expect("failed to create virtual account");
+
+        let batch = repository
+            .create_batch(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                "contractors.csv",
+                vec![
+                    batch_item(2, &bank_identifier, "30"),
+                    batch_item(3, &bank_identifier, "20.5"),
+                ],
+            )
+            .await
+            .unwrap();
+        let batch = repository
+            .export_batch(&batch.identifier, &fixture.user_identifier)
+            .await
+            .unwrap();
+        let debtor = repository.fetch_payment_debtor(&batch).await.unwrap();
+        let exported = repository.fetch_exported_payouts(&batch).await.unwrap();
+        let pain = render_pain001(&batch, &debtor, &exported).expect("failed to render pain.001");
+        assert!(pain.contains("<BICFI>EBILAEADXXX</BICFI>"));
+
+        // the funds are with the bank until it answers, so the wallet cannot be closed yet
+        let close_request = DeleteWalletRequest {
+            sweep_wallet_identifier: None,
+        };
+        let closed = wallet_repository
+            .close(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                &close_request,
+            )
+            .await;
+        assert!(
+            matches!(closed, Err(RepositoryError::OperationFailed(message)) if message.contains("settled"))
+        );
+
+        let paid = repository
+            .confirm_export(
+                &exported[0].payout_identifier,
+                &PayoutOutcome::Paid {
+                    provider_reference: "BANK-0001".to_string(),
+                },
+            )
+            .await
+            .expect("failed to confirm payout");
+        assert_eq!(paid.status, PayoutStatus::Paid);
+        let rejected = repository
+            .confirm_export(
+                &exported[1].payout_identifier,
+                &PayoutOutcome::Failed {
+                    reason: "the account is closed".to_string(),
+                },
+            )
+            .await
+            .expect("failed to confirm payout");
+        assert_eq!(rejected.status, PayoutStatus::Failed);
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(70));
+
+        let confirmed_again = repository
+            .confirm_export(
+                &exported[0].payout_identifier,
+                &PayoutOutcome::Failed {
+                    reason: "late return".to_string(),
+                },
+            )
+            .await;
+        assert!(matches!(
+            confirmed_again,
+            Err(RepositoryError::OperationFailed(_))
+        ));
+
+        // nothing is pending any more, only the balance left stands in the way
+        let closed = wallet_repository
+            .close(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                &close_request,
+            )
+            .await;
+        assert!(
+            matches!(closed, Err(RepositoryError::OperationFailed(message)) if message.contains("sweep"))
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_rows_to_a_bank_without_a_bic_are_not_exported(pool: PgPool) {
+        let repository = PayoutRepository::new(&pool);
+        let fixture = setup(&pool, "100", "0123456789").await;
+        let bank_identifier = uae_bank(&pool).await;
+
+        let batch = repository
+            .create_batch(
+                &fixture.wallet_identifier,
+                &fixture.user_identifier,
+                "contractors.csv",
+                vec![batch_item(2, &bank_identifier, "30")],
+            )
+            .await
+            .unwrap();
+        let batch = repository
+            .export_batch(&batch.identifier, &fixture.user_identifier)
+            .await
+            .unwrap();
+
+        assert_eq!(batch.items[0].status, PayoutBatchItemStatus::Failed);
+        assert_eq!(
+            batch.items[0].errors,
+            vec!["the bank has no BIC to route the payment to".to_string()]
+        );
+        assert_eq!(wallet_balance(&pool, &fixture).await, BigDecimal::from(100));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    payouts::handlers::{
+        confirm_exported_payout, create_payout, create_payout_batch, download_payout_batch_export,
+        download_payout_batch_result, execute_payout_batch, export_payout_batch, fetch_payout,
+        fetch_payout_batch, fetch_payout_batches, fetch_payouts,
+    },
+    shared::middlewares::idempotency::{IdempotencyStore, enforce_idempotency},
+    state::AppState,
+};
+use axum::{
+    Router,
+    extract::FromRef,
+    middleware::from_fn_with_state,
+    routing::{get, post},
+};
+
+pub fn payout_routes(state: &AppState) -> Router {
+    let idempotency_store = IdempotencyStore::from_ref(state);
+
+    Router::new()
+        .route(
+            "/",
+            post(create_payout).layer(from_fn_with_state(
+                idempotency_store.clone(),
+                enforce_idempotency,
+            )),
+        )
+        .route("/", get(fetch_payouts))
+        .route("/batches", post(create_payout_batch))
+        .route("/batches", get(fetch_payout_batches))
+        .route("/batches/{identifier}", get(fetch_payout_batch))
+        .route(
+            "/batches/{identifier}/commit",
+            post(execute_payout_batch).layer(from_fn_with_state(
+                idempotency_store.clone(),
+                enforce_idempotency,
+            )),
+        )
+        .route(
+            "/batches/{identifier}/result",
+            get(download_payout_batch_result),
+        )
+        .route(
+            "/batches/{identifier}/export",
+            post(export_payout_batch)
+                .layer(from_fn_with_state(idempotency_store, enforce_idempotency)),
+        )
+        .route(
+            "/batches/{identifier}/export",
+            get(download_payout_batch_export),
+        )
+        .route("/{identifier}", get(fetch_payout))
+        .with_state(state.clone())
+}
+
+pub fn admin_payout_routes(state: &AppState) -> Router {
+    let idempotency_store = IdempotencyStore::from_ref(state);
+
+    Router::new()
+        .route(
+            "/{identifier}/confirmation",
+            post(confirm_exported_payout)
+                .layer(from_fn_with_state(idempotency_store, enforce_idempotency)),
+        )
+        .with_state(state.clone())
+}
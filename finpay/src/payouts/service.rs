@@ -0,0 +1,626 @@
+use crate::authentication::claims::Claims;
+use crate::compliance::adapters::ScreeningSubject;
+use crate::compliance::entities::ScreeningSubjectType;
+use crate::compliance::service::{ComplianceService, ComplianceServiceExt};
+use crate::config::AppConfig;
+use crate::errors::{AuthenticationError, RepositoryError, ServiceError};
+use crate::payouts::adapters::{
+    ConfirmExportedPayoutRequest, CreatePayoutBatchRequest, CreatePayoutRequest,
+    NewPayoutBatchItem, PayoutBatchRow, flag_duplicate_rows,
+};
+use crate::payouts::entities::{Payout, PayoutBatch, PayoutBatchItemStatus, PayoutBatchStatus};
+use crate::payouts::pain::render_pain001;
+use crate::payouts::provider::{PayoutOutcome, PayoutProvider};
+use crate::payouts::repository::{PayoutRepository, PayoutRepositoryExt};
+use crate::risk::adapters::ScreenedRequest;
+use crate::risk::service::{RiskService, RiskServiceExt};
+use crate::users::enums::AccountType;
+use crate::users::service::{UsersService, UsersServiceExt};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use axum_typed_multipart::TypedMultipart;
+use bigdecimal::BigDecimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::path::Path;
+use uuid::Uuid;
+
+/// the most payouts a single processing pass sends to the provider
+const PAYOUT_BATCH_SIZE: usize = 50;
+
+/// the most rows a single uploaded batch can carry
+const MAX_BATCH_ROWS: usize = 1000;
+
+/// the columns every uploaded batch must have, narration is optional
+const BATCH_COLUMNS: [&str; 4] = [
+    "bank_identifier",
+    "account_number",
+    "account_name",
+    "amount",
+];
+
+#[derive(Clone)]
+pub struct PayoutService {
+    repository: PayoutRepository,
+    users_service: UsersService,
+    risk_service: RiskService,
+    compliance_service: ComplianceService,
+}
+
+impl PayoutService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: PayoutRepository::new(pool),
+            users_service: UsersService::new(pool),
+            risk_service: RiskService::new(pool),
+            compliance_service: ComplianceService::new(pool),
+        }
+    }
+
+    /// Screens the account holder of every row still to be paid, the way a beneficiary is
+    /// screened when it is saved. A row that matches the watch list is failed before the batch
+    /// is paid out
+    async fn screen_batch(&self, claims: &Claims, identifier: &Uuid) -> Result<(), ServiceError> {
+        let batch = self
+            .repository
+            .fetch_batch(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        for item in batch
+            .items
+            .iter()
+            .filter(|item| item.status == PayoutBatchItemStatus::Valid)
+        {
+            let Some(bank_identifier) = item.bank_identifier else {
+                continue;
+            };
+            let subject = ScreeningSubject {
+                subject_type: ScreeningSubjectType::Beneficiary,
+                subject_identifier: None,
+                user_identifier: Some(claims.user_identifier),
+                name: item.account_name.clone(),
+                reference: format!("{}/{}", bank_identifier, item.account_number),
+            };
+            let screening = self.compliance_service.screen_and_record(&subject).await?;
+            if screening.is_hit() {
+                self.repository
+                    .fail_batch_item(
+                        &item.identifier,
+                        "the account holder matches a name on the watch list",
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A line of the result file written once a batch has been executed
+#[derive(Serialize)]
+struct PayoutBatchResultRow<'a> {
+    row_number: i32,
+    bank_identifier: Option<Uuid>,
+    account_number: &'a str,
+    account_name: &'a str,
+    amount: Option<&'a BigDecimal>,
+    status: PayoutBatchItemStatus,
+    payout_identifier: Option<Uuid>,
+    errors: String,
+}
+
+/// Reads every row of an uploaded csv, rows that cannot be read are kept and reported as invalid
+fn read_batch_file(contents: &[u8]) -> Result<Vec<NewPayoutBatchItem>, ServiceError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(contents);
+
+    let headers = reader.headers().map_err(|err| {
+        ServiceError::UnprocessableEntity(format!("the file is not a valid csv: {err}"))
+    })?;
+    if let Some(missing) = BATCH_COLUMNS
+        .into_iter()
+        .find(|column| !headers.iter().any(|header| header == *column))
+    {
+        return Err(ServiceError::UnprocessableEntity(format!(
+            "the file is missing the {missing} column"
+        )));
+    }
+
+    let mut items = vec![];
+    for (index, row) in reader.deserialize::<PayoutBatchRow>().enumerate() {
+        // the header is line 1
+        let row_number = index as i32 + 2;
+        items.push(match row {
+            Ok(row) => NewPayoutBatchItem::from_row(row_number, row),
+            Err(err) => NewPayoutBatchItem::malformed(row_number, &err.to_string()),
+        });
+        if items.len() > MAX_BATCH_ROWS {
+            return Err(ServiceError::UnprocessableEntity(format!(
+                "a batch cannot have more than {MAX_BATCH_ROWS} rows"
+            )));
+        }
+    }
+
+    if items.is_empty() {
+        return Err(ServiceError::UnprocessableEntity(
+            "the file has no rows".to_string(),
+        ));
+    }
+
+    flag_duplicate_rows(&mut items);
+    Ok(items)
+}
+
+/// Writes the outcome of every row of an executed batch to `AppConfig.export_path`
+async fn write_batch_result(
+    batch: &PayoutBatch,
+    export_path: &str,
+) -> Result<String, ServiceError> {
+    let file_path = Path::new(export_path).join(format!("{}_result.csv", batch.reference));
+
+    let render = || -> Result<Vec<u8>, csv::Error> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        for item in &batch.items {
+            writer.serialize(PayoutBatchResultRow {
+                row_number: item.row_number,
+                bank_identifier: item.bank_identifier,
+                account_number: &item.account_number,
+                account_name: &item.account_name,
+                amount: item.amount.as_ref(),
+                status: item.status,
+                payout_identifier: item.payout_identifier,
+                errors: item.errors.join("; "),
+            })?;
+        }
+        writer.into_inner().map_err(|err| err.into_error().into())
+    };
+    let contents = render().map_err(|err| {
+        log::error!("error writing payout batch result due to {err}");
+        ServiceError::OperationFailed
+    })?;
+    tokio::fs::write(&file_path, contents)
+        .await
+        .map_err(|err| {
+            log::error!("error writing payout batch result due to {err}");
+            ServiceError::OperationFailed
+        })?;
+
+    Ok(file_path.to_string_lossy().to_string())
+}
+
+/// Writes the result file of an executed batch and keeps its path. The payouts are already
+/// queued, so a missing result file must not fail the request
+pub async fn record_batch_result(
+    repository: &PayoutRepository,
+    batch: &mut PayoutBatch,
+) -> Result<(), ServiceError> {
+    let config = AppConfig::from_env()?;
+    match write_batch_result(batch, &config.export_path).await {
+        Ok(result_file) => {
+            repository
+                .set_batch_result_file(&batch.identifier, &result_file)
+                .await?;
+            batch.result_file = Some(result_file);
+        }
+        Err(err) => log::error!("failed to write result of batch {}: {err}", batch.reference),
+    }
+
+    Ok(())
+}
+
+/// Writes the pain.001 file of an exported batch to `AppConfig.export_path`, built from the
+/// payouts booked for its rows
+async fn write_batch_export(
+    repository: &PayoutRepository,
+    batch: &PayoutBatch,
+    export_path: &str,
+) -> Result<String, ServiceError> {
+    let debtor = repository.fetch_payment_debtor(batch).await?;
+    let payouts = repository.fetch_exported_payouts(batch).await?;
+    let pain = render_pain001(batch, &debtor, &payouts).map_err(|err| {
+        ServiceError::UnprocessableEntity(format!(
+            "the payment file does not follow pain.001: {err}"
+        ))
+    })?;
+
+    let file_path = Path::new(export_path).join(format!("{}_pain001.xml", batch.reference));
+    tokio::fs::write(&file_path, pain).await.map_err(|err| {
+        log::error!("error writing payout batch export due to {err}");
+        ServiceError::OperationFailed
+    })?;
+
+    Ok(file_path.to_string_lossy().to_string())
+}
+
+/// Writes the pain.001 file of an exported batch and keeps its path
+pub async fn record_batch_export(
+    repository: &PayoutRepository,
+    batch: &mut PayoutBatch,
+) -> Result<(), ServiceError> {
+    let config = AppConfig::from_env()?;
+    let export_file = write_batch_export(repository, batch, &config.export_path).await?;
+    repository
+        .set_batch_export_file(&batch.identifier, &export_file)
+        .await?;
+    batch.export_file = Some(export_file);
+
+    Ok(())
+}
+
+pub trait PayoutServiceExt {
+    fn create_payout(
+        &self,
+        claims: &Claims,
+        request: &CreatePayoutRequest,
+    ) -> impl std::future::Future<Output = Result<Payout, ServiceError>> + Send;
+
+    fn fetch_payout(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Payout, ServiceError>> + Send;
+
+    fn fetch_payouts(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Payout>, ServiceError>> + Send;
+
+    /// Validates an uploaded csv of payouts, only company accounts can pay out in bulk
+    fn create_batch(
+        &self,
+        claims: &Claims,
+        request: TypedMultipart<CreatePayoutBatchRequest>,
+    ) -> impl std::future::Future<Output = Result<PayoutBatch, ServiceError>> + Send;
+
+    fn fetch_batch(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<PayoutBatch, ServiceError>> + Send;
+
+    fn fetch_batches(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<PayoutBatch>, ServiceError>> + Send;
+
+    /// Queues the payouts of a validated batch and writes the result file
+    fn execute_batch(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<PayoutBatch, ServiceError>> + Send;
+
+    /// The name and contents of an executed batch's result file
+    fn fetch_batch_result(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<(String, Vec<u8>), ServiceError>> + Send;
+
+    /// Books the payouts of a validated batch for a partner bank and writes its pain.001 file,
+    /// the rows are paid by the bank and never sent through the payout provider
+    fn export_batch(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<PayoutBatch, ServiceError>> + Send;
+
+    /// The name and contents of an exported batch's pain.001 file, written again if it is missing
+    fn fetch_batch_export(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<(String, Vec<u8>), ServiceError>> + Send;
+
+    /// Records what the bank did with an exported payout, a rejected one is handed back to the
+    /// wallet with its fee
+    fn confirm_exported_payout(
+        &self,
+        identifier: &Uuid,
+        request: &ConfirmExportedPayoutRequest,
+    ) -> impl std::future::Future<Output = Result<Payout, ServiceError>> + Send;
+}
+
+impl PayoutServiceExt for PayoutService {
+    async fn create_payout(
+        &self,
+        claims: &Claims,
+        request: &CreatePayoutRequest,
+    ) -> Result<Payout, ServiceError> {
+        self.risk_service
+            .screen(
+                &claims.user_identifier,
+                ScreenedRequest::Payout(request.clone()),
+            )
+            .await?;
+
+        let payout = self
+            .repository
+            .queue(request, &claims.user_identifier)
+            .await?;
+        Ok(payout)
+    }
+
+    async fn fetch_payout(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<Payout, ServiceError> {
+        let payout = self
+            .repository
+            .fetch_payout(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(payout)
+    }
+
+    async fn fetch_payouts(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Payout>, ServiceError> {
+        let payouts = self
+            .repository
+            .fetch_payouts(&claims.user_identifier, pagination_params)
+            .await?;
+        Ok(payouts)
+    }
+
+    async fn create_batch(
+        &self,
+        claims: &Claims,
+        TypedMultipart(request): TypedMultipart<CreatePayoutBatchRequest>,
+    ) -> Result<PayoutBatch, ServiceError> {
+        let user = self
+            .users_service
+            .find_user_by_pk(&claims.user_identifier)
+            .await?;
+        if user.account_type != AccountType::Company {
+            return Err(ServiceError::AuthenticationError(
+                AuthenticationError::Forbidden,
+            ));
+        }
+
+        let wallet_identifier =
+            Uuid::parse_str(&request.wallet_identifier).map_err(|_| ServiceError::BadRequest)?;
+        let file_name = request
+            .file
+            .metadata
+            .file_name
+            .clone()
+            .unwrap_or_else(|| "payouts.csv".to_string());
+        let contents = tokio::fs::read(request.file.contents.path())
+            .await
+            .map_err(|err| {
+                log::error!("error reading payout batch due to {err}");
+                ServiceError::OperationFailed
+            })?;
+        let items = read_batch_file(&contents)?;
+
+        let batch = self
+            .repository
+            .create_batch(
+                &wallet_identifier,
+                &claims.user_identifier,
+                &file_name,
+                items,
+            )
+            .await?;
+        Ok(batch)
+    }
+
+    async fn fetch_batch(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<PayoutBatch, ServiceError> {
+        let batch = self
+            .repository
+            .fetch_batch(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(batch)
+    }
+
+    async fn fetch_batches(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<PayoutBatch>, ServiceError> {
+        let batches = self
+            .repository
+            .fetch_batches(&claims.user_identifier, pagination_params)
+            .await?;
+        Ok(batches)
+    }
+
+    async fn execute_batch(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<PayoutBatch, ServiceError> {
+        self.risk_service
+            .screen(
+                &claims.user_identifier,
+                ScreenedRequest::PayoutBatch {
+                    batch_identifier: *identifier,
+                },
+            )
+            .await?;
+        self.screen_batch(claims, identifier).await?;
+
+        let mut batch = self
+            .repository
+            .execute_batch(identifier, &claims.user_identifier)
+            .await?;
+        record_batch_result(&self.repository, &mut batch).await?;
+
+        Ok(batch)
+    }
+
+    async fn fetch_batch_result(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<(String, Vec<u8>), ServiceError> {
+        let batch = self.fetch_batch(claims, identifier).await?;
+        let result_file = batch.result_file.ok_or(RepositoryError::RecordNotFound)?;
+
+        let contents = tokio::fs::read(&result_file).await.map_err(|err| {
+            log::error!("error reading payout batch result due to {err}");
+            ServiceError::OperationFailed
+        })?;
+        let file_name = format!("{}_result.csv", batch.reference);
+        Ok((file_name, contents))
+    }
+
+    async fn export_batch(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<PayoutBatch, ServiceError> {
+        self.risk_service
+            .screen(
+                &claims.user_identifier,
+                ScreenedRequest::PayoutBatch {
+                    batch_identifier: *identifier,
+                },
+            )
+            .await?;
+        self.screen_batch(claims, identifier).await?;
+
+        // nothing is booked for a file the bank would not be able to route
+        let batch = self
+            .repository
+            .fetch_batch(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        let debtor = self.repository.fetch_payment_debtor(&batch).await?;
+        if debtor.account_number.is_none() || debtor.bic.is_none() {
+            return Err(ServiceError::UnprocessableEntity(
+                "the wallet needs an account number at a bank with a BIC to be paid out from"
+                    .to_string(),
+            ));
+        }
+        // the file is drawn up from the rows before any of them is booked, rows the bank cannot
+        // route are failed with the booking and left out of it
+        let payouts = self.repository.fetch_exportable_payouts(&batch).await?;
+        if !payouts.is_empty() {
+            render_pain001(&batch, &debtor, &payouts).map_err(|err| {
+                ServiceError::UnprocessableEntity(format!(
+                    "the payment file does not follow pain.001: {err}"
+                ))
+            })?;
+        }
+
+        let mut batch = self
+            .repository
+            .export_batch(identifier, &claims.user_identifier)
+            .await?;
+        // the payouts are already booked, a file that cannot be written now is written again
+        // when it is downloaded
+        if let Err(err) = record_batch_export(&self.repository, &mut batch).await {
+            log::error!("failed to write export of batch {}: {err}", batch.reference);
+        }
+
+        Ok(batch)
+    }
+
+    async fn fetch_batch_export(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<(String, Vec<u8>), ServiceError> {
+        let mut batch = self.fetch_batch(claims, identifier).await?;
+        if batch.status != PayoutBatchStatus::Exported {
+            return Err(RepositoryError::RecordNotFound.into());
+        }
+        if batch.export_file.is_none() {
+            record_batch_export(&self.repository, &mut batch).await?;
+        }
+        let export_file = batch.export_file.ok_or(RepositoryError::RecordNotFound)?;
+
+        let contents = tokio::fs::read(&export_file).await.map_err(|err| {
+            log::error!("error reading payout batch export due to {err}");
+            ServiceError::OperationFailed
+        })?;
+        let file_name = format!("{}_pain001.xml", batch.reference);
+        Ok((file_name, contents))
+    }
+
+    async fn confirm_exported_payout(
+        &self,
+        identifier: &Uuid,
+        request: &ConfirmExportedPayoutRequest,
+    ) -> Result<Payout, ServiceError> {
+        let payout = self
+            .repository
+            .confirm_export(identifier, &PayoutOutcome::from(request))
+            .await?;
+        Ok(payout)
+    }
+}
+
+/// Sends queued payouts to a provider and books the outcome
+pub struct PayoutProcessor<P: PayoutProvider> {
+    repository: PayoutRepository,
+    provider: P,
+}
+
+impl<P: PayoutProvider> PayoutProcessor<P> {
+    pub fn new(pool: &PgPool, provider: P) -> Self {
+        Self {
+            repository: PayoutRepository::new(pool),
+            provider,
+        }
+    }
+
+    /// Works through the queue, returns how many payouts were settled
+    pub async fn process_queued(&self) -> Result<usize, ServiceError> {
+        let mut settled = 0;
+        let mut unsettled = vec![];
+
+        for _ in 0..PAYOUT_BATCH_SIZE {
+            let Some(payout) = self.repository.claim_next().await? else {
+                break;
+            };
+
+            let outcome = match self.provider.submit(&payout).await {
+                Ok(outcome) => outcome,
+                Err(err) => {
+                    // the provider is unreachable, leave the rest of the queue for the next pass
+                    tracing::warn!(
+                        "payout {} could not be submitted: {}",
+                        payout.identifier,
+                        err
+                    );
+                    self.repository.requeue(&payout).await?;
+                    break;
+                }
+            };
+
+            if let PayoutOutcome::Failed { reason } = &outcome {
+                tracing::warn!("payout {} failed: {}", payout.identifier, reason);
+            }
+
+            // one payout that cannot be settled does not hold up the rest of the queue
+            if let Err(err) = self.repository.settle(&payout, &outcome).await {
+                tracing::error!("payout {} could not be settled: {}", payout.identifier, err);
+                unsettled.push(payout);
+                continue;
+            }
+            settled += 1;
+        }
+
+        // back on the queue only once the pass is over so they are not claimed again in it, the
+        // provider sees them again under the same identifier
+        for payout in &unsettled {
+            self.repository.requeue(payout).await?;
+        }
+
+        Ok(settled)
+    }
+}
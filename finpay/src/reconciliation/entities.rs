@@ -0,0 +1,36 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationRun {
+    pub identifier: Uuid,
+    pub wallets_checked: i64,
+    pub discrepancies_found: i64,
+    pub started_date: DateTime<Local>,
+    pub completed_date: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceDiscrepancy {
+    pub identifier: Uuid,
+    pub reconciliation_run_identifier: Uuid,
+    pub wallet_identifier: Uuid,
+    pub recorded_balance: BigDecimal,
+    pub computed_balance: BigDecimal,
+    pub difference: BigDecimal,
+    pub wallet_frozen: bool,
+    pub created_date: DateTime<Local>,
+}
+
+/// A wallet whose stored balance does not match the sum of its postings
+#[derive(Debug, FromRow)]
+pub struct DriftedWallet {
+    pub wallet_identifier: Uuid,
+    pub recorded_balance: BigDecimal,
+    pub computed_balance: BigDecimal,
+}
@@ -0,0 +1,43 @@
+use crate::errors::ServiceError;
+use crate::reconciliation::entities::{BalanceDiscrepancy, ReconciliationRun};
+use crate::reconciliation::service::{ReconciliationService, ReconciliationServiceExt};
+use crate::shared::middlewares::admin_middleware::AdminClaims;
+use crate::utils::{ApiResponse, PaginatedResponse, PaginationParams};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+
+pub async fn run_reconciliation(
+    State(reconciliation_service): State<ReconciliationService>,
+    _: AdminClaims,
+) -> Result<ApiResponse<ReconciliationRun>, ServiceError> {
+    let run = reconciliation_service.reconcile().await?;
+
+    Ok(ApiResponse::builder()
+        .data(run)
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_reconciliation_runs(
+    State(reconciliation_service): State<ReconciliationService>,
+    _: AdminClaims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<ReconciliationRun>>, ServiceError> {
+    let runs = reconciliation_service
+        .fetch_runs(&pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(runs).build())
+}
+
+pub async fn fetch_balance_discrepancies(
+    State(reconciliation_service): State<ReconciliationService>,
+    _: AdminClaims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<BalanceDiscrepancy>>, ServiceError> {
+    let discrepancies = reconciliation_service
+        .fetch_discrepancies(&pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(discrepancies).build())
+}
@@ -0,0 +1,5 @@
+pub mod entities;
+pub mod handlers;
+pub mod repository;
+pub mod router;
+pub mod service;
@@ -0,0 +1,251 @@
+use crate::errors::RepositoryError;
+use crate::reconciliation::entities::{BalanceDiscrepancy, DriftedWallet, ReconciliationRun};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use crate::wallet::entities::{WalletFreezeOrigin, WalletStatus};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ReconciliationRepository {
+    pool: PgPool,
+}
+
+impl ReconciliationRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+pub trait ReconciliationRepositoryExt {
+    fn reconcile(
+        &self,
+        freeze_wallets: bool,
+    ) -> impl std::future::Future<Output = Result<ReconciliationRun, RepositoryError>> + Send;
+
+    fn fetch_runs(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<ReconciliationRun>, RepositoryError>,
+    > + Send;
+
+    fn fetch_discrepancies(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<BalanceDiscrepancy>, RepositoryError>,
+    > + Send;
+}
+
+impl ReconciliationRepositoryExt for ReconciliationRepository {
+    async fn reconcile(&self, freeze_wallets: bool) -> Result<ReconciliationRun, RepositoryError> {
+        let started_date = chrono::Local::now();
+
+        // read every balance and every posting from the same snapshot, so that money moving
+        // while the job runs does not show up as drift
+        let mut snapshot = self.pool.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .execute(&mut *snapshot)
+            .await?;
+
+        let wallets_checked: i64 = sqlx::query_scalar("SELECT COUNT(identifier) FROM wallets")
+            .fetch_one(&mut *snapshot)
+            .await?;
+
+        let query = r#"
+        SELECT
+            wallets.identifier AS wallet_identifier,
+            wallets.balance AS recorded_balance,
+            COALESCE(
+                SUM(CASE WHEN postings.direction = 'credit' THEN postings.amount ELSE -postings.amount END),
+                0
+            )::NUMERIC(20, 6) AS computed_balance
+        FROM wallets
+                 LEFT JOIN ledger_accounts ON ledger_accounts.wallet_identifier = wallets.identifier
+                 LEFT JOIN postings ON postings.ledger_account_identifier = ledger_accounts.identifier
+        GROUP BY wallets.identifier, wallets.balance
+        HAVING wallets.balance <> COALESCE(
+                SUM(CASE WHEN postings.direction = 'credit' THEN postings.amount ELSE -postings.amount END),
+                0
+            )
+        "#;
+        let drifted_wallets = sqlx::query_as::<_, DriftedWallet>(query)
+            .fetch_all(&mut *snapshot)
+            .await?;
+        snapshot.commit().await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let run = sqlx::query_as::<_, ReconciliationRun>(
+            r#"
+            INSERT INTO reconciliation_runs (identifier, wallets_checked, discrepancies_found, started_date, completed_date)
+            VALUES ($1, $2, $3, $4, NOW()) RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(wallets_checked)
+        .bind(drifted_wallets.len() as i64)
+        .bind(started_date)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for drifted_wallet in &drifted_wallets {
+            let query = r#"
+            INSERT INTO balance_discrepancies (
+                identifier,
+                reconciliation_run_identifier,
+                wallet_identifier,
+                recorded_balance,
+                computed_balance,
+                difference,
+                wallet_frozen
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#;
+            sqlx::query(query)
+                .bind(Uuid::new_v4())
+                .bind(run.identifier)
+                .bind(drifted_wallet.wallet_identifier)
+                .bind(&drifted_wallet.recorded_balance)
+                .bind(&drifted_wallet.computed_balance)
+                .bind(&drifted_wallet.recorded_balance - &drifted_wallet.computed_balance)
+                .bind(freeze_wallets)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if freeze_wallets && !drifted_wallets.is_empty() {
+            let wallet_identifiers: Vec<Uuid> = drifted_wallets
+                .iter()
+                .map(|drifted_wallet| drifted_wallet.wallet_identifier)
+                .collect();
+
+            // a wallet frozen for drift stays frozen until an admin has looked into it
+            sqlx::query(
+                r#"UPDATE wallets SET status = $1, frozen_by = $2 WHERE identifier = ANY($3) AND status <> $4"#,
+            )
+            .bind(WalletStatus::Frozen)
+            .bind(WalletFreezeOrigin::Admin)
+            .bind(wallet_identifiers)
+            .bind(WalletStatus::Closed)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(run)
+    }
+
+    async fn fetch_runs(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<ReconciliationRun>, RepositoryError> {
+        let total_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(identifier) FROM reconciliation_runs")
+                .fetch_one(&self.pool)
+                .await?;
+
+        let page_size = pagination_params.per_page();
+        let offset = (pagination_params.page() - 1) * page_size;
+
+        let runs = sqlx::query_as::<_, ReconciliationRun>(
+            r#"SELECT * FROM reconciliation_runs ORDER BY started_date DESC LIMIT $1 OFFSET $2"#,
+        )
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(runs, pagination_params, total_count))
+    }
+
+    async fn fetch_discrepancies(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<BalanceDiscrepancy>, RepositoryError> {
+        let total_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(identifier) FROM balance_discrepancies")
+                .fetch_one(&self.pool)
+                .await?;
+
+        let page_size = pagination_params.per_page();
+        let offset = (pagination_params.page() - 1) * page_size;
+
+        let discrepancies = sqlx::query_as::<_, BalanceDiscrepancy>(
+            r#"SELECT * FROM balance_discrepancies ORDER BY created_date DESC LIMIT $1 OFFSET $2"#,
+        )
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            discrepancies,
+            pagination_params,
+            total_count,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::fixtures::{create_user, create_wallet};
+
+    #[sqlx::test]
+    async fn test_reconcile_without_drift(pool: PgPool) {
+        create_wallet(&pool, &create_user(&pool).await).await;
+        let repository = ReconciliationRepository::new(&pool);
+
+        let run = repository
+            .reconcile(true)
+            .await
+            .expect("failed to reconcile");
+
+        assert_eq!(run.wallets_checked, 1);
+        assert_eq!(run.discrepancies_found, 0);
+    }
+
+    #[sqlx::test]
+    async fn test_reconcile_records_and_freezes_drifted_wallet(pool: PgPool) {
+        let wallet_identifier = create_wallet(&pool, &create_user(&pool).await).await;
+
+        // simulate a write that bypassed the ledger
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("SELECT set_config('finpay.ledger_projection', 'on', true)")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE wallets SET balance = 42 WHERE identifier = $1")
+            .bind(wallet_identifier)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        let repository = ReconciliationRepository::new(&pool);
+        let run = repository
+            .reconcile(true)
+            .await
+            .expect("failed to reconcile");
+        assert_eq!(run.discrepancies_found, 1);
+
+        let status: WalletStatus =
+            sqlx::query_scalar("SELECT status FROM wallets WHERE identifier = $1")
+                .bind(wallet_identifier)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, WalletStatus::Frozen);
+
+        let discrepancies = repository
+            .fetch_discrepancies(&PaginationParams::default())
+            .await
+            .unwrap();
+        assert_eq!(discrepancies.records.len(), 1);
+        assert_eq!(
+            discrepancies.records[0].wallet_identifier,
+            wallet_identifier
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use crate::reconciliation::handlers::{
+    fetch_balance_discrepancies, fetch_reconciliation_runs, run_reconciliation,
+};
+use crate::state::AppState;
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+pub fn reconciliation_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/runs", post(run_reconciliation))
+        .route("/runs", get(fetch_reconciliation_runs))
+        .route("/discrepancies", get(fetch_balance_discrepancies))
+        .with_state(state.clone())
+}
@@ -0,0 +1,75 @@
+use crate::errors::ServiceError;
+use crate::reconciliation::entities::{BalanceDiscrepancy, ReconciliationRun};
+use crate::reconciliation::repository::{ReconciliationRepository, ReconciliationRepositoryExt};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use finpay_utils::extract_env;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct ReconciliationService {
+    repository: ReconciliationRepository,
+}
+
+impl ReconciliationService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: ReconciliationRepository::new(pool),
+        }
+    }
+}
+
+pub trait ReconciliationServiceExt {
+    fn reconcile(
+        &self,
+    ) -> impl std::future::Future<Output = Result<ReconciliationRun, ServiceError>> + Send;
+
+    fn fetch_runs(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<ReconciliationRun>, ServiceError>,
+    > + Send;
+
+    fn fetch_discrepancies(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<
+        Output = Result<PaginatedResponse<BalanceDiscrepancy>, ServiceError>,
+    > + Send;
+}
+
+impl ReconciliationServiceExt for ReconciliationService {
+    async fn reconcile(&self) -> Result<ReconciliationRun, ServiceError> {
+        let freeze_wallets = extract_env::<bool>("RECONCILIATION_FREEZE_WALLETS");
+        let run = self.repository.reconcile(freeze_wallets).await?;
+
+        if run.discrepancies_found > 0 {
+            tracing::warn!(
+                "reconciliation run {} found {} wallet(s) whose balance drifted from the ledger",
+                run.identifier,
+                run.discrepancies_found
+            );
+        }
+
+        Ok(run)
+    }
+
+    async fn fetch_runs(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<ReconciliationRun>, ServiceError> {
+        let runs = self.repository.fetch_runs(pagination_params).await?;
+        Ok(runs)
+    }
+
+    async fn fetch_discrepancies(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<BalanceDiscrepancy>, ServiceError> {
+        let discrepancies = self
+            .repository
+            .fetch_discrepancies(pagination_params)
+            .await?;
+        Ok(discrepancies)
+    }
+}
@@ -0,0 +1,159 @@
+use crate::payouts::adapters::CreatePayoutRequest;
+use crate::risk::entities::{RiskAction, RiskReviewStatus, RiskRuleType};
+use crate::transactions::adapters::CreateTransferRequest;
+use crate::transactions::entities::TransactionType;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRiskRuleRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "name must be between 1 and 255 characters",
+        code = "name"
+    ))]
+    pub name: String,
+    pub rule_type: RiskRuleType,
+    /// screens transfers and withdrawals alike when left out
+    pub transaction_type: Option<TransactionType>,
+    /// applies to every currency when left out, required for currency pairs
+    pub currency_identifier: Option<Uuid>,
+    /// required for amount thresholds
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "threshold amount must be greater than zero",
+        code = "thresholdAmount"
+    ))]
+    pub threshold_amount: Option<BigDecimal>,
+    /// required for velocity rules, with `windowMinutes`
+    #[validate(range(min = 1, message = "max count must be at least 1", code = "maxCount"))]
+    pub max_count: Option<i32>,
+    #[validate(range(
+        min = 1,
+        max = 10080,
+        message = "window must be between 1 minute and a week",
+        code = "windowMinutes"
+    ))]
+    pub window_minutes: Option<i32>,
+    /// required for new beneficiary rules
+    #[validate(range(
+        min = 1,
+        message = "cooling off must be at least an hour",
+        code = "coolingOffHours"
+    ))]
+    pub cooling_off_hours: Option<i32>,
+    /// required for currency pairs
+    pub destination_currency_identifier: Option<Uuid>,
+    pub action: RiskAction,
+    #[validate(range(
+        min = 0,
+        max = 100,
+        message = "score must be between 0 and 100",
+        code = "score"
+    ))]
+    pub score: i32,
+}
+
+/// Changes a rule in place, anything left out keeps its current value
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRiskRuleRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "name must be between 1 and 255 characters",
+        code = "name"
+    ))]
+    pub name: Option<String>,
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "threshold amount must be greater than zero",
+        code = "thresholdAmount"
+    ))]
+    pub threshold_amount: Option<BigDecimal>,
+    #[validate(range(min = 1, message = "max count must be at least 1", code = "maxCount"))]
+    pub max_count: Option<i32>,
+    #[validate(range(
+        min = 1,
+        max = 10080,
+        message = "window must be between 1 minute and a week",
+        code = "windowMinutes"
+    ))]
+    pub window_minutes: Option<i32>,
+    #[validate(range(
+        min = 1,
+        message = "cooling off must be at least an hour",
+        code = "coolingOffHours"
+    ))]
+    pub cooling_off_hours: Option<i32>,
+    pub action: Option<RiskAction>,
+    #[validate(range(
+        min = 0,
+        max = 100,
+        message = "score must be between 0 and 100",
+        code = "score"
+    ))]
+    pub score: Option<i32>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DecideRiskReviewRequest {
+    #[validate(length(
+        max = 500,
+        message = "note cannot be more than 500 characters",
+        code = "note"
+    ))]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskReviewQuery {
+    /// the pending queue when left out
+    pub status: Option<RiskReviewStatus>,
+}
+
+/// A movement as it was asked for, kept on its review so that an approved one can be carried out
+/// unchanged
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScreenedRequest {
+    Transfer(CreateTransferRequest),
+    Payout(CreatePayoutRequest),
+    #[serde(rename_all = "camelCase")]
+    PayoutBatch {
+        batch_identifier: Uuid,
+    },
+}
+
+impl ScreenedRequest {
+    pub fn transaction_type(&self) -> TransactionType {
+        match self {
+            ScreenedRequest::Transfer(_) => TransactionType::Transfer,
+            ScreenedRequest::Payout(_) | ScreenedRequest::PayoutBatch { .. } => {
+                TransactionType::Withdrawal
+            }
+        }
+    }
+}
+
+/// What a movement is screened on, gathered before it is carried out
+#[derive(Debug, Clone)]
+pub struct RiskContext {
+    pub user_identifier: Uuid,
+    pub transaction_type: TransactionType,
+    pub amount: BigDecimal,
+    pub currency_identifier: Uuid,
+    /// the currency the money arrives in, only set when it differs
+    pub destination_currency_identifier: Option<Uuid>,
+    /// when the user first paid or saved the payee, not set when there is no single payee or
+    /// the money stays with the user
+    pub payee_since: Option<DateTime<Local>>,
+}
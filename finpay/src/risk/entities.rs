@@ -0,0 +1,279 @@
+use crate::risk::adapters::{RiskContext, ScreenedRequest};
+use crate::transactions::entities::TransactionType;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "risk_rule_type_enum")]
+pub enum RiskRuleType {
+    /// fires at or above `threshold_amount`
+    AmountThreshold,
+    /// fires once more than `max_count` movements are made within `window_minutes`
+    Velocity,
+    /// fires while the payee is younger than `cooling_off_hours`
+    NewBeneficiary,
+    /// fires when `currency_identifier` is sent into `destination_currency_identifier`
+    CurrencyPair,
+}
+
+/// What a rule asks for when it fires, ordered from the least to the most severe
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "risk_action_enum")]
+pub enum RiskAction {
+    Allow,
+    Review,
+    Block,
+}
+
+/// A check every transfer and withdrawal is screened against, a null transaction type or
+/// currency matches any
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskRule {
+    pub identifier: Uuid,
+    pub name: String,
+    pub rule_type: RiskRuleType,
+    pub transaction_type: Option<TransactionType>,
+    pub currency_identifier: Option<Uuid>,
+    pub threshold_amount: Option<BigDecimal>,
+    pub max_count: Option<i32>,
+    pub window_minutes: Option<i32>,
+    pub cooling_off_hours: Option<i32>,
+    pub destination_currency_identifier: Option<Uuid>,
+    pub action: RiskAction,
+    pub score: i32,
+    pub enabled: bool,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl RiskRule {
+    /// whether the rule looks at this movement at all
+    pub fn applies_to(&self, context: &RiskContext) -> bool {
+        self.enabled
+            && self
+                .transaction_type
+                .is_none_or(|transaction_type| transaction_type == context.transaction_type)
+            && self.currency_identifier.is_none_or(|currency_identifier| {
+                currency_identifier == context.currency_identifier
+            })
+    }
+
+    /// Runs the rule against a movement, `recent_count` is how many movements the user has made
+    /// within the rule's window, this one included
+    pub fn evaluate(
+        &self,
+        context: &RiskContext,
+        recent_count: Option<u64>,
+    ) -> Option<RiskRuleHit> {
+        if !self.applies_to(context) {
+            return None;
+        }
+
+        let fired = match self.rule_type {
+            RiskRuleType::AmountThreshold => self
+                .threshold_amount
+                .as_ref()
+                .is_some_and(|threshold_amount| &context.amount >= threshold_amount),
+            RiskRuleType::Velocity => match (recent_count, self.max_count) {
+                (Some(recent_count), Some(max_count)) => recent_count > max_count as u64,
+                _ => false,
+            },
+            RiskRuleType::NewBeneficiary => match (context.payee_since, self.cooling_off_hours) {
+                (Some(payee_since), Some(cooling_off_hours)) => {
+                    Local::now() - payee_since < Duration::hours(cooling_off_hours as i64)
+                }
+                _ => false,
+            },
+            RiskRuleType::CurrencyPair => {
+                context.destination_currency_identifier.is_some()
+                    && context.destination_currency_identifier
+                        == self.destination_currency_identifier
+            }
+        };
+
+        fired.then(|| RiskRuleHit {
+            rule_identifier: self.identifier,
+            name: self.name.clone(),
+            rule_type: self.rule_type,
+            action: self.action,
+            score: self.score,
+        })
+    }
+}
+
+/// A rule that fired on a movement
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskRuleHit {
+    pub rule_identifier: Uuid,
+    pub name: String,
+    pub rule_type: RiskRuleType,
+    pub action: RiskAction,
+    pub score: i32,
+}
+
+/// The verdict on a movement, the most severe action of the rules that fired and the sum of
+/// their scores
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskAssessment {
+    pub action: RiskAction,
+    pub score: i32,
+    pub triggered_rules: Vec<RiskRuleHit>,
+}
+
+impl RiskAssessment {
+    pub fn from_hits(triggered_rules: Vec<RiskRuleHit>) -> Self {
+        Self {
+            action: triggered_rules
+                .iter()
+                .map(|hit| hit.action)
+                .max()
+                .unwrap_or(RiskAction::Allow),
+            score: triggered_rules.iter().map(|hit| hit.score).sum(),
+            triggered_rules,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "risk_review_status_enum")]
+pub enum RiskReviewStatus {
+    /// held, waiting in the review queue
+    Pending,
+    /// an admin let it through and it was carried out
+    Approved,
+    Rejected,
+    /// stopped outright, it never enters the queue
+    Blocked,
+}
+
+/// A movement the rules did not allow, with the request as it was made
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskReview {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub user_identifier: Uuid,
+    pub transaction_type: TransactionType,
+    pub request: Json<ScreenedRequest>,
+    pub amount: BigDecimal,
+    pub currency_identifier: Uuid,
+    pub action: RiskAction,
+    pub score: i32,
+    pub triggered_rules: Json<Vec<RiskRuleHit>>,
+    pub status: RiskReviewStatus,
+    /// the transaction booked once an approved transfer or payout was carried out
+    pub transaction_identifier: Option<Uuid>,
+    pub decided_by: Option<Uuid>,
+    pub decision_note: Option<String>,
+    pub decided_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(rule_type: RiskRuleType, action: RiskAction, score: i32) -> RiskRule {
+        RiskRule {
+            identifier: Uuid::new_v4(),
+            name: "test".to_string(),
+            rule_type,
+            transaction_type: None,
+            currency_identifier: None,
+            threshold_amount: Some(BigDecimal::from(1000)),
+            max_count: Some(3),
+            window_minutes: Some(10),
+            cooling_off_hours: Some(24),
+            destination_currency_identifier: None,
+            action,
+            score,
+            enabled: true,
+            created_date: Local::now(),
+            updated_at: Local::now(),
+        }
+    }
+
+    fn context(amount: i32) -> RiskContext {
+        RiskContext {
+            user_identifier: Uuid::new_v4(),
+            transaction_type: TransactionType::Transfer,
+            amount: BigDecimal::from(amount),
+            currency_identifier: Uuid::new_v4(),
+            destination_currency_identifier: None,
+            payee_since: None,
+        }
+    }
+
+    #[test]
+    fn test_rules_fire_on_their_own_conditions() {
+        let threshold = rule(RiskRuleType::AmountThreshold, RiskAction::Review, 40);
+        assert!(threshold.evaluate(&context(999), None).is_none());
+        assert!(threshold.evaluate(&context(1000), None).is_some());
+
+        let velocity = rule(RiskRuleType::Velocity, RiskAction::Block, 80);
+        assert!(velocity.evaluate(&context(1), Some(3)).is_none());
+        assert!(velocity.evaluate(&context(1), Some(4)).is_some());
+
+        let new_beneficiary = rule(RiskRuleType::NewBeneficiary, RiskAction::Review, 20);
+        let mut fresh_payee = context(1);
+        fresh_payee.payee_since = Some(Local::now() - Duration::hours(1));
+        let mut known_payee = context(1);
+        known_payee.payee_since = Some(Local::now() - Duration::days(3));
+        assert!(new_beneficiary.evaluate(&fresh_payee, None).is_some());
+        assert!(new_beneficiary.evaluate(&known_payee, None).is_none());
+        assert!(new_beneficiary.evaluate(&context(1), None).is_none());
+
+        let mut currency_pair = rule(RiskRuleType::CurrencyPair, RiskAction::Review, 10);
+        let mut cross_currency = context(1);
+        currency_pair.currency_identifier = Some(cross_currency.currency_identifier);
+        currency_pair.destination_currency_identifier = Some(Uuid::new_v4());
+        assert!(currency_pair.evaluate(&cross_currency, None).is_none());
+        cross_currency.destination_currency_identifier =
+            currency_pair.destination_currency_identifier;
+        assert!(currency_pair.evaluate(&cross_currency, None).is_some());
+    }
+
+    #[test]
+    fn test_disabled_and_out_of_scope_rules_do_not_fire() {
+        let mut disabled = rule(RiskRuleType::AmountThreshold, RiskAction::Block, 100);
+        disabled.enabled = false;
+        assert!(disabled.evaluate(&context(5000), None).is_none());
+
+        let mut withdrawals_only = rule(RiskRuleType::AmountThreshold, RiskAction::Block, 100);
+        withdrawals_only.transaction_type = Some(TransactionType::Withdrawal);
+        assert!(withdrawals_only.evaluate(&context(5000), None).is_none());
+    }
+
+    #[test]
+    fn test_assessment_takes_the_most_severe_action_and_sums_scores() {
+        let movement = context(5000);
+        let hits = [
+            rule(RiskRuleType::AmountThreshold, RiskAction::Allow, 5),
+            rule(RiskRuleType::AmountThreshold, RiskAction::Review, 30),
+        ]
+        .iter()
+        .filter_map(|rule| rule.evaluate(&movement, None))
+        .collect();
+
+        let assessment = RiskAssessment::from_hits(hits);
+        assert_eq!(assessment.action, RiskAction::Review);
+        assert_eq!(assessment.score, 35);
+
+        let clean = RiskAssessment::from_hits(vec![]);
+        assert_eq!(clean.action, RiskAction::Allow);
+        assert_eq!(clean.score, 0);
+    }
+}
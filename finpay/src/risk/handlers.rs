@@ -0,0 +1,95 @@
+use crate::errors::ServiceError;
+use crate::risk::adapters::{
+    CreateRiskRuleRequest, DecideRiskReviewRequest, RiskReviewQuery, UpdateRiskRuleRequest,
+};
+use crate::risk::entities::{RiskReview, RiskRule};
+use crate::risk::service::{RiskService, RiskServiceExt};
+use crate::shared::middlewares::admin_middleware::AdminClaims;
+use crate::shared::middlewares::validator::ValidatedRequest;
+use crate::utils::{ApiResponse, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+pub async fn create_risk_rule(
+    State(risk_service): State<RiskService>,
+    _: AdminClaims,
+    ValidatedRequest(request): ValidatedRequest<CreateRiskRuleRequest>,
+) -> Result<ApiResponse<RiskRule>, ServiceError> {
+    let rule = risk_service.create_rule(&request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(rule)
+        .message("risk rule created successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_risk_rules(
+    State(risk_service): State<RiskService>,
+    _: AdminClaims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<RiskRule>>, ServiceError> {
+    let rules = risk_service.fetch_rules(&pagination_params).await?;
+
+    Ok(ApiResponse::builder().data(rules).build())
+}
+
+pub async fn update_risk_rule(
+    State(risk_service): State<RiskService>,
+    _: AdminClaims,
+    Path(identifier): Path<Uuid>,
+    ValidatedRequest(request): ValidatedRequest<UpdateRiskRuleRequest>,
+) -> Result<ApiResponse<RiskRule>, ServiceError> {
+    let rule = risk_service.update_rule(&identifier, &request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(rule)
+        .message("risk rule updated successfully")
+        .build())
+}
+
+pub async fn fetch_risk_reviews(
+    State(risk_service): State<RiskService>,
+    _: AdminClaims,
+    Query(query): Query<RiskReviewQuery>,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<RiskReview>>, ServiceError> {
+    let reviews = risk_service
+        .fetch_reviews(&query, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(reviews).build())
+}
+
+pub async fn approve_risk_review(
+    State(risk_service): State<RiskService>,
+    AdminClaims(claims): AdminClaims,
+    Path(identifier): Path<Uuid>,
+    ValidatedRequest(request): ValidatedRequest<DecideRiskReviewRequest>,
+) -> Result<ApiResponse<RiskReview>, ServiceError> {
+    let review = risk_service
+        .approve_review(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(review)
+        .message("review approved and carried out successfully")
+        .build())
+}
+
+pub async fn reject_risk_review(
+    State(risk_service): State<RiskService>,
+    AdminClaims(claims): AdminClaims,
+    Path(identifier): Path<Uuid>,
+    ValidatedRequest(request): ValidatedRequest<DecideRiskReviewRequest>,
+) -> Result<ApiResponse<RiskReview>, ServiceError> {
+    let review = risk_service
+        .reject_review(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(review)
+        .message("review rejected successfully")
+        .build())
+}
@@ -0,0 +1,618 @@
+use crate::errors::RepositoryError;
+use crate::payouts::adapters::CreatePayoutRequest;
+use crate::payouts::entities::PayoutBatchStatus;
+use crate::risk::adapters::{
+    CreateRiskRuleRequest, DecideRiskReviewRequest, RiskContext, RiskReviewQuery, ScreenedRequest,
+    UpdateRiskRuleRequest,
+};
+use crate::risk::entities::{RiskAction, RiskAssessment, RiskReview, RiskReviewStatus, RiskRule};
+use crate::transactions::adapters::CreateTransferRequest;
+use crate::transactions::entities::{TransactionStatus, TransactionType};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use finpay_utils::generate_reference;
+use sqlx::PgPool;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct RiskRepository {
+    pool: PgPool,
+}
+
+impl RiskRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Moves a pending review on, the review is left alone if someone else got to it first
+    async fn decide(
+        &self,
+        identifier: &Uuid,
+        status: RiskReviewStatus,
+        decided_by: &Uuid,
+        request: &DecideRiskReviewRequest,
+    ) -> Result<RiskReview, RepositoryError> {
+        let query = r#"
+        UPDATE risk_reviews
+        SET status        = $1,
+            decided_by    = $2,
+            decision_note = $3,
+            decided_date  = NOW()
+        WHERE identifier = $4
+          AND status = $5
+        RETURNING *
+        "#;
+        let review = sqlx::query_as::<_, RiskReview>(query)
+            .bind(status)
+            .bind(decided_by)
+            .bind(&request.note)
+            .bind(identifier)
+            .bind(RiskReviewStatus::Pending)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match review {
+            Some(review) => Ok(review),
+            None => {
+                sqlx::query_scalar::<_, Uuid>(
+                    r#"SELECT identifier FROM risk_reviews WHERE identifier = $1"#,
+                )
+                .bind(identifier)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or(RepositoryError::RecordNotFound)?;
+                Err(RepositoryError::InvalidReviewState)
+            }
+        }
+    }
+}
+
+pub trait RiskRepositoryExt {
+    /// What a transfer is screened on, nothing when the source wallet is not the user's or the
+    /// destination does not exist, the transfer reports that itself
+    fn transfer_context(
+        &self,
+        request: &CreateTransferRequest,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<RiskContext>, RepositoryError>> + Send;
+
+    fn payout_context(
+        &self,
+        request: &CreatePayoutRequest,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<RiskContext>, RepositoryError>> + Send;
+
+    /// A batch is screened as a single withdrawal of everything it pays out, only batches that
+    /// are waiting to be committed are screened
+    fn payout_batch_context(
+        &self,
+        batch_identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<RiskContext>, RepositoryError>> + Send;
+
+    fn fetch_enabled_rules(
+        &self,
+        transaction_type: TransactionType,
+    ) -> impl std::future::Future<Output = Result<Vec<RiskRule>, RepositoryError>> + Send;
+
+    fn create_rule(
+        &self,
+        request: &CreateRiskRuleRequest,
+    ) -> impl std::future::Future<Output = Result<RiskRule, RepositoryError>> + Send;
+
+    fn fetch_rules(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<RiskRule>, RepositoryError>> + Send;
+
+    fn update_rule(
+        &self,
+        identifier: &Uuid,
+        request: &UpdateRiskRuleRequest,
+    ) -> impl std::future::Future<Output = Result<RiskRule, RepositoryError>> + Send;
+
+    /// Keeps a movement the rules did not allow, held ones join the review queue
+    fn record_review(
+        &self,
+        context: &RiskContext,
+        request: &ScreenedRequest,
+        assessment: &RiskAssessment,
+    ) -> impl std::future::Future<Output = Result<RiskReview, RepositoryError>> + Send;
+
+    fn fetch_reviews(
+        &self,
+        query: &RiskReviewQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<RiskReview>, RepositoryError>> + Send;
+
+    /// Marks a pending review as approved before it is carried out, so that it cannot be
+    /// carried out twice
+    fn claim_review(
+        &self,
+        identifier: &Uuid,
+        decided_by: &Uuid,
+        request: &DecideRiskReviewRequest,
+    ) -> impl std::future::Future<Output = Result<RiskReview, RepositoryError>> + Send;
+
+    /// Puts a claimed review back in the queue when carrying it out failed
+    fn reopen_review(
+        &self,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<(), RepositoryError>> + Send;
+
+    fn complete_review(
+        &self,
+        identifier: &Uuid,
+        transaction_identifier: Option<Uuid>,
+    ) -> impl std::future::Future<Output = Result<RiskReview, RepositoryError>> + Send;
+
+    fn reject_review(
+        &self,
+        identifier: &Uuid,
+        decided_by: &Uuid,
+        request: &DecideRiskReviewRequest,
+    ) -> impl std::future::Future<Output = Result<RiskReview, RepositoryError>> + Send;
+}
+
+impl RiskRepositoryExt for RiskRepository {
+    async fn transfer_context(
+        &self,
+        request: &CreateTransferRequest,
+        user_identifier: &Uuid,
+    ) -> Result<Option<RiskContext>, RepositoryError> {
+        let query = r#"
+        SELECT source.currency_identifier, destination.currency_identifier, destination.user_identifier
+        FROM wallets source,
+             wallets destination
+        WHERE source.identifier = $1
+          AND source.user_identifier = $2
+          AND destination.identifier = $3
+        "#;
+        let wallets = sqlx::query_as::<_, (Uuid, Uuid, Uuid)>(query)
+            .bind(request.source_wallet_identifier)
+            .bind(user_identifier)
+            .bind(request.destination_wallet_identifier)
+            .fetch_optional(&self.pool)
+            .await?;
+        let Some((currency_identifier, destination_currency_identifier, payee_identifier)) =
+            wallets
+        else {
+            return Ok(None);
+        };
+
+        // money moved between the user's own wallets has no payee to cool off
+        let payee_since = if &payee_identifier == user_identifier {
+            None
+        } else {
+            let query = r#"
+            SELECT MIN(transactions.created_date)
+            FROM transactions
+                     JOIN wallets ON wallets.identifier = transactions.source_wallet_identifier
+            WHERE wallets.user_identifier = $1
+              AND transactions.destination_wallet_identifier = $2
+              AND transactions.transaction_type = $3
+              AND transactions.status = $4
+            "#;
+            let first_paid: Option<DateTime<Local>> = sqlx::query_scalar(query)
+                .bind(user_identifier)
+                .bind(request.destination_wallet_identifier)
+                .bind(TransactionType::Transfer)
+                .bind(TransactionStatus::Completed)
+                .fetch_one(&self.pool)
+                .await?;
+            Some(first_paid.unwrap_or_else(Local::now))
+        };
+
+        Ok(Some(RiskContext {
+            user_identifier: *user_identifier,
+            transaction_type: TransactionType::Transfer,
+            amount: request.amount.clone(),
+            currency_identifier,
+            destination_currency_identifier: (destination_currency_identifier
+                != currency_identifier)
+                .then_some(destination_currency_identifier),
+            payee_since,
+        }))
+    }
+
+    async fn payout_context(
+        &self,
+        request: &CreatePayoutRequest,
+        user_identifier: &Uuid,
+    ) -> Result<Option<RiskContext>, RepositoryError> {
+        let query = r#"
+        SELECT wallets.currency_identifier, beneficiaries.created_date
+        FROM wallets
+                 JOIN beneficiaries ON beneficiaries.user_identifier = wallets.user_identifier
+        WHERE wallets.identifier = $1
+          AND wallets.user_identifier = $2
+          AND beneficiaries.identifier = $3
+        "#;
+        let context = sqlx::query_as::<_, (Uuid, DateTime<Local>)>(query)
+            .bind(request.wallet_identifier)
+            .bind(user_identifier)
+            .bind(request.beneficiary_identifier)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(|(currency_identifier, payee_since)| RiskContext {
+                user_identifier: *user_identifier,
+                transaction_type: TransactionType::Withdrawal,
+                amount: request.amount.clone(),
+                currency_identifier,
+                destination_currency_identifier: None,
+                payee_since: Some(payee_since),
+            });
+        Ok(context)
+    }
+
+    async fn payout_batch_context(
+        &self,
+        batch_identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<RiskContext>, RepositoryError> {
+        let query = r#"
+        SELECT wallets.currency_identifier, payout_batches.total_amount
+        FROM payout_batches
+                 JOIN wallets ON wallets.identifier = payout_batches.wallet_identifier
+        WHERE payout_batches.identifier = $1
+          AND payout_batches.user_identifier = $2
+          AND payout_batches.status = $3
+        "#;
+        let context = sqlx::query_as::<_, (Uuid, BigDecimal)>(query)
+            .bind(batch_identifier)
+            .bind(user_identifier)
+            .bind(PayoutBatchStatus::Validated)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(|(currency_identifier, amount)| RiskContext {
+                user_identifier: *user_identifier,
+                transaction_type: TransactionType::Withdrawal,
+                amount,
+                currency_identifier,
+                destination_currency_identifier: None,
+                payee_since: None,
+            });
+        Ok(context)
+    }
+
+    async fn fetch_enabled_rules(
+        &self,
+        transaction_type: TransactionType,
+    ) -> Result<Vec<RiskRule>, RepositoryError> {
+        let query = r#"
+        SELECT *
+        FROM risk_rules
+        WHERE enabled
+          AND (transaction_type IS NULL OR transaction_type = $1)
+        "#;
+        let rules = sqlx::query_as::<_, RiskRule>(query)
+            .bind(transaction_type)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rules)
+    }
+
+    async fn create_rule(
+        &self,
+        request: &CreateRiskRuleRequest,
+    ) -> Result<RiskRule, RepositoryError> {
+        let query = r#"
+        INSERT INTO risk_rules (identifier, name, rule_type, transaction_type, currency_identifier,
+                                threshold_amount, max_count, window_minutes, cooling_off_hours,
+                                destination_currency_identifier, action, score)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING *
+        "#;
+        let rule = sqlx::query_as::<_, RiskRule>(query)
+            .bind(Uuid::new_v4())
+            .bind(&request.name)
+            .bind(request.rule_type)
+            .bind(request.transaction_type)
+            .bind(request.currency_identifier)
+            .bind(&request.threshold_amount)
+            .bind(request.max_count)
+            .bind(request.window_minutes)
+            .bind(request.cooling_off_hours)
+            .bind(request.destination_currency_identifier)
+            .bind(request.action)
+            .bind(request.score)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(rule)
+    }
+
+    async fn fetch_rules(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<RiskRule>, RepositoryError> {
+        let total_count: i64 = sqlx::query_scalar("SELECT COUNT(identifier) FROM risk_rules")
+            .fetch_one(&self.pool)
+            .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let rules = sqlx::query_as::<_, RiskRule>(
+            r#"SELECT * FROM risk_rules ORDER BY created_date DESC LIMIT $1 OFFSET $2"#,
+        )
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            rules,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn update_rule(
+        &self,
+        identifier: &Uuid,
+        request: &UpdateRiskRuleRequest,
+    ) -> Result<RiskRule, RepositoryError> {
+        let query = r#"
+        UPDATE risk_rules
+        SET name              = COALESCE($1, name),
+            threshold_amount  = COALESCE($2, threshold_amount),
+            max_count         = COALESCE($3, max_count),
+            window_minutes    = COALESCE($4, window_minutes),
+            cooling_off_hours = COALESCE($5, cooling_off_hours),
+            action            = COALESCE($6, action),
+            score             = COALESCE($7, score),
+            enabled           = COALESCE($8, enabled)
+        WHERE identifier = $9
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, RiskRule>(query)
+            .bind(&request.name)
+            .bind(&request.threshold_amount)
+            .bind(request.max_count)
+            .bind(request.window_minutes)
+            .bind(request.cooling_off_hours)
+            .bind(request.action)
+            .bind(request.score)
+            .bind(request.enabled)
+            .bind(identifier)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)
+    }
+
+    async fn record_review(
+        &self,
+        context: &RiskContext,
+        request: &ScreenedRequest,
+        assessment: &RiskAssessment,
+    ) -> Result<RiskReview, RepositoryError> {
+        let status = match assessment.action {
+            RiskAction::Block => RiskReviewStatus::Blocked,
+            _ => RiskReviewStatus::Pending,
+        };
+
+        let query = r#"
+        INSERT INTO risk_reviews (identifier, reference, user_identifier, transaction_type, request,
+                                  amount, currency_identifier, action, score, triggered_rules,
+                                  status)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#;
+        let review = sqlx::query_as::<_, RiskReview>(query)
+            .bind(Uuid::new_v4())
+            .bind(generate_reference("RSK"))
+            .bind(context.user_identifier)
+            .bind(context.transaction_type)
+            .bind(Json(request))
+            .bind(&context.amount)
+            .bind(context.currency_identifier)
+            .bind(assessment.action)
+            .bind(assessment.score)
+            .bind(Json(&assessment.triggered_rules))
+            .bind(status)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(review)
+    }
+
+    async fn fetch_reviews(
+        &self,
+        query: &RiskReviewQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<RiskReview>, RepositoryError> {
+        let status = query.status.unwrap_or(RiskReviewStatus::Pending);
+
+        let total_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(identifier) FROM risk_reviews WHERE status = $1")
+                .bind(status)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        // the highest scores first, then the ones that have waited the longest
+        let reviews = sqlx::query_as::<_, RiskReview>(
+            r#"
+            SELECT *
+            FROM risk_reviews
+            WHERE status = $1
+            ORDER BY score DESC, created_date
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            reviews,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn claim_review(
+        &self,
+        identifier: &Uuid,
+        decided_by: &Uuid,
+        request: &DecideRiskReviewRequest,
+    ) -> Result<RiskReview, RepositoryError> {
+        self.decide(identifier, RiskReviewStatus::Approved, decided_by, request)
+            .await
+    }
+
+    async fn reopen_review(&self, identifier: &Uuid) -> Result<(), RepositoryError> {
+        let query = r#"
+        UPDATE risk_reviews
+        SET status        = $1,
+            decided_by    = NULL,
+            decision_note = NULL,
+            decided_date  = NULL
+        WHERE identifier = $2
+        "#;
+        sqlx::query(query)
+            .bind(RiskReviewStatus::Pending)
+            .bind(identifier)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn complete_review(
+        &self,
+        identifier: &Uuid,
+        transaction_identifier: Option<Uuid>,
+    ) -> Result<RiskReview, RepositoryError> {
+        let review = sqlx::query_as::<_, RiskReview>(
+            r#"UPDATE risk_reviews SET transaction_identifier = $1 WHERE identifier = $2 RETURNING *"#,
+        )
+        .bind(transaction_identifier)
+        .bind(identifier)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(review)
+    }
+
+    async fn reject_review(
+        &self,
+        identifier: &Uuid,
+        decided_by: &Uuid,
+        request: &DecideRiskReviewRequest,
+    ) -> Result<RiskReview, RepositoryError> {
+        self.decide(identifier, RiskReviewStatus::Rejected, decided_by, request)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk::entities::{RiskRuleHit, RiskRuleType};
+    use crate::shared::fixtures::{create_user, create_wallet};
+
+    fn transfer(source: Uuid, destination: Uuid) -> CreateTransferRequest {
+        CreateTransferRequest {
+            source_wallet_identifier: source,
+            destination_wallet_identifier: destination,
+            amount: BigDecimal::from(50),
+            description: None,
+            quote_identifier: None,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_only_transfers_to_someone_else_have_a_payee(pool: PgPool) {
+        let repository = RiskRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let source = create_wallet(&pool, &user_identifier).await;
+        let own_wallet = create_wallet(&pool, &user_identifier).await;
+        let recipient_identifier = create_user(&pool).await;
+        let recipient_wallet = create_wallet(&pool, &recipient_identifier).await;
+
+        let own = repository
+            .transfer_context(&transfer(source, own_wallet), &user_identifier)
+            .await
+            .unwrap()
+            .expect("own wallets should be screened");
+        assert!(own.payee_since.is_none());
+        assert!(own.destination_currency_identifier.is_none());
+
+        // never paid before, so the payee is as new as it gets
+        let started = Local::now();
+        let other = repository
+            .transfer_context(&transfer(source, recipient_wallet), &user_identifier)
+            .await
+            .unwrap()
+            .expect("transfers to other users should be screened");
+        assert!(other.payee_since.is_some_and(|since| since >= started));
+
+        // someone else's wallet cannot be screened as the source
+        let foreign = repository
+            .transfer_context(&transfer(recipient_wallet, source), &user_identifier)
+            .await
+            .unwrap();
+        assert!(foreign.is_none());
+    }
+
+    #[sqlx::test]
+    async fn test_held_review_is_decided_only_once(pool: PgPool) {
+        let repository = RiskRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let admin_identifier = create_user(&pool).await;
+        let source = create_wallet(&pool, &user_identifier).await;
+        let destination = create_wallet(&pool, &user_identifier).await;
+
+        let request = ScreenedRequest::Transfer(transfer(source, destination));
+        let context = repository
+            .transfer_context(&transfer(source, destination), &user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        let assessment = RiskAssessment::from_hits(vec![RiskRuleHit {
+            rule_identifier: Uuid::new_v4(),
+            name: "large transfer".to_string(),
+            rule_type: RiskRuleType::AmountThreshold,
+            action: RiskAction::Review,
+            score: 40,
+        }]);
+
+        let review = repository
+            .record_review(&context, &request, &assessment)
+            .await
+            .expect("failed to record review");
+        assert_eq!(review.status, RiskReviewStatus::Pending);
+        assert_eq!(review.score, 40);
+
+        let queue = repository
+            .fetch_reviews(&RiskReviewQuery::default(), &PaginationParams::default())
+            .await
+            .unwrap();
+        assert_eq!(queue.records.len(), 1);
+
+        let decision = DecideRiskReviewRequest { note: None };
+        let approved = repository
+            .claim_review(&review.identifier, &admin_identifier, &decision)
+            .await
+            .expect("failed to claim review");
+        assert_eq!(approved.status, RiskReviewStatus::Approved);
+
+        let rejected = repository
+            .reject_review(&review.identifier, &admin_identifier, &decision)
+            .await;
+        assert!(matches!(rejected, Err(RepositoryError::InvalidReviewState)));
+
+        repository.reopen_review(&review.identifier).await.unwrap();
+        let rejected = repository
+            .reject_review(&review.identifier, &admin_identifier, &decision)
+            .await
+            .expect("a reopened review can be decided again");
+        assert_eq!(rejected.status, RiskReviewStatus::Rejected);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::risk::handlers::{
+    approve_risk_review, create_risk_rule, fetch_risk_reviews, fetch_risk_rules,
+    reject_risk_review, update_risk_rule,
+};
+use crate::shared::middlewares::idempotency::{IdempotencyStore, enforce_idempotency};
+use crate::state::AppState;
+use axum::{
+    Router,
+    extract::FromRef,
+    middleware::from_fn_with_state,
+    routing::{get, post, put},
+};
+
+pub fn admin_risk_routes(state: &AppState) -> Router {
+    let idempotency_store = IdempotencyStore::from_ref(state);
+
+    Router::new()
+        .route("/rules", post(create_risk_rule))
+        .route("/rules", get(fetch_risk_rules))
+        .route("/rules/{identifier}", put(update_risk_rule))
+        .route("/reviews", get(fetch_risk_reviews))
+        .route(
+            "/reviews/{identifier}/approve",
+            post(approve_risk_review)
+                .layer(from_fn_with_state(idempotency_store, enforce_idempotency)),
+        )
+        .route("/reviews/{identifier}/reject", post(reject_risk_review))
+        .with_state(state.clone())
+}
@@ -0,0 +1,319 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::payouts::repository::{PayoutRepository, PayoutRepositoryExt};
+use crate::payouts::service::record_batch_result;
+use crate::risk::adapters::{
+    CreateRiskRuleRequest, DecideRiskReviewRequest, RiskContext, RiskReviewQuery, ScreenedRequest,
+    UpdateRiskRuleRequest,
+};
+use crate::risk::entities::{RiskAction, RiskAssessment, RiskReview, RiskRule, RiskRuleType};
+use crate::risk::repository::{RiskRepository, RiskRepositoryExt};
+use crate::transactions::entities::TransactionType;
+use crate::utils::{PaginatedResponse, PaginationParams};
+use crate::wallet::repository::{WalletRepository, WalletRepositoryExt};
+use finpay_redis::{RedisClient, RedisClientExt};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct RiskService {
+    repository: RiskRepository,
+    wallet_repository: WalletRepository,
+    payout_repository: PayoutRepository,
+}
+
+impl RiskService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: RiskRepository::new(pool),
+            wallet_repository: WalletRepository::new(pool.clone()),
+            payout_repository: PayoutRepository::new(pool),
+        }
+    }
+
+    /// Counts the movement against every velocity rule it falls under, each rule keeps its own
+    /// window per user
+    async fn recent_counts(
+        &self,
+        rules: &[RiskRule],
+        context: &RiskContext,
+    ) -> Result<HashMap<Uuid, u64>, ServiceError> {
+        let mut recent_counts = HashMap::new();
+        let velocity_rules: Vec<&RiskRule> = rules
+            .iter()
+            .filter(|rule| rule.rule_type == RiskRuleType::Velocity && rule.applies_to(context))
+            .collect();
+        if velocity_rules.is_empty() {
+            return Ok(recent_counts);
+        }
+
+        let mut redis_client = RedisClient::new().await?;
+        for rule in velocity_rules {
+            let key = format!("{}:{}", rule.identifier, context.user_identifier);
+            let window_secs = rule.window_minutes.unwrap_or_default() as u64 * 60;
+            let count = redis_client
+                .increment_velocity_counter(&key, window_secs)
+                .await?;
+            recent_counts.insert(rule.identifier, count);
+        }
+
+        Ok(recent_counts)
+    }
+
+    async fn assess(&self, context: &RiskContext) -> Result<RiskAssessment, ServiceError> {
+        let rules = self
+            .repository
+            .fetch_enabled_rules(context.transaction_type)
+            .await?;
+        let recent_counts = self.recent_counts(&rules, context).await?;
+
+        let hits = rules
+            .iter()
+            .filter_map(|rule| rule.evaluate(context, recent_counts.get(&rule.identifier).copied()))
+            .collect();
+        Ok(RiskAssessment::from_hits(hits))
+    }
+
+    /// Carries out an approved movement on behalf of the user who asked for it, returns the
+    /// transaction it booked
+    async fn execute(&self, review: &RiskReview) -> Result<Option<Uuid>, ServiceError> {
+        let transaction_identifier = match &review.request.0 {
+            ScreenedRequest::Transfer(request) => {
+                let transaction = self
+                    .wallet_repository
+                    .transfer(request, &review.user_identifier)
+                    .await?;
+                Some(transaction.identifier)
+            }
+            ScreenedRequest::Payout(request) => {
+                let payout = self
+                    .payout_repository
+                    .queue(request, &review.user_identifier)
+                    .await?;
+                Some(payout.transaction_identifier)
+            }
+            ScreenedRequest::PayoutBatch { batch_identifier } => {
+                let mut batch = self
+                    .payout_repository
+                    .execute_batch(batch_identifier, &review.user_identifier)
+                    .await?;
+                record_batch_result(&self.payout_repository, &mut batch).await?;
+                None
+            }
+        };
+
+        Ok(transaction_identifier)
+    }
+}
+
+pub trait RiskServiceExt {
+    /// Runs a movement past every enabled rule before it is carried out. Anything the rules do
+    /// not allow is kept, held movements wait in the review queue and come back as
+    /// `TransactionHeldForReview`, blocked ones as `TransactionBlocked`
+    fn screen(
+        &self,
+        user_identifier: &Uuid,
+        request: ScreenedRequest,
+    ) -> impl std::future::Future<Output = Result<(), ServiceError>> + Send;
+
+    fn create_rule(
+        &self,
+        request: &CreateRiskRuleRequest,
+    ) -> impl std::future::Future<Output = Result<RiskRule, ServiceError>> + Send;
+
+    fn fetch_rules(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<RiskRule>, ServiceError>> + Send;
+
+    fn update_rule(
+        &self,
+        identifier: &Uuid,
+        request: &UpdateRiskRuleRequest,
+    ) -> impl std::future::Future<Output = Result<RiskRule, ServiceError>> + Send;
+
+    fn fetch_reviews(
+        &self,
+        query: &RiskReviewQuery,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<RiskReview>, ServiceError>> + Send;
+
+    /// Lets a held movement through and carries it out, it goes back in the queue if it fails
+    fn approve_review(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &DecideRiskReviewRequest,
+    ) -> impl std::future::Future<Output = Result<RiskReview, ServiceError>> + Send;
+
+    fn reject_review(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &DecideRiskReviewRequest,
+    ) -> impl std::future::Future<Output = Result<RiskReview, ServiceError>> + Send;
+}
+
+impl RiskServiceExt for RiskService {
+    async fn screen(
+        &self,
+        user_identifier: &Uuid,
+        request: ScreenedRequest,
+    ) -> Result<(), ServiceError> {
+        let context = match &request {
+            ScreenedRequest::Transfer(transfer) => {
+                self.repository
+                    .transfer_context(transfer, user_identifier)
+                    .await?
+            }
+            ScreenedRequest::Payout(payout) => {
+                self.repository
+                    .payout_context(payout, user_identifier)
+                    .await?
+            }
+            ScreenedRequest::PayoutBatch { batch_identifier } => {
+                self.repository
+                    .payout_batch_context(batch_identifier, user_identifier)
+                    .await?
+            }
+        };
+        // nothing to screen, the movement itself reports why it cannot go ahead
+        let Some(context) = context else {
+            return Ok(());
+        };
+
+        let assessment = self.assess(&context).await?;
+        if assessment.action == RiskAction::Allow {
+            return Ok(());
+        }
+
+        let review = self
+            .repository
+            .record_review(&context, &request, &assessment)
+            .await?;
+        log::warn!(
+            "{} by user {} scored {} and was {}",
+            review.reference,
+            user_identifier,
+            assessment.score,
+            if assessment.action == RiskAction::Block {
+                "blocked"
+            } else {
+                "held for review"
+            }
+        );
+
+        match assessment.action {
+            RiskAction::Block => Err(ServiceError::TransactionBlocked),
+            _ => Err(ServiceError::TransactionHeldForReview(review.reference)),
+        }
+    }
+
+    async fn create_rule(&self, request: &CreateRiskRuleRequest) -> Result<RiskRule, ServiceError> {
+        if request.transaction_type.is_some_and(|transaction_type| {
+            !matches!(
+                transaction_type,
+                TransactionType::Transfer | TransactionType::Withdrawal
+            )
+        }) {
+            return Err(ServiceError::UnprocessableEntity(
+                "only transfers and withdrawals are screened".to_string(),
+            ));
+        }
+
+        let missing = match request.rule_type {
+            RiskRuleType::AmountThreshold => request
+                .threshold_amount
+                .is_none()
+                .then_some("thresholdAmount is required for amount thresholds"),
+            RiskRuleType::Velocity => (request.max_count.is_none()
+                || request.window_minutes.is_none())
+            .then_some("maxCount and windowMinutes are required for velocity rules"),
+            RiskRuleType::NewBeneficiary => request
+                .cooling_off_hours
+                .is_none()
+                .then_some("coolingOffHours is required for new beneficiary rules"),
+            RiskRuleType::CurrencyPair => (request.currency_identifier.is_none()
+                || request.destination_currency_identifier.is_none()
+                || request.currency_identifier == request.destination_currency_identifier)
+                .then_some(
+                    "currencyIdentifier and a different destinationCurrencyIdentifier are required for currency pairs",
+                ),
+        };
+        if let Some(message) = missing {
+            return Err(ServiceError::UnprocessableEntity(message.to_string()));
+        }
+
+        let rule = self.repository.create_rule(request).await?;
+        Ok(rule)
+    }
+
+    async fn fetch_rules(
+        &self,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<RiskRule>, ServiceError> {
+        let rules = self.repository.fetch_rules(pagination_params).await?;
+        Ok(rules)
+    }
+
+    async fn update_rule(
+        &self,
+        identifier: &Uuid,
+        request: &UpdateRiskRuleRequest,
+    ) -> Result<RiskRule, ServiceError> {
+        let rule = self.repository.update_rule(identifier, request).await?;
+        Ok(rule)
+    }
+
+    async fn fetch_reviews(
+        &self,
+        query: &RiskReviewQuery,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<RiskReview>, ServiceError> {
+        let reviews = self
+            .repository
+            .fetch_reviews(query, pagination_params)
+            .await?;
+        Ok(reviews)
+    }
+
+    async fn approve_review(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &DecideRiskReviewRequest,
+    ) -> Result<RiskReview, ServiceError> {
+        let review = self
+            .repository
+            .claim_review(identifier, &claims.user_identifier, request)
+            .await?;
+
+        match self.execute(&review).await {
+            Ok(transaction_identifier) => {
+                let review = self
+                    .repository
+                    .complete_review(identifier, transaction_identifier)
+                    .await?;
+                Ok(review)
+            }
+            Err(err) => {
+                self.repository.reopen_review(identifier).await?;
+                Err(err)
+            }
+        }
+    }
+
+    async fn reject_review(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &DecideRiskReviewRequest,
+    ) -> Result<RiskReview, ServiceError> {
+        let review = self
+            .repository
+            .reject_review(identifier, &claims.user_identifier, request)
+            .await?;
+        Ok(review)
+    }
+}
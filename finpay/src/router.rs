@@ -1,8 +1,23 @@
 use std::sync::Arc;
 
+use crate::accounts::router::account_routes;
+use crate::bank_statements::router::bank_statement_routes;
 use crate::banks::router::banks_routes;
+use crate::beneficiaries::router::beneficiary_routes;
+use crate::compliance::router::admin_compliance_routes;
 use crate::countries::router::country_routes;
-use crate::wallet::router::wallet_routes;
+use crate::disputes::router::{admin_dispute_routes, dispute_routes};
+use crate::fees::router::{admin_fee_routes, fee_routes};
+use crate::fx::router::fx_routes;
+use crate::holds::router::hold_routes;
+use crate::invoices::router::invoice_routes;
+use crate::ledger::router::ledger_routes;
+use crate::payouts::router::{admin_payout_routes, payout_routes};
+use crate::reconciliation::router::reconciliation_routes;
+use crate::risk::router::admin_risk_routes;
+use crate::statements::router::statement_routes;
+use crate::transactions::router::{admin_transaction_routes, transaction_routes};
+use crate::wallet::router::{admin_wallet_routes, wallet_routes};
 use crate::{
     authentication::router::authentication_routers,
     state::AppState,
@@ -23,6 +38,26 @@ pub fn load_routes(pool: Arc<Pool<sqlx::Postgres>>) -> Router {
         .nest("/countries", country_routes(&state))
         .nest("/wallet", wallet_routes(&state))
         .nest("/banks", banks_routes(&state))
+        .nest("/accounts", account_routes(&state))
+        .nest("/beneficiaries", beneficiary_routes(&state))
+        .nest("/payouts", payout_routes(&state))
+        .nest("/holds", hold_routes(&state))
+        .nest("/fx", fx_routes(&state))
+        .nest("/fees", fee_routes(&state))
+        .nest("/transactions", transaction_routes(&state))
+        .nest("/disputes", dispute_routes(&state))
+        .nest("/statements", statement_routes(&state))
+        .nest("/bank-statements", bank_statement_routes(&state))
+        .nest("/invoices", invoice_routes(&state))
+        .nest("/ledger", ledger_routes(&state))
+        .nest("/admin/reconciliation", reconciliation_routes(&state))
+        .nest("/admin/wallets", admin_wallet_routes(&state))
+        .nest("/admin/fees", admin_fee_routes(&state))
+        .nest("/admin/transactions", admin_transaction_routes(&state))
+        .nest("/admin/disputes", admin_dispute_routes(&state))
+        .nest("/admin/payouts", admin_payout_routes(&state))
+        .nest("/admin/risk", admin_risk_routes(&state))
+        .nest("/admin/compliance", admin_compliance_routes(&state))
         .route("/health", get(async move || "Healthy..."))
 
         .fallback(async || {
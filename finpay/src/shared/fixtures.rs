@@ -0,0 +1,80 @@
+//! Records the repository tests build on
+
+use crate::ledger::adapters::NewJournalEntry;
+use crate::ledger::entities::SystemAccount;
+use crate::ledger::repository::LedgerRepository;
+use crate::users::adapters::CreateUserRequest;
+use crate::users::repositories::{UsersRepository, UsersRepositoryExt};
+use crate::wallet::adapters::CreateWalletRequest;
+use crate::wallet::repository::{WalletRepository, WalletRepositoryExt};
+use bigdecimal::BigDecimal;
+use fake::{Fake, Faker};
+use sqlx::PgPool;
+use std::str::FromStr;
+use uuid::Uuid;
+
+pub const UAE_DIRHAM: &str = "e829463e-a7f0-461c-b094-da566ad82801";
+pub const AFGHAN_AFGHANI: &str = "0a7680f9-d402-4c36-8749-ff5083d1ee85";
+
+pub async fn create_user(pool: &PgPool) -> Uuid {
+    let create_user_request: CreateUserRequest = Faker.fake();
+    UsersRepository::new(pool)
+        .create_account(&create_user_request)
+        .await
+        .expect("failed to create user")
+        .identifier
+}
+
+/// a dirham wallet
+pub async fn create_wallet(pool: &PgPool, user_identifier: &Uuid) -> Uuid {
+    create_wallet_in(pool, user_identifier, UAE_DIRHAM).await
+}
+
+pub async fn create_wallet_in(
+    pool: &PgPool,
+    user_identifier: &Uuid,
+    currency_identifier: &str,
+) -> Uuid {
+    let request = CreateWalletRequest {
+        name: Faker.fake(),
+        currency_identifier: currency_identifier.to_string(),
+    };
+    WalletRepository::new(pool.clone())
+        .create(&request, user_identifier)
+        .await
+        .expect("failed to create wallet")
+}
+
+/// credits the wallet out of opening balances, the way money that predates finpay comes in
+pub async fn fund(pool: &PgPool, wallet_identifier: &Uuid, amount: &str) {
+    let amount = BigDecimal::from_str(amount).unwrap();
+    let mut tx = pool.begin().await.unwrap();
+
+    let wallet_account = LedgerRepository::wallet_account(&mut tx, wallet_identifier)
+        .await
+        .unwrap();
+    let opening_balances = LedgerRepository::system_account(
+        &mut tx,
+        SystemAccount::OpeningBalances,
+        &wallet_account.currency_identifier,
+    )
+    .await
+    .unwrap();
+
+    let entry = NewJournalEntry::new("test funding")
+        .debit(&opening_balances, &amount)
+        .credit(&wallet_account, &amount);
+    LedgerRepository::record_entry(&mut tx, &entry)
+        .await
+        .expect("failed to fund wallet");
+
+    tx.commit().await.unwrap();
+}
+
+pub async fn balance(pool: &PgPool, wallet_identifier: &Uuid) -> BigDecimal {
+    sqlx::query_scalar("SELECT balance FROM wallets WHERE identifier = $1")
+        .bind(wallet_identifier)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
@@ -0,0 +1,35 @@
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::request::Parts;
+
+use crate::authentication::claims::Claims;
+use crate::errors::AuthenticationError;
+use crate::users::service::{UsersService, UsersServiceExt};
+
+/// Claims of an authenticated user that has been granted admin rights
+pub struct AdminClaims(pub Claims);
+
+impl<S> FromRequestParts<S> for AdminClaims
+where
+    UsersService: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthenticationError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = Claims::from_request_parts(parts, state).await?;
+
+        let user = UsersService::from_ref(state)
+            .find_user_by_pk(&claims.user_identifier)
+            .await
+            .map_err(|err| {
+                log::error!("failed to fetch admin user due to {err}");
+                AuthenticationError::Unauthenticated
+            })?;
+
+        if !user.is_admin {
+            return Err(AuthenticationError::Forbidden);
+        }
+
+        Ok(AdminClaims(claims))
+    }
+}
@@ -0,0 +1,469 @@
+use axum::body::{Body, Bytes, to_bytes};
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, Method, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use finpay_redis::{RedisClient, RedisClientExt};
+use finpay_utils::extract_env;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// What is stored against an idempotency key, the response is empty while the first request
+/// is still being processed
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdempotencyRecord {
+    pub fingerprint: String,
+    pub status_code: Option<i32>,
+    pub response_body: Option<String>,
+}
+
+impl IdempotencyRecord {
+    fn in_progress(fingerprint: &str) -> Self {
+        Self {
+            fingerprint: fingerprint.to_string(),
+            status_code: None,
+            response_body: None,
+        }
+    }
+
+    fn completed(fingerprint: &str, status_code: StatusCode, body: &Bytes) -> Self {
+        Self {
+            fingerprint: fingerprint.to_string(),
+            status_code: Some(status_code.as_u16() as i32),
+            response_body: Some(String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+
+    /// Rebuilds the stored response for a retried request
+    fn replay(self, fingerprint: &str) -> Result<Response, ServiceError> {
+        if self.fingerprint != fingerprint {
+            return Err(ServiceError::IdempotencyKeyReused);
+        }
+
+        let (Some(status_code), Some(response_body)) = (self.status_code, self.response_body)
+        else {
+            return Err(ServiceError::IdempotentRequestInProgress);
+        };
+
+        let status_code =
+            StatusCode::from_u16(status_code as u16).map_err(|_| ServiceError::OperationFailed)?;
+
+        let mut response = (status_code, response_body).into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+        Ok(response)
+    }
+}
+
+/// Hashes everything that identifies a request, so a reused key can be told apart from a retry
+pub fn request_fingerprint(method: &Method, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Keeps idempotency records in redis, falling back to postgres whenever redis is unavailable
+#[derive(Clone)]
+pub struct IdempotencyStore {
+    pool: PgPool,
+    /// connected on first use and shared by every request after that
+    redis_client: Arc<OnceCell<RedisClient>>,
+}
+
+impl IdempotencyStore {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            pool: pool.clone(),
+            redis_client: Arc::new(OnceCell::new()),
+        }
+    }
+
+    fn ttl_secs() -> u64 {
+        let ttl_in_hours: u64 = extract_env("IDEMPOTENCY_KEY_TTL_IN_HOURS");
+        ttl_in_hours * 60 * 60
+    }
+
+    /// A failed connection is not kept, the next request tries again
+    async fn redis_client(&self) -> Option<RedisClient> {
+        self.redis_client
+            .get_or_try_init(RedisClient::new)
+            .await
+            .inspect_err(|err| {
+                log::warn!("redis is unavailable, idempotency keys will use postgres: {err}")
+            })
+            .ok()
+            .cloned()
+    }
+
+    /// Claims the key for this request, returns the record already held against the key when
+    /// an earlier request got there first
+    async fn reserve(
+        &self,
+        user_identifier: &Uuid,
+        idempotency_key: &str,
+        fingerprint: &str,
+    ) -> Result<Option<IdempotencyRecord>, ServiceError> {
+        let record = IdempotencyRecord::in_progress(fingerprint);
+        let ttl_secs = Self::ttl_secs();
+
+        if let Some(mut redis_client) = self.redis_client().await {
+            let key = format!("{user_identifier}:{idempotency_key}");
+            let reservation = match redis_client
+                .reserve_idempotency_key(&key, &serde_json::to_string(&record)?, ttl_secs)
+                .await
+            {
+                Ok(true) => Ok(None),
+                Ok(false) => redis_client
+                    .fetch_idempotency_record(&key)
+                    .await
+                    .map(|stored| {
+                        // the key may have expired between the two calls, the client can retry
+                        Some(match stored {
+                            Some(stored) => serde_json::from_str(&stored)
+                                .unwrap_or_else(|_| IdempotencyRecord::in_progress(fingerprint)),
+                            None => IdempotencyRecord::in_progress(fingerprint),
+                        })
+                    }),
+                Err(err) => Err(err),
+            };
+
+            match reservation {
+                Ok(Some(existing)) if existing.status_code.is_none() => {
+                    // redis may have been down when the first request finished, in which case
+                    // its response went to postgres instead
+                    let completed = self
+                        .fetch_completed_in_database(user_identifier, idempotency_key)
+                        .await?;
+                    return Ok(Some(completed.unwrap_or(existing)));
+                }
+                Ok(existing) => return Ok(existing),
+                Err(err) => log::warn!("failed to reserve idempotency key in redis: {err}"),
+            }
+        }
+
+        self.reserve_in_database(user_identifier, idempotency_key, &record, ttl_secs)
+            .await
+    }
+
+    /// Stores the final response, failing to do so must not fail a request that already ran
+    async fn complete(
+        &self,
+        user_identifier: &Uuid,
+        idempotency_key: &str,
+        record: &IdempotencyRecord,
+    ) {
+        let ttl_secs = Self::ttl_secs();
+
+        if let Some(mut redis_client) = self.redis_client().await {
+            let key = format!("{user_identifier}:{idempotency_key}");
+            let saved = match serde_json::to_string(record) {
+                Ok(serialized) => redis_client
+                    .save_idempotency_record(&key, &serialized, ttl_secs)
+                    .await
+                    .inspect_err(|err| log::warn!("failed to save idempotency record: {err}"))
+                    .is_ok(),
+                Err(err) => {
+                    log::error!("failed to serialize idempotency record due to {err}");
+                    false
+                }
+            };
+            if saved {
+                return;
+            }
+        }
+
+        if let Err(err) = self
+            .complete_in_database(user_identifier, idempotency_key, record, ttl_secs)
+            .await
+        {
+            log::error!("failed to save idempotency record due to {err}");
+        }
+    }
+
+    /// Frees the key so a request that failed on our side can be retried. The key is cleared
+    /// from both stores, it may have been reserved in one and written to the other
+    async fn release(&self, user_identifier: &Uuid, idempotency_key: &str) {
+        if let Some(mut redis_client) = self.redis_client().await {
+            let key = format!("{user_identifier}:{idempotency_key}");
+            if let Err(err) = redis_client.release_idempotency_key(&key).await {
+                log::warn!("failed to release idempotency key in redis: {err}");
+            }
+        }
+
+        if let Err(err) = sqlx::query(
+            "DELETE FROM idempotency_keys WHERE user_identifier = $1 AND idempotency_key = $2",
+        )
+        .bind(user_identifier)
+        .bind(idempotency_key)
+        .execute(&self.pool)
+        .await
+        {
+            log::error!("failed to release idempotency key due to {err}");
+        }
+    }
+
+    async fn reserve_in_database(
+        &self,
+        user_identifier: &Uuid,
+        idempotency_key: &str,
+        record: &IdempotencyRecord,
+        ttl_secs: u64,
+    ) -> Result<Option<IdempotencyRecord>, ServiceError> {
+        // an expired key is taken over as though it had never been used
+        let query = r#"
+        INSERT INTO idempotency_keys (identifier, idempotency_key, user_identifier, fingerprint, expires_at)
+        VALUES ($1, $2, $3, $4, NOW() + make_interval(secs => $5))
+        ON CONFLICT (user_identifier, idempotency_key) DO UPDATE
+            SET fingerprint   = EXCLUDED.fingerprint,
+                status_code   = NULL,
+                response_body = NULL,
+                created_date  = NOW(),
+                expires_at    = EXCLUDED.expires_at
+        WHERE idempotency_keys.expires_at < NOW()
+        RETURNING identifier
+        "#;
+        let reserved: Option<Uuid> = sqlx::query_scalar(query)
+            .bind(Uuid::new_v4())
+            .bind(idempotency_key)
+            .bind(user_identifier)
+            .bind(&record.fingerprint)
+            .bind(ttl_secs as f64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if reserved.is_some() {
+            return Ok(None);
+        }
+
+        let existing = sqlx::query_as::<_, IdempotencyRecord>(
+            r#"SELECT fingerprint, status_code, response_body FROM idempotency_keys WHERE user_identifier = $1 AND idempotency_key = $2"#,
+        )
+        .bind(user_identifier)
+        .bind(idempotency_key)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Some(existing))
+    }
+
+    async fn fetch_completed_in_database(
+        &self,
+        user_identifier: &Uuid,
+        idempotency_key: &str,
+    ) -> Result<Option<IdempotencyRecord>, ServiceError> {
+        let record = sqlx::query_as::<_, IdempotencyRecord>(
+            r#"SELECT fingerprint, status_code, response_body FROM idempotency_keys WHERE user_identifier = $1 AND idempotency_key = $2 AND status_code IS NOT NULL AND expires_at >= NOW()"#,
+        )
+        .bind(user_identifier)
+        .bind(idempotency_key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    async fn complete_in_database(
+        &self,
+        user_identifier: &Uuid,
+        idempotency_key: &str,
+        record: &IdempotencyRecord,
+        ttl_secs: u64,
+    ) -> Result<(), ServiceError> {
+        // upsert, the key may have been reserved in redis before it became unavailable
+        let query = r#"
+        INSERT INTO idempotency_keys (identifier, idempotency_key, user_identifier, fingerprint, status_code, response_body, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NOW() + make_interval(secs => $7))
+        ON CONFLICT (user_identifier, idempotency_key) DO UPDATE
+            SET status_code   = EXCLUDED.status_code,
+                response_body = EXCLUDED.response_body
+        "#;
+        sqlx::query(query)
+            .bind(Uuid::new_v4())
+            .bind(idempotency_key)
+            .bind(user_identifier)
+            .bind(&record.fingerprint)
+            .bind(record.status_code)
+            .bind(&record.response_body)
+            .bind(ttl_secs as f64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Makes a money moving endpoint safe to retry. Requests carrying an `Idempotency-Key` header
+/// run once per user and key, retries get the original response back and reusing the key for
+/// a different request is rejected
+pub async fn enforce_idempotency(
+    State(store): State<IdempotencyStore>,
+    claims: Claims,
+    request: Request,
+    next: Next,
+) -> Result<Response, ServiceError> {
+    let Some(header_value) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+
+    let idempotency_key = header_value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH)
+        .ok_or(ServiceError::BadRequest)?
+        .to_string();
+
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|_| ServiceError::BadRequest)?;
+    let fingerprint = request_fingerprint(&parts.method, parts.uri.path(), &body);
+
+    if let Some(existing) = store
+        .reserve(&claims.user_identifier, &idempotency_key, &fingerprint)
+        .await?
+    {
+        return existing.replay(&fingerprint);
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            log::error!("failed to buffer idempotent response due to {err}");
+            store
+                .release(&claims.user_identifier, &idempotency_key)
+                .await;
+            return Err(ServiceError::OperationFailed);
+        }
+    };
+
+    if parts.status.is_server_error() {
+        store
+            .release(&claims.user_identifier, &idempotency_key)
+            .await;
+    } else {
+        let record = IdempotencyRecord::completed(&fingerprint, parts.status, &body);
+        store
+            .complete(&claims.user_identifier, &idempotency_key, &record)
+            .await;
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::fixtures::create_user;
+
+    #[test]
+    fn test_fingerprint_changes_with_the_request_body() {
+        let path = "/transactions/transfers";
+        let first = request_fingerprint(&Method::POST, path, br#"{"amount":"10"}"#);
+        let retry = request_fingerprint(&Method::POST, path, br#"{"amount":"10"}"#);
+        let different = request_fingerprint(&Method::POST, path, br#"{"amount":"11"}"#);
+
+        assert_eq!(first, retry);
+        assert_ne!(first, different);
+    }
+
+    #[test]
+    fn test_replay_rejects_a_different_request() {
+        let record = IdempotencyRecord::completed(
+            "original",
+            StatusCode::CREATED,
+            &Bytes::from_static(b"{}"),
+        );
+
+        assert!(matches!(
+            record.replay("different"),
+            Err(ServiceError::IdempotencyKeyReused)
+        ));
+    }
+
+    #[test]
+    fn test_replay_returns_the_stored_response() {
+        let record = IdempotencyRecord::completed(
+            "original",
+            StatusCode::CREATED,
+            &Bytes::from_static(b"{}"),
+        );
+
+        let response = record.replay("original").unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            response.headers().get(IDEMPOTENT_REPLAYED_HEADER).unwrap(),
+            "true"
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_database_fallback_holds_the_key(pool: PgPool) {
+        let user_identifier = create_user(&pool).await;
+
+        let store = IdempotencyStore::new(&pool);
+        let record = IdempotencyRecord::in_progress("fingerprint");
+
+        let first = store
+            .reserve_in_database(&user_identifier, "key", &record, 60)
+            .await
+            .unwrap();
+        assert!(first.is_none());
+
+        let retry = store
+            .reserve_in_database(&user_identifier, "key", &record, 60)
+            .await
+            .unwrap();
+        assert_eq!(retry, Some(record.clone()));
+        assert!(
+            store
+                .fetch_completed_in_database(&user_identifier, "key")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let completed =
+            IdempotencyRecord::completed("fingerprint", StatusCode::CREATED, &Bytes::from("{}"));
+        store
+            .complete_in_database(&user_identifier, "key", &completed, 60)
+            .await
+            .unwrap();
+
+        let replayed = store
+            .reserve_in_database(&user_identifier, "key", &record, 60)
+            .await
+            .unwrap();
+        assert_eq!(replayed, Some(completed.clone()));
+        assert_eq!(
+            store
+                .fetch_completed_in_database(&user_identifier, "key")
+                .await
+                .unwrap(),
+            Some(completed)
+        );
+    }
+}
@@ -1,5 +1,7 @@
 // pub mod authenticated_request;
 // pub mod authenticated_request;
+pub mod admin_middleware;
 pub mod authentication_middleware;
+pub mod idempotency;
 pub mod validator;
 mod country;
@@ -1,2 +1,4 @@
 pub mod middlewares;
 pub mod repository;
+#[cfg(test)]
+pub mod fixtures;
@@ -3,10 +3,27 @@ use std::sync::Arc;
 use axum::extract::FromRef;
 use sqlx::{Pool, Postgres};
 
+use crate::accounts::service::AccountService;
 use crate::authentication::service::AuthenticationService;
+use crate::bank_statements::service::BankStatementService;
 use crate::banks::service::BankService;
+use crate::beneficiaries::service::BeneficiaryService;
+use crate::compliance::service::ComplianceService;
+use crate::conversions::service::ConversionService;
 use crate::countries::service::CountryService;
+use crate::disputes::service::DisputeService;
+use crate::fees::service::FeeService;
+use crate::fx::service::FxService;
+use crate::holds::service::HoldService;
+use crate::invoices::service::InvoiceService;
+use crate::ledger::service::LedgerService;
+use crate::payouts::service::PayoutService;
+use crate::reconciliation::service::ReconciliationService;
+use crate::risk::service::RiskService;
 use crate::security::otp::service::OtpService;
+use crate::shared::middlewares::idempotency::IdempotencyStore;
+use crate::statements::service::StatementService;
+use crate::transactions::service::TransactionService;
 use crate::users::service::UsersService;
 use crate::wallet::service::WalletService;
 
@@ -17,6 +34,23 @@ pub struct AppState {
     country_service: CountryService,
     wallet_service: WalletService,
     banks_service: BankService,
+    ledger_service: LedgerService,
+    reconciliation_service: ReconciliationService,
+    transaction_service: TransactionService,
+    idempotency_store: IdempotencyStore,
+    account_service: AccountService,
+    beneficiary_service: BeneficiaryService,
+    payout_service: PayoutService,
+    hold_service: HoldService,
+    conversion_service: ConversionService,
+    fx_service: FxService,
+    fee_service: FeeService,
+    dispute_service: DisputeService,
+    risk_service: RiskService,
+    compliance_service: ComplianceService,
+    statement_service: StatementService,
+    bank_statement_service: BankStatementService,
+    invoice_service: InvoiceService,
 }
 
 impl FromRef<AppState> for UsersService {
@@ -49,15 +83,137 @@ impl FromRef<AppState> for BankService {
     }
 }
 
+impl FromRef<AppState> for LedgerService {
+    fn from_ref(services: &AppState) -> LedgerService {
+        services.ledger_service.clone()
+    }
+}
+
+impl FromRef<AppState> for ReconciliationService {
+    fn from_ref(services: &AppState) -> ReconciliationService {
+        services.reconciliation_service.clone()
+    }
+}
+
+impl FromRef<AppState> for TransactionService {
+    fn from_ref(services: &AppState) -> TransactionService {
+        services.transaction_service.clone()
+    }
+}
+
+impl FromRef<AppState> for IdempotencyStore {
+    fn from_ref(services: &AppState) -> IdempotencyStore {
+        services.idempotency_store.clone()
+    }
+}
+
+impl FromRef<AppState> for AccountService {
+    fn from_ref(services: &AppState) -> AccountService {
+        services.account_service.clone()
+    }
+}
+
+impl FromRef<AppState> for BeneficiaryService {
+    fn from_ref(services: &AppState) -> BeneficiaryService {
+        services.beneficiary_service.clone()
+    }
+}
+
+impl FromRef<AppState> for PayoutService {
+    fn from_ref(services: &AppState) -> PayoutService {
+        services.payout_service.clone()
+    }
+}
+
+impl FromRef<AppState> for HoldService {
+    fn from_ref(services: &AppState) -> HoldService {
+        services.hold_service.clone()
+    }
+}
+
+impl FromRef<AppState> for ConversionService {
+    fn from_ref(services: &AppState) -> ConversionService {
+        services.conversion_service.clone()
+    }
+}
+
+impl FromRef<AppState> for FxService {
+    fn from_ref(services: &AppState) -> FxService {
+        services.fx_service.clone()
+    }
+}
+
+impl FromRef<AppState> for FeeService {
+    fn from_ref(services: &AppState) -> FeeService {
+        services.fee_service.clone()
+    }
+}
+
+impl FromRef<AppState> for DisputeService {
+    fn from_ref(services: &AppState) -> DisputeService {
+        services.dispute_service.clone()
+    }
+}
+
+impl FromRef<AppState> for RiskService {
+    fn from_ref(services: &AppState) -> RiskService {
+        services.risk_service.clone()
+    }
+}
+
+impl FromRef<AppState> for ComplianceService {
+    fn from_ref(services: &AppState) -> ComplianceService {
+        services.compliance_service.clone()
+    }
+}
+
+impl FromRef<AppState> for StatementService {
+    fn from_ref(services: &AppState) -> StatementService {
+        services.statement_service.clone()
+    }
+}
+
+impl FromRef<AppState> for BankStatementService {
+    fn from_ref(services: &AppState) -> BankStatementService {
+        services.bank_statement_service.clone()
+    }
+}
+
+impl FromRef<AppState> for InvoiceService {
+    fn from_ref(services: &AppState) -> InvoiceService {
+        services.invoice_service.clone()
+    }
+}
+
 impl AppState {
     pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
         let users_service = UsersService::new(&pool);
         let otp_service = OtpService::new(&pool);
-        let authentication_service =
-            AuthenticationService::new(users_service.clone(), otp_service.clone());
+        let compliance_service = ComplianceService::new(&pool);
+        let authentication_service = AuthenticationService::new(
+            users_service.clone(),
+            otp_service.clone(),
+            compliance_service.clone(),
+        );
         let country_service = CountryService::new(&pool);
         let wallet_service = WalletService::new(&pool);
         let banks_service = BankService::new(&pool);
+        let ledger_service = LedgerService::new(&pool);
+        let reconciliation_service = ReconciliationService::new(&pool);
+        let transaction_service = TransactionService::new(&pool);
+        let idempotency_store = IdempotencyStore::new(&pool);
+        let account_service = AccountService::new(&pool);
+        let beneficiary_service = BeneficiaryService::new(&pool);
+        let payout_service = PayoutService::new(&pool);
+        let hold_service = HoldService::new(&pool);
+        let conversion_service = ConversionService::new(&pool);
+        let fx_service = FxService::new(&pool);
+        let fee_service = FeeService::new(&pool);
+        let dispute_service = DisputeService::new(&pool);
+        let risk_service = RiskService::new(&pool);
+        let statement_service = StatementService::new(&pool);
+        let bank_statement_service = BankStatementService::new(&pool);
+        let invoice_service = InvoiceService::new(&pool);
 
         Self {
             authentication_service,
@@ -65,6 +221,23 @@ impl AppState {
             country_service,
             wallet_service,
             banks_service,
+            ledger_service,
+            reconciliation_service,
+            transaction_service,
+            idempotency_store,
+            account_service,
+            beneficiary_service,
+            payout_service,
+            hold_service,
+            conversion_service,
+            fx_service,
+            fee_service,
+            dispute_service,
+            risk_service,
+            compliance_service,
+            statement_service,
+            bank_statement_service,
+            invoice_service,
         }
     }
 }
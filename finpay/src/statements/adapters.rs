@@ -0,0 +1,27 @@
+use crate::statements::entities::StatementFormat;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStatementRequest {
+    pub wallet_identifier: Uuid,
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+    pub format: StatementFormat,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StatementDownloadQuery {
+    pub token: String,
+}
+
+/// What a download link carries, signed with a key of its own so it is never accepted as an
+/// access token
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatementLinkClaims {
+    pub statement_identifier: Uuid,
+    pub exp: i64,
+}
@@ -0,0 +1,243 @@
+use crate::transactions::entities::TransactionType;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "statement_format_enum")]
+pub enum StatementFormat {
+    Csv,
+    Pdf,
+    /// OFX 2.2, read by most personal and small business accounting tools
+    Ofx,
+    /// SWIFT MT940 customer statement
+    Mt940,
+    /// ISO 20022 camt.053.001.02 bank to customer statement
+    Camt053,
+}
+
+impl StatementFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            StatementFormat::Csv => "csv",
+            StatementFormat::Pdf => "pdf",
+            StatementFormat::Ofx => "ofx",
+            StatementFormat::Mt940 => "sta",
+            StatementFormat::Camt053 => "xml",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            StatementFormat::Csv => "text/csv",
+            StatementFormat::Pdf => "application/pdf",
+            StatementFormat::Ofx => "application/x-ofx",
+            StatementFormat::Mt940 => "text/plain",
+            StatementFormat::Camt053 => "application/xml",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "statement_status_enum")]
+pub enum StatementStatus {
+    Pending,
+    Generating,
+    Ready,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Statement {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub user_identifier: Uuid,
+    pub wallet_identifier: Uuid,
+    pub format: StatementFormat,
+    pub period_start: DateTime<Local>,
+    pub period_end: DateTime<Local>,
+    pub status: StatementStatus,
+    /// the file under `AppConfig.export_path`, set once the statement is ready
+    #[serde(skip)]
+    pub file_name: Option<String>,
+    pub opening_balance: Option<BigDecimal>,
+    pub closing_balance: Option<BigDecimal>,
+    pub line_count: Option<i32>,
+    pub failure_reason: Option<String>,
+    pub generated_date: Option<DateTime<Local>>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+/// A statement along with a link to download it, only ready statements have one
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementWithLink {
+    #[serde(flatten)]
+    pub statement: Statement,
+    pub download_url: Option<String>,
+    pub download_url_expires_at: Option<DateTime<Local>>,
+}
+
+/// The wallet owner and the wallet a statement is addressed for
+#[derive(Debug, FromRow)]
+pub struct StatementHolder {
+    pub first_name: String,
+    pub last_name: String,
+    pub address: String,
+    pub country: String,
+    pub wallet_name: String,
+    pub currency_code: String,
+}
+
+/// A posting on the wallet, credits are positive and debits negative
+#[derive(Debug, FromRow)]
+pub struct StatementLine {
+    /// the posting the line comes from
+    pub identifier: Uuid,
+    pub posted_date: DateTime<Local>,
+    pub reference: Option<String>,
+    pub transaction_type: Option<TransactionType>,
+    pub description: String,
+    pub amount: BigDecimal,
+}
+
+impl StatementLine {
+    pub fn debit(&self) -> Option<BigDecimal> {
+        (self.amount < BigDecimal::zero()).then(|| self.amount.abs())
+    }
+
+    pub fn credit(&self) -> Option<BigDecimal> {
+        (self.amount > BigDecimal::zero()).then(|| self.amount.clone())
+    }
+}
+
+/// Everything that goes on a statement, each line carries the balance right after it
+#[derive(Debug)]
+pub struct StatementDocument {
+    pub reference: String,
+    pub wallet_identifier: Uuid,
+    pub holder: StatementHolder,
+    pub period_start: DateTime<Local>,
+    pub period_end: DateTime<Local>,
+    pub opening_balance: BigDecimal,
+    pub lines: Vec<(StatementLine, BigDecimal)>,
+    pub closing_balance: BigDecimal,
+}
+
+impl StatementDocument {
+    pub fn new(
+        statement: &Statement,
+        holder: StatementHolder,
+        opening_balance: BigDecimal,
+        lines: Vec<StatementLine>,
+    ) -> Self {
+        let mut balance = opening_balance.clone();
+        let lines = lines
+            .into_iter()
+            .map(|line| {
+                balance += &line.amount;
+                (line, balance.clone())
+            })
+            .collect();
+
+        Self {
+            reference: statement.reference.clone(),
+            wallet_identifier: statement.wallet_identifier,
+            holder,
+            period_start: statement.period_start,
+            period_end: statement.period_end,
+            opening_balance,
+            lines,
+            closing_balance: balance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement() -> Statement {
+        Statement {
+            identifier: Uuid::new_v4(),
+            reference: "STM-TEST".to_string(),
+            user_identifier: Uuid::new_v4(),
+            wallet_identifier: Uuid::new_v4(),
+            format: StatementFormat::Csv,
+            period_start: Local::now(),
+            period_end: Local::now(),
+            status: StatementStatus::Generating,
+            file_name: None,
+            opening_balance: None,
+            closing_balance: None,
+            line_count: None,
+            failure_reason: None,
+            generated_date: None,
+            created_date: Local::now(),
+            updated_at: Local::now(),
+        }
+    }
+
+    fn holder() -> StatementHolder {
+        StatementHolder {
+            first_name: "Ada".to_string(),
+            last_name: "Obi".to_string(),
+            address: "12 Marina Road".to_string(),
+            country: "Nigeria".to_string(),
+            wallet_name: "Savings".to_string(),
+            currency_code: "NGN".to_string(),
+        }
+    }
+
+    fn line(amount: i32) -> StatementLine {
+        StatementLine {
+            identifier: Uuid::new_v4(),
+            posted_date: Local::now(),
+            reference: None,
+            transaction_type: None,
+            description: "transfer".to_string(),
+            amount: BigDecimal::from(amount),
+        }
+    }
+
+    #[test]
+    fn test_running_balance_starts_from_the_opening_balance() {
+        let document = StatementDocument::new(
+            &statement(),
+            holder(),
+            BigDecimal::from(100),
+            vec![line(-30), line(50), line(-5)],
+        );
+
+        let balances: Vec<BigDecimal> = document
+            .lines
+            .iter()
+            .map(|(_, balance)| balance.clone())
+            .collect();
+        assert_eq!(
+            balances,
+            vec![
+                BigDecimal::from(70),
+                BigDecimal::from(120),
+                BigDecimal::from(115)
+            ]
+        );
+        assert_eq!(document.closing_balance, BigDecimal::from(115));
+        assert_eq!(document.lines[0].0.debit(), Some(BigDecimal::from(30)));
+        assert_eq!(document.lines[0].0.credit(), None);
+    }
+
+    #[test]
+    fn test_empty_period_closes_at_the_opening_balance() {
+        let document = StatementDocument::new(&statement(), holder(), BigDecimal::from(42), vec![]);
+
+        assert!(document.lines.is_empty());
+        assert_eq!(document.closing_balance, BigDecimal::from(42));
+    }
+}
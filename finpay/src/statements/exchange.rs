@@ -0,0 +1,861 @@
+//! The standard statement formats accounting tools import. Each is written from the same
+//! `StatementDocument` as the csv and pdf statements and checked against the structure of its
+//! format before it is handed out
+
+use crate::statements::entities::{StatementDocument, StatementLine};
+use crate::utils::is_currency_code;
+use crate::utils::xml::{XmlElement, XmlWriter, descendants, parse, required};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, Utc};
+use std::collections::HashSet;
+use std::iter::Peekable;
+use uuid::Uuid;
+
+/// the institution written into the formats that ask for one
+const BANK_IDENTIFIER: &str = "FINPAY";
+
+/// OFX account ids are at most this long, shorter than a wallet identifier
+const OFX_ACCOUNT_ID_LENGTH: usize = 22;
+
+const CAMT_053_NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:camt.053.001.02";
+
+/// MT940 lines are at most this long, an `:86:` narrative runs over at most six of them
+const MT940_LINE_LENGTH: usize = 65;
+const MT940_NARRATIVE_LINES: usize = 6;
+
+/// the SWIFT x character set, everything else is replaced before it is written
+const SWIFT_CHARACTERS: &str =
+    "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789/-?:().,'+ ";
+
+fn check_currency_code(code: &str) -> Result<(), String> {
+    if is_currency_code(code) {
+        Ok(())
+    } else {
+        Err(format!("{code} is not an ISO 4217 currency code"))
+    }
+}
+
+fn amount(value: &BigDecimal) -> String {
+    value.abs().with_scale(2).to_string()
+}
+
+fn signed_amount(value: &BigDecimal) -> String {
+    value.with_scale(2).to_string()
+}
+
+/// An unsigned decimal, negative amounts are written with a debit mark instead of a sign
+fn is_amount(text: &str, separator: char) -> bool {
+    let mut parts = text.splitn(2, separator);
+    let whole = parts.next().unwrap_or_default();
+    let fraction = parts.next().unwrap_or_default();
+    !whole.is_empty()
+        && whole.chars().all(|c| c.is_ascii_digit())
+        && fraction.len() <= 5
+        && fraction.chars().all(|c| c.is_ascii_digit())
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+/// the unique end of a reference, for fields too short to carry all of it
+fn short_reference(reference: &str) -> String {
+    let characters: Vec<char> = reference.chars().collect();
+    characters[characters.len().saturating_sub(16)..]
+        .iter()
+        .collect()
+}
+
+/// the transaction code of a line, three characters wide
+fn transaction_code(line: &StatementLine) -> &'static str {
+    line.transaction_type
+        .map(|transaction_type| transaction_type.reference_prefix())
+        .unwrap_or("MSC")
+}
+
+/// the end of the wallet identifier, as much of it as fits an OFX account id
+fn ofx_account_id(wallet_identifier: &Uuid) -> String {
+    let simple = wallet_identifier.simple().to_string();
+    simple[simple.len() - OFX_ACCOUNT_ID_LENGTH..].to_string()
+}
+
+fn ofx_date(date: &DateTime<Local>) -> String {
+    format!("{}[0:GMT]", date.with_timezone(&Utc).format("%Y%m%d%H%M%S"))
+}
+
+fn iso_date_time(date: &DateTime<Local>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+pub fn render_ofx(document: &StatementDocument) -> Result<String, String> {
+    let currency_code = &document.holder.currency_code;
+    check_currency_code(currency_code)?;
+
+    let mut writer = XmlWriter::new();
+    writer.instruction(
+        r#"OFX OFXHEADER="200" VERSION="220" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE""#,
+    );
+    writer.open("OFX");
+
+    writer.open("SIGNONMSGSRSV1");
+    writer.open("SONRS");
+    write_ofx_status(&mut writer);
+    writer.element("DTSERVER", &ofx_date(&Local::now()));
+    writer.element("LANGUAGE", "ENG");
+    writer.close();
+    writer.close();
+
+    writer.open("BANKMSGSRSV1");
+    writer.open("STMTTRNRS");
+    writer.element("TRNUID", &document.reference);
+    write_ofx_status(&mut writer);
+    writer.open("STMTRS");
+    writer.element("CURDEF", currency_code);
+    writer.open("BANKACCTFROM");
+    writer.element("BANKID", BANK_IDENTIFIER);
+    writer.element("ACCTID", &ofx_account_id(&document.wallet_identifier));
+    writer.element("ACCTTYPE", "CHECKING");
+    writer.close();
+
+    writer.open("BANKTRANLIST");
+    writer.element("DTSTART", &ofx_date(&document.period_start));
+    writer.element("DTEND", &ofx_date(&document.period_end));
+    for (line, _) in &document.lines {
+        writer.open("STMTTRN");
+        writer.element(
+            "TRNTYPE",
+            if line.amount < BigDecimal::zero() {
+                "DEBIT"
+            } else {
+                "CREDIT"
+            },
+        );
+        writer.element("DTPOSTED", &ofx_date(&line.posted_date));
+        writer.element("TRNAMT", &signed_amount(&line.amount));
+        writer.element("FITID", &line.identifier.simple().to_string());
+        if let Some(reference) = &line.reference {
+            writer.element("REFNUM", &truncate(reference, 32));
+        }
+        writer.element("MEMO", &truncate(&line.description, 255));
+        writer.close();
+    }
+    writer.close();
+
+    writer.open("LEDGERBAL");
+    writer.element("BALAMT", &signed_amount(&document.closing_balance));
+    writer.element("DTASOF", &ofx_date(&document.period_end));
+
+    let ofx = writer.finish();
+    validate_ofx(&ofx)?;
+    Ok(ofx)
+}
+
+fn write_ofx_status(writer: &mut XmlWriter) {
+    writer.open("STATUS");
+    writer.element("CODE", "0");
+    writer.element("SEVERITY", "INFO");
+    writer.close();
+}
+
+fn is_ofx_date(text: &str) -> bool {
+    let digits = text.split(['.', '[']).next().unwrap_or_default();
+    [8, 12, 14].contains(&digits.len())
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && NaiveDate::parse_from_str(&digits[..8], "%Y%m%d").is_ok()
+}
+
+pub fn validate_ofx(ofx: &str) -> Result<(), String> {
+    if !ofx.contains(r#"<?OFX OFXHEADER="200""#) {
+        return Err("the OFX 2 header is missing".to_string());
+    }
+    let elements = parse(ofx)?;
+    if elements[0].path != "OFX" {
+        return Err("the root element is not OFX".to_string());
+    }
+
+    required(&elements, "OFX/SIGNONMSGSRSV1/SONRS/STATUS/CODE")?;
+    let statement = "OFX/BANKMSGSRSV1/STMTTRNRS/STMTRS";
+    check_currency_code(&required(&elements, &format!("{statement}/CURDEF"))?.text)?;
+    for (path, max_length) in [
+        ("OFX/BANKMSGSRSV1/STMTTRNRS/TRNUID".to_string(), 36),
+        (format!("{statement}/BANKACCTFROM/BANKID"), 9),
+        (
+            format!("{statement}/BANKACCTFROM/ACCTID"),
+            OFX_ACCOUNT_ID_LENGTH,
+        ),
+        (format!("{statement}/BANKACCTFROM/ACCTTYPE"), 10),
+    ] {
+        let text = &required(&elements, &path)?.text;
+        if text.is_empty() || text.chars().count() > max_length {
+            return Err(format!("{path} is empty or longer than {max_length}"));
+        }
+    }
+    for path in [
+        format!("{statement}/BANKTRANLIST/DTSTART"),
+        format!("{statement}/BANKTRANLIST/DTEND"),
+        format!("{statement}/LEDGERBAL/DTASOF"),
+    ] {
+        if !is_ofx_date(&required(&elements, &path)?.text) {
+            return Err(format!("{path} is not an OFX date"));
+        }
+    }
+    let balance = &required(&elements, &format!("{statement}/LEDGERBAL/BALAMT"))?.text;
+    if !is_amount(balance.trim_start_matches('-'), '.') {
+        return Err(format!("{balance} is not an amount"));
+    }
+
+    let transaction_path = format!("{statement}/BANKTRANLIST/STMTTRN");
+    let mut fitids = HashSet::new();
+    for (index, _) in elements
+        .iter()
+        .enumerate()
+        .filter(|(_, element)| element.path == transaction_path)
+    {
+        let fields = descendants(&elements, index);
+        let trntype = &required(fields, &format!("{transaction_path}/TRNTYPE"))?.text;
+        if !["CREDIT", "DEBIT"].contains(&trntype.as_str()) {
+            return Err(format!("{trntype} is not a transaction type"));
+        }
+        if !is_ofx_date(&required(fields, &format!("{transaction_path}/DTPOSTED"))?.text) {
+            return Err("a transaction has no posting date".to_string());
+        }
+        let amount = &required(fields, &format!("{transaction_path}/TRNAMT"))?.text;
+        if !is_amount(amount.trim_start_matches('-'), '.') {
+            return Err(format!("{amount} is not an amount"));
+        }
+        let fitid = &required(fields, &format!("{transaction_path}/FITID"))?.text;
+        if fitid.is_empty() || fitid.len() > 255 || !fitids.insert(fitid.clone()) {
+            return Err(format!(
+                "transaction id {fitid} is empty, too long or repeated"
+            ));
+        }
+        for (field, max_length) in [("REFNUM", 32), ("MEMO", 255)] {
+            let path = format!("{transaction_path}/{field}");
+            if fields
+                .iter()
+                .filter(|element| element.path == path)
+                .any(|element| element.text.chars().count() > max_length)
+            {
+                return Err(format!("{path} is longer than {max_length}"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps the characters SWIFT accepts, a colon could be mistaken for the start of a field
+fn swift_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            ':' => '.',
+            c if SWIFT_CHARACTERS.contains(c) => c,
+            _ => ' ',
+        })
+        .collect()
+}
+
+fn mt940_amount(value: &BigDecimal) -> String {
+    amount(value).replace('.', ",")
+}
+
+fn mt940_mark(value: &BigDecimal) -> &'static str {
+    if value < &BigDecimal::zero() {
+        "D"
+    } else {
+        "C"
+    }
+}
+
+fn mt940_balance(tag: &str, value: &BigDecimal, date: &DateTime<Local>, currency: &str) -> String {
+    format!(
+        ":{tag}:{}{}{currency}{}",
+        mt940_mark(value),
+        date.format("%y%m%d"),
+        mt940_amount(value)
+    )
+}
+
+/// Spreads a narrative over the lines of an `:86:` field, what does not fit is dropped
+fn mt940_narrative(text: &str) -> Vec<String> {
+    let text: Vec<char> = swift_text(text).chars().collect();
+    let first_line = MT940_LINE_LENGTH - ":86:".len();
+
+    let mut lines = vec![];
+    let mut start = 0;
+    while start < text.len() && lines.len() < MT940_NARRATIVE_LINES {
+        let width = if lines.is_empty() {
+            first_line
+        } else {
+            MT940_LINE_LENGTH
+        };
+        let mut line: String = text[start..(start + width).min(text.len())]
+            .iter()
+            .collect();
+        // a continuation line starting with a dash would read as the end of the message
+        if !lines.is_empty() && line.starts_with('-') {
+            line.replace_range(..1, " ");
+        }
+        lines.push(line);
+        start += width;
+    }
+    if lines.is_empty() {
+        lines.push("NONREF".to_string());
+    }
+    lines[0] = format!(":86:{}", lines[0]);
+    lines
+}
+
+pub fn render_mt940(document: &StatementDocument) -> Result<String, String> {
+    let currency_code = &document.holder.currency_code;
+    check_currency_code(currency_code)?;
+
+    let mut lines = vec![
+        format!(":20:{}", swift_text(&short_reference(&document.reference))),
+        format!(":25:{}", document.wallet_identifier.simple()),
+        // every statement is a single message of its own
+        ":28C:1/1".to_string(),
+        mt940_balance(
+            "60F",
+            &document.opening_balance,
+            &document.period_start,
+            currency_code,
+        ),
+    ];
+    for (line, _) in &document.lines {
+        let reference = line
+            .reference
+            .as_deref()
+            .map(|reference| swift_text(&short_reference(reference)))
+            .unwrap_or_else(|| "NONREF".to_string());
+        lines.push(format!(
+            ":61:{}{}{}{}N{}{reference}//{}",
+            line.posted_date.format("%y%m%d"),
+            line.posted_date.format("%m%d"),
+            mt940_mark(&line.amount),
+            mt940_amount(&line.amount),
+            transaction_code(line),
+            &line.identifier.simple().to_string()[..16]
+        ));
+        lines.extend(mt940_narrative(&line.description));
+    }
+    lines.push(mt940_balance(
+        "62F",
+        &document.closing_balance,
+        &document.period_end,
+        currency_code,
+    ));
+    lines.push("-".to_string());
+
+    let mt940 = lines.join("\r\n") + "\r\n";
+    validate_mt940(&mt940)?;
+    Ok(mt940)
+}
+
+/// Checks a balance field, a debit or credit mark, a date, a currency and an amount
+fn check_mt940_balance(value: &str) -> Result<(), String> {
+    let valid = value.is_ascii()
+        && value.len() > 10
+        && ["C", "D"].contains(&&value[..1])
+        && NaiveDate::parse_from_str(&value[1..7], "%y%m%d").is_ok()
+        && check_currency_code(&value[7..10]).is_ok()
+        && value[10..].len() <= 15
+        && value[10..].contains(',')
+        && is_amount(&value[10..], ',');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("{value} is not a balance"))
+    }
+}
+
+/// Checks a statement line, a value date, an optional entry date, the mark, the amount, the
+/// transaction type and a reference
+fn check_mt940_line(value: &str) -> Result<(), String> {
+    let invalid = || format!("{value} is not a statement line");
+    if !value.is_ascii() || value.len() < 6 {
+        return Err(invalid());
+    }
+    NaiveDate::parse_from_str(&value[..6], "%y%m%d").map_err(|_| invalid())?;
+
+    let mut rest = &value[6..];
+    if rest.len() >= 4 && rest[..4].chars().all(|c| c.is_ascii_digit()) {
+        rest = &rest[4..];
+    }
+    rest = ["RC", "RD", "C", "D"]
+        .iter()
+        .find_map(|mark| rest.strip_prefix(mark))
+        .ok_or_else(invalid)?;
+    if rest.starts_with(|c: char| c.is_ascii_uppercase()) {
+        rest = &rest[1..];
+    }
+
+    let amount_length = rest
+        .find(|c: char| !c.is_ascii_digit() && c != ',')
+        .ok_or_else(invalid)?;
+    let amount = &rest[..amount_length];
+    if amount.len() > 15 || !amount.contains(',') || !is_amount(amount, ',') {
+        return Err(invalid());
+    }
+
+    let rest = &rest[amount_length..];
+    let valid_type = rest.len() > 4
+        && ["N", "F", "S"].contains(&&rest[..1])
+        && rest[1..4].chars().all(|c| c.is_ascii_alphanumeric());
+    if valid_type { Ok(()) } else { Err(invalid()) }
+}
+
+/// Takes the next field, which must have one of `tags`
+fn next_mt940_field<'a>(
+    fields: &mut Peekable<impl Iterator<Item = (&'a str, Vec<&'a str>)>>,
+    tags: &[&str],
+) -> Result<(&'a str, Vec<&'a str>), String> {
+    match fields.next() {
+        Some((tag, values)) if tags.contains(&tag) => Ok((tag, values)),
+        Some((tag, _)) => Err(format!(":{tag}: is out of place, expected :{}:", tags[0])),
+        None => Err(format!(":{}: is missing", tags[0])),
+    }
+}
+
+pub fn validate_mt940(mt940: &str) -> Result<(), String> {
+    let lines: Vec<&str> = mt940.trim_end_matches("\r\n").split("\r\n").collect();
+    if lines.last() != Some(&"-") {
+        return Err("the message does not end with -".to_string());
+    }
+
+    // every field as its tag and its lines, continuation lines belong to the field above them
+    let mut fields: Vec<(&str, Vec<&str>)> = vec![];
+    for (number, line) in lines[..lines.len() - 1].iter().enumerate() {
+        if line.chars().count() > MT940_LINE_LENGTH {
+            return Err(format!(
+                "line {} is longer than {MT940_LINE_LENGTH}",
+                number + 1
+            ));
+        }
+        if let Some(c) = line.chars().find(|c| !SWIFT_CHARACTERS.contains(*c)) {
+            return Err(format!(
+                "line {} has {c:?}, which SWIFT does not accept",
+                number + 1
+            ));
+        }
+
+        let tag = line.strip_prefix(':').and_then(|field| {
+            field
+                .find(':')
+                .map(|end| (&field[..end], &field[end + 1..]))
+        });
+        match tag {
+            Some((tag, value)) => fields.push((tag, vec![value])),
+            None => match fields.last_mut() {
+                Some((_, values)) if !line.starts_with('-') => values.push(line),
+                _ => return Err(format!("line {} is not part of a field", number + 1)),
+            },
+        }
+    }
+
+    let mut fields = fields.into_iter().peekable();
+    let (_, values) = next_mt940_field(&mut fields, &["20"])?;
+    let reference = values.concat();
+    if reference.is_empty()
+        || reference.len() > 16
+        || reference.starts_with('/')
+        || reference.ends_with('/')
+        || reference.contains("//")
+    {
+        return Err(format!("{reference} is not a transaction reference"));
+    }
+    let (_, values) = next_mt940_field(&mut fields, &["25"])?;
+    let account = values.concat();
+    if account.is_empty() || account.len() > 35 {
+        return Err(format!("{account} is not an account"));
+    }
+    let (_, values) = next_mt940_field(&mut fields, &["28C"])?;
+    let number = values.concat();
+    let valid_number = number.split('/').count() <= 2
+        && number
+            .split('/')
+            .all(|part| (1..=5).contains(&part.len()) && part.chars().all(|c| c.is_ascii_digit()));
+    if !valid_number {
+        return Err(format!("{number} is not a statement number"));
+    }
+    let (_, values) = next_mt940_field(&mut fields, &["60F", "60M"])?;
+    check_mt940_balance(&values.concat())?;
+
+    loop {
+        let (tag, values) = next_mt940_field(&mut fields, &["61", "62F", "62M"])?;
+        if values.len() > 1 {
+            return Err(format!(":{tag}: runs over more than one line"));
+        }
+        if tag != "61" {
+            check_mt940_balance(&values.concat())?;
+            break;
+        }
+        check_mt940_line(&values.concat())?;
+
+        // a statement line is optionally followed by its narrative
+        if let Some(("86", values)) = fields.peek() {
+            if values.len() > MT940_NARRATIVE_LINES {
+                return Err(format!(
+                    "a narrative runs over more than {MT940_NARRATIVE_LINES} lines"
+                ));
+            }
+            fields.next();
+        }
+    }
+
+    for (tag, values) in fields {
+        match tag {
+            "64" | "65" => check_mt940_balance(&values.concat())?,
+            "86" => {}
+            _ => return Err(format!(":{tag}: is out of place after the closing balance")),
+        }
+    }
+
+    Ok(())
+}
+
+fn write_camt_balance(
+    writer: &mut XmlWriter,
+    code: &str,
+    value: &BigDecimal,
+    currency_code: &str,
+    date: &DateTime<Local>,
+) {
+    writer.open("Bal");
+    writer.open("Tp");
+    writer.open("CdOrPrtry");
+    writer.element("Cd", code);
+    writer.close();
+    writer.close();
+    writer.element_with("Amt", &[("Ccy", currency_code)], &amount(value));
+    writer.element("CdtDbtInd", camt_indicator(value));
+    writer.open("Dt");
+    writer.element("DtTm", &iso_date_time(date));
+    writer.close();
+    writer.close();
+}
+
+fn camt_indicator(value: &BigDecimal) -> &'static str {
+    if value < &BigDecimal::zero() {
+        "DBIT"
+    } else {
+        "CRDT"
+    }
+}
+
+pub fn render_camt053(document: &StatementDocument) -> Result<String, String> {
+    let holder = &document.holder;
+    let currency_code = &holder.currency_code;
+    check_currency_code(currency_code)?;
+    let created = iso_date_time(&Local::now());
+
+    let mut writer = XmlWriter::new();
+    writer.open_with("Document", &[("xmlns", CAMT_053_NAMESPACE)]);
+    writer.open("BkToCstmrStmt");
+
+    writer.open("GrpHdr");
+    writer.element("MsgId", &document.reference);
+    writer.element("CreDtTm", &created);
+    writer.close();
+
+    writer.open("Stmt");
+    writer.element("Id", &document.reference);
+    writer.element("CreDtTm", &created);
+    writer.open("FrToDt");
+    writer.element("FrDtTm", &iso_date_time(&document.period_start));
+    writer.element("ToDtTm", &iso_date_time(&document.period_end));
+    writer.close();
+
+    writer.open("Acct");
+    writer.open("Id");
+    writer.open("Othr");
+    writer.element("Id", &document.wallet_identifier.simple().to_string());
+    writer.close();
+    writer.close();
+    writer.element("Ccy", currency_code);
+    writer.element("Nm", &truncate(&holder.wallet_name, 70));
+    writer.open("Ownr");
+    writer.element(
+        "Nm",
+        &truncate(&format!("{} {}", holder.first_name, holder.last_name), 140),
+    );
+    writer.open("PstlAdr");
+    for address_line in [&holder.address, &holder.country] {
+        if !address_line.is_empty() {
+            writer.element("AdrLine", &truncate(address_line, 70));
+        }
+    }
+    writer.close();
+    writer.close();
+    writer.open("Svcr");
+    writer.open("FinInstnId");
+    writer.element("Nm", BANK_IDENTIFIER);
+    writer.close();
+    writer.close();
+    writer.close();
+
+    write_camt_balance(
+        &mut writer,
+        "OPBD",
+        &document.opening_balance,
+        currency_code,
+        &document.period_start,
+    );
+    write_camt_balance(
+        &mut writer,
+        "CLBD",
+        &document.closing_balance,
+        currency_code,
+        &document.period_end,
+    );
+
+    for (line, _) in &document.lines {
+        let posted = iso_date_time(&line.posted_date);
+        writer.open("Ntry");
+        if let Some(reference) = &line.reference {
+            writer.element("NtryRef", &truncate(reference, 35));
+        }
+        writer.element_with("Amt", &[("Ccy", currency_code)], &amount(&line.amount));
+        writer.element("CdtDbtInd", camt_indicator(&line.amount));
+        writer.element("Sts", "BOOK");
+        writer.open("BookgDt");
+        writer.element("DtTm", &posted);
+        writer.close();
+        writer.open("ValDt");
+        writer.element("DtTm", &posted);
+        writer.close();
+        writer.element("AcctSvcrRef", &line.identifier.simple().to_string());
+        writer.open("BkTxCd");
+        writer.open("Prtry");
+        writer.element("Cd", transaction_code(line));
+        writer.element("Issr", BANK_IDENTIFIER);
+        writer.close();
+        writer.close();
+        writer.element("AddtlNtryInf", &truncate(&line.description, 500));
+        writer.close();
+    }
+
+    let camt = writer.finish();
+    validate_camt053(&camt)?;
+    Ok(camt)
+}
+
+/// Checks an amount with its currency and the debit or credit indicator beside it
+fn check_camt_amount(elements: &[XmlElement], parent: &str) -> Result<(), String> {
+    let amount = required(elements, &format!("{parent}/Amt"))?;
+    if !is_amount(&amount.text, '.') {
+        return Err(format!("{} is not an amount", amount.text));
+    }
+    check_currency_code(
+        amount
+            .attribute("Ccy")
+            .ok_or_else(|| format!("{parent}/Amt has no currency"))?,
+    )?;
+    let indicator = &required(elements, &format!("{parent}/CdtDbtInd"))?.text;
+    if !["CRDT", "DBIT"].contains(&indicator.as_str()) {
+        return Err(format!("{indicator} is neither CRDT nor DBIT"));
+    }
+    Ok(())
+}
+
+pub fn validate_camt053(camt: &str) -> Result<(), String> {
+    let elements = parse(camt)?;
+    if elements[0].path != "Document" {
+        return Err("the root element is not Document".to_string());
+    }
+    if elements[0].attribute("xmlns") != Some(CAMT_053_NAMESPACE) {
+        return Err("the document is not in the camt.053.001.02 namespace".to_string());
+    }
+
+    for (path, max_length) in [
+        ("Document/BkToCstmrStmt/GrpHdr/MsgId", 35),
+        ("Document/BkToCstmrStmt/GrpHdr/CreDtTm", 35),
+        ("Document/BkToCstmrStmt/Stmt/Id", 35),
+        ("Document/BkToCstmrStmt/Stmt/CreDtTm", 35),
+        ("Document/BkToCstmrStmt/Stmt/Acct/Id/Othr/Id", 34),
+    ] {
+        let text = &required(&elements, path)?.text;
+        if text.is_empty() || text.chars().count() > max_length {
+            return Err(format!("{path} is empty or longer than {max_length}"));
+        }
+    }
+    for path in [
+        "Document/BkToCstmrStmt/GrpHdr/CreDtTm",
+        "Document/BkToCstmrStmt/Stmt/CreDtTm",
+    ] {
+        DateTime::parse_from_rfc3339(&required(&elements, path)?.text)
+            .map_err(|_| format!("{path} is not a date and time"))?;
+    }
+    check_currency_code(&required(&elements, "Document/BkToCstmrStmt/Stmt/Acct/Ccy")?.text)?;
+
+    let balance_path = "Document/BkToCstmrStmt/Stmt/Bal";
+    let mut balance_codes = vec![];
+    for (index, _) in elements
+        .iter()
+        .enumerate()
+        .filter(|(_, element)| element.path == balance_path)
+    {
+        let fields = descendants(&elements, index);
+        balance_codes.push(
+            required(fields, &format!("{balance_path}/Tp/CdOrPrtry/Cd"))?
+                .text
+                .clone(),
+        );
+        check_camt_amount(fields, balance_path)?;
+        required(fields, &format!("{balance_path}/Dt/DtTm"))?;
+    }
+    for code in ["OPBD", "CLBD"] {
+        if !balance_codes
+            .iter()
+            .any(|balance_code| balance_code == code)
+        {
+            return Err(format!("the {code} balance is missing"));
+        }
+    }
+
+    let entry_path = "Document/BkToCstmrStmt/Stmt/Ntry";
+    for (index, _) in elements
+        .iter()
+        .enumerate()
+        .filter(|(_, element)| element.path == entry_path)
+    {
+        let fields = descendants(&elements, index);
+        check_camt_amount(fields, entry_path)?;
+        let status = &required(fields, &format!("{entry_path}/Sts"))?.text;
+        if !["BOOK", "PDNG", "INFO"].contains(&status.as_str()) {
+            return Err(format!("{status} is not an entry status"));
+        }
+        required(fields, &format!("{entry_path}/BookgDt/DtTm"))?;
+        required(fields, &format!("{entry_path}/BkTxCd/Prtry/Cd"))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statements::entities::{
+        Statement, StatementFormat, StatementHolder, StatementStatus,
+    };
+    use crate::transactions::entities::TransactionType;
+
+    fn document(currency_code: &str) -> StatementDocument {
+        let statement = Statement {
+            identifier: Uuid::new_v4(),
+            reference: "STM-ABCDEFGHIJKLMNOP".to_string(),
+            user_identifier: Uuid::new_v4(),
+            wallet_identifier: Uuid::new_v4(),
+            format: StatementFormat::Mt940,
+            period_start: Local::now(),
+            period_end: Local::now(),
+            status: StatementStatus::Generating,
+            file_name: None,
+            opening_balance: None,
+            closing_balance: None,
+            line_count: None,
+            failure_reason: None,
+            generated_date: None,
+            created_date: Local::now(),
+            updated_at: Local::now(),
+        };
+        let holder = StatementHolder {
+            first_name: "Ada".to_string(),
+            last_name: "Obi".to_string(),
+            address: "12 Marina Road".to_string(),
+            country: "Nigeria".to_string(),
+            wallet_name: "Savings & Co".to_string(),
+            currency_code: currency_code.to_string(),
+        };
+        let lines = vec![
+            StatementLine {
+                identifier: Uuid::new_v4(),
+                posted_date: Local::now(),
+                reference: Some("TRF-QRSTUVWXYZ012345".to_string()),
+                transaction_type: Some(TransactionType::Transfer),
+                description: "rent: march <flat 2> ".repeat(30),
+                amount: BigDecimal::from(-140),
+            },
+            StatementLine {
+                identifier: Uuid::new_v4(),
+                posted_date: Local::now(),
+                reference: None,
+                transaction_type: None,
+                description: "—".to_string(),
+                amount: "12.5".parse().unwrap(),
+            },
+        ];
+        StatementDocument::new(&statement, holder, BigDecimal::from(100), lines)
+    }
+
+    #[test]
+    fn test_ofx_lists_every_line_with_a_unique_id() {
+        let ofx = render_ofx(&document("NGN")).unwrap();
+
+        assert!(ofx.contains("<CURDEF>NGN</CURDEF>"));
+        assert!(ofx.contains("<TRNAMT>-140.00</TRNAMT>"));
+        assert!(ofx.contains("<BALAMT>-27.50</BALAMT>"));
+        assert_eq!(ofx.matches("<STMTTRN>").count(), 2);
+        let elements = parse(&ofx).unwrap();
+        let account_id = required(
+            &elements,
+            "OFX/BANKMSGSRSV1/STMTTRNRS/STMTRS/BANKACCTFROM/ACCTID",
+        )
+        .unwrap();
+        assert_eq!(account_id.text.len(), OFX_ACCOUNT_ID_LENGTH);
+    }
+
+    #[test]
+    fn test_mt940_fields_fit_the_swift_layout() {
+        let mt940 = render_mt940(&document("NGN")).unwrap();
+        let lines: Vec<&str> = mt940.split("\r\n").collect();
+
+        assert_eq!(lines[0], ":20:ABCDEFGHIJKLMNOP");
+        assert!(lines[3].starts_with(":60F:C") && lines[3].ends_with("NGN100,00"));
+        assert!(lines[4].starts_with(":61:") && lines[4].contains("D140,00NTRFQRSTUVWXYZ012345//"));
+        assert!(lines.iter().all(|line| line.len() <= MT940_LINE_LENGTH));
+        assert!(mt940.contains("NMSCNONREF//"));
+        assert!(mt940.contains(":62F:D"));
+        assert!(mt940.ends_with("NGN27,50\r\n-\r\n"));
+    }
+
+    #[test]
+    fn test_camt053_carries_both_balances_and_every_entry() {
+        let camt = render_camt053(&document("NGN")).unwrap();
+        let elements = parse(&camt).unwrap();
+
+        let entries = elements
+            .iter()
+            .filter(|element| element.path == "Document/BkToCstmrStmt/Stmt/Ntry")
+            .count();
+        assert_eq!(entries, 2);
+        assert!(camt.contains("<Amt Ccy=\"NGN\">27.50</Amt>\n"));
+        assert!(camt.contains("<Nm>Savings &amp; Co</Nm>"));
+    }
+
+    #[test]
+    fn test_currency_codes_must_be_iso_4217() {
+        assert!(render_ofx(&document("naira")).is_err());
+        assert!(render_mt940(&document("NG")).is_err());
+        assert!(render_camt053(&document("")).is_err());
+    }
+
+    #[test]
+    fn test_structurally_broken_files_are_rejected() {
+        let mt940 = render_mt940(&document("AED")).unwrap();
+        assert!(validate_mt940(&mt940.replace(":28C:1/1\r\n", "")).is_err());
+        assert!(validate_mt940(&mt940.replace("\r\n-\r\n", "\r\n")).is_err());
+        assert!(validate_mt940(&mt940.replace(":60F:C", ":60F:X")).is_err());
+
+        let ofx = render_ofx(&document("AED")).unwrap();
+        assert!(validate_ofx(&ofx.replace("<CURDEF>AED</CURDEF>", "")).is_err());
+        assert!(validate_ofx(&ofx.replace("</STMTTRN>", "")).is_err());
+        assert!(validate_ofx(&ofx.replace("<ACCTID>", "<ACCTID>0123456789")).is_err());
+        assert!(validate_ofx(&ofx.replace("<REFNUM>", "<REFNUM>0123456789ABCDEF")).is_err());
+
+        let camt = render_camt053(&document("AED")).unwrap();
+        assert!(validate_camt053(&camt.replace("CLBD", "XXXX")).is_err());
+        assert!(validate_camt053(&camt.replace(" Ccy=\"AED\"", "")).is_err());
+        assert!(validate_camt053(&camt.replace(CAMT_053_NAMESPACE, "urn:other")).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::statements::adapters::{CreateStatementRequest, StatementDownloadQuery};
+use crate::statements::entities::{Statement, StatementWithLink};
+use crate::statements::service::{StatementService, StatementServiceExt};
+use crate::utils::{ApiResponse, AuthenticatedRequest, PaginatedResponse, PaginationParams};
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use uuid::Uuid;
+
+pub async fn request_statement(
+    State(statement_service): State<StatementService>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateStatementRequest>,
+) -> Result<ApiResponse<Statement>, ServiceError> {
+    let statement = statement_service
+        .request_statement(&claims, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(statement)
+        .message("statement requested, it will be ready shortly")
+        .status_code(StatusCode::ACCEPTED)
+        .build())
+}
+
+pub async fn fetch_statements(
+    State(statement_service): State<StatementService>,
+    claims: Claims,
+    Query(pagination_params): Query<PaginationParams>,
+) -> Result<ApiResponse<PaginatedResponse<Statement>>, ServiceError> {
+    let statements = statement_service
+        .fetch_statements(&claims, &pagination_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(statements).build())
+}
+
+pub async fn fetch_statement(
+    State(statement_service): State<StatementService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<StatementWithLink>, ServiceError> {
+    let statement = statement_service
+        .fetch_statement(&claims, &identifier)
+        .await?;
+
+    Ok(ApiResponse::builder().data(statement).build())
+}
+
+/// The signed link is the only credential, so the file can be opened straight from a browser
+pub async fn download_statement(
+    State(statement_service): State<StatementService>,
+    Query(query): Query<StatementDownloadQuery>,
+) -> Result<impl IntoResponse, ServiceError> {
+    let (file_name, content_type, contents) = statement_service.download(&query.token).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        contents,
+    ))
+}
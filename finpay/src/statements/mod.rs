@@ -0,0 +1,9 @@
+pub mod adapters;
+pub mod entities;
+pub mod exchange;
+pub mod handlers;
+pub mod pdf;
+pub mod render;
+pub mod repository;
+pub mod router;
+pub mod service;
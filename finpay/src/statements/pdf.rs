@@ -0,0 +1,257 @@
+//! Just enough of PDF to lay out lines of text on A4 pages with the standard fonts every reader
+//! ships with, nothing is embedded
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+pub const MARGIN: f32 = 40.0;
+
+/// the first objects are fixed, pages start after them
+const CATALOG: usize = 1;
+const PAGES: usize = 2;
+const FONTS: [(&str, &str); 3] = [
+    ("F1", "Helvetica"),
+    ("F2", "Helvetica-Bold"),
+    ("F3", "Courier"),
+];
+const FIRST_PAGE_OBJECT: usize = 3 + FONTS.len();
+
+#[derive(Debug, Clone, Copy)]
+pub enum Font {
+    Regular,
+    Bold,
+    /// fixed width, used wherever columns of figures have to line up
+    Mono,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+
+    /// a rough width of a character, exact for courier
+    fn char_width(&self, size: f32) -> f32 {
+        match self {
+            Font::Mono => size * 0.6,
+            _ => size * 0.5,
+        }
+    }
+}
+
+/// A piece of text on a row, `Right` ends at `x` rather than starting there
+pub enum Cell<'a> {
+    Left(f32, &'a str),
+    Right(f32, &'a str),
+}
+
+pub struct PdfWriter {
+    pages: Vec<String>,
+    content: String,
+    cursor_y: f32,
+}
+
+impl Default for PdfWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PdfWriter {
+    pub fn new() -> Self {
+        Self {
+            pages: vec![],
+            content: String::new(),
+            cursor_y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    pub fn page_width(&self) -> f32 {
+        PAGE_WIDTH
+    }
+
+    /// Writes a row of text below the previous one, a new page is started when the row does not
+    /// fit on the current one
+    pub fn row(&mut self, font: Font, size: f32, cells: &[Cell]) {
+        let line_height = size * 1.5;
+        if self.cursor_y - line_height < MARGIN {
+            self.new_page();
+        }
+        self.cursor_y -= line_height;
+
+        for cell in cells {
+            let (x, text) = match cell {
+                Cell::Left(x, text) => (*x, *text),
+                Cell::Right(x, text) => (
+                    *x - text.chars().count() as f32 * font.char_width(size),
+                    *text,
+                ),
+            };
+            self.content.push_str(&format!(
+                "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+                font.resource(),
+                size,
+                x,
+                self.cursor_y,
+                escape(text)
+            ));
+        }
+    }
+
+    /// Leaves some blank space, or a horizontal rule across the page when `rule` is set
+    pub fn gap(&mut self, height: f32, rule: bool) {
+        self.cursor_y -= height;
+        if rule {
+            self.content.push_str(&format!(
+                "{:.2} {:.2} m {:.2} {:.2} l 0.5 w S\n",
+                MARGIN,
+                self.cursor_y,
+                PAGE_WIDTH - MARGIN,
+                self.cursor_y
+            ));
+        }
+    }
+
+    pub fn new_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.content));
+        self.cursor_y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Lays the pages out as a complete file
+    pub fn finish(mut self) -> Vec<u8> {
+        if !self.content.is_empty() || self.pages.is_empty() {
+            self.new_page();
+        }
+
+        // every page is a page object followed by its content stream
+        let page_objects: Vec<usize> = (0..self.pages.len())
+            .map(|index| FIRST_PAGE_OBJECT + index * 2)
+            .collect();
+
+        let mut objects = vec![
+            format!("<< /Type /Catalog /Pages {PAGES} 0 R >>"),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_objects
+                    .iter()
+                    .map(|object| format!("{object} 0 R"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                page_objects.len()
+            ),
+        ];
+        for (_, base_font) in FONTS {
+            objects.push(format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{base_font} /Encoding /WinAnsiEncoding >>"
+            ));
+        }
+        let fonts = FONTS
+            .iter()
+            .enumerate()
+            .map(|(index, (name, _))| format!("/{name} {} 0 R", PAGES + 1 + index))
+            .collect::<Vec<_>>()
+            .join(" ");
+        for (page, object) in self.pages.iter().zip(&page_objects) {
+            objects.push(format!(
+                "<< /Type /Page /Parent {PAGES} 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] /Resources << /Font << {fonts} >> >> /Contents {} 0 R >>",
+                object + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{page}endstream",
+                page.len()
+            ));
+        }
+
+        let mut file = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(file.len());
+            file.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", index + 1).as_bytes());
+        }
+
+        let xref = file.len();
+        file.extend_from_slice(
+            format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+        );
+        for offset in offsets {
+            file.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+        }
+        file.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {CATALOG} 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+                objects.len() + 1
+            )
+            .as_bytes(),
+        );
+        file
+    }
+}
+
+/// Escapes the characters that end or break a string, anything the standard fonts cannot show
+/// becomes a question mark
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' | '(' | ')' => format!("\\{c}"),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_reference_points_at_every_object() {
+        let mut writer = PdfWriter::new();
+        writer.row(Font::Bold, 14.0, &[Cell::Left(MARGIN, "Statement (draft)")]);
+        writer.new_page();
+        writer.row(Font::Mono, 9.0, &[Cell::Right(300.0, "1,000.00")]);
+        let text = String::from_utf8(writer.finish()).unwrap();
+
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("(Statement \\(draft\\))"));
+
+        let startxref: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(text[startxref..].starts_with("xref"));
+
+        let offsets: Vec<usize> = text[startxref..]
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        assert_eq!(offsets.len(), FIRST_PAGE_OBJECT - 1 + 4);
+        for (index, offset) in offsets.into_iter().enumerate() {
+            assert!(text[offset..].starts_with(&format!("{} 0 obj", index + 1)));
+        }
+    }
+
+    #[test]
+    fn test_rows_flow_onto_a_new_page() {
+        let mut writer = PdfWriter::new();
+        for _ in 0..100 {
+            writer.row(Font::Regular, 10.0, &[Cell::Left(MARGIN, "line")]);
+        }
+        let text = String::from_utf8(writer.finish()).unwrap();
+
+        assert!(text.contains("/Count 2"));
+    }
+
+    #[test]
+    fn test_text_the_fonts_cannot_show_is_replaced() {
+        assert_eq!(escape("café"), "caf?");
+        assert_eq!(escape(r"a\b"), r"a\\b");
+    }
+}
@@ -0,0 +1,270 @@
+use crate::statements::entities::{StatementDocument, StatementFormat};
+use crate::statements::exchange::{render_camt053, render_mt940, render_ofx};
+use crate::statements::pdf::{Cell, Font, MARGIN, PdfWriter};
+use bigdecimal::BigDecimal;
+use chrono::Local;
+
+/// the longest description that fits its column on a pdf statement
+const PDF_DESCRIPTION_WIDTH: usize = 30;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatementRenderError {
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error("the {format:?} statement does not follow its format: {reason}")]
+    Invalid {
+        format: StatementFormat,
+        reason: String,
+    },
+}
+
+/// Writes a statement in the format it was requested in
+pub fn render(
+    document: &StatementDocument,
+    format: StatementFormat,
+) -> Result<Vec<u8>, StatementRenderError> {
+    let exchange = match format {
+        StatementFormat::Csv => return Ok(render_csv(document)?),
+        StatementFormat::Pdf => return Ok(render_pdf(document)),
+        StatementFormat::Ofx => render_ofx(document),
+        StatementFormat::Mt940 => render_mt940(document),
+        StatementFormat::Camt053 => render_camt053(document),
+    };
+    exchange
+        .map(String::into_bytes)
+        .map_err(|reason| StatementRenderError::Invalid { format, reason })
+}
+
+fn money(amount: &BigDecimal) -> String {
+    amount.with_scale(2).to_string()
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width - 3).collect();
+    truncated.push_str("...");
+    truncated
+}
+
+/// The holder and period on a few leading rows, then one row per line between the opening and
+/// closing balances
+pub fn render_csv(document: &StatementDocument) -> Result<Vec<u8>, csv::Error> {
+    let holder = &document.holder;
+    let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(vec![]);
+
+    writer.write_record(["Statement", &document.reference])?;
+    writer.write_record([
+        "Account holder",
+        &format!("{} {}", holder.first_name, holder.last_name),
+    ])?;
+    writer.write_record([
+        "Address",
+        &format!("{}, {}", holder.address, holder.country),
+    ])?;
+    writer.write_record([
+        "Wallet",
+        &format!("{} ({})", holder.wallet_name, holder.currency_code),
+    ])?;
+    writer.write_record(["Period start", &document.period_start.to_rfc3339()])?;
+    writer.write_record(["Period end", &document.period_end.to_rfc3339()])?;
+
+    writer.write_record([
+        "Date",
+        "Reference",
+        "Description",
+        "Debit",
+        "Credit",
+        "Balance",
+    ])?;
+    writer.write_record([
+        "",
+        "",
+        "Opening balance",
+        "",
+        "",
+        &money(&document.opening_balance),
+    ])?;
+    for (line, balance) in &document.lines {
+        writer.write_record([
+            line.posted_date.to_rfc3339(),
+            line.reference.clone().unwrap_or_default(),
+            line.description.clone(),
+            line.debit().map(|debit| money(&debit)).unwrap_or_default(),
+            line.credit()
+                .map(|credit| money(&credit))
+                .unwrap_or_default(),
+            money(balance),
+        ])?;
+    }
+    writer.write_record([
+        "",
+        "",
+        "Closing balance",
+        "",
+        "",
+        &money(&document.closing_balance),
+    ])?;
+
+    writer.into_inner().map_err(|err| err.into_error().into())
+}
+
+pub fn render_pdf(document: &StatementDocument) -> Vec<u8> {
+    let holder = &document.holder;
+    let mut writer = PdfWriter::new();
+    let right_edge = writer.page_width() - MARGIN;
+    let (debit_x, credit_x) = (right_edge - 150.0, right_edge - 75.0);
+
+    writer.row(Font::Bold, 16.0, &[Cell::Left(MARGIN, "Account statement")]);
+    writer.gap(6.0, false);
+    let name = format!("{} {}", holder.first_name, holder.last_name);
+    for text in [&name, &holder.address, &holder.country] {
+        writer.row(Font::Regular, 10.0, &[Cell::Left(MARGIN, text)]);
+    }
+    writer.gap(6.0, false);
+
+    let wallet = format!("{} ({})", holder.wallet_name, holder.currency_code);
+    let period = format!(
+        "{} to {}",
+        document.period_start.format("%d %b %Y %H:%M"),
+        document.period_end.format("%d %b %Y %H:%M")
+    );
+    for (label, value) in [
+        ("Statement", document.reference.as_str()),
+        ("Wallet", &wallet),
+        ("Period", &period),
+    ] {
+        writer.row(
+            Font::Regular,
+            10.0,
+            &[Cell::Left(MARGIN, label), Cell::Left(MARGIN + 80.0, value)],
+        );
+    }
+    writer.gap(8.0, true);
+
+    writer.row(
+        Font::Bold,
+        9.0,
+        &[
+            Cell::Left(MARGIN, "Date"),
+            Cell::Left(MARGIN + 52.0, "Reference"),
+            Cell::Left(MARGIN + 155.0, "Description"),
+            Cell::Right(debit_x, "Debit"),
+            Cell::Right(credit_x, "Credit"),
+            Cell::Right(right_edge, "Balance"),
+        ],
+    );
+    let opening_balance = money(&document.opening_balance);
+    writer.row(
+        Font::Mono,
+        8.0,
+        &[
+            Cell::Left(MARGIN + 155.0, "Opening balance"),
+            Cell::Right(right_edge, &opening_balance),
+        ],
+    );
+    for (line, balance) in &document.lines {
+        let date = line.posted_date.format("%Y-%m-%d").to_string();
+        let description = truncate(&line.description, PDF_DESCRIPTION_WIDTH);
+        let debit = line.debit().map(|debit| money(&debit)).unwrap_or_default();
+        let credit = line
+            .credit()
+            .map(|credit| money(&credit))
+            .unwrap_or_default();
+        let balance = money(balance);
+        writer.row(
+            Font::Mono,
+            8.0,
+            &[
+                Cell::Left(MARGIN, &date),
+                Cell::Left(MARGIN + 52.0, line.reference.as_deref().unwrap_or_default()),
+                Cell::Left(MARGIN + 155.0, &description),
+                Cell::Right(debit_x, &debit),
+                Cell::Right(credit_x, &credit),
+                Cell::Right(right_edge, &balance),
+            ],
+        );
+    }
+    let closing_balance = money(&document.closing_balance);
+    writer.row(
+        Font::Bold,
+        9.0,
+        &[
+            Cell::Left(MARGIN + 155.0, "Closing balance"),
+            Cell::Right(right_edge, &closing_balance),
+        ],
+    );
+
+    writer.gap(8.0, true);
+    let generated = format!("Generated on {}", Local::now().format("%d %b %Y %H:%M"));
+    writer.row(Font::Regular, 8.0, &[Cell::Left(MARGIN, &generated)]);
+
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statements::entities::{
+        Statement, StatementFormat, StatementHolder, StatementLine, StatementStatus,
+    };
+    use crate::transactions::entities::TransactionType;
+    use uuid::Uuid;
+
+    fn document() -> StatementDocument {
+        let statement = Statement {
+            identifier: Uuid::new_v4(),
+            reference: "STM-TEST".to_string(),
+            user_identifier: Uuid::new_v4(),
+            wallet_identifier: Uuid::new_v4(),
+            format: StatementFormat::Csv,
+            period_start: Local::now(),
+            period_end: Local::now(),
+            status: StatementStatus::Generating,
+            file_name: None,
+            opening_balance: None,
+            closing_balance: None,
+            line_count: None,
+            failure_reason: None,
+            generated_date: None,
+            created_date: Local::now(),
+            updated_at: Local::now(),
+        };
+        let holder = StatementHolder {
+            first_name: "Ada".to_string(),
+            last_name: "Obi".to_string(),
+            address: "12 Marina Road".to_string(),
+            country: "Nigeria".to_string(),
+            wallet_name: "Savings".to_string(),
+            currency_code: "NGN".to_string(),
+        };
+        let lines = vec![StatementLine {
+            identifier: Uuid::new_v4(),
+            posted_date: Local::now(),
+            reference: Some("TRF-ABC".to_string()),
+            transaction_type: Some(TransactionType::Transfer),
+            description: "rent, march".to_string(),
+            amount: BigDecimal::from(-40),
+        }];
+        StatementDocument::new(&statement, holder, BigDecimal::from(100), lines)
+    }
+
+    #[test]
+    fn test_csv_lists_lines_between_opening_and_closing_balances() {
+        let csv = String::from_utf8(render_csv(&document()).unwrap()).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(rows[1], "Account holder,Ada Obi");
+        assert_eq!(rows[2], "Address,\"12 Marina Road, Nigeria\"");
+        assert_eq!(rows[7], ",,Opening balance,,,100.00");
+        assert!(rows[8].ends_with(",TRF-ABC,\"rent, march\",40.00,,60.00"));
+        assert_eq!(rows[9], ",,Closing balance,,,60.00");
+    }
+
+    #[test]
+    fn test_long_descriptions_are_cut_short() {
+        assert_eq!(truncate("groceries", 30), "groceries");
+        assert_eq!(truncate("abcdefghij", 8), "abcde...");
+    }
+}
@@ -0,0 +1,370 @@
+use crate::errors::RepositoryError;
+use crate::statements::adapters::CreateStatementRequest;
+use crate::statements::entities::{Statement, StatementHolder, StatementLine, StatementStatus};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use bigdecimal::BigDecimal;
+use finpay_utils::generate_reference;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// a statement left generating this long is assumed abandoned by a worker that went away
+const STALE_GENERATION_IN_MINUTES: i32 = 15;
+
+#[derive(Clone)]
+pub struct StatementRepository {
+    pool: PgPool,
+}
+
+impl StatementRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+pub trait StatementRepositoryExt {
+    fn create(
+        &self,
+        request: &CreateStatementRequest,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Statement, RepositoryError>> + Send;
+
+    fn fetch_statement(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<Statement>, RepositoryError>> + Send;
+
+    fn find_by_identifier(
+        &self,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<Statement>, RepositoryError>> + Send;
+
+    fn fetch_statements(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Statement>, RepositoryError>> + Send;
+
+    /// Moves the oldest pending statement to generating, concurrent workers never claim the same
+    /// one. Statements stuck generating are picked up again
+    fn claim_next(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Option<Statement>, RepositoryError>> + Send;
+
+    fn fetch_holder(
+        &self,
+        statement: &Statement,
+    ) -> impl std::future::Future<Output = Result<StatementHolder, RepositoryError>> + Send;
+
+    /// The wallet balance from the postings made before the period starts
+    fn fetch_opening_balance(
+        &self,
+        statement: &Statement,
+    ) -> impl std::future::Future<Output = Result<BigDecimal, RepositoryError>> + Send;
+
+    /// The postings on the wallet within the period, oldest first
+    fn fetch_lines(
+        &self,
+        statement: &Statement,
+    ) -> impl std::future::Future<Output = Result<Vec<StatementLine>, RepositoryError>> + Send;
+
+    fn mark_ready(
+        &self,
+        identifier: &Uuid,
+        file_name: &str,
+        opening_balance: &BigDecimal,
+        closing_balance: &BigDecimal,
+        line_count: i32,
+    ) -> impl std::future::Future<Output = Result<Statement, RepositoryError>> + Send;
+
+    fn mark_failed(
+        &self,
+        identifier: &Uuid,
+        failure_reason: &str,
+    ) -> impl std::future::Future<Output = Result<Statement, RepositoryError>> + Send;
+}
+
+impl StatementRepositoryExt for StatementRepository {
+    async fn create(
+        &self,
+        request: &CreateStatementRequest,
+        user_identifier: &Uuid,
+    ) -> Result<Statement, RepositoryError> {
+        let query = r#"
+        INSERT INTO statements (identifier, reference, user_identifier, wallet_identifier, format, period_start, period_end)
+        SELECT $1, $2, wallets.user_identifier, wallets.identifier, $3, $4, $5
+        FROM wallets
+        WHERE wallets.identifier = $6 AND wallets.user_identifier = $7
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, Statement>(query)
+            .bind(Uuid::new_v4())
+            .bind(generate_reference("STM"))
+            .bind(request.format)
+            .bind(request.from)
+            .bind(request.to)
+            .bind(request.wallet_identifier)
+            .bind(user_identifier)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)
+    }
+
+    async fn fetch_statement(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<Statement>, RepositoryError> {
+        sqlx::query_as::<_, Statement>(
+            r#"SELECT * FROM statements WHERE identifier = $1 AND user_identifier = $2"#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn find_by_identifier(
+        &self,
+        identifier: &Uuid,
+    ) -> Result<Option<Statement>, RepositoryError> {
+        sqlx::query_as::<_, Statement>(r#"SELECT * FROM statements WHERE identifier = $1"#)
+            .bind(identifier)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_statements(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Statement>, RepositoryError> {
+        let total_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(identifier) FROM statements WHERE user_identifier = $1",
+        )
+        .bind(user_identifier)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let page = pagination_params.page();
+        let page_size = pagination_params.per_page();
+        let offset = (page - 1) * page_size;
+
+        let statements = sqlx::query_as::<_, Statement>(
+            r#"SELECT * FROM statements WHERE user_identifier = $1 ORDER BY created_date DESC LIMIT $2 OFFSET $3"#,
+        )
+        .bind(user_identifier)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            statements,
+            pagination_params,
+            total_count,
+        ))
+    }
+
+    async fn claim_next(&self) -> Result<Option<Statement>, RepositoryError> {
+        let query = r#"
+        UPDATE statements
+        SET status = $1
+        WHERE identifier = (SELECT identifier
+                            FROM statements
+                            WHERE status = $2
+                               OR (status = $1 AND updated_at < NOW() - make_interval(mins => $3))
+                            ORDER BY created_date
+                            LIMIT 1 FOR UPDATE SKIP LOCKED)
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, Statement>(query)
+            .bind(StatementStatus::Generating)
+            .bind(StatementStatus::Pending)
+            .bind(STALE_GENERATION_IN_MINUTES)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_holder(
+        &self,
+        statement: &Statement,
+    ) -> Result<StatementHolder, RepositoryError> {
+        let query = r#"
+        SELECT COALESCE(users.first_name, '') AS first_name,
+               COALESCE(users.last_name, '')  AS last_name,
+               COALESCE(users.address, '')    AS address,
+               COALESCE(users.country, '')    AS country,
+               COALESCE(wallets.name, '')     AS wallet_name,
+               countries.currency_code
+        FROM wallets
+                 JOIN users ON users.identifier = wallets.user_identifier
+                 JOIN countries ON countries.identifier = wallets.currency_identifier
+        WHERE wallets.identifier = $1
+        "#;
+        sqlx::query_as::<_, StatementHolder>(query)
+            .bind(statement.wallet_identifier)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)
+    }
+
+    async fn fetch_opening_balance(
+        &self,
+        statement: &Statement,
+    ) -> Result<BigDecimal, RepositoryError> {
+        let query = r#"
+        SELECT COALESCE(SUM(CASE WHEN postings.direction = 'credit' THEN postings.amount ELSE -postings.amount END), 0)
+        FROM postings
+                 JOIN ledger_accounts ON ledger_accounts.identifier = postings.ledger_account_identifier
+        WHERE ledger_accounts.wallet_identifier = $1
+          AND postings.created_date < $2
+        "#;
+        sqlx::query_scalar(query)
+            .bind(statement.wallet_identifier)
+            .bind(statement.period_start)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_lines(
+        &self,
+        statement: &Statement,
+    ) -> Result<Vec<StatementLine>, RepositoryError> {
+        let query = r#"
+        SELECT postings.identifier,
+               postings.created_date AS posted_date,
+               transactions.reference,
+               transactions.transaction_type,
+               COALESCE(transactions.description, journal_entries.description) AS description,
+               CASE WHEN postings.direction = 'credit' THEN postings.amount ELSE -postings.amount END AS amount
+        FROM postings
+                 JOIN ledger_accounts ON ledger_accounts.identifier = postings.ledger_account_identifier
+                 JOIN journal_entries ON journal_entries.identifier = postings.journal_entry_identifier
+                 LEFT JOIN transactions ON transactions.identifier = journal_entries.transaction_identifier
+        WHERE ledger_accounts.wallet_identifier = $1
+          AND postings.created_date >= $2
+          AND postings.created_date < $3
+        ORDER BY postings.created_date, postings.identifier
+        "#;
+        sqlx::query_as::<_, StatementLine>(query)
+            .bind(statement.wallet_identifier)
+            .bind(statement.period_start)
+            .bind(statement.period_end)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn mark_ready(
+        &self,
+        identifier: &Uuid,
+        file_name: &str,
+        opening_balance: &BigDecimal,
+        closing_balance: &BigDecimal,
+        line_count: i32,
+    ) -> Result<Statement, RepositoryError> {
+        let query = r#"
+        UPDATE statements
+        SET status = $1, file_name = $2, opening_balance = $3, closing_balance = $4, line_count = $5,
+            failure_reason = NULL, generated_date = NOW()
+        WHERE identifier = $6
+        RETURNING *
+        "#;
+        sqlx::query_as::<_, Statement>(query)
+            .bind(StatementStatus::Ready)
+            .bind(file_name)
+            .bind(opening_balance)
+            .bind(closing_balance)
+            .bind(line_count)
+            .bind(identifier)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)
+    }
+
+    async fn mark_failed(
+        &self,
+        identifier: &Uuid,
+        failure_reason: &str,
+    ) -> Result<Statement, RepositoryError> {
+        sqlx::query_as::<_, Statement>(
+            r#"UPDATE statements SET status = $1, failure_reason = $2 WHERE identifier = $3 RETURNING *"#,
+        )
+        .bind(StatementStatus::Failed)
+        .bind(failure_reason)
+        .bind(identifier)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::fixtures::{create_user, create_wallet, fund};
+    use crate::statements::entities::StatementFormat;
+    use chrono::{Duration, Local};
+
+    #[sqlx::test]
+    async fn test_claimed_statement_covers_the_postings_in_its_period(pool: PgPool) {
+        let user_identifier = create_user(&pool).await;
+        let wallet_identifier = create_wallet(&pool, &user_identifier).await;
+
+        fund(&pool, &wallet_identifier, "100").await;
+        let period_start = Local::now();
+        fund(&pool, &wallet_identifier, "40").await;
+        fund(&pool, &wallet_identifier, "2").await;
+
+        let repository = StatementRepository::new(&pool);
+        let request = CreateStatementRequest {
+            wallet_identifier,
+            from: period_start,
+            to: Local::now() + Duration::minutes(1),
+            format: StatementFormat::Pdf,
+        };
+        assert!(matches!(
+            repository.create(&request, &Uuid::new_v4()).await,
+            Err(RepositoryError::RecordNotFound)
+        ));
+        let statement = repository.create(&request, &user_identifier).await.unwrap();
+        assert_eq!(statement.status, StatementStatus::Pending);
+
+        let claimed = repository.claim_next().await.unwrap().unwrap();
+        assert_eq!(claimed.identifier, statement.identifier);
+        assert_eq!(claimed.status, StatementStatus::Generating);
+        assert!(repository.claim_next().await.unwrap().is_none());
+
+        let opening_balance = repository.fetch_opening_balance(&claimed).await.unwrap();
+        let lines = repository.fetch_lines(&claimed).await.unwrap();
+        assert_eq!(opening_balance, BigDecimal::from(100));
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| line.amount.clone())
+                .collect::<Vec<_>>(),
+            vec![BigDecimal::from(40), BigDecimal::from(2)]
+        );
+
+        let holder = repository.fetch_holder(&claimed).await.unwrap();
+        assert_eq!(holder.currency_code, "AED");
+
+        let ready = repository
+            .mark_ready(
+                &claimed.identifier,
+                "statement.pdf",
+                &opening_balance,
+                &BigDecimal::from(142),
+                2,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ready.status, StatementStatus::Ready);
+        assert!(ready.generated_date.is_some());
+    }
+}
@@ -0,0 +1,17 @@
+use crate::state::AppState;
+use crate::statements::handlers::{
+    download_statement, fetch_statement, fetch_statements, request_statement,
+};
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+pub fn statement_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/", post(request_statement))
+        .route("/", get(fetch_statements))
+        .route("/download", get(download_statement))
+        .route("/{identifier}", get(fetch_statement))
+        .with_state(state.clone())
+}
@@ -0,0 +1,257 @@
+use crate::authentication::claims::Claims;
+use crate::config::AppConfig;
+use crate::errors::{AuthenticationError, RepositoryError, ServiceError};
+use crate::statements::adapters::{CreateStatementRequest, StatementLinkClaims};
+use crate::statements::entities::{
+    Statement, StatementDocument, StatementStatus, StatementWithLink,
+};
+use crate::statements::render::render;
+use crate::statements::repository::{StatementRepository, StatementRepositoryExt};
+use crate::utils::{PaginatedResponse, PaginationParams};
+use chrono::{DateTime, Duration, Local};
+use finpay_utils::extract_env;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use sqlx::PgPool;
+use std::path::Path;
+use uuid::Uuid;
+
+/// the most statements a single generation pass writes
+const STATEMENT_BATCH_SIZE: usize = 20;
+
+/// Download links are signed with a key derived from the JWT key, so a link can never pass as an
+/// access token or the other way round
+fn link_signing_key() -> Vec<u8> {
+    format!("{}:statements", extract_env::<String>("JWT_SIGNING_KEY")).into_bytes()
+}
+
+fn sign_link(statement: &Statement) -> Result<(String, DateTime<Local>), ServiceError> {
+    let ttl_in_minutes = extract_env::<i64>("STATEMENT_LINK_TTL_IN_MINUTES");
+    let expires_at = Local::now() + Duration::minutes(ttl_in_minutes);
+    let claims = StatementLinkClaims {
+        statement_identifier: statement.identifier,
+        exp: expires_at.timestamp(),
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(&link_signing_key()),
+    )
+    .map_err(AuthenticationError::from)?;
+    let download_url = format!(
+        "{}/statements/download?token={token}",
+        extract_env::<String>("API_BASE_URL")
+    );
+    Ok((download_url, expires_at))
+}
+
+fn with_link(statement: Statement) -> Result<StatementWithLink, ServiceError> {
+    let (download_url, download_url_expires_at) = match statement.status {
+        StatementStatus::Ready => {
+            let (download_url, expires_at) = sign_link(&statement)?;
+            (Some(download_url), Some(expires_at))
+        }
+        _ => (None, None),
+    };
+
+    Ok(StatementWithLink {
+        statement,
+        download_url,
+        download_url_expires_at,
+    })
+}
+
+#[derive(Clone)]
+pub struct StatementService {
+    repository: StatementRepository,
+}
+
+impl StatementService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: StatementRepository::new(pool),
+        }
+    }
+}
+
+pub trait StatementServiceExt {
+    /// Queues a statement for generation, a period ending in the future is cut short at now
+    fn request_statement(
+        &self,
+        claims: &Claims,
+        request: &CreateStatementRequest,
+    ) -> impl std::future::Future<Output = Result<Statement, ServiceError>> + Send;
+
+    fn fetch_statements(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Statement>, ServiceError>> + Send;
+
+    /// A statement with a freshly signed download link once it is ready
+    fn fetch_statement(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<StatementWithLink, ServiceError>> + Send;
+
+    /// Reads the file a signed link points at, returns its name, content type and contents
+    fn download(
+        &self,
+        token: &str,
+    ) -> impl std::future::Future<Output = Result<(String, &'static str, Vec<u8>), ServiceError>> + Send;
+}
+
+impl StatementServiceExt for StatementService {
+    async fn request_statement(
+        &self,
+        claims: &Claims,
+        request: &CreateStatementRequest,
+    ) -> Result<Statement, ServiceError> {
+        let request = CreateStatementRequest {
+            to: request.to.min(Local::now()),
+            ..request.clone()
+        };
+        if request.from >= request.to {
+            return Err(ServiceError::UnprocessableEntity(
+                "the statement period must start before it ends".to_string(),
+            ));
+        }
+
+        let statement = self
+            .repository
+            .create(&request, &claims.user_identifier)
+            .await?;
+        Ok(statement)
+    }
+
+    async fn fetch_statements(
+        &self,
+        claims: &Claims,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<Statement>, ServiceError> {
+        let statements = self
+            .repository
+            .fetch_statements(&claims.user_identifier, pagination_params)
+            .await?;
+        Ok(statements)
+    }
+
+    async fn fetch_statement(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<StatementWithLink, ServiceError> {
+        let statement = self
+            .repository
+            .fetch_statement(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        with_link(statement)
+    }
+
+    async fn download(&self, token: &str) -> Result<(String, &'static str, Vec<u8>), ServiceError> {
+        let link = decode::<StatementLinkClaims>(
+            token,
+            &DecodingKey::from_secret(&link_signing_key()),
+            &Validation::default(),
+        )
+        .map_err(|err| {
+            log::error!("failed to decode statement link due to {err}");
+            AuthenticationError::InvalidToken
+        })?
+        .claims;
+
+        let statement = self
+            .repository
+            .find_by_identifier(&link.statement_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        let file_name = match (statement.status, statement.file_name) {
+            (StatementStatus::Ready, Some(file_name)) => file_name,
+            _ => return Err(RepositoryError::RecordNotFound.into()),
+        };
+
+        let config = AppConfig::from_env()?;
+        let contents = tokio::fs::read(Path::new(&config.export_path).join(&file_name))
+            .await
+            .map_err(|err| {
+                log::error!("error reading statement due to {err}");
+                ServiceError::OperationFailed
+            })?;
+        Ok((file_name, statement.format.content_type(), contents))
+    }
+}
+
+/// Writes requested statements to `AppConfig.export_path`
+pub struct StatementGenerator {
+    repository: StatementRepository,
+}
+
+impl StatementGenerator {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: StatementRepository::new(pool),
+        }
+    }
+
+    /// Works through the pending statements, returns how many were written. A statement that
+    /// cannot be written is marked failed and does not hold up the rest
+    pub async fn generate_pending(&self) -> Result<usize, ServiceError> {
+        let mut generated = 0;
+
+        for _ in 0..STATEMENT_BATCH_SIZE {
+            let Some(statement) = self.repository.claim_next().await? else {
+                break;
+            };
+
+            match self.generate(&statement).await {
+                Ok(_) => generated += 1,
+                Err(err) => {
+                    tracing::warn!("statement {} failed: {}", statement.reference, err);
+                    self.repository
+                        .mark_failed(&statement.identifier, &err.to_string())
+                        .await?;
+                }
+            }
+        }
+
+        Ok(generated)
+    }
+
+    async fn generate(&self, statement: &Statement) -> Result<Statement, ServiceError> {
+        let holder = self.repository.fetch_holder(statement).await?;
+        let opening_balance = self.repository.fetch_opening_balance(statement).await?;
+        let lines = self.repository.fetch_lines(statement).await?;
+        let document = StatementDocument::new(statement, holder, opening_balance, lines);
+
+        let contents = render(&document, statement.format).map_err(|err| {
+            log::error!(
+                "error writing statement {} due to {err}",
+                statement.reference
+            );
+            ServiceError::UnprocessableEntity(err.to_string())
+        })?;
+
+        let config = AppConfig::from_env()?;
+        let file_name = format!("{}.{}", statement.reference, statement.format.extension());
+        tokio::fs::write(Path::new(&config.export_path).join(&file_name), contents)
+            .await
+            .map_err(|err| {
+                log::error!("error writing statement due to {err}");
+                ServiceError::OperationFailed
+            })?;
+
+        let statement = self
+            .repository
+            .mark_ready(
+                &statement.identifier,
+                &file_name,
+                &document.opening_balance,
+                &document.closing_balance,
+                document.lines.len() as i32,
+            )
+            .await?;
+        Ok(statement)
+    }
+}
@@ -0,0 +1,225 @@
+use crate::transactions::entities::{Transaction, TransactionStatus, TransactionType};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTransferRequest {
+    pub source_wallet_identifier: Uuid,
+    pub destination_wallet_identifier: Uuid,
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: BigDecimal,
+    #[validate(length(
+        max = 255,
+        message = "description cannot be more than 255 characters",
+        code = "description"
+    ))]
+    pub description: Option<String>,
+    /// Required when the wallets hold different currencies, the transfer executes at exactly
+    /// the quoted amounts
+    pub quote_identifier: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRefundRequest {
+    /// refunds whatever is left to refund when left out
+    #[validate(custom(
+        function = "crate::utils::validate_positive_amount",
+        message = "amount must be greater than zero",
+        code = "amount"
+    ))]
+    pub amount: Option<BigDecimal>,
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "reason must be between 1 and 255 characters",
+        code = "reason"
+    ))]
+    pub reason: String,
+}
+
+/// The details needed to open a transaction, it always starts out pending
+#[derive(Debug)]
+pub struct NewTransaction {
+    pub transaction_type: TransactionType,
+    pub source_wallet_identifier: Option<Uuid>,
+    pub destination_wallet_identifier: Option<Uuid>,
+    pub amount: BigDecimal,
+    pub currency_identifier: Uuid,
+    pub description: Option<String>,
+    pub initiated_by: Option<Uuid>,
+    pub external_reference: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTransactionNotesRequest {
+    /// clears the notes when left out
+    #[validate(length(
+        max = 1000,
+        message = "notes cannot be more than 1000 characters",
+        code = "notes"
+    ))]
+    pub notes: Option<String>,
+}
+
+/// The orders search results can come back in, ties are broken by the identifier so that every
+/// order is stable across pages
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionSort {
+    #[default]
+    Newest,
+    Oldest,
+    Largest,
+    Smallest,
+}
+
+/// Where a page of search results stopped, only the key the results are sorted on is set
+#[derive(Debug, Default, PartialEq)]
+pub struct TransactionCursor {
+    pub created_date: Option<DateTime<Local>>,
+    pub amount: Option<BigDecimal>,
+    pub identifier: Option<Uuid>,
+}
+
+impl TransactionSort {
+    /// the column results are sorted on
+    pub fn column(&self) -> &'static str {
+        match self {
+            TransactionSort::Newest | TransactionSort::Oldest => "created_date",
+            TransactionSort::Largest | TransactionSort::Smallest => "amount",
+        }
+    }
+
+    pub fn is_descending(&self) -> bool {
+        matches!(self, TransactionSort::Newest | TransactionSort::Largest)
+    }
+
+    /// An opaque cursor pointing right after a transaction, made of its sort key and identifier
+    pub fn cursor(&self, transaction: &Transaction) -> String {
+        match self {
+            TransactionSort::Newest | TransactionSort::Oldest => format!(
+                "{}_{}",
+                transaction.created_date.timestamp_micros(),
+                transaction.identifier
+            ),
+            TransactionSort::Largest | TransactionSort::Smallest => {
+                format!("{}_{}", transaction.amount, transaction.identifier)
+            }
+        }
+    }
+
+    /// Reads a cursor handed out for this order, nothing comes back for a cursor that was not
+    pub fn parse_cursor(&self, cursor: &str) -> Option<TransactionCursor> {
+        let (key, identifier) = cursor.rsplit_once('_')?;
+        let identifier = Some(Uuid::parse_str(identifier).ok()?);
+
+        match self {
+            TransactionSort::Newest | TransactionSort::Oldest => {
+                let micros = key.parse::<i64>().ok()?;
+                Some(TransactionCursor {
+                    created_date: Some(
+                        DateTime::from_timestamp_micros(micros)?.with_timezone(&Local),
+                    ),
+                    identifier,
+                    ..Default::default()
+                })
+            }
+            TransactionSort::Largest | TransactionSort::Smallest => Some(TransactionCursor {
+                amount: Some(BigDecimal::from_str(key).ok()?),
+                identifier,
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSearchQuery {
+    /// transactions moving money in or out of this wallet
+    pub wallet_identifier: Option<Uuid>,
+    pub from: Option<DateTime<Local>>,
+    pub to: Option<DateTime<Local>>,
+    pub min_amount: Option<BigDecimal>,
+    pub max_amount: Option<BigDecimal>,
+    pub status: Option<TransactionStatus>,
+    #[serde(rename = "type")]
+    pub transaction_type: Option<TransactionType>,
+    /// part of the name or email of whoever owns the wallet on the other side
+    pub counterparty: Option<String>,
+    /// words to look for in the description and notes
+    pub q: Option<String>,
+    #[serde(default)]
+    pub sort: TransactionSort,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::Zero;
+
+    fn transaction() -> Transaction {
+        Transaction {
+            identifier: Uuid::new_v4(),
+            reference: "TRF-TEST".to_string(),
+            transaction_type: TransactionType::Transfer,
+            status: TransactionStatus::Completed,
+            source_wallet_identifier: None,
+            destination_wallet_identifier: None,
+            amount: BigDecimal::from_str("12.500000").unwrap(),
+            refunded_amount: BigDecimal::zero(),
+            currency_identifier: Uuid::new_v4(),
+            description: None,
+            notes: None,
+            failure_reason: None,
+            initiated_by: None,
+            external_reference: None,
+            created_date: Local::now(),
+            updated_at: Local::now(),
+        }
+    }
+
+    #[test]
+    fn test_cursor_points_back_at_the_transaction_it_was_made_from() {
+        let transaction = transaction();
+
+        let by_date = TransactionSort::Newest
+            .parse_cursor(&TransactionSort::Newest.cursor(&transaction))
+            .unwrap();
+        assert_eq!(
+            by_date.created_date.unwrap().timestamp_micros(),
+            transaction.created_date.timestamp_micros()
+        );
+        assert_eq!(by_date.identifier, Some(transaction.identifier));
+        assert!(by_date.amount.is_none());
+
+        let by_amount = TransactionSort::Smallest
+            .parse_cursor(&TransactionSort::Smallest.cursor(&transaction))
+            .unwrap();
+        assert_eq!(by_amount.amount, Some(transaction.amount.clone()));
+        assert!(by_amount.created_date.is_none());
+    }
+
+    #[test]
+    fn test_cursor_from_another_order_is_rejected() {
+        let cursor = TransactionSort::Largest.cursor(&transaction());
+
+        assert!(TransactionSort::Newest.parse_cursor(&cursor).is_none());
+        assert!(
+            TransactionSort::Newest
+                .parse_cursor("not-a-cursor")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt::{Display, Formatter};
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "transaction_status_enum")]
+pub enum TransactionStatus {
+    Pending,
+    Completed,
+    Failed,
+    Reversed,
+}
+
+impl TransactionStatus {
+    /// pending -> completed | failed, completed -> reversed; failed and reversed are final
+    pub fn can_transition_to(&self, next: TransactionStatus) -> bool {
+        matches!(
+            (self, next),
+            (TransactionStatus::Pending, TransactionStatus::Completed)
+                | (TransactionStatus::Pending, TransactionStatus::Failed)
+                | (TransactionStatus::Completed, TransactionStatus::Reversed)
+        )
+    }
+}
+
+impl Display for TransactionStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionStatus::Pending => write!(f, "pending"),
+            TransactionStatus::Completed => write!(f, "completed"),
+            TransactionStatus::Failed => write!(f, "failed"),
+            TransactionStatus::Reversed => write!(f, "reversed"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "transaction_type_enum")]
+pub enum TransactionType {
+    Transfer,
+    Deposit,
+    Withdrawal,
+    HoldCapture,
+    Conversion,
+    /// gives back all or part of an earlier transfer
+    Refund,
+}
+
+impl TransactionType {
+    pub fn reference_prefix(&self) -> &'static str {
+        match self {
+            TransactionType::Transfer => "TRF",
+            TransactionType::Deposit => "DEP",
+            TransactionType::Withdrawal => "WDL",
+            TransactionType::HoldCapture => "CAP",
+            TransactionType::Conversion => "FXC",
+            TransactionType::Refund => "RFD",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error("a {from} transaction cannot be marked as {to}")]
+pub struct IllegalTransition {
+    pub from: TransactionStatus,
+    pub to: TransactionStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub identifier: Uuid,
+    pub reference: String,
+    pub transaction_type: TransactionType,
+    pub status: TransactionStatus,
+    pub source_wallet_identifier: Option<Uuid>,
+    pub destination_wallet_identifier: Option<Uuid>,
+    pub amount: BigDecimal,
+    /// how much of `amount` has been refunded so far
+    pub refunded_amount: BigDecimal,
+    pub currency_identifier: Uuid,
+    pub description: Option<String>,
+    /// free text kept by whoever started the transaction
+    pub notes: Option<String>,
+    pub failure_reason: Option<String>,
+    pub initiated_by: Option<Uuid>,
+    pub external_reference: Option<String>,
+    pub created_date: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl Transaction {
+    /// Moves the transaction to its next state, rejecting anything the lifecycle does not allow
+    pub fn transition_to(&mut self, next: TransactionStatus) -> Result<(), IllegalTransition> {
+        if !self.status.can_transition_to(next) {
+            return Err(IllegalTransition {
+                from: self.status,
+                to: next,
+            });
+        }
+
+        self.status = next;
+        Ok(())
+    }
+
+    /// what is left to refund, only completed transactions can be refunded
+    pub fn refundable_amount(&self) -> BigDecimal {
+        match self.status {
+            TransactionStatus::Completed => &self.amount - &self.refunded_amount,
+            _ => BigDecimal::zero(),
+        }
+    }
+
+    /// the status reported to users, it tells partly refunded transactions apart from the rest
+    pub fn derived_status(&self) -> DerivedTransactionStatus {
+        match self.status {
+            TransactionStatus::Pending => DerivedTransactionStatus::Pending,
+            TransactionStatus::Failed => DerivedTransactionStatus::Failed,
+            TransactionStatus::Reversed => DerivedTransactionStatus::Refunded,
+            TransactionStatus::Completed if self.refunded_amount.is_zero() => {
+                DerivedTransactionStatus::Completed
+            }
+            TransactionStatus::Completed => DerivedTransactionStatus::PartiallyRefunded,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DerivedTransactionStatus {
+    Pending,
+    Completed,
+    PartiallyRefunded,
+    Refunded,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTransition {
+    pub identifier: Uuid,
+    pub transaction_identifier: Uuid,
+    pub from_status: Option<TransactionStatus>,
+    pub to_status: TransactionStatus,
+    pub reason: Option<String>,
+    pub created_date: DateTime<Local>,
+}
+
+/// Ties a refund transaction to the transaction it gives money back for
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Refund {
+    pub identifier: Uuid,
+    pub original_transaction_identifier: Uuid,
+    pub refund_transaction_identifier: Uuid,
+    pub amount: BigDecimal,
+    pub reason: String,
+    pub requested_by: Option<Uuid>,
+    /// what a chargeback advanced because the payee could not cover it, in the payee's currency
+    pub receivable_amount: BigDecimal,
+    pub created_date: DateTime<Local>,
+}
+
+/// An owner of one of the wallets a refund moved money between
+#[derive(Debug, FromRow)]
+pub struct RefundRecipient {
+    pub email: String,
+    pub first_name: String,
+    pub currency_code: String,
+    pub reference: String,
+    /// whether the refund was paid into this owner's wallet or taken out of it
+    pub credited: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionWithHistory {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    pub derived_status: DerivedTransactionStatus,
+    pub history: Vec<TransactionTransition>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(status: TransactionStatus) -> Transaction {
+        Transaction {
+            identifier: Uuid::new_v4(),
+            reference: "TRF-TEST".to_string(),
+            transaction_type: TransactionType::Transfer,
+            status,
+            source_wallet_identifier: None,
+            destination_wallet_identifier: None,
+            amount: BigDecimal::from(10),
+            refunded_amount: BigDecimal::zero(),
+            currency_identifier: Uuid::new_v4(),
+            description: None,
+            notes: None,
+            failure_reason: None,
+            initiated_by: None,
+            external_reference: None,
+            created_date: Local::now(),
+            updated_at: Local::now(),
+        }
+    }
+
+    #[test]
+    fn test_pending_transaction_completes_or_fails() {
+        let mut completed = transaction(TransactionStatus::Pending);
+        assert!(completed.transition_to(TransactionStatus::Completed).is_ok());
+        assert_eq!(completed.status, TransactionStatus::Completed);
+
+        let mut failed = transaction(TransactionStatus::Pending);
+        assert!(failed.transition_to(TransactionStatus::Failed).is_ok());
+        assert_eq!(failed.status, TransactionStatus::Failed);
+    }
+
+    #[test]
+    fn test_only_completed_transaction_can_be_reversed() {
+        let mut completed = transaction(TransactionStatus::Completed);
+        assert!(completed.transition_to(TransactionStatus::Reversed).is_ok());
+
+        let mut pending = transaction(TransactionStatus::Pending);
+        assert_eq!(
+            pending.transition_to(TransactionStatus::Reversed),
+            Err(IllegalTransition {
+                from: TransactionStatus::Pending,
+                to: TransactionStatus::Reversed
+            })
+        );
+    }
+
+    #[test]
+    fn test_derived_status_reports_refunds() {
+        let mut transaction = transaction(TransactionStatus::Completed);
+        assert_eq!(
+            transaction.derived_status(),
+            DerivedTransactionStatus::Completed
+        );
+
+        transaction.refunded_amount = BigDecimal::from(4);
+        assert_eq!(
+            transaction.derived_status(),
+            DerivedTransactionStatus::PartiallyRefunded
+        );
+        assert_eq!(transaction.refundable_amount(), BigDecimal::from(6));
+
+        transaction.refunded_amount = BigDecimal::from(10);
+        transaction
+            .transition_to(TransactionStatus::Reversed)
+            .unwrap();
+        assert_eq!(
+            transaction.derived_status(),
+            DerivedTransactionStatus::Refunded
+        );
+        assert_eq!(transaction.refundable_amount(), BigDecimal::zero());
+    }
+
+    #[test]
+    fn test_final_states_reject_transitions() {
+        for status in [TransactionStatus::Failed, TransactionStatus::Reversed] {
+            let mut transaction = transaction(status);
+            for next in [
+                TransactionStatus::Pending,
+                TransactionStatus::Completed,
+                TransactionStatus::Failed,
+                TransactionStatus::Reversed,
+            ] {
+                assert!(transaction.transition_to(next).is_err());
+            }
+            assert_eq!(transaction.status, status);
+        }
+    }
+}
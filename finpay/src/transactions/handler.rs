@@ -0,0 +1,98 @@
+use crate::authentication::claims::Claims;
+use crate::errors::ServiceError;
+use crate::shared::middlewares::admin_middleware::AdminClaims;
+use crate::shared::middlewares::validator::ValidatedRequest;
+use crate::transactions::adapters::{
+    CreateRefundRequest, CreateTransferRequest, TransactionSearchQuery,
+    UpdateTransactionNotesRequest,
+};
+use crate::transactions::entities::{Refund, Transaction, TransactionWithHistory};
+use crate::transactions::service::{TransactionService, TransactionServiceExt};
+use crate::utils::{ApiResponse, AuthenticatedRequest, CursorPaginatedResponse, CursorParams};
+use crate::wallet::service::{WalletService, WalletServiceExt};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+pub async fn create_transfer(
+    State(wallet_service): State<WalletService>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateTransferRequest>,
+) -> Result<ApiResponse<Transaction>, ServiceError> {
+    let transaction = wallet_service.transfer(&claims, &request).await?;
+
+    Ok(ApiResponse::builder()
+        .data(transaction)
+        .message("transfer completed successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_transaction(
+    State(transaction_service): State<TransactionService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<TransactionWithHistory>, ServiceError> {
+    let transaction = transaction_service
+        .fetch_transaction(&claims, &identifier)
+        .await?;
+
+    Ok(ApiResponse::builder().data(transaction).build())
+}
+
+pub async fn fetch_transactions(
+    State(transaction_service): State<TransactionService>,
+    claims: Claims,
+    Query(query): Query<TransactionSearchQuery>,
+    Query(cursor_params): Query<CursorParams>,
+) -> Result<ApiResponse<CursorPaginatedResponse<TransactionWithHistory>>, ServiceError> {
+    let transactions = transaction_service
+        .search_transactions(&claims, &query, &cursor_params)
+        .await?;
+
+    Ok(ApiResponse::builder().data(transactions).build())
+}
+
+pub async fn update_transaction_notes(
+    State(transaction_service): State<TransactionService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+    ValidatedRequest(request): ValidatedRequest<UpdateTransactionNotesRequest>,
+) -> Result<ApiResponse<TransactionWithHistory>, ServiceError> {
+    let transaction = transaction_service
+        .update_notes(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(transaction)
+        .message("notes updated successfully")
+        .build())
+}
+
+pub async fn refund_transaction(
+    State(transaction_service): State<TransactionService>,
+    AdminClaims(claims): AdminClaims,
+    Path(identifier): Path<Uuid>,
+    ValidatedRequest(request): ValidatedRequest<CreateRefundRequest>,
+) -> Result<ApiResponse<Refund>, ServiceError> {
+    let refund = transaction_service
+        .refund_transaction(&claims, &identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(refund)
+        .message("refund completed successfully")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+pub async fn fetch_refunds(
+    State(transaction_service): State<TransactionService>,
+    claims: Claims,
+    Path(identifier): Path<Uuid>,
+) -> Result<ApiResponse<Vec<Refund>>, ServiceError> {
+    let refunds = transaction_service
+        .fetch_refunds(&claims, &identifier)
+        .await?;
+
+    Ok(ApiResponse::builder().data(refunds).build())
+}
@@ -0,0 +1,1077 @@
+use crate::conversions::repository::ConversionRepository;
+use crate::errors::RepositoryError;
+use crate::fees::repository::FeeRepository;
+use crate::holds::repository::HoldRepository;
+use crate::ledger::adapters::NewJournalEntry;
+use crate::ledger::entities::SystemAccount;
+use crate::ledger::repository::LedgerRepository;
+use crate::transactions::adapters::{
+    CreateRefundRequest, NewTransaction, TransactionCursor, TransactionSearchQuery,
+};
+use crate::transactions::entities::{
+    IllegalTransition, Refund, RefundRecipient, Transaction, TransactionStatus,
+    TransactionTransition, TransactionType, TransactionWithHistory,
+};
+use crate::utils::{CursorPaginatedResponse, CursorParams};
+use crate::wallet::entities::{Wallet, WalletStatus};
+use bigdecimal::{BigDecimal, Zero};
+use finpay_utils::generate_reference;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TransactionRepository {
+    pool: PgPool,
+}
+
+impl TransactionRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Opens a pending transaction and records its first entry in the status history
+    pub async fn open(
+        connection: &mut PgConnection,
+        payload: &NewTransaction,
+    ) -> Result<Transaction, RepositoryError> {
+        Self::open_once(connection, payload)
+            .await?
+            .ok_or(RepositoryError::DuplicateRecord)
+    }
+
+    /// Opens a pending transaction unless one of the same type already carries the payload's
+    /// external reference. Concurrent deliveries of the same reference wait on each other and
+    /// only the first one opens a transaction
+    pub async fn open_once(
+        connection: &mut PgConnection,
+        payload: &NewTransaction,
+    ) -> Result<Option<Transaction>, RepositoryError> {
+        let query = r#"
+        INSERT INTO transactions (
+            identifier,
+            reference,
+            transaction_type,
+            status,
+            source_wallet_identifier,
+            destination_wallet_identifier,
+            amount,
+            currency_identifier,
+            description,
+            initiated_by,
+            external_reference
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (transaction_type, external_reference) WHERE external_reference IS NOT NULL DO NOTHING
+        RETURNING *
+        "#;
+        let Some(transaction) = sqlx::query_as::<_, Transaction>(query)
+            .bind(Uuid::new_v4())
+            .bind(generate_reference(payload.transaction_type.reference_prefix()))
+            .bind(payload.transaction_type)
+            .bind(TransactionStatus::Pending)
+            .bind(payload.source_wallet_identifier)
+            .bind(payload.destination_wallet_identifier)
+            .bind(&payload.amount)
+            .bind(payload.currency_identifier)
+            .bind(&payload.description)
+            .bind(payload.initiated_by)
+            .bind(&payload.external_reference)
+            .fetch_optional(&mut *connection)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        Self::record_transition(connection, &transaction.identifier, None, transaction.status, None)
+            .await?;
+
+        Ok(Some(transaction))
+    }
+
+    /// Moves a transaction to its next status, this must run inside the same database
+    /// transaction as the work that justifies the change
+    pub async fn transition(
+        connection: &mut PgConnection,
+        transaction: &mut Transaction,
+        next: TransactionStatus,
+        reason: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        let previous = transaction.status;
+        transaction.transition_to(next)?;
+
+        // guard against the stored row having moved on since it was read
+        let query = r#"
+        UPDATE transactions
+        SET status = $1,
+            failure_reason = COALESCE($2, failure_reason)
+        WHERE identifier = $3 AND status = $4
+        RETURNING *
+        "#;
+        let failure_reason = match next {
+            TransactionStatus::Failed => reason,
+            _ => None,
+        };
+        *transaction = sqlx::query_as::<_, Transaction>(query)
+            .bind(next)
+            .bind(failure_reason)
+            .bind(transaction.identifier)
+            .bind(previous)
+            .fetch_optional(&mut *connection)
+            .await?
+            .ok_or(RepositoryError::IllegalTransition(IllegalTransition {
+                from: previous,
+                to: next,
+            }))?;
+
+        Self::record_transition(
+            connection,
+            &transaction.identifier,
+            Some(previous),
+            next,
+            reason,
+        )
+        .await
+    }
+
+    async fn record_transition(
+        connection: &mut PgConnection,
+        transaction_identifier: &Uuid,
+        from_status: Option<TransactionStatus>,
+        to_status: TransactionStatus,
+        reason: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        let query = r#"
+        INSERT INTO transaction_status_transitions (identifier, transaction_identifier, from_status, to_status, reason)
+        VALUES ($1, $2, $3, $4, $5)
+        "#;
+        sqlx::query(query)
+            .bind(Uuid::new_v4())
+            .bind(transaction_identifier)
+            .bind(from_status)
+            .bind(to_status)
+            .bind(reason)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_history(
+        &self,
+        transaction_identifiers: &[Uuid],
+    ) -> Result<Vec<TransactionTransition>, RepositoryError> {
+        sqlx::query_as::<_, TransactionTransition>(
+            r#"SELECT * FROM transaction_status_transitions WHERE transaction_identifier = ANY($1) ORDER BY sequence"#,
+        )
+        .bind(transaction_identifiers)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    /// Pairs every transaction with its status history, keeping their order
+    async fn with_history(
+        &self,
+        transactions: Vec<Transaction>,
+    ) -> Result<Vec<TransactionWithHistory>, RepositoryError> {
+        let identifiers: Vec<Uuid> = transactions.iter().map(|t| t.identifier).collect();
+        let mut history = self.fetch_history(&identifiers).await?;
+
+        let records = transactions
+            .into_iter()
+            .map(|transaction| {
+                let (own, rest): (Vec<_>, Vec<_>) = history
+                    .drain(..)
+                    .partition(|entry| entry.transaction_identifier == transaction.identifier);
+                history = rest;
+                TransactionWithHistory {
+                    derived_status: transaction.derived_status(),
+                    transaction,
+                    history: own,
+                }
+            })
+            .collect();
+
+        Ok(records)
+    }
+
+    /// Refunds `request.amount` of a transfer, or whatever is left of it, back to the payer. A refund
+    /// that cannot be paid is recorded as a failed transaction before the error is returned, it is
+    /// up to the caller to commit it. A chargeback goes through even when the payee cannot cover
+    /// it, the shortfall is advanced out of the chargeback receivable and recorded on the refund
+    pub async fn book_refund(
+        connection: &mut PgConnection,
+        identifier: &Uuid,
+        request: &CreateRefundRequest,
+        requested_by: &Uuid,
+        chargeback: bool,
+    ) -> Result<Refund, RepositoryError> {
+        let mut original = sqlx::query_as::<_, Transaction>(
+            r#"SELECT * FROM transactions WHERE identifier = $1 FOR UPDATE"#,
+        )
+        .bind(identifier)
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+
+        let (Some(payer_wallet_identifier), Some(payee_wallet_identifier)) = (
+            original.source_wallet_identifier,
+            original.destination_wallet_identifier,
+        ) else {
+            return Err(RepositoryError::OperationFailed(
+                "only transfers between wallets can be refunded".into(),
+            ));
+        };
+        if !matches!(
+            original.transaction_type,
+            TransactionType::Transfer | TransactionType::HoldCapture
+        ) {
+            return Err(RepositoryError::OperationFailed(
+                "only transfers between wallets can be refunded".into(),
+            ));
+        }
+
+        let refundable_amount = original.refundable_amount();
+        if refundable_amount.is_zero() {
+            return Err(RepositoryError::OperationFailed(
+                "the transaction is not completed or has already been fully refunded".into(),
+            ));
+        }
+        let amount = request
+            .amount
+            .clone()
+            .unwrap_or_else(|| refundable_amount.clone());
+        if amount > refundable_amount {
+            return Err(RepositoryError::RefundExceedsOriginal);
+        }
+
+        // lock both rows in a stable order, the same way transfers do
+        let wallets = sqlx::query_as::<_, Wallet>(
+            r#"SELECT * FROM wallets WHERE identifier = ANY($1) ORDER BY identifier FOR UPDATE"#,
+        )
+        .bind(vec![payer_wallet_identifier, payee_wallet_identifier])
+        .fetch_all(&mut *connection)
+        .await?;
+        let payer_wallet = wallets
+            .iter()
+            .find(|wallet| wallet.identifier == payer_wallet_identifier)
+            .ok_or(RepositoryError::RecordNotFound)?;
+        let payee_wallet = wallets
+            .iter()
+            .find(|wallet| wallet.identifier == payee_wallet_identifier)
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        let held_amount =
+            HoldRepository::held_amount(&mut *connection, &payee_wallet.identifier).await?;
+
+        // refunds are asked for in the currency the payer sent, when the transfer crossed
+        // currencies the payee gives back its share of what it received instead
+        let conversion =
+            ConversionRepository::fetch_for_transaction(&mut *connection, &original.identifier)
+                .await?;
+        let payee_amount = match &conversion {
+            Some(conversion) => {
+                conversion.destination_share(&(&original.refunded_amount + &amount))
+                    - conversion.destination_share(&original.refunded_amount)
+            }
+            None => amount.clone(),
+        };
+
+        let available_amount = (&payee_wallet.balance - held_amount).max(BigDecimal::zero());
+        let receivable_amount = if chargeback && available_amount < payee_amount {
+            &payee_amount - &available_amount
+        } else {
+            BigDecimal::zero()
+        };
+
+        let failure = if payer_wallet.status != WalletStatus::Active
+            || payee_wallet.status != WalletStatus::Active
+        {
+            Some(RepositoryError::InactiveWallet)
+        } else if available_amount + &receivable_amount < payee_amount {
+            Some(RepositoryError::InsufficientFunds)
+        } else {
+            None
+        };
+
+        // the money flows back the way it came
+        let mut transaction = Self::open(
+            &mut *connection,
+            &NewTransaction {
+                transaction_type: TransactionType::Refund,
+                source_wallet_identifier: Some(payee_wallet.identifier),
+                destination_wallet_identifier: Some(payer_wallet.identifier),
+                amount: amount.clone(),
+                currency_identifier: original.currency_identifier,
+                description: Some(request.reason.clone()),
+                initiated_by: Some(*requested_by),
+                external_reference: None,
+            },
+        )
+        .await?;
+
+        if let Some(err) = failure {
+            Self::transition(
+                &mut *connection,
+                &mut transaction,
+                TransactionStatus::Failed,
+                Some(&err.to_string()),
+            )
+            .await?;
+            return Err(err);
+        }
+
+        let payee_account =
+            LedgerRepository::wallet_account(&mut *connection, &payee_wallet.identifier).await?;
+        let payer_account =
+            LedgerRepository::wallet_account(&mut *connection, &payer_wallet.identifier).await?;
+        let mut entry = NewJournalEntry::new(&format!(
+            "refund {} of {}",
+            transaction.reference, original.reference
+        ))
+        .transaction(&transaction.identifier);
+        if receivable_amount.is_zero() {
+            entry = entry.debit(&payee_account, &payee_amount);
+        } else {
+            let chargeback_receivable = LedgerRepository::system_account(
+                &mut *connection,
+                SystemAccount::ChargebackReceivable,
+                &payee_wallet.currency_identifier,
+            )
+            .await?;
+            let covered_amount = &payee_amount - &receivable_amount;
+            if !covered_amount.is_zero() {
+                entry = entry.debit(&payee_account, &covered_amount);
+            }
+            entry = entry.debit(&chargeback_receivable, &receivable_amount);
+        }
+        // each leg balances within its own currency, the same way the conversion was booked
+        if let Some(conversion) = &conversion {
+            let destination_position = LedgerRepository::system_account(
+                &mut *connection,
+                SystemAccount::FxPosition,
+                &conversion.destination_currency_identifier,
+            )
+            .await?;
+            let source_position = LedgerRepository::system_account(
+                &mut *connection,
+                SystemAccount::FxPosition,
+                &conversion.source_currency_identifier,
+            )
+            .await?;
+            entry = entry
+                .credit(&destination_position, &payee_amount)
+                .debit(&source_position, &amount);
+        }
+        entry = entry.credit(&payer_account, &amount);
+        LedgerRepository::record_entry(&mut *connection, &entry).await?;
+
+        Self::transition(
+            &mut *connection,
+            &mut transaction,
+            TransactionStatus::Completed,
+            None,
+        )
+        .await?;
+
+        original = sqlx::query_as::<_, Transaction>(
+            r#"UPDATE transactions SET refunded_amount = refunded_amount + $1 WHERE identifier = $2 RETURNING *"#,
+        )
+        .bind(&amount)
+        .bind(original.identifier)
+        .fetch_one(&mut *connection)
+        .await?;
+        if original.refundable_amount().is_zero() {
+            Self::transition(
+                &mut *connection,
+                &mut original,
+                TransactionStatus::Reversed,
+                Some("refunded in full"),
+            )
+            .await?;
+            // the fee is kept on partial refunds and handed back once nothing of the transfer
+            // is left standing
+            FeeRepository::refund(&mut *connection, &original).await?;
+        }
+
+        let query = r#"
+        INSERT INTO refunds (identifier, original_transaction_identifier, refund_transaction_identifier,
+                             amount, reason, requested_by, receivable_amount)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#;
+        let refund = sqlx::query_as::<_, Refund>(query)
+            .bind(Uuid::new_v4())
+            .bind(original.identifier)
+            .bind(transaction.identifier)
+            .bind(&amount)
+            .bind(&request.reason)
+            .bind(requested_by)
+            .bind(&receivable_amount)
+            .fetch_one(&mut *connection)
+            .await?;
+
+        Ok(refund)
+    }
+}
+
+pub trait TransactionRepositoryExt {
+    fn fetch_transaction(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Option<TransactionWithHistory>, RepositoryError>> + Send;
+
+    /// Filters the transactions a user can see a page at a time, each page starts right after
+    /// the cursor rather than at an offset
+    fn search_transactions(
+        &self,
+        user_identifier: &Uuid,
+        query: &TransactionSearchQuery,
+        cursor: &TransactionCursor,
+        cursor_params: &CursorParams,
+    ) -> impl std::future::Future<Output = Result<CursorPaginatedResponse<TransactionWithHistory>, RepositoryError>> + Send;
+
+    /// Only whoever started a transaction can keep notes on it
+    fn update_notes(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        notes: Option<&str>,
+    ) -> impl std::future::Future<Output = Result<TransactionWithHistory, RepositoryError>> + Send;
+
+    /// Gives back all or part of a completed transfer through a linked refund transaction, the
+    /// original's postings are left untouched
+    fn refund(
+        &self,
+        identifier: &Uuid,
+        request: &CreateRefundRequest,
+        requested_by: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Refund, RepositoryError>> + Send;
+
+    fn fetch_refunds(
+        &self,
+        original_transaction_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Vec<Refund>, RepositoryError>> + Send;
+
+    fn fetch_refund_recipients(
+        &self,
+        refund: &Refund,
+    ) -> impl std::future::Future<Output = Result<Vec<RefundRecipient>, RepositoryError>> + Send;
+}
+
+/// a user can see the transactions they started and those that touch any of their wallets
+const VISIBLE_TO_USER: &str = r#"
+    (transactions.initiated_by = $1
+        OR EXISTS (SELECT 1
+                   FROM wallets
+                   WHERE wallets.user_identifier = $1
+                     AND wallets.identifier IN (transactions.source_wallet_identifier,
+                                                transactions.destination_wallet_identifier)))
+"#;
+
+/// what full-text search runs against, it must match the expression of `transactions_search_idx`
+const SEARCH_DOCUMENT: &str = r#"
+    to_tsvector('english', COALESCE(transactions.description, '') || ' ' || COALESCE(transactions.notes, ''))
+"#;
+
+/// the description alone, what a search matches for anyone but whoever started the transaction
+const SHARED_SEARCH_DOCUMENT: &str = r#"
+    to_tsvector('english', COALESCE(transactions.description, ''))
+"#;
+
+/// Notes are private to whoever started a transaction, the other party sees it without them
+fn without_others_notes(mut transaction: Transaction, user_identifier: &Uuid) -> Transaction {
+    if transaction.initiated_by.as_ref() != Some(user_identifier) {
+        transaction.notes = None;
+    }
+    transaction
+}
+
+/// An ILIKE pattern finding `text` anywhere in a value, with the wildcards in it taken literally
+fn contains_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
+
+impl TransactionRepositoryExt for TransactionRepository {
+    async fn fetch_transaction(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+    ) -> Result<Option<TransactionWithHistory>, RepositoryError> {
+        let query = format!(
+            "SELECT * FROM transactions WHERE {VISIBLE_TO_USER} AND transactions.identifier = $2"
+        );
+        let Some(transaction) = sqlx::query_as::<_, Transaction>(&query)
+            .bind(user_identifier)
+            .bind(identifier)
+            .fetch_optional(&self.pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let transaction = without_others_notes(transaction, user_identifier);
+        let history = self.fetch_history(&[transaction.identifier]).await?;
+        Ok(Some(TransactionWithHistory {
+            derived_status: transaction.derived_status(),
+            transaction,
+            history,
+        }))
+    }
+
+    async fn search_transactions(
+        &self,
+        user_identifier: &Uuid,
+        query: &TransactionSearchQuery,
+        cursor: &TransactionCursor,
+        cursor_params: &CursorParams,
+    ) -> Result<CursorPaginatedResponse<TransactionWithHistory>, RepositoryError> {
+        let sort = query.sort;
+        let (operator, direction) = if sort.is_descending() {
+            ("<", "DESC")
+        } else {
+            (">", "ASC")
+        };
+        let column = sort.column();
+
+        let statement = format!(
+            r#"
+            SELECT transactions.*
+            FROM transactions
+            WHERE {VISIBLE_TO_USER}
+              AND ($2::UUID IS NULL
+                OR $2 IN (transactions.source_wallet_identifier, transactions.destination_wallet_identifier))
+              AND ($3::TIMESTAMPTZ IS NULL OR transactions.created_date >= $3)
+              AND ($4::TIMESTAMPTZ IS NULL OR transactions.created_date <= $4)
+              AND ($5::NUMERIC IS NULL OR transactions.amount >= $5)
+              AND ($6::NUMERIC IS NULL OR transactions.amount <= $6)
+              AND ($7::transaction_status_enum IS NULL OR transactions.status = $7)
+              AND ($8::transaction_type_enum IS NULL OR transactions.transaction_type = $8)
+              AND ($9::TEXT IS NULL
+                OR EXISTS (SELECT 1
+                           FROM wallets
+                                    JOIN users ON users.identifier = wallets.user_identifier
+                           WHERE wallets.identifier IN (transactions.source_wallet_identifier,
+                                                        transactions.destination_wallet_identifier)
+                             AND wallets.user_identifier <> $1
+                             AND (users.email ILIKE $9
+                               OR COALESCE(users.first_name, '') || ' ' || COALESCE(users.last_name, '') ILIKE $9)))
+              AND ($10::TEXT IS NULL
+                OR ({SEARCH_DOCUMENT} @@ websearch_to_tsquery('english', $10)
+                  AND (transactions.initiated_by = $1
+                    OR {SHARED_SEARCH_DOCUMENT} @@ websearch_to_tsquery('english', $10))))
+              AND ($11::TIMESTAMPTZ IS NULL
+                OR (transactions.created_date, transactions.identifier) {operator} ($11, $13))
+              AND ($12::NUMERIC IS NULL
+                OR (transactions.amount, transactions.identifier) {operator} ($12, $13))
+            ORDER BY transactions.{column} {direction}, transactions.identifier {direction}
+            LIMIT $14
+            "#
+        );
+        let transactions = sqlx::query_as::<_, Transaction>(&statement)
+            .bind(user_identifier)
+            .bind(query.wallet_identifier)
+            .bind(query.from)
+            .bind(query.to)
+            .bind(&query.min_amount)
+            .bind(&query.max_amount)
+            .bind(query.status)
+            .bind(query.transaction_type)
+            .bind(query.counterparty.as_deref().map(contains_pattern))
+            .bind(&query.q)
+            .bind(cursor.created_date)
+            .bind(&cursor.amount)
+            .bind(cursor.identifier)
+            // one more than the page holds tells whether there is a next page
+            .bind(cursor_params.per_page() + 1)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|transaction| without_others_notes(transaction, user_identifier))
+            .collect();
+
+        let records = self.with_history(transactions).await?;
+        Ok(CursorPaginatedResponse::new(
+            records,
+            cursor_params,
+            |record| sort.cursor(&record.transaction),
+        ))
+    }
+
+    async fn update_notes(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        notes: Option<&str>,
+    ) -> Result<TransactionWithHistory, RepositoryError> {
+        let transaction = sqlx::query_as::<_, Transaction>(
+            r#"UPDATE transactions SET notes = $1 WHERE identifier = $2 AND initiated_by = $3 RETURNING *"#,
+        )
+        .bind(notes)
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)?;
+
+        let mut records = self.with_history(vec![transaction]).await?;
+        records.pop().ok_or(RepositoryError::RecordNotFound)
+    }
+
+    async fn refund(
+        &self,
+        identifier: &Uuid,
+        request: &CreateRefundRequest,
+        requested_by: &Uuid,
+    ) -> Result<Refund, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        match Self::book_refund(&mut tx, identifier, request, requested_by, false).await {
+            Ok(refund) => {
+                tx.commit().await?;
+                Ok(refund)
+            }
+            // failed attempts are committed too, so that the transaction history is complete
+            Err(err @ (RepositoryError::InactiveWallet | RepositoryError::InsufficientFunds)) => {
+                tx.commit().await?;
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn fetch_refunds(
+        &self,
+        original_transaction_identifier: &Uuid,
+    ) -> Result<Vec<Refund>, RepositoryError> {
+        sqlx::query_as::<_, Refund>(
+            r#"SELECT * FROM refunds WHERE original_transaction_identifier = $1 ORDER BY created_date"#,
+        )
+        .bind(original_transaction_identifier)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_refund_recipients(
+        &self,
+        refund: &Refund,
+    ) -> Result<Vec<RefundRecipient>, RepositoryError> {
+        let query = r#"
+        SELECT users.email,
+               users.first_name,
+               countries.currency_code,
+               transactions.reference,
+               wallets.identifier = transactions.destination_wallet_identifier AS credited
+        FROM transactions
+                 JOIN wallets ON wallets.identifier IN (transactions.source_wallet_identifier,
+                                                        transactions.destination_wallet_identifier)
+                 JOIN users ON users.identifier = wallets.user_identifier
+                 JOIN countries ON countries.identifier = transactions.currency_identifier
+        WHERE transactions.identifier = $1
+        "#;
+        sqlx::query_as::<_, RefundRecipient>(query)
+            .bind(refund.refund_transaction_identifier)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversions::entities::ConversionPricing;
+    use crate::fees::adapters::CreateFeeScheduleRequest;
+    use crate::fees::entities::FeeMethod;
+    use crate::fees::repository::FeeRepositoryExt;
+    use crate::fx::adapters::NewFxQuote;
+    use crate::fx::repository::{FxRepository, FxRepositoryExt};
+    use crate::shared::fixtures::{
+        AFGHAN_AFGHANI, UAE_DIRHAM, balance, create_user, create_wallet, create_wallet_in, fund,
+    };
+    use crate::transactions::adapters::{CreateTransferRequest, TransactionSort};
+    use crate::transactions::entities::DerivedTransactionStatus;
+    use crate::wallet::repository::{WalletRepository, WalletRepositoryExt};
+    use chrono::{Duration, Local};
+    use std::str::FromStr;
+
+    struct Fixture {
+        user_identifier: Uuid,
+        recipient_identifier: Uuid,
+        source: Uuid,
+        destination: Uuid,
+        transfer: Transaction,
+    }
+
+    /// transfers `amount` out of a wallet funded with 100
+    async fn setup(pool: &PgPool, amount: i32) -> Fixture {
+        let user_identifier = create_user(pool).await;
+        let recipient_identifier = create_user(pool).await;
+        let source = create_wallet(pool, &user_identifier).await;
+        let destination = create_wallet(pool, &recipient_identifier).await;
+
+        fund(pool, &source, "100").await;
+
+        let transfer = WalletRepository::new(pool.clone())
+            .transfer(
+                &CreateTransferRequest {
+                    source_wallet_identifier: source,
+                    destination_wallet_identifier: destination,
+                    amount: BigDecimal::from(amount),
+                    description: None,
+                    quote_identifier: None,
+                },
+                &user_identifier,
+            )
+            .await
+            .expect("failed to transfer");
+
+        Fixture {
+            user_identifier,
+            recipient_identifier,
+            source,
+            destination,
+            transfer,
+        }
+    }
+
+    fn refund_request(amount: Option<&str>) -> CreateRefundRequest {
+        CreateRefundRequest {
+            amount: amount.map(|amount| BigDecimal::from_str(amount).unwrap()),
+            reason: "goods not delivered".to_string(),
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_partial_then_full_refund_reverses_the_transfer(pool: PgPool) {
+        let repository = TransactionRepository::new(&pool);
+        let fixture = setup(&pool, 40).await;
+
+        let refund = repository
+            .refund(
+                &fixture.transfer.identifier,
+                &refund_request(Some("15")),
+                &fixture.recipient_identifier,
+            )
+            .await
+            .unwrap();
+        assert_eq!(refund.amount, BigDecimal::from(15));
+        assert_eq!(balance(&pool, &fixture.source).await, BigDecimal::from(75));
+        assert_eq!(
+            balance(&pool, &fixture.destination).await,
+            BigDecimal::from(25)
+        );
+
+        let original = repository
+            .fetch_transaction(&fixture.transfer.identifier, &fixture.user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(original.transaction.refunded_amount, BigDecimal::from(15));
+        assert_eq!(
+            original.derived_status,
+            DerivedTransactionStatus::PartiallyRefunded
+        );
+
+        // the remainder is refunded when no amount is given
+        let refund = repository
+            .refund(
+                &fixture.transfer.identifier,
+                &refund_request(None),
+                &fixture.recipient_identifier,
+            )
+            .await
+            .unwrap();
+        assert_eq!(refund.amount, BigDecimal::from(25));
+        assert_eq!(balance(&pool, &fixture.source).await, BigDecimal::from(100));
+
+        let original = repository
+            .fetch_transaction(&fixture.transfer.identifier, &fixture.user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(original.transaction.status, TransactionStatus::Reversed);
+        assert_eq!(original.derived_status, DerivedTransactionStatus::Refunded);
+        assert_eq!(
+            repository
+                .fetch_refunds(&fixture.transfer.identifier)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_cross_currency_refund_returns_each_leg_in_its_own_currency(pool: PgPool) {
+        let repository = TransactionRepository::new(&pool);
+        let user_identifier = create_user(&pool).await;
+        let recipient_identifier = create_user(&pool).await;
+        let source = create_wallet_in(&pool, &user_identifier, UAE_DIRHAM).await;
+        let destination = create_wallet_in(&pool, &recipient_identifier, AFGHAN_AFGHANI).await;
+        fund(&pool, &source, "100").await;
+        FeeRepository::new(&pool)
+            .create_schedule(&CreateFeeScheduleRequest {
+                name: "conversions".to_string(),
+                transaction_type: TransactionType::Conversion,
+                currency_identifier: None,
+                account_type: None,
+                minimum_amount: None,
+                maximum_amount: None,
+                fee_method: FeeMethod::Flat,
+                flat_amount: Some(BigDecimal::from(1)),
+                percentage_basis_points: None,
+                minimum_fee: None,
+                maximum_fee: None,
+                tiers: vec![],
+                effective_from: None,
+                effective_to: None,
+            })
+            .await
+            .unwrap();
+        let quote = FxRepository::new(&pool)
+            .create_quote(&NewFxQuote {
+                user_identifier,
+                source_wallet_identifier: source,
+                destination_wallet_identifier: destination,
+                source_currency_identifier: Uuid::from_str(UAE_DIRHAM).unwrap(),
+                destination_currency_identifier: Uuid::from_str(AFGHAN_AFGHANI).unwrap(),
+                source_amount: BigDecimal::from(10),
+                pricing: ConversionPricing {
+                    mid_rate: BigDecimal::from_str("18.65").unwrap(),
+                    spread_basis_points: 100,
+                    rate_source: "test".to_string(),
+                },
+                expires_at: Local::now() + Duration::minutes(1),
+            })
+            .await
+            .unwrap();
+        let transfer = WalletRepository::new(pool.clone())
+            .transfer(
+                &CreateTransferRequest {
+                    source_wallet_identifier: source,
+                    destination_wallet_identifier: destination,
+                    amount: BigDecimal::from(10),
+                    description: None,
+                    quote_identifier: Some(quote.identifier),
+                },
+                &user_identifier,
+            )
+            .await
+            .expect("failed to transfer");
+        assert_eq!(balance(&pool, &source).await, BigDecimal::from(89));
+        assert_eq!(
+            balance(&pool, &destination).await,
+            BigDecimal::from_str("184.63").unwrap()
+        );
+
+        // 4 of the 10 dirhams bought 73.85 of the 184.63 afghanis
+        repository
+            .refund(
+                &transfer.identifier,
+                &refund_request(Some("4")),
+                &recipient_identifier,
+            )
+            .await
+            .unwrap();
+        assert_eq!(balance(&pool, &source).await, BigDecimal::from(93));
+        assert_eq!(
+            balance(&pool, &destination).await,
+            BigDecimal::from_str("110.78").unwrap()
+        );
+
+        // the last refund takes back whatever is left and hands back the fee
+        repository
+            .refund(
+                &transfer.identifier,
+                &refund_request(None),
+                &recipient_identifier,
+            )
+            .await
+            .unwrap();
+        assert_eq!(balance(&pool, &source).await, BigDecimal::from(100));
+        assert_eq!(balance(&pool, &destination).await, BigDecimal::zero());
+    }
+
+    #[sqlx::test]
+    async fn test_refund_is_limited_to_the_original_amount(pool: PgPool) {
+        let repository = TransactionRepository::new(&pool);
+        let fixture = setup(&pool, 40).await;
+
+        let result = repository
+            .refund(
+                &fixture.transfer.identifier,
+                &refund_request(Some("40.01")),
+                &fixture.recipient_identifier,
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(RepositoryError::RefundExceedsOriginal)
+        ));
+
+        repository
+            .refund(
+                &fixture.transfer.identifier,
+                &refund_request(None),
+                &fixture.recipient_identifier,
+            )
+            .await
+            .unwrap();
+        let result = repository
+            .refund(
+                &fixture.transfer.identifier,
+                &refund_request(Some("1")),
+                &fixture.recipient_identifier,
+            )
+            .await;
+        assert!(matches!(result, Err(RepositoryError::OperationFailed(_))));
+    }
+
+    #[sqlx::test]
+    async fn test_search_pages_through_matches_without_repeats(pool: PgPool) {
+        let repository = TransactionRepository::new(&pool);
+        let fixture = setup(&pool, 10).await;
+        let wallet_repository = WalletRepository::new(pool.clone());
+        for (amount, description) in [
+            (20, "rent for march"),
+            (5, "groceries"),
+            (15, "rent for april"),
+        ] {
+            wallet_repository
+                .transfer(
+                    &CreateTransferRequest {
+                        source_wallet_identifier: fixture.source,
+                        destination_wallet_identifier: fixture.destination,
+                        amount: BigDecimal::from(amount),
+                        description: Some(description.to_string()),
+                        quote_identifier: None,
+                    },
+                    &fixture.user_identifier,
+                )
+                .await
+                .expect("failed to transfer");
+        }
+
+        let query = TransactionSearchQuery {
+            sort: TransactionSort::Largest,
+            ..Default::default()
+        };
+        let mut cursor = TransactionCursor::default();
+        let mut amounts = vec![];
+        loop {
+            let page = repository
+                .search_transactions(
+                    &fixture.user_identifier,
+                    &query,
+                    &cursor,
+                    &CursorParams {
+                        cursor: None,
+                        per_page: Some(3),
+                    },
+                )
+                .await
+                .unwrap();
+            amounts.extend(
+                page.records
+                    .iter()
+                    .map(|record| record.transaction.amount.clone()),
+            );
+            match page.next_cursor {
+                Some(next_cursor) => cursor = query.sort.parse_cursor(&next_cursor).unwrap(),
+                None => break,
+            }
+        }
+        let expected: Vec<BigDecimal> = [20, 15, 10, 5].into_iter().map(BigDecimal::from).collect();
+        assert_eq!(amounts, expected);
+
+        let rent = repository
+            .search_transactions(
+                &fixture.user_identifier,
+                &TransactionSearchQuery {
+                    q: Some("rent".to_string()),
+                    ..Default::default()
+                },
+                &TransactionCursor::default(),
+                &CursorParams::default(),
+            )
+            .await
+            .unwrap();
+        let descriptions: Vec<_> = rent
+            .records
+            .iter()
+            .map(|record| record.transaction.description.as_deref().unwrap())
+            .collect();
+        assert_eq!(descriptions, vec!["rent for april", "rent for march"]);
+
+        // the recipient sees the same transfers, but cannot keep notes on them
+        let noted = repository
+            .update_notes(
+                &fixture.transfer.identifier,
+                &fixture.recipient_identifier,
+                Some("split with flatmates"),
+            )
+            .await;
+        assert!(matches!(noted, Err(RepositoryError::RecordNotFound)));
+        repository
+            .update_notes(
+                &fixture.transfer.identifier,
+                &fixture.user_identifier,
+                Some("split with flatmates"),
+            )
+            .await
+            .expect("failed to update notes");
+        let flatmates = repository
+            .search_transactions(
+                &fixture.user_identifier,
+                &TransactionSearchQuery {
+                    q: Some("flatmates".to_string()),
+                    ..Default::default()
+                },
+                &TransactionCursor::default(),
+                &CursorParams::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(flatmates.records.len(), 1);
+        assert!(flatmates.next_cursor.is_none());
+        assert_eq!(
+            flatmates.records[0].transaction.notes.as_deref(),
+            Some("split with flatmates")
+        );
+
+        // notes are not shared with the recipient, neither to search nor to read
+        let flatmates = repository
+            .search_transactions(
+                &fixture.recipient_identifier,
+                &TransactionSearchQuery {
+                    q: Some("flatmates".to_string()),
+                    ..Default::default()
+                },
+                &TransactionCursor::default(),
+                &CursorParams::default(),
+            )
+            .await
+            .unwrap();
+        assert!(flatmates.records.is_empty());
+        let seen_by_recipient = repository
+            .fetch_transaction(&fixture.transfer.identifier, &fixture.recipient_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(seen_by_recipient.transaction.notes.is_none());
+    }
+
+    #[test]
+    fn test_counterparty_wildcards_are_taken_literally() {
+        assert_eq!(contains_pattern("john"), "%john%");
+        assert_eq!(contains_pattern("50%_off"), "%50\\%\\_off%");
+        assert_eq!(contains_pattern("a\\b"), "%a\\\\b%");
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    shared::middlewares::idempotency::{IdempotencyStore, enforce_idempotency},
+    state::AppState,
+    transactions::handler::{
+        create_transfer, fetch_refunds, fetch_transaction, fetch_transactions, refund_transaction,
+        update_transaction_notes,
+    },
+};
+use axum::{
+    Router,
+    extract::FromRef,
+    middleware::from_fn_with_state,
+    routing::{get, post, put},
+};
+
+pub fn transaction_routes(state: &AppState) -> Router {
+    let idempotency_store = IdempotencyStore::from_ref(state);
+
+    Router::new()
+        .route("/", get(fetch_transactions))
+        .route("/{identifier}", get(fetch_transaction))
+        .route("/{identifier}/refunds", get(fetch_refunds))
+        .route("/{identifier}/notes", put(update_transaction_notes))
+        .route(
+            "/transfers",
+            post(create_transfer).layer(from_fn_with_state(
+                idempotency_store.clone(),
+                enforce_idempotency,
+            )),
+        )
+        .with_state(state.clone())
+}
+
+pub fn admin_transaction_routes(state: &AppState) -> Router {
+    let idempotency_store = IdempotencyStore::from_ref(state);
+
+    Router::new()
+        .route(
+            "/{identifier}/refunds",
+            post(refund_transaction)
+                .layer(from_fn_with_state(idempotency_store, enforce_idempotency)),
+        )
+        .with_state(state.clone())
+}
@@ -0,0 +1,191 @@
+use crate::authentication::claims::Claims;
+use crate::errors::{RepositoryError, ServiceError};
+use crate::transactions::adapters::{
+    CreateRefundRequest, TransactionCursor, TransactionSearchQuery, UpdateTransactionNotesRequest,
+};
+use crate::transactions::entities::{Refund, TransactionWithHistory};
+use crate::transactions::repository::{TransactionRepository, TransactionRepositoryExt};
+use crate::utils::{CursorPaginatedResponse, CursorParams};
+use finpay_mailer::{EmailClient, EmailClientExt, RefundTemplate};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TransactionService {
+    repository: TransactionRepository,
+}
+
+impl TransactionService {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            repository: TransactionRepository::new(pool),
+        }
+    }
+}
+
+pub trait TransactionServiceExt {
+    fn fetch_transaction(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<TransactionWithHistory, ServiceError>> + Send;
+
+    fn search_transactions(
+        &self,
+        claims: &Claims,
+        query: &TransactionSearchQuery,
+        cursor_params: &CursorParams,
+    ) -> impl std::future::Future<Output = Result<CursorPaginatedResponse<TransactionWithHistory>, ServiceError>> + Send;
+
+    fn update_notes(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &UpdateTransactionNotesRequest,
+    ) -> impl std::future::Future<Output = Result<TransactionWithHistory, ServiceError>> + Send;
+
+    /// Refunds a transfer on behalf of support and emails the owners of both wallets
+    fn refund_transaction(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &CreateRefundRequest,
+    ) -> impl std::future::Future<Output = Result<Refund, ServiceError>> + Send;
+
+    fn fetch_refunds(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Vec<Refund>, ServiceError>> + Send;
+}
+
+impl TransactionServiceExt for TransactionService {
+    async fn fetch_transaction(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<TransactionWithHistory, ServiceError> {
+        let transaction = self
+            .repository
+            .fetch_transaction(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+        Ok(transaction)
+    }
+
+    async fn search_transactions(
+        &self,
+        claims: &Claims,
+        query: &TransactionSearchQuery,
+        cursor_params: &CursorParams,
+    ) -> Result<CursorPaginatedResponse<TransactionWithHistory>, ServiceError> {
+        if query
+            .from
+            .is_some_and(|from| query.to.is_some_and(|to| from > to))
+        {
+            return Err(ServiceError::UnprocessableEntity(
+                "from cannot be later than to".to_string(),
+            ));
+        }
+        if matches!(
+            (&query.min_amount, &query.max_amount),
+            (Some(min_amount), Some(max_amount)) if min_amount > max_amount
+        ) {
+            return Err(ServiceError::UnprocessableEntity(
+                "minAmount cannot be more than maxAmount".to_string(),
+            ));
+        }
+
+        // a cursor is only good for the order it was handed out with
+        let cursor = match cursor_params.cursor() {
+            Some(cursor) => query.sort.parse_cursor(cursor).ok_or_else(|| {
+                ServiceError::UnprocessableEntity(
+                    "the cursor is not valid for this sort order".to_string(),
+                )
+            })?,
+            None => TransactionCursor::default(),
+        };
+
+        let transactions = self
+            .repository
+            .search_transactions(&claims.user_identifier, query, &cursor, cursor_params)
+            .await?;
+        Ok(transactions)
+    }
+
+    async fn update_notes(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &UpdateTransactionNotesRequest,
+    ) -> Result<TransactionWithHistory, ServiceError> {
+        let notes = request
+            .notes
+            .as_deref()
+            .map(str::trim)
+            .filter(|notes| !notes.is_empty());
+
+        let transaction = self
+            .repository
+            .update_notes(identifier, &claims.user_identifier, notes)
+            .await?;
+        Ok(transaction)
+    }
+
+    async fn refund_transaction(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+        request: &CreateRefundRequest,
+    ) -> Result<Refund, ServiceError> {
+        let refund = self
+            .repository
+            .refund(identifier, request, &claims.user_identifier)
+            .await?;
+
+        // the refund has been booked, failing to reach the parties must not undo it
+        match self.repository.fetch_refund_recipients(&refund).await {
+            Ok(recipients) => {
+                let amount = refund.amount.with_scale(2).to_string();
+                let reason = refund.reason.clone();
+                tokio::task::spawn(async move {
+                    let email_client = EmailClient::new();
+                    for recipient in recipients {
+                        let template = RefundTemplate::new(
+                            &recipient.first_name,
+                            &amount,
+                            &recipient.currency_code,
+                            &recipient.reference,
+                            &reason,
+                            recipient.credited,
+                        );
+                        if let Err(error) = email_client
+                            .send_refund_email(&recipient.email, template)
+                            .await
+                        {
+                            log::error!("Failed to send refund email: {error}");
+                        }
+                    }
+                });
+            }
+            Err(error) => log::error!("Failed to fetch refund recipients: {error}"),
+        }
+
+        Ok(refund)
+    }
+
+    async fn fetch_refunds(
+        &self,
+        claims: &Claims,
+        identifier: &Uuid,
+    ) -> Result<Vec<Refund>, ServiceError> {
+        // only someone who can see the transaction can see its refunds
+        self.repository
+            .fetch_transaction(identifier, &claims.user_identifier)
+            .await?
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        let refunds = self.repository.fetch_refunds(identifier).await?;
+        Ok(refunds)
+    }
+}
@@ -25,4 +25,5 @@ pub struct User {
     #[serde(skip)]
     pub password: String,
     pub avatar_url: Option<String>,
+    pub is_admin: bool,
 }
@@ -1,7 +1,10 @@
 mod api_request;
 mod api_response;
 mod pagination;
+mod validators;
+pub mod xml;
 
 pub use api_request::AuthenticatedRequest;
 pub use api_response::*;
 pub use pagination::*;
+pub use validators::*;
@@ -49,3 +49,57 @@ impl PaginationParams {
         self.per_page.unwrap_or(10i64)
     }
 }
+
+/// A page of a keyset paginated listing, the next page starts right after `next_cursor`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPaginatedResponse<T> {
+    pub records: Vec<T>,
+    pub per_page: i64,
+    /// not set on the last page
+    pub next_cursor: Option<String>,
+}
+
+impl<T> CursorPaginatedResponse<T>
+where
+    T: Serialize,
+{
+    /// Takes a page fetched with one record more than asked for, the extra record is dropped and
+    /// only tells that there is a next page
+    pub fn new(
+        mut records: Vec<T>,
+        params: &CursorParams,
+        cursor_of: impl Fn(&T) -> String,
+    ) -> Self {
+        let per_page = params.per_page();
+        let next_cursor = if records.len() as i64 > per_page {
+            records.truncate(per_page as usize);
+            records.last().map(cursor_of)
+        } else {
+            None
+        };
+
+        Self {
+            records,
+            per_page,
+            next_cursor,
+        }
+    }
+}
+
+/// Keyset paging, unlike `PaginationParams` it does not slow down the deeper it goes
+#[derive(Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorParams {
+    pub(crate) cursor: Option<String>,
+    pub(crate) per_page: Option<i64>,
+}
+
+impl CursorParams {
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+    pub fn per_page(&self) -> i64 {
+        self.per_page.unwrap_or(10i64).clamp(1, 100)
+    }
+}
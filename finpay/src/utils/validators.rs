@@ -0,0 +1,58 @@
+use bigdecimal::{BigDecimal, Zero};
+use validator::ValidationError;
+
+pub fn validate_positive_amount(amount: &BigDecimal) -> Result<(), ValidationError> {
+    if amount <= &BigDecimal::zero() {
+        return Err(ValidationError::new("amount must be greater than zero"));
+    }
+
+    Ok(())
+}
+
+pub fn validate_non_negative_amount(amount: &BigDecimal) -> Result<(), ValidationError> {
+    if amount < &BigDecimal::zero() {
+        return Err(ValidationError::new("amount cannot be negative"));
+    }
+
+    Ok(())
+}
+
+/// ISO 4217 codes are three capital letters
+pub fn is_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// A SWIFT BIC, four letters for the bank, two for the country, two letters or digits for the
+/// location and an optional three for the branch
+pub fn is_bic(code: &str) -> bool {
+    let characters: Vec<char> = code.chars().collect();
+    [8, 11].contains(&characters.len())
+        && characters[..6].iter().all(|c| c.is_ascii_uppercase())
+        && characters[6..]
+            .iter()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// An IBAN in its electronic form, whose check digits hold under ISO 7064 mod 97-10
+pub fn is_iban(text: &str) -> bool {
+    let well_formed = (15..=34).contains(&text.len())
+        && text
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        && text[..2].chars().all(|c| c.is_ascii_uppercase())
+        && text[2..4].chars().all(|c| c.is_ascii_digit());
+    if !well_formed {
+        return false;
+    }
+
+    // the country and check digits move to the end and every letter counts as 10 to 35
+    let remainder = text[4..]
+        .chars()
+        .chain(text[..4].chars())
+        .fold(0u32, |remainder, c| {
+            let value = c.to_digit(36).unwrap_or_default();
+            let shift = if value < 10 { 10 } else { 100 };
+            (remainder * shift + value) % 97
+        });
+    remainder == 1
+}
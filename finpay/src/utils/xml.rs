@@ -0,0 +1,338 @@
+//! Just enough xml to write the exchange formats statements and payout batches are exported as
+//! and to read them back. There is no schema processor, reading back checks the output is well
+//! formed and lists the elements so each format can check the ones it requires
+
+pub struct XmlWriter {
+    content: String,
+    open: Vec<&'static str>,
+}
+
+impl Default for XmlWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XmlWriter {
+    pub fn new() -> Self {
+        Self {
+            content: "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".to_string(),
+            open: vec![],
+        }
+    }
+
+    /// Writes a processing instruction, `text` goes between `<?` and `?>` as it is
+    pub fn instruction(&mut self, text: &str) {
+        self.content.push_str(&format!("<?{text}?>\n"));
+    }
+
+    pub fn open(&mut self, name: &'static str) {
+        self.open_with(name, &[]);
+    }
+
+    pub fn open_with(&mut self, name: &'static str, attributes: &[(&str, &str)]) {
+        self.indent();
+        self.content
+            .push_str(&format!("<{name}{}>\n", write_attributes(attributes)));
+        self.open.push(name);
+    }
+
+    /// Writes an element holding only text
+    pub fn element(&mut self, name: &'static str, text: &str) {
+        self.element_with(name, &[], text);
+    }
+
+    pub fn element_with(&mut self, name: &'static str, attributes: &[(&str, &str)], text: &str) {
+        self.indent();
+        self.content.push_str(&format!(
+            "<{name}{}>{}</{name}>\n",
+            write_attributes(attributes),
+            escape(text)
+        ));
+    }
+
+    pub fn close(&mut self) {
+        if let Some(name) = self.open.pop() {
+            self.indent();
+            self.content.push_str(&format!("</{name}>\n"));
+        }
+    }
+
+    /// Closes whatever is still open and hands back the document
+    pub fn finish(mut self) -> String {
+        while !self.open.is_empty() {
+            self.close();
+        }
+        self.content
+    }
+
+    fn indent(&mut self) {
+        self.content.push_str(&"  ".repeat(self.open.len()));
+    }
+}
+
+fn write_attributes(attributes: &[(&str, &str)]) -> String {
+    attributes
+        .iter()
+        .map(|(name, value)| format!(" {name}=\"{}\"", escape(value)))
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&apos;".to_string(),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| "an entity is not terminated".to_string())?;
+        let entity = &rest[start + 1..start + end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16).ok())
+                .unwrap_or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        unescaped.push(character.ok_or_else(|| format!("&{entity}; is not a known entity"))?);
+        rest = &rest[start + end + 1..];
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+/// An element as it was read back, `path` names it from the root down, e.g. `Document/Stmt/Id`
+#[derive(Debug)]
+pub struct XmlElement {
+    pub path: String,
+    pub attributes: Vec<(String, String)>,
+    pub text: String,
+}
+
+impl XmlElement {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Reads a document into its elements in document order, failing on anything that is not well
+/// formed. Doctypes are refused outright
+pub fn parse(document: &str) -> Result<Vec<XmlElement>, String> {
+    let mut elements: Vec<XmlElement> = vec![];
+    let mut stack: Vec<(String, usize)> = vec![];
+    let mut roots = 0;
+    let mut rest = document;
+
+    loop {
+        let Some(start) = rest.find('<') else {
+            if !rest.trim().is_empty() {
+                return Err("there is text outside the root element".to_string());
+            }
+            break;
+        };
+
+        let text = &rest[..start];
+        match stack.last() {
+            Some((_, index)) => elements[*index].text.push_str(&unescape(text)?),
+            None if !text.trim().is_empty() => {
+                return Err("there is text outside the root element".to_string());
+            }
+            None => {}
+        }
+        rest = &rest[start..];
+
+        if let Some(instruction) = rest.strip_prefix("<?") {
+            let end = instruction
+                .find("?>")
+                .ok_or_else(|| "a processing instruction is not closed".to_string())?;
+            rest = &instruction[end + 2..];
+            continue;
+        }
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment
+                .find("-->")
+                .ok_or_else(|| "a comment is not closed".to_string())?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+        if rest.starts_with("<!") {
+            return Err("doctypes and cdata sections are not accepted".to_string());
+        }
+
+        let end = tag_end(rest).ok_or_else(|| "a tag is not closed".to_string())?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            match stack.pop() {
+                Some((open, _)) if open == name => continue,
+                Some((open, _)) => return Err(format!("<{open}> is closed by </{name}>")),
+                None => return Err(format!("</{name}> closes nothing")),
+            }
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, attributes) = match tag.find(char::is_whitespace) {
+            Some(split) => (&tag[..split], read_attributes(&tag[split..])?),
+            None => (tag, vec![]),
+        };
+        if name.is_empty() {
+            return Err("an element has no name".to_string());
+        }
+
+        if stack.is_empty() {
+            roots += 1;
+            if roots > 1 {
+                return Err("there is more than one root element".to_string());
+            }
+        }
+        let path = match stack.last() {
+            Some((_, parent)) => format!("{}/{name}", elements[*parent].path),
+            None => name.to_string(),
+        };
+        elements.push(XmlElement {
+            path,
+            attributes,
+            text: String::new(),
+        });
+        if !self_closing {
+            stack.push((name.to_string(), elements.len() - 1));
+        }
+    }
+
+    if let Some((open, _)) = stack.pop() {
+        return Err(format!("<{open}> is never closed"));
+    }
+    if elements.is_empty() {
+        return Err("there is no root element".to_string());
+    }
+
+    for element in &mut elements {
+        element.text = element.text.trim().to_string();
+    }
+    Ok(elements)
+}
+
+/// Where the tag starting `rest` ends, a `>` inside a quoted attribute does not count
+fn tag_end(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in rest.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn read_attributes(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = vec![];
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let equals = rest
+            .find('=')
+            .ok_or_else(|| format!("attribute {rest} has no value"))?;
+        let name = rest[..equals].trim();
+        let value = rest[equals + 1..].trim_start();
+        let quote = value
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("the value of {name} is not quoted"))?;
+        let end = value[1..]
+            .find(quote)
+            .ok_or_else(|| format!("the value of {name} is not closed"))?;
+        if attributes.iter().any(|(existing, _)| existing == name) {
+            return Err(format!("attribute {name} is repeated"));
+        }
+        attributes.push((name.to_string(), unescape(&value[1..end + 1])?));
+        rest = value[end + 2..].trim_start();
+    }
+    Ok(attributes)
+}
+
+/// The elements nested in the one at `index`, they always follow it directly
+pub fn descendants(elements: &[XmlElement], index: usize) -> &[XmlElement] {
+    let prefix = format!("{}/", elements[index].path);
+    let count = elements[index + 1..]
+        .iter()
+        .take_while(|element| element.path.starts_with(&prefix))
+        .count();
+    &elements[index + 1..index + 1 + count]
+}
+
+/// Reads the text of the only element at `path`
+pub fn required<'a>(elements: &'a [XmlElement], path: &str) -> Result<&'a XmlElement, String> {
+    let mut matches = elements.iter().filter(|element| element.path == path);
+    match (matches.next(), matches.next()) {
+        (Some(element), None) => Ok(element),
+        (None, _) => Err(format!("{path} is missing")),
+        (Some(_), Some(_)) => Err(format!("{path} appears more than once")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_written_documents_read_back() {
+        let mut writer = XmlWriter::new();
+        writer.open_with("Document", &[("xmlns", "urn:test")]);
+        writer.element("Nm", "Tom & Jerry <ltd>");
+        writer.open("Amts");
+        writer.element_with("Amt", &[("Ccy", "AED")], "10.00");
+        let document = writer.finish();
+
+        let elements = parse(&document).unwrap();
+        let paths: Vec<&str> = elements.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "Document",
+                "Document/Nm",
+                "Document/Amts",
+                "Document/Amts/Amt"
+            ]
+        );
+        assert_eq!(elements[0].attribute("xmlns"), Some("urn:test"));
+        assert_eq!(elements[1].text, "Tom & Jerry <ltd>");
+        assert_eq!(elements[3].attribute("Ccy"), Some("AED"));
+        assert_eq!(descendants(&elements, 2).len(), 1);
+    }
+
+    #[test]
+    fn test_malformed_documents_are_refused() {
+        assert!(parse("<a><b></a></b>").is_err());
+        assert!(parse("<a></a><b></b>").is_err());
+        assert!(parse("<a>&nbsp;</a>").is_err());
+        assert!(parse("<a>").is_err());
+        assert!(parse("<!DOCTYPE a><a></a>").is_err());
+        assert!(parse("<a x=\"1\" x=\"2\"/>").is_err());
+    }
+}
@@ -1,4 +1,6 @@
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use validator::Validate;
 
 #[derive(Serialize, Deserialize, Validate, Debug)]
@@ -14,8 +16,28 @@ pub struct CreateWalletRequest {
     pub currency_identifier: String,
 }
 
-#[derive(Serialize, Deserialize, Validate)]
-pub struct UpdateWalletRequest {}
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWalletRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "name must be between 1 and 255 characters",
+        code = "wallet name"
+    ))]
+    pub name: String,
+}
 
-#[derive(Serialize, Deserialize, Validate)]
-pub struct DeleteWalletRequest {}
+#[derive(Serialize, Deserialize, Validate, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteWalletRequest {
+    /// where any remaining balance is swept to, required unless the wallet is empty
+    pub sweep_wallet_identifier: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletBalanceQuery {
+    /// reconstructs the balance as it stood at this instant, the current balance when left out
+    pub as_of: Option<DateTime<Local>>,
+}
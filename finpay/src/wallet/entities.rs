@@ -1,21 +1,77 @@
+use crate::transactions::entities::Transaction;
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "wallet_status_enum")]
+pub enum WalletStatus {
+    Active,
+    Frozen,
+    Closed,
+}
+
+/// Who froze a wallet, an owner may only lift a freeze they placed themselves
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case", type_name = "wallet_freeze_origin_enum")]
+pub enum WalletFreezeOrigin {
+    Owner,
+    Admin,
+}
+
 #[derive(Serialize, Deserialize, FromRow, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Wallet {
     pub identifier: Uuid,
     pub name: String,
+    /// projected from the postings on the wallet's ledger account, never written directly
     pub balance: BigDecimal,
     pub user_identifier: Uuid,
     pub currency_identifier: Uuid,
+    pub status: WalletStatus,
+    pub frozen_by: Option<WalletFreezeOrigin>,
+    pub closed_date: Option<DateTime<Local>>,
     pub created_date: DateTime<Local>,
     pub updated_at: Option<DateTime<Local>>,
 }
 
+/// A wallet together with how much of its balance is reserved by active holds
+#[derive(Serialize, Deserialize, FromRow, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletDetails {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub wallet: Wallet,
+    pub held_balance: BigDecimal,
+    /// what can still be spent, the balance less active holds
+    pub available_balance: BigDecimal,
+}
+
+/// The ledger and available balance of a wallet at a point in time
+#[derive(Serialize, Deserialize, FromRow, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletBalance {
+    pub wallet_identifier: Uuid,
+    pub currency_identifier: Uuid,
+    /// the sum of the postings on the wallet's ledger account
+    pub ledger_balance: BigDecimal,
+    pub held_balance: BigDecimal,
+    pub available_balance: BigDecimal,
+    pub as_of: DateTime<Local>,
+}
+
+/// A closed wallet and, when it still held funds, the transfer that swept them out
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletClosure {
+    pub wallet: Wallet,
+    pub sweep_transaction: Option<Transaction>,
+}
+
 #[derive(Debug, FromRow)]
 pub struct WalletWithCount {
     pub id: Uuid,
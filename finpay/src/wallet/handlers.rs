@@ -1,8 +1,11 @@
 use crate::authentication::claims::Claims;
 use crate::errors::ServiceError;
+use crate::shared::middlewares::admin_middleware::AdminClaims;
 use crate::utils::{ApiResponse, AuthenticatedRequest, PaginatedResponse, PaginationParams};
-use crate::wallet::adapters::CreateWalletRequest;
-use crate::wallet::entities::Wallet;
+use crate::wallet::adapters::{
+    CreateWalletRequest, DeleteWalletRequest, UpdateWalletRequest, WalletBalanceQuery,
+};
+use crate::wallet::entities::{Wallet, WalletBalance, WalletClosure, WalletDetails};
 use crate::wallet::service::{WalletService, WalletServiceExt};
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
@@ -11,7 +14,7 @@ use uuid::Uuid;
 pub async fn create_wallet(
     State(wallet_service): State<WalletService>,
     AuthenticatedRequest { claims, request }: AuthenticatedRequest<CreateWalletRequest>,
-) -> Result<ApiResponse<Wallet>, ServiceError> {
+) -> Result<ApiResponse<WalletDetails>, ServiceError> {
     let inserted_identifier = wallet_service.create_wallet(&claims, &request).await?;
     let wallet = wallet_service
         .fetch_wallet(&claims, &inserted_identifier)
@@ -27,32 +30,124 @@ pub async fn fetch_wallet(
     State(wallet_service): State<WalletService>,
     claims: Claims,
     Path(wallet_identifier): Path<Uuid>,
-) -> Result<ApiResponse<Wallet>, ServiceError> {
+) -> Result<ApiResponse<WalletDetails>, ServiceError> {
     let wallet = wallet_service
         .fetch_wallet(&claims, &wallet_identifier)
         .await?;
 
-    Ok(ApiResponse::builder()
-        .data(wallet)
-        .status_code(StatusCode::CREATED)
-        .build())
+    Ok(ApiResponse::builder().data(wallet).build())
 }
 
 pub async fn fetch_all_wallets(
     State(wallet_service): State<WalletService>,
     claims: Claims,
     Query(pagination_params): Query<PaginationParams>,
-) -> Result<ApiResponse<PaginatedResponse<Wallet>>, ServiceError> {
+) -> Result<ApiResponse<PaginatedResponse<WalletDetails>>, ServiceError> {
     let response = wallet_service
         .fetch_all_wallets(&claims, &pagination_params)
         .await?;
     Ok(ApiResponse::builder().data(response).build())
 }
 
+pub async fn update_wallet(
+    State(wallet_service): State<WalletService>,
+    Path(wallet_identifier): Path<Uuid>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<UpdateWalletRequest>,
+) -> Result<ApiResponse<Wallet>, ServiceError> {
+    let wallet = wallet_service
+        .rename_wallet(&claims, &wallet_identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(wallet)
+        .message("wallet updated successfully")
+        .build())
+}
+
+pub async fn freeze_wallet(
+    State(wallet_service): State<WalletService>,
+    claims: Claims,
+    Path(wallet_identifier): Path<Uuid>,
+) -> Result<ApiResponse<Wallet>, ServiceError> {
+    let wallet = wallet_service
+        .freeze_wallet(&claims, &wallet_identifier)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(wallet)
+        .message("wallet frozen successfully")
+        .build())
+}
+
+pub async fn unfreeze_wallet(
+    State(wallet_service): State<WalletService>,
+    claims: Claims,
+    Path(wallet_identifier): Path<Uuid>,
+) -> Result<ApiResponse<Wallet>, ServiceError> {
+    let wallet = wallet_service
+        .unfreeze_wallet(&claims, &wallet_identifier)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(wallet)
+        .message("wallet unfrozen successfully")
+        .build())
+}
+
+pub async fn close_wallet(
+    State(wallet_service): State<WalletService>,
+    Path(wallet_identifier): Path<Uuid>,
+    AuthenticatedRequest { claims, request }: AuthenticatedRequest<DeleteWalletRequest>,
+) -> Result<ApiResponse<WalletClosure>, ServiceError> {
+    let closure = wallet_service
+        .close_wallet(&claims, &wallet_identifier, &request)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(closure)
+        .message("wallet closed successfully")
+        .build())
+}
+
+pub async fn admin_freeze_wallet(
+    State(wallet_service): State<WalletService>,
+    _: AdminClaims,
+    Path(wallet_identifier): Path<Uuid>,
+) -> Result<ApiResponse<Wallet>, ServiceError> {
+    let wallet = wallet_service
+        .admin_freeze_wallet(&wallet_identifier)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(wallet)
+        .message("wallet frozen successfully")
+        .build())
+}
+
+pub async fn admin_unfreeze_wallet(
+    State(wallet_service): State<WalletService>,
+    _: AdminClaims,
+    Path(wallet_identifier): Path<Uuid>,
+) -> Result<ApiResponse<Wallet>, ServiceError> {
+    let wallet = wallet_service
+        .admin_unfreeze_wallet(&wallet_identifier)
+        .await?;
+
+    Ok(ApiResponse::builder()
+        .data(wallet)
+        .message("wallet unfrozen successfully")
+        .build())
+}
+
 pub async fn retrieve_wallet_balance(
     State(wallet_service): State<WalletService>,
     claims: Claims,
     Path(wallet_identifier): Path<Uuid>,
-) -> Result<ApiResponse<PaginatedResponse<Wallet>>, ServiceError> {
-  todo!()
+    Query(query): Query<WalletBalanceQuery>,
+) -> Result<ApiResponse<WalletBalance>, ServiceError> {
+    let balance = wallet_service
+        .fetch_balance(&claims, &wallet_identifier, &query)
+        .await?;
+
+    Ok(ApiResponse::builder().data(balance).build())
 }
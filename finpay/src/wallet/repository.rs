@@ -1,9 +1,25 @@
+use crate::conversions::repository::ConversionRepository;
 use crate::errors::RepositoryError;
+use crate::fees::adapters::FeeQuery;
+use crate::fees::repository::FeeRepository;
+use crate::fx::repository::FxRepository;
+use crate::holds::entities::HoldStatus;
+use crate::holds::repository::HoldRepository;
+use crate::ledger::adapters::NewJournalEntry;
+use crate::ledger::repository::LedgerRepository;
+use crate::payouts::entities::PayoutStatus;
 use crate::shared::repository::DatabaseInsertResult;
+use crate::transactions::adapters::{CreateTransferRequest, NewTransaction};
+use crate::transactions::entities::{Transaction, TransactionStatus, TransactionType};
+use crate::transactions::repository::TransactionRepository;
 use crate::utils::{PaginatedResponse, PaginationParams};
-use crate::wallet::adapters::CreateWalletRequest;
-use crate::wallet::entities::Wallet;
-use sqlx::PgPool;
+use crate::wallet::adapters::{CreateWalletRequest, DeleteWalletRequest};
+use crate::wallet::entities::{
+    Wallet, WalletBalance, WalletClosure, WalletDetails, WalletFreezeOrigin, WalletStatus,
+};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Local};
+use sqlx::{PgConnection, PgPool};
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -16,6 +32,28 @@ impl WalletRepository {
     pub fn new(pool: PgPool) -> Self {
         WalletRepository { pool }
     }
+
+    /// Locks a wallet for the rest of the transaction, scoped to its owner when one is given
+    async fn lock_wallet(
+        connection: &mut PgConnection,
+        identifier: &Uuid,
+        user_identifier: Option<&Uuid>,
+    ) -> Result<Wallet, RepositoryError> {
+        sqlx::query_as::<_, Wallet>(
+            r#"
+            SELECT *
+            FROM wallets
+            WHERE identifier = $1
+              AND ($2::UUID IS NULL OR user_identifier = $2)
+            FOR UPDATE
+            "#,
+        )
+        .bind(identifier)
+        .bind(user_identifier)
+        .fetch_optional(connection)
+        .await?
+        .ok_or(RepositoryError::RecordNotFound)
+    }
 }
 
 pub trait WalletRepositoryExt {
@@ -29,15 +67,71 @@ pub trait WalletRepositoryExt {
         &self,
         identifier: &Uuid,
         user_identifier: &Uuid,
-    ) -> impl std::future::Future<Output = Result<Option<Wallet>, RepositoryError>> + Send;
+    ) -> impl std::future::Future<Output = Result<Option<WalletDetails>, RepositoryError>> + Send;
+
+    /// replays the wallet's postings up to `as_of`, or up to now when it is left out
+    fn fetch_balance(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        as_of: Option<&DateTime<Local>>,
+    ) -> impl std::future::Future<Output = Result<Option<WalletBalance>, RepositoryError>> + Send;
 
     fn fetch_all_wallet(
         &self,
         user_identifier: &Uuid,
         pagination_params: &PaginationParams,
-    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Wallet>, RepositoryError>> + Send;
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<WalletDetails>, RepositoryError>> + Send;
+
+    fn transfer(
+        &self,
+        payload: &CreateTransferRequest,
+        user_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Transaction, RepositoryError>> + Send;
+
+    fn rename(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Wallet, RepositoryError>> + Send;
+
+    /// `user_identifier` scopes the freeze to the owner's wallets, admins pass `None`
+    fn freeze(
+        &self,
+        identifier: &Uuid,
+        user_identifier: Option<&Uuid>,
+        origin: WalletFreezeOrigin,
+    ) -> impl std::future::Future<Output = Result<Wallet, RepositoryError>> + Send;
+
+    fn unfreeze(
+        &self,
+        identifier: &Uuid,
+        user_identifier: Option<&Uuid>,
+        origin: WalletFreezeOrigin,
+    ) -> impl std::future::Future<Output = Result<Wallet, RepositoryError>> + Send;
+
+    fn close(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        payload: &DeleteWalletRequest,
+    ) -> impl std::future::Future<Output = Result<WalletClosure, RepositoryError>> + Send;
 }
 
+/// selects wallets along with their held and available balances, `$1` binds the active hold status
+const WALLET_DETAILS: &str = r#"
+    SELECT wallets.*,
+           COALESCE(holds.held_balance, 0)                  AS held_balance,
+           wallets.balance - COALESCE(holds.held_balance, 0) AS available_balance
+    FROM wallets
+             LEFT JOIN LATERAL (SELECT SUM(wallet_holds.amount) AS held_balance
+                                FROM wallet_holds
+                                WHERE wallet_holds.wallet_identifier = wallets.identifier
+                                  AND wallet_holds.status = $1
+                                  AND wallet_holds.expires_at > NOW()) holds ON TRUE
+"#;
+
 impl WalletRepositoryExt for WalletRepository {
     async fn create(
         &self,
@@ -65,10 +159,13 @@ impl WalletRepositoryExt for WalletRepository {
         &self,
         identifier: &Uuid,
         user_identifier: &Uuid,
-    ) -> Result<Option<Wallet>, RepositoryError> {
-        let query = r#"SELECT * from wallets WHERE identifier = $1 AND user_identifier = $2"#;
+    ) -> Result<Option<WalletDetails>, RepositoryError> {
+        let query = format!(
+            "{WALLET_DETAILS} WHERE wallets.identifier = $2 AND wallets.user_identifier = $3"
+        );
 
-        sqlx::query_as::<_, Wallet>(query)
+        sqlx::query_as::<_, WalletDetails>(&query)
+            .bind(HoldStatus::Active)
             .bind(identifier)
             .bind(user_identifier)
             .fetch_optional(&self.pool)
@@ -76,20 +173,62 @@ impl WalletRepositoryExt for WalletRepository {
             .map_err(RepositoryError::from)
     }
 
-    async fn fetch_all_wallet(
+    async fn fetch_balance(
         &self,
+        identifier: &Uuid,
         user_identifier: &Uuid,
-        pagination_params: &PaginationParams,
-    ) -> Result<PaginatedResponse<Wallet>, RepositoryError> {
+        as_of: Option<&DateTime<Local>>,
+    ) -> Result<Option<WalletBalance>, RepositoryError> {
+        // holds carry no status history, one that has since been resolved still reserved funds at
+        // `as_of` if it was last touched after that instant
         let query = r#"
-    SELECT
-      *
-    FROM wallets
-    WHERE user_identifier = $1
-    ORDER BY created_date DESC
-    LIMIT $2 OFFSET $3
+        WITH point_in_time AS (SELECT COALESCE($3::TIMESTAMPTZ, NOW()) AS as_of)
+        SELECT wallets.identifier                    AS wallet_identifier,
+               wallets.currency_identifier,
+               ledger.balance                        AS ledger_balance,
+               holds.held_balance,
+               ledger.balance - holds.held_balance AS available_balance,
+               point_in_time.as_of
+        FROM wallets
+                 CROSS JOIN point_in_time
+                 JOIN ledger_accounts ON ledger_accounts.wallet_identifier = wallets.identifier
+                 CROSS JOIN LATERAL (SELECT COALESCE(SUM(CASE
+                                                             WHEN postings.direction = 'credit'
+                                                                 THEN postings.amount
+                                                             ELSE -postings.amount END), 0) AS balance
+                                     FROM postings
+                                     WHERE postings.ledger_account_identifier = ledger_accounts.identifier
+                                       AND postings.created_date <= point_in_time.as_of) ledger
+                 CROSS JOIN LATERAL (SELECT COALESCE(SUM(wallet_holds.amount), 0) AS held_balance
+                                     FROM wallet_holds
+                                     WHERE wallet_holds.wallet_identifier = wallets.identifier
+                                       AND wallet_holds.created_date <= point_in_time.as_of
+                                       AND wallet_holds.expires_at > point_in_time.as_of
+                                       AND (wallet_holds.status = $4
+                                         OR wallet_holds.updated_at > point_in_time.as_of)) holds
+        WHERE wallets.identifier = $1
+          AND wallets.user_identifier = $2
         "#;
 
+        sqlx::query_as::<_, WalletBalance>(query)
+            .bind(identifier)
+            .bind(user_identifier)
+            .bind(as_of)
+            .bind(HoldStatus::Active)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_all_wallet(
+        &self,
+        user_identifier: &Uuid,
+        pagination_params: &PaginationParams,
+    ) -> Result<PaginatedResponse<WalletDetails>, RepositoryError> {
+        let query = format!(
+            "{WALLET_DETAILS} WHERE wallets.user_identifier = $2 ORDER BY wallets.created_date DESC LIMIT $3 OFFSET $4"
+        );
+
         let total_count: i64 =
             sqlx::query_scalar("SELECT COUNT(identifier) FROM wallets WHERE user_identifier = $1")
                 .bind(user_identifier)
@@ -101,7 +240,8 @@ impl WalletRepositoryExt for WalletRepository {
 
         let offset = (page - 1) * page_size;
 
-        let wallets = sqlx::query_as::<_, Wallet>(query)
+        let wallets = sqlx::query_as::<_, WalletDetails>(&query)
+            .bind(HoldStatus::Active)
             .bind(user_identifier)
             .bind(page_size as i64)
             .bind(offset as i64)
@@ -112,4 +252,665 @@ impl WalletRepositoryExt for WalletRepository {
 
         Ok(paginated_data)
     }
+
+    async fn transfer(
+        &self,
+        payload: &CreateTransferRequest,
+        user_identifier: &Uuid,
+    ) -> Result<Transaction, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        // a quote is locked before the wallets, the same order conversions take them in
+        let quote = match &payload.quote_identifier {
+            Some(quote_identifier) => {
+                let quote =
+                    FxRepository::lock_quote(&mut tx, quote_identifier, user_identifier).await?;
+                if quote.source_wallet_identifier != payload.source_wallet_identifier
+                    || quote.destination_wallet_identifier != payload.destination_wallet_identifier
+                    || quote.source_amount != payload.amount
+                {
+                    return Err(RepositoryError::OperationFailed(
+                        "the quote does not match the transfer".into(),
+                    ));
+                }
+                Some(quote)
+            }
+            None => None,
+        };
+
+        // lock both rows in a stable order so concurrent transfers between the same pair of
+        // wallets cannot deadlock
+        let wallets = sqlx::query_as::<_, Wallet>(
+            r#"SELECT * FROM wallets WHERE identifier = ANY($1) ORDER BY identifier FOR UPDATE"#,
+        )
+        .bind(vec![
+            payload.source_wallet_identifier,
+            payload.destination_wallet_identifier,
+        ])
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let source_wallet = wallets
+            .iter()
+            .find(|wallet| {
+                wallet.identifier == payload.source_wallet_identifier
+                    && wallet.user_identifier == *user_identifier
+            })
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        let destination_wallet = wallets
+            .iter()
+            .find(|wallet| wallet.identifier == payload.destination_wallet_identifier)
+            .ok_or(RepositoryError::RecordNotFound)?;
+
+        let held_amount = HoldRepository::held_amount(&mut tx, &source_wallet.identifier).await?;
+        // a quote carries the fee it was priced with
+        let fee = match &quote {
+            Some(quote) => quote.fee(),
+            None => {
+                FeeRepository::assess(
+                    &mut tx,
+                    &FeeQuery {
+                        transaction_type: TransactionType::Transfer,
+                        currency_identifier: source_wallet.currency_identifier,
+                        user_identifier: *user_identifier,
+                        amount: payload.amount.clone(),
+                    },
+                )
+                .await?
+            }
+        };
+        let total_debit = &payload.amount + &fee.fee_amount;
+
+        let failure = if source_wallet.status != WalletStatus::Active
+            || destination_wallet.status != WalletStatus::Active
+        {
+            Some(RepositoryError::InactiveWallet)
+        } else if source_wallet.currency_identifier != destination_wallet.currency_identifier
+            && quote.is_none()
+        {
+            Some(RepositoryError::CurrencyMismatch)
+        } else if &source_wallet.balance - held_amount < total_debit {
+            Some(RepositoryError::InsufficientFunds)
+        } else {
+            None
+        };
+
+        let mut transaction = TransactionRepository::open(
+            &mut tx,
+            &NewTransaction {
+                transaction_type: TransactionType::Transfer,
+                source_wallet_identifier: Some(source_wallet.identifier),
+                destination_wallet_identifier: Some(destination_wallet.identifier),
+                amount: payload.amount.clone(),
+                currency_identifier: source_wallet.currency_identifier,
+                description: payload.description.clone(),
+                initiated_by: Some(*user_identifier),
+                external_reference: None,
+            },
+        )
+        .await?;
+
+        match (&failure, &quote) {
+            (Some(err), _) => {
+                TransactionRepository::transition(
+                    &mut tx,
+                    &mut transaction,
+                    TransactionStatus::Failed,
+                    Some(&err.to_string()),
+                )
+                .await?;
+            }
+            (None, Some(quote)) => {
+                ConversionRepository::execute_quote(&mut tx, quote, &transaction).await?;
+
+                TransactionRepository::transition(
+                    &mut tx,
+                    &mut transaction,
+                    TransactionStatus::Completed,
+                    None,
+                )
+                .await?;
+            }
+            (None, None) => {
+                let source_account =
+                    LedgerRepository::wallet_account(&mut tx, &source_wallet.identifier).await?;
+                let destination_account =
+                    LedgerRepository::wallet_account(&mut tx, &destination_wallet.identifier)
+                        .await?;
+
+                let entry = NewJournalEntry::new(&format!("transfer {}", transaction.reference))
+                    .transaction(&transaction.identifier)
+                    .debit(&source_account, &payload.amount)
+                    .credit(&destination_account, &payload.amount);
+                LedgerRepository::record_entry(&mut tx, &entry).await?;
+                FeeRepository::charge(&mut tx, &transaction, &source_wallet.identifier, &fee)
+                    .await?;
+
+                TransactionRepository::transition(
+                    &mut tx,
+                    &mut transaction,
+                    TransactionStatus::Completed,
+                    None,
+                )
+                .await?;
+            }
+        }
+
+        // failed attempts are committed too, so that the transaction history is complete
+        tx.commit().await?;
+
+        match failure {
+            Some(err) => Err(err),
+            None => Ok(transaction),
+        }
+    }
+
+    async fn rename(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        name: &str,
+    ) -> Result<Wallet, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let wallet = Self::lock_wallet(&mut tx, identifier, Some(user_identifier)).await?;
+        if wallet.status == WalletStatus::Closed {
+            return Err(RepositoryError::InactiveWallet);
+        }
+
+        let wallet = sqlx::query_as::<_, Wallet>(
+            r#"UPDATE wallets SET name = $1 WHERE identifier = $2 RETURNING *"#,
+        )
+        .bind(name)
+        .bind(wallet.identifier)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(wallet)
+    }
+
+    async fn freeze(
+        &self,
+        identifier: &Uuid,
+        user_identifier: Option<&Uuid>,
+        origin: WalletFreezeOrigin,
+    ) -> Result<Wallet, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let wallet = Self::lock_wallet(&mut tx, identifier, user_identifier).await?;
+        match (wallet.status, wallet.frozen_by) {
+            (WalletStatus::Closed, _) => return Err(RepositoryError::InactiveWallet),
+            // an owner freezing again must not downgrade a freeze placed by an admin
+            (WalletStatus::Frozen, _) if origin == WalletFreezeOrigin::Owner => return Ok(wallet),
+            _ => {}
+        }
+
+        let wallet = sqlx::query_as::<_, Wallet>(
+            r#"UPDATE wallets SET status = $1, frozen_by = $2 WHERE identifier = $3 RETURNING *"#,
+        )
+        .bind(WalletStatus::Frozen)
+        .bind(origin)
+        .bind(wallet.identifier)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(wallet)
+    }
+
+    async fn unfreeze(
+        &self,
+        identifier: &Uuid,
+        user_identifier: Option<&Uuid>,
+        origin: WalletFreezeOrigin,
+    ) -> Result<Wallet, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let wallet = Self::lock_wallet(&mut tx, identifier, user_identifier).await?;
+        match wallet.status {
+            WalletStatus::Closed => return Err(RepositoryError::InactiveWallet),
+            WalletStatus::Active => return Ok(wallet),
+            WalletStatus::Frozen => {}
+        }
+        if origin == WalletFreezeOrigin::Owner
+            && wallet.frozen_by != Some(WalletFreezeOrigin::Owner)
+        {
+            return Err(RepositoryError::AdministrativeFreeze);
+        }
+
+        let wallet = sqlx::query_as::<_, Wallet>(
+            r#"UPDATE wallets SET status = $1, frozen_by = NULL WHERE identifier = $2 RETURNING *"#,
+        )
+        .bind(WalletStatus::Active)
+        .bind(wallet.identifier)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(wallet)
+    }
+
+    async fn close(
+        &self,
+        identifier: &Uuid,
+        user_identifier: &Uuid,
+        payload: &DeleteWalletRequest,
+    ) -> Result<WalletClosure, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut wallet_identifiers = vec![*identifier];
+        wallet_identifiers.extend(payload.sweep_wallet_identifier);
+        let wallets = sqlx::query_as::<_, Wallet>(
+            r#"SELECT * FROM wallets WHERE identifier = ANY($1) ORDER BY identifier FOR UPDATE"#,
+        )
+        .bind(wallet_identifiers)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let wallet = wallets
+            .iter()
+            .find(|wallet| {
+                wallet.identifier == *identifier && wallet.user_identifier == *user_identifier
+            })
+            .ok_or(RepositoryError::RecordNotFound)?;
+        if wallet.status != WalletStatus::Active {
+            return Err(RepositoryError::InactiveWallet);
+        }
+
+        // funds still reserved or in flight would have nowhere to land once the wallet is closed
+        let held_amount = HoldRepository::held_amount(&mut tx, &wallet.identifier).await?;
+        if !held_amount.is_zero() {
+            return Err(RepositoryError::OperationFailed(
+                "the wallet has active holds, capture or void them before closing it".into(),
+            ));
+        }
+        let pending_payouts: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM payouts WHERE wallet_identifier = $1 AND status IN ($2, $3, $4)"#,
+        )
+        .bind(wallet.identifier)
+        .bind(PayoutStatus::Queued)
+        .bind(PayoutStatus::Processing)
+        .bind(PayoutStatus::Exported)
+        .fetch_one(&mut *tx)
+        .await?;
+        if pending_payouts > 0 {
+            return Err(RepositoryError::OperationFailed(
+                "the wallet has payouts that have not settled yet".into(),
+            ));
+        }
+
+        let sweep_transaction = if wallet.balance > BigDecimal::zero() {
+            let Some(sweep_wallet_identifier) = payload.sweep_wallet_identifier else {
+                return Err(RepositoryError::OperationFailed(
+                    "the wallet still holds a balance, nominate a wallet to sweep it into".into(),
+                ));
+            };
+            // the remainder is swept to another of the owner's wallets, never to a third party
+            let sweep_wallet = wallets
+                .iter()
+                .find(|sweep_wallet| {
+                    sweep_wallet.identifier == sweep_wallet_identifier
+                        && sweep_wallet.identifier != wallet.identifier
+                        && sweep_wallet.user_identifier == *user_identifier
+                })
+                .ok_or(RepositoryError::RecordNotFound)?;
+            if sweep_wallet.status != WalletStatus::Active {
+                return Err(RepositoryError::InactiveWallet);
+            }
+            if sweep_wallet.currency_identifier != wallet.currency_identifier {
+                return Err(RepositoryError::CurrencyMismatch);
+            }
+
+            let mut transaction = TransactionRepository::open(
+                &mut tx,
+                &NewTransaction {
+                    transaction_type: TransactionType::Transfer,
+                    source_wallet_identifier: Some(wallet.identifier),
+                    destination_wallet_identifier: Some(sweep_wallet.identifier),
+                    amount: wallet.balance.clone(),
+                    currency_identifier: wallet.currency_identifier,
+                    description: Some(format!("closing balance of {}", wallet.name)),
+                    initiated_by: Some(*user_identifier),
+                    external_reference: None,
+                },
+            )
+            .await?;
+
+            let source_account =
+                LedgerRepository::wallet_account(&mut tx, &wallet.identifier).await?;
+            let destination_account =
+                LedgerRepository::wallet_account(&mut tx, &sweep_wallet.identifier).await?;
+            let entry = NewJournalEntry::new(&format!("closing sweep {}", transaction.reference))
+                .transaction(&transaction.identifier)
+                .debit(&source_account, &wallet.balance)
+                .credit(&destination_account, &wallet.balance);
+            LedgerRepository::record_entry(&mut tx, &entry).await?;
+
+            TransactionRepository::transition(
+                &mut tx,
+                &mut transaction,
+                TransactionStatus::Completed,
+                None,
+            )
+            .await?;
+            Some(transaction)
+        } else {
+            None
+        };
+
+        let wallet = sqlx::query_as::<_, Wallet>(
+            r#"
+            UPDATE wallets
+            SET status      = $1,
+                frozen_by   = NULL,
+                closed_date = NOW()
+            WHERE identifier = $2
+            RETURNING *
+            "#,
+        )
+        .bind(WalletStatus::Closed)
+        .bind(wallet.identifier)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(WalletClosure {
+            wallet,
+            sweep_transaction,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversions::entities::ConversionPricing;
+    use crate::fx::adapters::NewFxQuote;
+    use crate::fx::repository::FxRepositoryExt;
+    use crate::shared::fixtures::{
+        AFGHAN_AFGHANI, UAE_DIRHAM, create_user, create_wallet, create_wallet_in, fund,
+    };
+    use chrono::Duration;
+
+    #[sqlx::test]
+    async fn test_transfer_moves_funds(pool: PgPool) {
+        let repository = WalletRepository::new(pool.clone());
+        let user_identifier = create_user(&pool).await;
+        let source = create_wallet(&pool, &user_identifier).await;
+        let destination = create_wallet(&pool, &user_identifier).await;
+        fund(&pool, &source, "100").await;
+
+        let request = CreateTransferRequest {
+            source_wallet_identifier: source,
+            destination_wallet_identifier: destination,
+            amount: BigDecimal::from(40),
+            description: None,
+            quote_identifier: None,
+        };
+        let transaction = repository
+            .transfer(&request, &user_identifier)
+            .await
+            .expect("failed to transfer funds");
+
+        assert_eq!(transaction.status, TransactionStatus::Completed);
+
+        let history: Vec<(Option<TransactionStatus>, TransactionStatus)> = sqlx::query_as(
+            "SELECT from_status, to_status FROM transaction_status_transitions WHERE transaction_identifier = $1 ORDER BY sequence",
+        )
+        .bind(transaction.identifier)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            history,
+            vec![
+                (None, TransactionStatus::Pending),
+                (Some(TransactionStatus::Pending), TransactionStatus::Completed)
+            ]
+        );
+
+        let source_wallet = repository
+            .fetch_wallet(&source, &user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        let destination_wallet = repository
+            .fetch_wallet(&destination, &user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(source_wallet.wallet.balance, BigDecimal::from(60));
+        assert_eq!(destination_wallet.wallet.balance, BigDecimal::from(40));
+    }
+
+    #[sqlx::test]
+    async fn test_transfer_rejects_overdraft(pool: PgPool) {
+        let repository = WalletRepository::new(pool.clone());
+        let user_identifier = create_user(&pool).await;
+        let source = create_wallet(&pool, &user_identifier).await;
+        let destination = create_wallet(&pool, &user_identifier).await;
+        fund(&pool, &source, "10").await;
+
+        let request = CreateTransferRequest {
+            source_wallet_identifier: source,
+            destination_wallet_identifier: destination,
+            amount: BigDecimal::from(40),
+            description: None,
+            quote_identifier: None,
+        };
+        let result = repository.transfer(&request, &user_identifier).await;
+
+        assert!(matches!(result, Err(RepositoryError::InsufficientFunds)));
+
+        let failed_transfers: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM transactions WHERE source_wallet_identifier = $1 AND status = 'failed'",
+        )
+        .bind(source)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(failed_transfers, 1);
+    }
+
+    #[sqlx::test]
+    async fn test_transfer_rejects_currency_mismatch(pool: PgPool) {
+        let repository = WalletRepository::new(pool.clone());
+        let user_identifier = create_user(&pool).await;
+        let source = create_wallet(&pool, &user_identifier).await;
+        let destination = create_wallet_in(&pool, &user_identifier, AFGHAN_AFGHANI).await;
+        fund(&pool, &source, "100").await;
+
+        let request = CreateTransferRequest {
+            source_wallet_identifier: source,
+            destination_wallet_identifier: destination,
+            amount: BigDecimal::from(40),
+            description: None,
+            quote_identifier: None,
+        };
+        let result = repository.transfer(&request, &user_identifier).await;
+
+        assert!(matches!(result, Err(RepositoryError::CurrencyMismatch)));
+    }
+
+    #[sqlx::test]
+    async fn test_transfer_across_currencies_executes_quote(pool: PgPool) {
+        let repository = WalletRepository::new(pool.clone());
+        let user_identifier = create_user(&pool).await;
+        let recipient_identifier = create_user(&pool).await;
+        let source = create_wallet(&pool, &user_identifier).await;
+        let destination = create_wallet_in(&pool, &recipient_identifier, AFGHAN_AFGHANI).await;
+        fund(&pool, &source, "150").await;
+
+        let quote = FxRepository::new(&pool)
+            .create_quote(&NewFxQuote {
+                user_identifier,
+                source_wallet_identifier: source,
+                destination_wallet_identifier: destination,
+                source_currency_identifier: Uuid::from_str(UAE_DIRHAM).unwrap(),
+                destination_currency_identifier: Uuid::from_str(AFGHAN_AFGHANI).unwrap(),
+                source_amount: BigDecimal::from(100),
+                pricing: ConversionPricing {
+                    mid_rate: BigDecimal::from_str("18.65").unwrap(),
+                    spread_basis_points: 100,
+                    rate_source: "test".to_string(),
+                },
+                expires_at: Local::now() + Duration::seconds(30),
+            })
+            .await
+            .unwrap();
+
+        let mut request = CreateTransferRequest {
+            source_wallet_identifier: source,
+            destination_wallet_identifier: destination,
+            amount: BigDecimal::from(40),
+            description: None,
+            quote_identifier: Some(quote.identifier),
+        };
+        let result = repository.transfer(&request, &user_identifier).await;
+        assert!(matches!(result, Err(RepositoryError::OperationFailed(_))));
+
+        request.amount = BigDecimal::from(100);
+        let transaction = repository
+            .transfer(&request, &user_identifier)
+            .await
+            .unwrap();
+        assert_eq!(transaction.status, TransactionStatus::Completed);
+
+        let source_wallet = repository
+            .fetch_wallet(&source, &user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        let destination_wallet = repository
+            .fetch_wallet(&destination, &recipient_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(source_wallet.wallet.balance, BigDecimal::from(50));
+        assert_eq!(
+            destination_wallet.wallet.balance,
+            BigDecimal::from_str("1846.35").unwrap()
+        );
+
+        let replay = repository.transfer(&request, &user_identifier).await;
+        assert!(matches!(replay, Err(RepositoryError::InvalidQuote)));
+    }
+
+    #[sqlx::test]
+    async fn test_owner_cannot_lift_admin_freeze(pool: PgPool) {
+        let repository = WalletRepository::new(pool.clone());
+        let user_identifier = create_user(&pool).await;
+        let wallet = create_wallet(&pool, &user_identifier).await;
+
+        repository
+            .freeze(&wallet, None, WalletFreezeOrigin::Admin)
+            .await
+            .unwrap();
+        let frozen = repository
+            .freeze(&wallet, Some(&user_identifier), WalletFreezeOrigin::Owner)
+            .await
+            .unwrap();
+        assert_eq!(frozen.frozen_by, Some(WalletFreezeOrigin::Admin));
+
+        let result = repository
+            .unfreeze(&wallet, Some(&user_identifier), WalletFreezeOrigin::Owner)
+            .await;
+        assert!(matches!(result, Err(RepositoryError::AdministrativeFreeze)));
+
+        let unfrozen = repository
+            .unfreeze(&wallet, None, WalletFreezeOrigin::Admin)
+            .await
+            .unwrap();
+        assert_eq!(unfrozen.status, WalletStatus::Active);
+        assert_eq!(unfrozen.frozen_by, None);
+    }
+
+    #[sqlx::test]
+    async fn test_close_requires_sweep_wallet_for_remaining_balance(pool: PgPool) {
+        let repository = WalletRepository::new(pool.clone());
+        let user_identifier = create_user(&pool).await;
+        let wallet = create_wallet(&pool, &user_identifier).await;
+        fund(&pool, &wallet, "25").await;
+
+        let result = repository
+            .close(&wallet, &user_identifier, &DeleteWalletRequest::default())
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::OperationFailed(_))));
+    }
+
+    #[sqlx::test]
+    async fn test_close_sweeps_balance_and_blocks_further_credits(pool: PgPool) {
+        let repository = WalletRepository::new(pool.clone());
+        let user_identifier = create_user(&pool).await;
+        let wallet = create_wallet(&pool, &user_identifier).await;
+        let sweep_wallet = create_wallet(&pool, &user_identifier).await;
+        fund(&pool, &wallet, "25").await;
+
+        let closure = repository
+            .close(
+                &wallet,
+                &user_identifier,
+                &DeleteWalletRequest {
+                    sweep_wallet_identifier: Some(sweep_wallet),
+                },
+            )
+            .await
+            .expect("failed to close wallet");
+
+        assert_eq!(closure.wallet.status, WalletStatus::Closed);
+        assert_eq!(closure.wallet.balance, BigDecimal::zero());
+        assert!(closure.wallet.closed_date.is_some());
+        let sweep_transaction = closure.sweep_transaction.expect("balance was not swept");
+        assert_eq!(sweep_transaction.amount, BigDecimal::from(25));
+
+        let swept = repository
+            .fetch_wallet(&sweep_wallet, &user_identifier)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(swept.wallet.balance, BigDecimal::from(25));
+
+        let request = CreateTransferRequest {
+            source_wallet_identifier: sweep_wallet,
+            destination_wallet_identifier: wallet,
+            amount: BigDecimal::from(5),
+            description: None,
+            quote_identifier: None,
+        };
+        let result = repository.transfer(&request, &user_identifier).await;
+        assert!(matches!(result, Err(RepositoryError::InactiveWallet)));
+    }
+
+    #[sqlx::test]
+    async fn test_fetch_balance_as_of_replays_postings(pool: PgPool) {
+        let repository = WalletRepository::new(pool.clone());
+        let user_identifier = create_user(&pool).await;
+        let wallet = create_wallet(&pool, &user_identifier).await;
+        fund(&pool, &wallet, "100").await;
+
+        let month_end: DateTime<Local> = sqlx::query_scalar("SELECT NOW()")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        fund(&pool, &wallet, "50").await;
+
+        let historical = repository
+            .fetch_balance(&wallet, &user_identifier, Some(&month_end))
+            .await
+            .unwrap()
+            .unwrap();
+        let current = repository
+            .fetch_balance(&wallet, &user_identifier, None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(historical.ledger_balance, BigDecimal::from(100));
+        assert_eq!(historical.available_balance, BigDecimal::from(100));
+        assert_eq!(current.ledger_balance, BigDecimal::from(150));
+    }
 }
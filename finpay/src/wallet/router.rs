@@ -1,16 +1,47 @@
 use crate::{
+    conversions::handlers::convert,
+    shared::middlewares::idempotency::{IdempotencyStore, enforce_idempotency},
     state::AppState,
-    wallet::handlers::{create_wallet, fetch_all_wallets, fetch_wallet},
+    wallet::handlers::{
+        admin_freeze_wallet, admin_unfreeze_wallet, close_wallet, create_wallet, fetch_all_wallets,
+        fetch_wallet, freeze_wallet, retrieve_wallet_balance, unfreeze_wallet, update_wallet,
+    },
 };
 use axum::{
     Router,
-    routing::{get, post},
+    extract::FromRef,
+    middleware::from_fn_with_state,
+    routing::{delete, get, post, put},
 };
 
 pub fn wallet_routes(state: &AppState) -> Router {
+    let idempotency_store = IdempotencyStore::from_ref(state);
+
     Router::new()
         .route("/", post(create_wallet))
+        .route(
+            "/convert",
+            post(convert).layer(from_fn_with_state(
+                idempotency_store.clone(),
+                enforce_idempotency,
+            )),
+        )
         .route("/{wallet_identifier}", get(fetch_wallet))
+        .route("/{wallet_identifier}", put(update_wallet))
+        .route(
+            "/{wallet_identifier}",
+            delete(close_wallet).layer(from_fn_with_state(idempotency_store, enforce_idempotency)),
+        )
+        .route("/{wallet_identifier}/balance", get(retrieve_wallet_balance))
+        .route("/{wallet_identifier}/freeze", post(freeze_wallet))
+        .route("/{wallet_identifier}/unfreeze", post(unfreeze_wallet))
         .route("/", get(fetch_all_wallets))
         .with_state(state.clone())
 }
+
+pub fn admin_wallet_routes(state: &AppState) -> Router {
+    Router::new()
+        .route("/{wallet_identifier}/freeze", post(admin_freeze_wallet))
+        .route("/{wallet_identifier}/unfreeze", post(admin_unfreeze_wallet))
+        .with_state(state.clone())
+}
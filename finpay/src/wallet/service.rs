@@ -2,22 +2,33 @@ use crate::authentication::claims::Claims;
 use crate::errors::RepositoryError::RecordNotFound;
 use crate::errors::ServiceError;
 use crate::errors::ServiceError::RepositoryError;
+use crate::risk::adapters::ScreenedRequest;
+use crate::risk::service::{RiskService, RiskServiceExt};
+use crate::transactions::adapters::CreateTransferRequest;
+use crate::transactions::entities::Transaction;
 use crate::utils::{PaginatedResponse, PaginationParams};
-use crate::wallet::adapters::CreateWalletRequest;
-use crate::wallet::entities::Wallet;
+use crate::wallet::adapters::{
+    CreateWalletRequest, DeleteWalletRequest, UpdateWalletRequest, WalletBalanceQuery,
+};
+use crate::wallet::entities::{
+    Wallet, WalletBalance, WalletClosure, WalletDetails, WalletFreezeOrigin,
+};
 use crate::wallet::repository::{WalletRepository, WalletRepositoryExt};
+use chrono::Local;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct WalletService {
     repository: WalletRepository,
+    risk_service: RiskService,
 }
 
 impl WalletService {
     pub fn new(pool: &PgPool) -> Self {
         Self {
             repository: WalletRepository::new(pool.clone()),
+            risk_service: RiskService::new(pool),
         }
     }
 }
@@ -33,15 +44,63 @@ pub trait WalletServiceExt: Send + Sync + 'static {
         &self,
         claims: &Claims,
         wallet_identifier: &Uuid,
-    ) -> impl std::future::Future<Output = Result<Wallet, ServiceError>> + Send;
+    ) -> impl std::future::Future<Output = Result<WalletDetails, ServiceError>> + Send;
 
     fn fetch_all_wallets(
         &self,
         claims: &Claims,
         pagination_params: &PaginationParams,
-    ) -> impl std::future::Future<Output = Result<PaginatedResponse<Wallet>, ServiceError>> + Send;
-    
-    async fn fetch_bank_details(&self, user_identifier: &Uuid, wallet_identifier: &Uuid)-> Result<BankDetails, ServiceError>;
+    ) -> impl std::future::Future<Output = Result<PaginatedResponse<WalletDetails>, ServiceError>> + Send;
+
+    fn transfer(
+        &self,
+        claims: &Claims,
+        request: &CreateTransferRequest,
+    ) -> impl std::future::Future<Output = Result<Transaction, ServiceError>> + Send;
+
+    fn rename_wallet(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+        request: &UpdateWalletRequest,
+    ) -> impl std::future::Future<Output = Result<Wallet, ServiceError>> + Send;
+
+    fn freeze_wallet(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Wallet, ServiceError>> + Send;
+
+    fn unfreeze_wallet(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Wallet, ServiceError>> + Send;
+
+    fn close_wallet(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+        request: &DeleteWalletRequest,
+    ) -> impl std::future::Future<Output = Result<WalletClosure, ServiceError>> + Send;
+
+    /// freezes any wallet, only an admin can lift the freeze again
+    fn admin_freeze_wallet(
+        &self,
+        wallet_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Wallet, ServiceError>> + Send;
+
+    fn admin_unfreeze_wallet(
+        &self,
+        wallet_identifier: &Uuid,
+    ) -> impl std::future::Future<Output = Result<Wallet, ServiceError>> + Send;
+
+    fn fetch_balance(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+        query: &WalletBalanceQuery,
+    ) -> impl std::future::Future<Output = Result<WalletBalance, ServiceError>> + Send;
 }
 
 impl WalletServiceExt for WalletService {
@@ -61,22 +120,156 @@ impl WalletServiceExt for WalletService {
         &self,
         claims: &Claims,
         wallet_identifier: &Uuid,
-    ) -> Result<Wallet, ServiceError> {
+    ) -> Result<WalletDetails, ServiceError> {
         self.repository
             .fetch_wallet(wallet_identifier, &claims.user_identifier)
             .await?
             .ok_or(RepositoryError(RecordNotFound))
     }
 
+    async fn fetch_balance(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+        query: &WalletBalanceQuery,
+    ) -> Result<WalletBalance, ServiceError> {
+        if query.as_of.is_some_and(|as_of| as_of > Local::now()) {
+            return Err(ServiceError::UnprocessableEntity(
+                "asOf cannot be in the future".to_string(),
+            ));
+        }
+
+        self.repository
+            .fetch_balance(
+                wallet_identifier,
+                &claims.user_identifier,
+                query.as_of.as_ref(),
+            )
+            .await?
+            .ok_or(RepositoryError(RecordNotFound))
+    }
+
     async fn fetch_all_wallets(
         &self,
         claims: &Claims,
         pagination_params: &PaginationParams,
-    ) -> Result<PaginatedResponse<Wallet>, ServiceError> {
+    ) -> Result<PaginatedResponse<WalletDetails>, ServiceError> {
         let result = self
             .repository
             .fetch_all_wallet(&claims.user_identifier, pagination_params)
             .await?;
         Ok(result)
     }
+
+    async fn transfer(
+        &self,
+        claims: &Claims,
+        request: &CreateTransferRequest,
+    ) -> Result<Transaction, ServiceError> {
+        if request.source_wallet_identifier == request.destination_wallet_identifier {
+            return Err(ServiceError::UnprocessableEntity(
+                "source and destination wallets must be different".to_string(),
+            ));
+        }
+
+        self.risk_service
+            .screen(
+                &claims.user_identifier,
+                ScreenedRequest::Transfer(request.clone()),
+            )
+            .await?;
+
+        let transaction = self
+            .repository
+            .transfer(request, &claims.user_identifier)
+            .await?;
+        Ok(transaction)
+    }
+
+    async fn rename_wallet(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+        request: &UpdateWalletRequest,
+    ) -> Result<Wallet, ServiceError> {
+        let wallet = self
+            .repository
+            .rename(
+                wallet_identifier,
+                &claims.user_identifier,
+                request.name.trim(),
+            )
+            .await?;
+        Ok(wallet)
+    }
+
+    async fn freeze_wallet(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+    ) -> Result<Wallet, ServiceError> {
+        let wallet = self
+            .repository
+            .freeze(
+                wallet_identifier,
+                Some(&claims.user_identifier),
+                WalletFreezeOrigin::Owner,
+            )
+            .await?;
+        Ok(wallet)
+    }
+
+    async fn unfreeze_wallet(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+    ) -> Result<Wallet, ServiceError> {
+        let wallet = self
+            .repository
+            .unfreeze(
+                wallet_identifier,
+                Some(&claims.user_identifier),
+                WalletFreezeOrigin::Owner,
+            )
+            .await?;
+        Ok(wallet)
+    }
+
+    async fn close_wallet(
+        &self,
+        claims: &Claims,
+        wallet_identifier: &Uuid,
+        request: &DeleteWalletRequest,
+    ) -> Result<WalletClosure, ServiceError> {
+        if request.sweep_wallet_identifier == Some(*wallet_identifier) {
+            return Err(ServiceError::UnprocessableEntity(
+                "a wallet cannot be swept into itself".to_string(),
+            ));
+        }
+
+        let closure = self
+            .repository
+            .close(wallet_identifier, &claims.user_identifier, request)
+            .await?;
+        Ok(closure)
+    }
+
+    async fn admin_freeze_wallet(&self, wallet_identifier: &Uuid) -> Result<Wallet, ServiceError> {
+        let wallet = self
+            .repository
+            .freeze(wallet_identifier, None, WalletFreezeOrigin::Admin)
+            .await?;
+        Ok(wallet)
+    }
+
+    async fn admin_unfreeze_wallet(
+        &self,
+        wallet_identifier: &Uuid,
+    ) -> Result<Wallet, ServiceError> {
+        let wallet = self
+            .repository
+            .unfreeze(wallet_identifier, None, WalletFreezeOrigin::Admin)
+            .await?;
+        Ok(wallet)
+    }
 }